@@ -3,28 +3,37 @@ use gv_core::error::DbErr;
 use gv_core::io::{Io, SystemIo};
 use gv_core::{
     DEFAULT_USER_ID,
-    actions::{Action, CreateActivity, CreateAttribute, CreateUser, CreateValue},
-    error::Result,
+    actions::{
+        Action, AttributeConversion, ConvertAttributeType, CreateActivity, CreateAttribute,
+        CreateUser, CreateValue, Redo, Undo,
+    },
+    analytics::AnalyticsQuery,
+    error::{DomainError, Result, ValidationError},
     models::{
         activity::{Activity, ActivityName},
+        attribute::AttributeConfig,
         user::User,
     },
     mutators,
     queries::{
-        AnyQuery, AnyQueryResponse, FindActivityById, FindAttributeById, FindUserById, Query,
+        AllActivities, AllAttributes, AnyQuery, AnyQueryResponse, FindActivityById,
+        FindAttributeById, FindEntryById, FindUserById, FindValuesForEntries, MutationHistory,
+        Query,
     },
     query_executor::QueryExecutor,
+    query_language::{self, Library},
     std_lib::StandardLibrary,
 };
 use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use tokio::sync::broadcast;
 use tracing::{debug, info, instrument};
 use uuid::Uuid;
 
-use gv_sql::sqlite::{SqliteDeltaExecutor, SqliteQueryExecutor};
+use gv_sql::sqlite::{SqliteDeltaExecutor, SqliteMutationLog, SqliteQueryExecutor};
 
 use crate::query_store::{QueryStore, QuerySubscription};
+use crate::undo_stack::UndoStacks;
 
 #[derive(Debug, Clone)]
 pub struct SqliteClient {
@@ -32,7 +41,8 @@ pub struct SqliteClient {
     change_transmitter: broadcast::Sender<()>,
     cache_ready_transmitter: broadcast::Sender<()>,
     query_store: QueryStore,
-    io: Arc<dyn Io>,
+    undo_stacks: UndoStacks,
+    pub(crate) io: Arc<dyn Io>,
 }
 
 impl SqliteClient {
@@ -44,11 +54,28 @@ impl SqliteClient {
             .db_err()?;
         let client = Self::from_pool(pool, Arc::new(SystemIo::default()));
         client.run_migrations().await?;
+        client.reindex_values().await?;
         client.seed_default_user().await?;
         client.seed_std_lib().await?;
+        client.restore_undo_stacks().await?;
         Ok(client)
     }
 
+    /// Rebuild the undo and redo stacks from the local mutation log, so undo
+    /// history survives a restart.
+    pub(crate) async fn restore_undo_stacks(&self) -> Result<()> {
+        let mut log = {
+            let mut conn = self.pool.acquire().await.db_err()?;
+            // The whole log: an undo or redo can target any earlier mutation.
+            SqliteQueryExecutor::new(&mut conn)
+                .execute(MutationHistory { limit: u32::MAX })
+                .await?
+        };
+        log.reverse();
+        self.undo_stacks.restore(log);
+        Ok(())
+    }
+
     /// Seed the single default user into a fresh database. Idempotent: a no-op if
     /// the user already exists, so it's safe to call on every launch alongside
     /// migrations. This replaces the system actor that migrations used to insert;
@@ -64,7 +91,7 @@ impl SqliteClient {
         };
         if existing.is_none() {
             let action: Action = CreateUser::from(User::default_user()).into();
-            self.run_action(action).await?;
+            self.apply_action(action, false).await?;
         }
         Ok(())
     }
@@ -73,7 +100,11 @@ impl SqliteClient {
     /// Idempotent: each item is keyed by a stable id (see `gv_core::std_lib`) and
     /// created only if absent, so this is safe to run on every launch alongside
     /// migrations. It does not reconcile edits — an item whose definition changed
-    /// in code is left untouched, and one the user deleted is re-created.
+    /// in code is left untouched, and one the user deleted is re-created. Items
+    /// added in code reach databases seeded before them the same way, including
+    /// attributes added to an existing activity's template: a template value is
+    /// created when its entry is there but the value isn't. A std-lib select
+    /// that has since become a grade is converted.
     async fn seed_std_lib(&self) -> Result<()> {
         for attribute in StandardLibrary::attributes() {
             let exists = {
@@ -84,9 +115,25 @@ impl SqliteClient {
                     })
                     .await?
             };
-            if exists.is_none() {
-                self.run_action(CreateAttribute::from(attribute).into())
-                    .await?;
+            match (exists, &attribute.config) {
+                (None, _) => {
+                    self.apply_action(CreateAttribute::from(attribute).into(), false)
+                        .await?;
+                }
+                // YDS Grade and V Grade were ordered selects before grades had
+                // their own type; convert them, values and all, so every
+                // install holds the same attribute.
+                (Some(stored), AttributeConfig::Grade(grade))
+                    if matches!(stored.config, AttributeConfig::Select(_)) =>
+                {
+                    let convert = ConvertAttributeType {
+                        actor_id: stored.owner_id,
+                        attribute_id: stored.id,
+                        conversion: AttributeConversion::ToGrade(grade.scale.clone()),
+                    };
+                    self.apply_action(convert.into(), false).await?;
+                }
+                (Some(_), _) => {}
             }
         }
 
@@ -99,18 +146,47 @@ impl SqliteClient {
                     })
                     .await?
             };
+            let actor_id = std_activity.activity.owner_id;
             if exists.is_some() {
+                let missing = {
+                    let mut conn = self.pool.acquire().await.db_err()?;
+                    let mut executor = SqliteQueryExecutor::new(&mut conn);
+                    let mut template_ids = HashSet::new();
+                    for entry in &std_activity.template {
+                        let found = executor
+                            .execute(FindEntryById { entry_id: entry.id })
+                            .await?;
+                        template_ids.extend(found.map(|e| e.id));
+                    }
+                    let stored: HashSet<(Uuid, Uuid)> = executor
+                        .execute(FindValuesForEntries {
+                            entry_ids: template_ids.iter().copied().collect(),
+                        })
+                        .await?
+                        .into_iter()
+                        .map(|v| (v.entry_id, v.attribute_id))
+                        .collect();
+                    std_activity
+                        .template_values
+                        .into_iter()
+                        .filter(|v| template_ids.contains(&v.entry_id))
+                        .filter(|v| !stored.contains(&(v.entry_id, v.attribute_id)))
+                        .collect::<Vec<_>>()
+                };
+                for value in missing {
+                    self.apply_action(CreateValue { actor_id, value }.into(), false)
+                        .await?;
+                }
                 continue;
             }
-            let actor_id = std_activity.activity.owner_id;
             let create = CreateActivity {
                 actor_id,
                 activity: std_activity.activity,
                 template: std_activity.template,
             };
-            self.run_action(create.into()).await?;
+            self.apply_action(create.into(), false).await?;
             for value in std_activity.template_values {
-                self.run_action(CreateValue { actor_id, value }.into())
+                self.apply_action(CreateValue { actor_id, value }.into(), false)
                     .await?;
             }
         }
@@ -133,6 +209,7 @@ impl SqliteClient {
             change_transmitter,
             cache_ready_transmitter,
             query_store,
+            undo_stacks: UndoStacks::default(),
             io,
         }
     }
@@ -146,8 +223,87 @@ impl SqliteClient {
             .map_err(|e| gv_core::error::DomainError::Database(Box::new(e)))
     }
 
-    #[instrument(skip_all)]
+    /// Derive the index columns of values stored before values were indexed.
+    /// Local, like seeding: every install derives the same columns from the
+    /// same actuals, so nothing is logged for sync.
+    async fn reindex_values(&self) -> Result<()> {
+        let mut tx = self.pool.begin().await.db_err()?;
+        gv_sql::sqlite::reindex_values(&mut tx).await?;
+        tx.commit().await.db_err()
+    }
+
+    /// Run an action and, if the resulting mutation is undoable (see
+    /// `Mutation::is_undoable`), record it on the actor's undo stack.
     pub async fn run_action(&self, action: Action) -> Result<mutators::Mutation> {
+        let actor_id = action.actor_id();
+        let mx = self.apply_action(action, true).await?;
+        if mx.is_undoable() {
+            self.undo_stacks.record(actor_id, mx.clone());
+        }
+        Ok(mx)
+    }
+
+    /// Undo the actor's most recent recorded action. Returns `None` when there
+    /// is nothing to undo. An undo rejected because the affected rows have
+    /// since changed is dropped from the history (it can't succeed later
+    /// either) and the rejection returned.
+    pub async fn undo(&self, actor_id: Uuid) -> Result<Option<mutators::Mutation>> {
+        let Some(target) = self.undo_stacks.peek_undo(actor_id) else {
+            return Ok(None);
+        };
+        let action = Undo {
+            actor_id,
+            mutation_id: target.id,
+        };
+        match self.apply_action(action.into(), true).await {
+            Ok(mx) => {
+                self.undo_stacks.mark_undone(actor_id, target.id);
+                Ok(Some(mx))
+            }
+            Err(e @ DomainError::Rejected(_)) => {
+                self.undo_stacks.discard(actor_id, target.id);
+                Err(e)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Redo the actor's most recently undone action. Returns `None` when there
+    /// is nothing to redo. Rejections are handled as in [`Self::undo`].
+    pub async fn redo(&self, actor_id: Uuid) -> Result<Option<mutators::Mutation>> {
+        let Some(target) = self.undo_stacks.peek_redo(actor_id) else {
+            return Ok(None);
+        };
+        let action = Redo {
+            actor_id,
+            mutation_id: target.id,
+        };
+        match self.apply_action(action.into(), true).await {
+            Ok(mx) => {
+                self.undo_stacks.mark_redone(actor_id, target.id);
+                Ok(Some(mx))
+            }
+            Err(e @ DomainError::Rejected(_)) => {
+                self.undo_stacks.discard(actor_id, target.id);
+                Err(e)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn can_undo(&self, actor_id: Uuid) -> bool {
+        self.undo_stacks.can_undo(actor_id)
+    }
+
+    pub fn can_redo(&self, actor_id: Uuid) -> bool {
+        self.undo_stacks.can_redo(actor_id)
+    }
+
+    /// Apply an action in its own transaction. Unless the action only seeds
+    /// local data (`log_mutation == false`), the mutation is written to the
+    /// local mutation log in the same transaction, pending sync.
+    #[instrument(skip_all)]
+    async fn apply_action(&self, action: Action, log_mutation: bool) -> Result<mutators::Mutation> {
         debug!("Began running action = {:?}", action);
         debug!(
             "Active broadcast receivers: {}",
@@ -159,60 +315,7 @@ impl SqliteClient {
         let mut executor = SqliteQueryExecutor::new(&mut tx);
 
         // Create mutation.
-        let mx = match action {
-            Action::CreateActivity(action) => {
-                mutators::create_activity(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateUser(action) => {
-                mutators::create_user(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateEntry(action) => {
-                mutators::create_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateEntryFromActivity(action) => {
-                mutators::create_entry_from_activity(&mut executor, self.io.as_ref(), action)
-                    .await?
-            }
-            Action::MoveEntry(action) => {
-                mutators::move_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::DeleteEntryRecursive(action) => {
-                mutators::delete_entry_recursive(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateAttribute(action) => {
-                mutators::create_attribute(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateValue(action) => {
-                mutators::create_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::AttachValue(action) => {
-                mutators::attach_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::DeleteAttributeValue(action) => {
-                mutators::delete_attribute_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateEntryCompletion(action) => {
-                mutators::update_entry_completion(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateAttributeValue(action) => {
-                mutators::update_attribute_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateAttribute(action) => {
-                mutators::update_attribute(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateEntry(action) => {
-                mutators::update_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::ConvertToSets(action) => {
-                mutators::convert_to_sets(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::DuplicateEntry(action) => {
-                mutators::duplicate_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-        };
-
-        // TODO: write this mutation into the local mutation log.
-        // sync_service.log_mutation(mx);
+        let mx = run_mutator(&mut executor, self.io.as_ref(), action).await?;
 
         // Defer FK constraint checking until commit so delta order doesn't matter.
         sqlx::query("PRAGMA defer_foreign_keys = ON")
@@ -221,44 +324,84 @@ impl SqliteClient {
             .db_err()?;
 
         info!("delta count = {}", mx.changes.len());
-        // Apply deltas.
-        // for delta in mx.changes {
-        //     delta.apply_delta(&mut tx).await?;
-        // }
         let mut delta_executor = SqliteDeltaExecutor::new(&mut *tx);
         for delta in mx.changes.iter().cloned() {
             delta_executor.apply_any_delta(delta).await?;
         }
+        // Log the mutation alongside its deltas so the log never disagrees
+        // with the data.
+        if log_mutation {
+            SqliteMutationLog::new(&mut tx).append(&mx).await?;
+        }
         // Commit the transaction.
         tx.commit().await.db_err()?;
 
         // Broadcast notification that the database changed.
-        let _ = self.change_transmitter.send(());
+        self.notify_changed();
         debug!("Broadcast sent, returning from run_action");
 
-        // TODO: send mutation to service (or to a pending_mutations queue).
-        // sync_service.append_applied_mutation(mx);
-
         Ok(mx)
     }
 
-    pub async fn run_query<Q: Query>(&self, query: Q) -> Result<Q::Response>
+    /// Notify query subscribers that the database changed.
+    pub(crate) fn notify_changed(&self) {
+        let _ = self.change_transmitter.send(());
+    }
+
+    /// Run a query on behalf of `actor_id`: only the rows the actor can read
+    /// come back.
+    pub async fn run_query<Q: Query>(&self, actor_id: Uuid, query: Q) -> Result<Q::Response>
     where
         for<'c> SqliteQueryExecutor<'c>: QueryExecutor<Q>,
     {
-        self.query_store.run_query(query).await
+        self.query_store.run_query(actor_id, query).await
+    }
+
+    pub async fn run_any_query(&self, actor_id: Uuid, query: AnyQuery) -> Result<AnyQueryResponse> {
+        self.query_store.run_any_query(actor_id, query).await
+    }
+
+    pub async fn subscribe_query(
+        &self,
+        actor_id: Uuid,
+        query: AnyQuery,
+    ) -> Result<Arc<QuerySubscription>> {
+        self.query_store.subscribe_query(actor_id, query).await
+    }
+
+    pub fn read_cached_query(&self, actor_id: Uuid, query: AnyQuery) -> Option<AnyQueryResponse> {
+        self.query_store.read_cached_query(actor_id, query)
     }
 
-    pub async fn run_any_query(&self, query: AnyQuery) -> Result<AnyQueryResponse> {
-        self.query_store.run_any_query(query).await
+    /// Parse analytics query text (see [`query_language`]), resolving names
+    /// against the actor's library and the standard library.
+    pub async fn parse_analytics_query(
+        &self,
+        actor_id: Uuid,
+        text: &str,
+        utc_offset_minutes: i32,
+    ) -> Result<AnalyticsQuery> {
+        let library = self.query_library(actor_id).await?;
+        query_language::parse(text, &library, utc_offset_minutes)
+            .map_err(|e| ValidationError::InvalidQueryText(e).into())
     }
 
-    pub async fn subscribe_query(&self, query: AnyQuery) -> Result<Arc<QuerySubscription>> {
-        self.query_store.subscribe_query(query).await
+    /// Write an analytics query as query text, the inverse of
+    /// [`Self::parse_analytics_query`].
+    pub async fn format_analytics_query(
+        &self,
+        actor_id: Uuid,
+        query: &AnalyticsQuery,
+        utc_offset_minutes: i32,
+    ) -> Result<String> {
+        let library = self.query_library(actor_id).await?;
+        Ok(query_language::format(query, &library, utc_offset_minutes))
     }
 
-    pub fn read_cached_query(&self, query: AnyQuery) -> Option<AnyQueryResponse> {
-        self.query_store.read_cached_query(query)
+    async fn query_library(&self, actor_id: Uuid) -> Result<Library> {
+        let activities = self.run_query(actor_id, AllActivities).await?;
+        let attributes = self.run_query(actor_id, AllAttributes).await?;
+        Ok(Library::new(actor_id, activities, attributes))
     }
 
     /// Subscribe to cache-ready notifications. Fires after each database change
@@ -292,6 +435,7 @@ impl SqliteClient {
                         .unwrap_or_else(|_| ActivityName::parse("Auto".to_string()).unwrap()),
                     description: Some(format!("Created by background ticker (tick #{counter})")),
                     source_activity_id: None,
+                    archived: false,
                 };
                 let create_activity = activity.into_create_activity(client.io.uuid());
                 let _ = client.run_action(create_activity.into()).await;
@@ -300,6 +444,58 @@ impl SqliteClient {
     }
 }
 
+/// Run the mutator for `action` against `executor`, producing its mutation
+/// without applying it.
+pub(crate) async fn run_mutator(
+    executor: &mut SqliteQueryExecutor<'_>,
+    io: &dyn Io,
+    action: Action,
+) -> Result<mutators::Mutation> {
+    let mx = match action {
+        Action::CreateActivity(action) => mutators::create_activity(executor, io, action).await?,
+        Action::CreateUser(action) => mutators::create_user(executor, io, action).await?,
+        Action::CreateEntry(action) => mutators::create_entry(executor, io, action).await?,
+        Action::CreateEntryFromActivity(action) => {
+            mutators::create_entry_from_activity(executor, io, action).await?
+        }
+        Action::MoveEntry(action) => mutators::move_entry(executor, io, action).await?,
+        Action::DeleteEntryRecursive(action) => {
+            mutators::delete_entry_recursive(executor, io, action).await?
+        }
+        Action::CreateAttribute(action) => mutators::create_attribute(executor, io, action).await?,
+        Action::CreateValue(action) => mutators::create_value(executor, io, action).await?,
+        Action::AttachValue(action) => mutators::attach_value(executor, io, action).await?,
+        Action::DeleteAttributeValue(action) => {
+            mutators::delete_attribute_value(executor, io, action).await?
+        }
+        Action::UpdateEntryCompletion(action) => {
+            mutators::update_entry_completion(executor, io, action).await?
+        }
+        Action::UpdateAttributeValue(action) => {
+            mutators::update_attribute_value(executor, io, action).await?
+        }
+        Action::UpdateAttribute(action) => mutators::update_attribute(executor, io, action).await?,
+        Action::UpdateEntry(action) => mutators::update_entry(executor, io, action).await?,
+        Action::ConvertToSets(action) => mutators::convert_to_sets(executor, io, action).await?,
+        Action::DuplicateEntry(action) => mutators::duplicate_entry(executor, io, action).await?,
+        Action::Undo(action) => mutators::undo(executor, io, action).await?,
+        Action::Redo(action) => mutators::redo(executor, io, action).await?,
+        Action::GrantPermission(action) => mutators::grant_permission(executor, io, action).await?,
+        Action::AcceptGrant(action) => mutators::accept_grant(executor, io, action).await?,
+        Action::RevokeGrant(action) => mutators::revoke_grant(executor, io, action).await?,
+        Action::DeleteActivity(action) => mutators::delete_activity(executor, io, action).await?,
+        Action::ArchiveActivity(action) => mutators::archive_activity(executor, io, action).await?,
+        Action::UpdateActivity(action) => mutators::update_activity(executor, io, action).await?,
+        Action::AttributeMigration(action) => {
+            mutators::migrate_attribute(executor, io, action).await?
+        }
+        Action::ConvertAttributeType(action) => {
+            mutators::convert_attribute_type(executor, io, action).await?
+        }
+    };
+    Ok(mx)
+}
+
 pub mod tests {
     pub use super::*;
     pub use gv_core::SYSTEM_ACTOR_ID;
@@ -317,20 +513,28 @@ pub mod tests {
             entry_id: Uuid::new_v4(),
         });
 
-        let sub1 = client.subscribe_query(query.clone()).await.unwrap();
-        let sub2 = client.subscribe_query(query.clone()).await.unwrap();
+        let sub1 = client
+            .subscribe_query(SYSTEM_ACTOR_ID, query.clone())
+            .await
+            .unwrap();
+        let sub2 = client
+            .subscribe_query(SYSTEM_ACTOR_ID, query.clone())
+            .await
+            .unwrap();
 
         // Dropping one of two subscribers must NOT evict the shared key.
         drop(sub2);
         assert!(
-            client.read_cached_query(query.clone()).is_some(),
+            client
+                .read_cached_query(SYSTEM_ACTOR_ID, query.clone())
+                .is_some(),
             "cache key must survive while another subscriber is alive"
         );
 
         // Dropping the last subscriber evicts it.
         drop(sub1);
         assert!(
-            client.read_cached_query(query).is_none(),
+            client.read_cached_query(SYSTEM_ACTOR_ID, query).is_none(),
             "cache key must be evicted once the last subscriber drops"
         );
     }
@@ -345,7 +549,10 @@ pub mod tests {
         client.seed_default_user().await.unwrap();
 
         client.seed_std_lib().await.unwrap();
-        let first = client.run_query(SnapshotAll).await.unwrap();
+        let first = client
+            .run_query(SYSTEM_ACTOR_ID, SnapshotAll)
+            .await
+            .unwrap();
 
         // The first pass seeds every std-lib item exactly once.
         assert_eq!(first.attributes.len(), StandardLibrary::attributes().len());
@@ -357,10 +564,210 @@ pub mod tests {
 
         // A second pass over the same DB converges instead of duplicating.
         client.seed_std_lib().await.unwrap();
-        let second = client.run_query(SnapshotAll).await.unwrap();
+        let second = client
+            .run_query(SYSTEM_ACTOR_ID, SnapshotAll)
+            .await
+            .unwrap();
         assert_eq!(first.attributes.len(), second.attributes.len());
         assert_eq!(first.activities.len(), second.activities.len());
         assert_eq!(first.entries.len(), second.entries.len());
         assert_eq!(first.values.len(), second.values.len());
     }
+
+    /// A database seeded when YDS Grade was an ordered select has it converted
+    /// to a grade on the next launch, with its values read as YDS grades.
+    #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+    fn test_seed_std_lib_converts_old_grade_selects(pool: SqlitePool) {
+        use gv_core::{
+            delta::Delta,
+            models::{
+                attribute::{Attribute, AttributeValue, SelectConfig, SelectValue, Value},
+                grade::GradeScale,
+            },
+            queries::FindValueByKey,
+        };
+
+        let client = SqliteClient::from_pool(pool, Arc::new(SystemIo::default()));
+        client.seed_default_user().await.unwrap();
+        client.seed_std_lib().await.unwrap();
+
+        // Stand in for a database seeded before the change.
+        let grade = StandardLibrary::attributes()
+            .into_iter()
+            .find(|a| a.name == "YDS Grade")
+            .unwrap();
+        let old_select = Attribute {
+            config: SelectConfig {
+                options: [
+                    "<5.5", "5.5", "5.6", "5.7", "10-", "10", "10+", "11-", "11", "11+",
+                ]
+                .map(String::from)
+                .to_vec(),
+                ordered: true,
+                default: None,
+            }
+            .into(),
+            ..grade.clone()
+        };
+        let route = StandardLibrary::activities()
+            .into_iter()
+            .find(|a| a.activity.name.to_string() == "Route Climb")
+            .unwrap();
+        let select = |v| Some(AttributeValue::Select(v));
+        let value = Value {
+            entry_id: route.template[0].id,
+            attribute_id: grade.id,
+            index_float: None,
+            index_string: None,
+            plan: select(SelectValue::Range {
+                min: "<5.5".to_string(),
+                max: "5.7".to_string(),
+            }),
+            actual: select(SelectValue::Exact("11+".to_string())),
+        };
+        let seeded = client
+            .run_query(
+                SYSTEM_ACTOR_ID,
+                FindValueByKey {
+                    entry_id: value.entry_id,
+                    attribute_id: grade.id,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        {
+            let mut conn = client.pool.acquire().await.unwrap();
+            let mut deltas = SqliteDeltaExecutor::new(&mut conn);
+            deltas
+                .apply_any_delta(
+                    Delta::Update {
+                        old: grade.clone(),
+                        new: old_select.clone(),
+                    }
+                    .into(),
+                )
+                .await
+                .unwrap();
+            deltas
+                .apply_any_delta(
+                    Delta::Update {
+                        old: seeded,
+                        new: old_select.indexed(value.clone()),
+                    }
+                    .into(),
+                )
+                .await
+                .unwrap();
+        }
+
+        client.seed_std_lib().await.unwrap();
+        let converted = client
+            .run_query(
+                SYSTEM_ACTOR_ID,
+                FindAttributeById {
+                    attribute_id: grade.id,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(converted.config, grade.config);
+        let stored = client
+            .run_query(
+                SYSTEM_ACTOR_ID,
+                FindValueByKey {
+                    entry_id: value.entry_id,
+                    attribute_id: grade.id,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let grades = |v| Some(AttributeValue::Grade(v));
+        assert_eq!(
+            stored.plan,
+            grades(SelectValue::Range {
+                min: "5.0".to_string(),
+                max: "5.7".to_string(),
+            })
+        );
+        assert_eq!(
+            stored.actual,
+            grades(SelectValue::Exact("5.11+".to_string()))
+        );
+        assert_eq!(stored.index_float, GradeScale::Yds.difficulty("5.11+"));
+    }
+
+    /// A template value added to the std lib after a database was seeded (as
+    /// Hold Time was to Repeaters) reaches it on the next launch.
+    #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+    fn test_seed_std_lib_adds_new_template_values(pool: SqlitePool) {
+        let client = SqliteClient::from_pool(pool, Arc::new(SystemIo::default()));
+        client.seed_default_user().await.unwrap();
+        client.seed_std_lib().await.unwrap();
+        let seeded = client
+            .run_query(SYSTEM_ACTOR_ID, SnapshotAll)
+            .await
+            .unwrap();
+
+        // Stand in for a database seeded before the value was in the std lib.
+        let added = StandardLibrary::activities()
+            .into_iter()
+            .flat_map(|a| a.template_values)
+            .next()
+            .unwrap();
+        sqlx::query("DELETE FROM attribute_values WHERE entry_id = ? AND attribute_id = ?")
+            .bind(added.entry_id)
+            .bind(added.attribute_id)
+            .execute(&client.pool)
+            .await
+            .unwrap();
+
+        client.seed_std_lib().await.unwrap();
+        let reseeded = client
+            .run_query(SYSTEM_ACTOR_ID, SnapshotAll)
+            .await
+            .unwrap();
+        assert_eq!(reseeded.values.len(), seeded.values.len());
+        assert!(
+            reseeded
+                .values
+                .iter()
+                .any(|v| v.entry_id == added.entry_id && v.attribute_id == added.attribute_id)
+        );
+    }
+
+    /// Undo history is rebuilt from the mutation log, so a restarted client
+    /// can undo and redo what the previous session did.
+    #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+    fn test_undo_history_survives_restart(pool: SqlitePool) {
+        let client = SqliteClient::from_pool(pool.clone(), Arc::new(SystemIo::default()));
+        client.seed_default_user().await.unwrap();
+        let activity = |name: &str| Activity {
+            id: Uuid::new_v4(),
+            owner_id: DEFAULT_USER_ID,
+            name: ActivityName::parse(name.to_string()).unwrap(),
+            description: None,
+            source_activity_id: None,
+            archived: false,
+        };
+        let (first, second) = (activity("First"), activity("Second"));
+        for activity in [&first, &second] {
+            let create = activity.clone().into_create_activity(client.io.uuid());
+            client.run_action(create.into()).await.unwrap();
+        }
+        client.undo(DEFAULT_USER_ID).await.unwrap();
+
+        let restarted = SqliteClient::from_pool(pool, Arc::new(SystemIo::default()));
+        restarted.restore_undo_stacks().await.unwrap();
+        let find = |id| restarted.run_query(DEFAULT_USER_ID, FindActivityById { id });
+        assert!(find(second.id).await.unwrap().is_none());
+        assert!(restarted.redo(DEFAULT_USER_ID).await.unwrap().is_some());
+        assert!(find(second.id).await.unwrap().is_some());
+        restarted.undo(DEFAULT_USER_ID).await.unwrap();
+        restarted.undo(DEFAULT_USER_ID).await.unwrap();
+        assert!(find(first.id).await.unwrap().is_none());
+        assert!(!restarted.can_undo(DEFAULT_USER_ID));
+    }
 }
@@ -1,2 +1,4 @@
 pub mod client;
 pub mod query_store;
+pub mod sync;
+pub mod undo_stack;
@@ -6,13 +6,18 @@ use std::{
 use gv_core::{
     error::{DbErr, Result},
     queries::{AnyQuery, AnyQueryResponse, Query},
-    query_executor::QueryExecutor,
+    query_executor::{QueryExecutor, ScopedQueryExecutor},
 };
 use sqlx::SqlitePool;
 use tokio::sync::broadcast;
+use uuid::Uuid;
 
 use gv_sql::sqlite::SqliteQueryExecutor;
 
+/// A subscribed query and the actor it runs on behalf of: the same query
+/// returns different rows to different actors.
+type CacheKey = (Uuid, AnyQuery);
+
 /// Shared cache state. `cache` holds the latest result per subscribed query;
 /// `refcounts` tracks how many live `QuerySubscription` handles reference each
 /// key. A key is evicted only when its last subscriber drops — multiple views
@@ -20,8 +25,8 @@ use gv_sql::sqlite::SqliteQueryExecutor;
 /// `FindEntryJoinById`) without one's teardown starving the others.
 #[derive(Debug, Default)]
 struct CacheState {
-    cache: HashMap<AnyQuery, AnyQueryResponse>,
-    refcounts: HashMap<AnyQuery, usize>,
+    cache: HashMap<CacheKey, AnyQueryResponse>,
+    refcounts: HashMap<CacheKey, usize>,
 }
 
 type SharedCache = Arc<Mutex<CacheState>>;
@@ -56,82 +61,123 @@ impl QueryStore {
         store
     }
 
-    /// Run a query once, directly against sqlite, and return the result.
-    pub async fn run_query<Q: Query>(&self, query: Q) -> Result<Q::Response>
+    /// Run a query once, directly against sqlite, on behalf of `actor_id`, and
+    /// return the result: only the rows the actor can read.
+    pub async fn run_query<Q: Query>(&self, actor_id: Uuid, query: Q) -> Result<Q::Response>
     where
         for<'c> SqliteQueryExecutor<'c>: QueryExecutor<Q>,
     {
         let mut conn = self.pool.acquire().await.db_err()?;
-        SqliteQueryExecutor::new(&mut conn).execute(query).await
+        ScopedQueryExecutor::new(SqliteQueryExecutor::new(&mut conn), actor_id)
+            .execute(query)
+            .await
     }
 
     /// Type-erased version of run_query.
-    pub async fn run_any_query(&self, query: AnyQuery) -> Result<AnyQueryResponse> {
+    pub async fn run_any_query(&self, actor_id: Uuid, query: AnyQuery) -> Result<AnyQueryResponse> {
         match query {
             // Auth
             AnyQuery::IsEmailRegistered(q) => Ok(AnyQueryResponse::IsEmailRegistered(
-                self.run_query(q).await?,
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::FindUserById(q) => Ok(AnyQueryResponse::FindUserById(
+                self.run_query(actor_id, q).await?,
             )),
-            AnyQuery::FindUserById(q) => {
-                Ok(AnyQueryResponse::FindUserById(self.run_query(q).await?))
-            }
             AnyQuery::FindUserByUsername(q) => Ok(AnyQueryResponse::FindUserByUsername(
-                self.run_query(q).await?,
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::AllActorIds(q) => Ok(AnyQueryResponse::AllActorIds(
+                self.run_query(actor_id, q).await?,
             )),
-            AnyQuery::AllActorIds(q) => Ok(AnyQueryResponse::AllActorIds(self.run_query(q).await?)),
             // Activity
-            AnyQuery::FindActivityById(q) => {
-                Ok(AnyQueryResponse::FindActivityById(self.run_query(q).await?))
-            }
-            AnyQuery::AllActivities(q) => {
-                Ok(AnyQueryResponse::AllActivities(self.run_query(q).await?))
-            }
+            AnyQuery::FindActivityById(q) => Ok(AnyQueryResponse::FindActivityById(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::AllActivities(q) => Ok(AnyQueryResponse::AllActivities(
+                self.run_query(actor_id, q).await?,
+            )),
             AnyQuery::FindActivityTemplateRoot(q) => Ok(
-                AnyQueryResponse::FindActivityTemplateRoot(self.run_query(q).await?),
+                AnyQueryResponse::FindActivityTemplateRoot(self.run_query(actor_id, q).await?),
             ),
+            AnyQuery::FindEntriesByActivity(q) => Ok(AnyQueryResponse::FindEntriesByActivity(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::FindActivitiesBySource(q) => Ok(AnyQueryResponse::FindActivitiesBySource(
+                self.run_query(actor_id, q).await?,
+            )),
             // Entry
-            AnyQuery::AllEntries(q) => Ok(AnyQueryResponse::AllEntries(self.run_query(q).await?)),
+            AnyQuery::AllEntries(q) => Ok(AnyQueryResponse::AllEntries(
+                self.run_query(actor_id, q).await?,
+            )),
             AnyQuery::EntriesRootedInTimeInterval(q) => Ok(
-                AnyQueryResponse::EntriesRootedInTimeInterval(self.run_query(q).await?),
+                AnyQueryResponse::EntriesRootedInTimeInterval(self.run_query(actor_id, q).await?),
             ),
-            AnyQuery::FindAncestors(q) => {
-                Ok(AnyQueryResponse::FindAncestors(self.run_query(q).await?))
-            }
-            AnyQuery::FindEntryById(q) => {
-                Ok(AnyQueryResponse::FindEntryById(self.run_query(q).await?))
-            }
+            AnyQuery::FindAncestors(q) => Ok(AnyQueryResponse::FindAncestors(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::FindEntryById(q) => Ok(AnyQueryResponse::FindEntryById(
+                self.run_query(actor_id, q).await?,
+            )),
             AnyQuery::FindEntryJoinById(q) => Ok(AnyQueryResponse::FindEntryJoinById(
-                self.run_query(q).await?,
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::FindDescendants(q) => Ok(AnyQueryResponse::FindDescendants(
+                self.run_query(actor_id, q).await?,
             )),
-            AnyQuery::FindDescendants(q) => {
-                Ok(AnyQueryResponse::FindDescendants(self.run_query(q).await?))
-            }
             // Attribute
             AnyQuery::FindAttributeById(q) => Ok(AnyQueryResponse::FindAttributeById(
-                self.run_query(q).await?,
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::AllAttributes(q) => Ok(AnyQueryResponse::AllAttributes(
+                self.run_query(actor_id, q).await?,
             )),
-            AnyQuery::AllAttributes(q) => {
-                Ok(AnyQueryResponse::AllAttributes(self.run_query(q).await?))
-            }
             AnyQuery::FindAttributesByOwner(q) => Ok(AnyQueryResponse::FindAttributesByOwner(
-                self.run_query(q).await?,
+                self.run_query(actor_id, q).await?,
             )),
             // Value
-            AnyQuery::FindValueByKey(q) => {
-                Ok(AnyQueryResponse::FindValueByKey(self.run_query(q).await?))
-            }
+            AnyQuery::FindValueByKey(q) => Ok(AnyQueryResponse::FindValueByKey(
+                self.run_query(actor_id, q).await?,
+            )),
             AnyQuery::FindValuesForEntry(q) => Ok(AnyQueryResponse::FindValuesForEntry(
-                self.run_query(q).await?,
+                self.run_query(actor_id, q).await?,
             )),
             AnyQuery::FindValuesForEntries(q) => Ok(AnyQueryResponse::FindValuesForEntries(
-                self.run_query(q).await?,
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::FindValuesForAttribute(q) => Ok(AnyQueryResponse::FindValuesForAttribute(
+                self.run_query(actor_id, q).await?,
             )),
             AnyQuery::FindAttributePairsForEntry(q) => Ok(
-                AnyQueryResponse::FindAttributePairsForEntry(self.run_query(q).await?),
-            ),
-            AnyQuery::DistinctTextValuesForAttribute(q) => Ok(
-                AnyQueryResponse::DistinctTextValuesForAttribute(self.run_query(q).await?),
+                AnyQueryResponse::FindAttributePairsForEntry(self.run_query(actor_id, q).await?),
             ),
+            AnyQuery::DistinctTextValuesForAttribute(q) => {
+                Ok(AnyQueryResponse::DistinctTextValuesForAttribute(
+                    self.run_query(actor_id, q).await?,
+                ))
+            }
+            // Analytics
+            AnyQuery::RunAnalytics(q) => Ok(AnyQueryResponse::RunAnalytics(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::PersonalRecords(q) => Ok(AnyQueryResponse::PersonalRecords(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::TrainingLoad(q) => Ok(AnyQueryResponse::TrainingLoad(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::AttributeSeries(q) => Ok(AnyQueryResponse::AttributeSeries(
+                self.run_query(actor_id, q).await?,
+            )),
+            // Grant
+            AnyQuery::FindGrantById(q) => Ok(AnyQueryResponse::FindGrantById(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::FindGrantsForGrantee(q) => Ok(AnyQueryResponse::FindGrantsForGrantee(
+                self.run_query(actor_id, q).await?,
+            )),
+            AnyQuery::FindGrantsByGrantor(q) => Ok(AnyQueryResponse::FindGrantsByGrantor(
+                self.run_query(actor_id, q).await?,
+            )),
         }
     }
 
@@ -139,15 +185,20 @@ impl QueryStore {
     /// cache, increments the key's refcount, and returns a `QuerySubscription`
     /// handle. Dropping the handle decrements the refcount; the cache entry is
     /// removed only when the last subscriber for that key drops.
-    pub async fn subscribe_query(&self, query: AnyQuery) -> Result<Arc<QuerySubscription>> {
-        let initial = self.run_any_query(query.clone()).await?;
+    pub async fn subscribe_query(
+        &self,
+        actor_id: Uuid,
+        query: AnyQuery,
+    ) -> Result<Arc<QuerySubscription>> {
+        let initial = self.run_any_query(actor_id, query.clone()).await?;
+        let key = (actor_id, query);
         {
             let mut state = self.state.lock().unwrap();
-            state.cache.insert(query.clone(), initial);
-            *state.refcounts.entry(query.clone()).or_insert(0) += 1;
+            state.cache.insert(key.clone(), initial);
+            *state.refcounts.entry(key.clone()).or_insert(0) += 1;
         }
         Ok(Arc::new(QuerySubscription {
-            key: query,
+            key,
             state: Arc::clone(&self.state),
         }))
     }
@@ -155,8 +206,13 @@ impl QueryStore {
     /// Read the current cached result for a query. Returns `None` if the query
     /// is not subscribed. Swift calls this synchronously from the main thread
     /// after receiving `on_data_changed()`.
-    pub fn read_cached_query(&self, query: AnyQuery) -> Option<AnyQueryResponse> {
-        self.state.lock().unwrap().cache.get(&query).cloned()
+    pub fn read_cached_query(&self, actor_id: Uuid, query: AnyQuery) -> Option<AnyQueryResponse> {
+        self.state
+            .lock()
+            .unwrap()
+            .cache
+            .get(&(actor_id, query))
+            .cloned()
     }
 
     /// Refresh all keys currently present in the cache.
@@ -165,13 +221,14 @@ impl QueryStore {
     /// is safe to call from async contexts. A key that was unsubscribed during
     /// an await is not resurrected.
     async fn refresh_subscribed_queries(&self) -> Result<()> {
-        let queries: Vec<AnyQuery> = self.state.lock().unwrap().cache.keys().cloned().collect();
-        for query in queries {
-            let result = self.run_any_query(query.clone()).await?;
+        let keys: Vec<CacheKey> = self.state.lock().unwrap().cache.keys().cloned().collect();
+        for key in keys {
+            let (actor_id, query) = key.clone();
+            let result = self.run_any_query(actor_id, query).await?;
             let mut state = self.state.lock().unwrap();
             // Don't resurrect a key whose last subscriber dropped mid-refresh.
-            if state.refcounts.contains_key(&query) {
-                state.cache.insert(query, result);
+            if state.refcounts.contains_key(&key) {
+                state.cache.insert(key, result);
             }
         }
         Ok(())
@@ -182,7 +239,7 @@ impl QueryStore {
 /// the key's refcount and removes it from the cache once no subscribers remain —
 /// no manual unsubscribe call needed.
 pub struct QuerySubscription {
-    key: AnyQuery,
+    key: CacheKey,
     state: SharedCache,
 }
 
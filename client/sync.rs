@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use gv_core::{
+    actions::Action,
+    delta::inverse_changes,
+    delta_executor::AnyDeltaExecutor,
+    error::{DbErr, DomainError, RejectReason, Result},
+    io::Io,
+    mutation_log::{CommittedMutation, MutationState},
+    mutators::Mutation,
+    queries::{LastSeenServerSeq, UnackedMutations},
+    query_executor::QueryExecutor,
+    sync::{PullRequest, PushRequest, PushResult, SyncServer},
+};
+use gv_sql::sqlite::{SqliteDeltaExecutor, SqliteMutationLog, SqliteQueryExecutor};
+use sqlx::{Connection, Row, SqliteConnection};
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::client::{SqliteClient, run_mutator};
+
+/// How many committed mutations to request per pull.
+const PULL_PAGE_SIZE: u32 = 500;
+
+/// What a sync round did.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Local mutations the server committed in this round.
+    pub acked: Vec<Uuid>,
+    /// Local mutations the server refused, with its reason. Their local
+    /// effects were reverted.
+    pub rejected: Vec<(Uuid, String)>,
+    /// Local mutations that no longer applied after rebasing onto the server
+    /// state, so were dropped before reaching the server.
+    pub dropped: Vec<Uuid>,
+    /// Server mutations applied to the local replica.
+    pub pulled: usize,
+    /// The latest server `seq_num` now reflected locally.
+    pub last_seen_server_seq: i64,
+}
+
+impl SqliteClient {
+    /// Run one round of write-path sync against `server`.
+    ///
+    /// 1. Push every unacked local mutation, in the order it was applied.
+    /// 2. Pull every mutation the server committed since the last sync.
+    /// 3. Rebase, in one transaction: revert the local-only mutations, apply
+    ///    the server's deltas, then re-run the still-unacked actions through
+    ///    their mutators on top of the new state.
+    ///
+    /// Acked mutations are not re-applied: their effects arrive with the
+    /// pulled server deltas. If the push or pull fails, nothing local changes
+    /// apart from pushed mutations moving to `Sent`; pushing is idempotent,
+    /// so the next round retries them.
+    #[instrument(skip_all, err(level = "warn"))]
+    pub async fn sync<S: SyncServer>(&self, server: &S) -> Result<SyncReport> {
+        let (unacked, last_seen) = {
+            let mut conn = self.pool.acquire().await.db_err()?;
+            let mut executor = SqliteQueryExecutor::new(&mut conn);
+            let unacked = executor.execute(UnackedMutations).await?;
+            let last_seen = executor.execute(LastSeenServerSeq).await?;
+            (unacked, last_seen)
+        };
+
+        let mut results = HashMap::new();
+        if !unacked.is_empty() {
+            {
+                let mut tx = self.pool.begin().await.db_err()?;
+                let mut log = SqliteMutationLog::new(&mut tx);
+                for logged in &unacked {
+                    log.mark_sent(logged.mutation.id).await?;
+                }
+                tx.commit().await.db_err()?;
+            }
+            let response = server
+                .push(PushRequest {
+                    last_seen_server_seq: last_seen,
+                    mutations: unacked.into_iter().map(|l| l.mutation).collect(),
+                })
+                .await?;
+            for result in response.results {
+                results.insert(result.mutation_id(), result);
+            }
+        }
+
+        // Pages can come back short, or empty, when the server leaves out
+        // mutations this actor can't see, so follow the server's cursor until
+        // a page covers nothing new.
+        let mut pulled = Vec::new();
+        let mut after_seq_num = last_seen;
+        loop {
+            let page = server
+                .pull(PullRequest {
+                    after_seq_num,
+                    limit: PULL_PAGE_SIZE,
+                })
+                .await?;
+            let through = page.mutations.last().map_or(page.through_seq_num, |last| {
+                last.seq_num.max(page.through_seq_num)
+            });
+            pulled.extend(page.mutations);
+            if through <= after_seq_num {
+                break;
+            }
+            after_seq_num = through;
+        }
+
+        let report = self.rebase(pulled, after_seq_num, results).await?;
+        self.notify_changed();
+        Ok(report)
+    }
+
+    /// Rebase the local-only mutations onto `pulled`, which brings the
+    /// replica up to server `seq_num` `through`, resolving those the server
+    /// answered in `results`.
+    async fn rebase(
+        &self,
+        pulled: Vec<CommittedMutation>,
+        through: i64,
+        results: HashMap<Uuid, PushResult>,
+    ) -> Result<SyncReport> {
+        let mut tx = self.pool.begin().await.db_err()?;
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await
+            .db_err()?;
+
+        // Read inside the transaction: includes mutations applied locally while
+        // the push and pull were in flight.
+        let (local, mut last_seen) = {
+            let mut executor = SqliteQueryExecutor::new(&mut tx);
+            let local = executor.execute(UnackedMutations).await?;
+            let last_seen = executor.execute(LastSeenServerSeq).await?;
+            (local, last_seen)
+        };
+
+        // Revert local-only mutations, newest first, back to the last server
+        // state this replica saw.
+        {
+            let mut delta_executor = SqliteDeltaExecutor::new(&mut tx);
+            for logged in local.iter().rev() {
+                for delta in inverse_changes(logged.mutation.changes.clone()) {
+                    delta_executor.apply_any_delta(delta).await?;
+                }
+            }
+        }
+
+        // Fast-forward to the server's state.
+        let mut report = SyncReport::default();
+        {
+            let mut delta_executor = SqliteDeltaExecutor::new(&mut tx);
+            for committed in pulled {
+                if committed.seq_num <= last_seen {
+                    continue;
+                }
+                for delta in committed.mutation.changes {
+                    delta_executor.apply_any_delta(delta).await?;
+                }
+                last_seen = committed.seq_num;
+                report.pulled += 1;
+            }
+        }
+        // Mutations this actor can't see still move the replica forward.
+        last_seen = last_seen.max(through);
+        SqliteMutationLog::new(&mut tx)
+            .set_last_seen_server_seq(last_seen)
+            .await?;
+        report.last_seen_server_seq = last_seen;
+
+        // Re-apply what is still local-only on top.
+        for logged in local {
+            let id = logged.mutation.id;
+            match results.get(&id) {
+                Some(PushResult::Acked { seq_num, .. }) if *seq_num <= last_seen => {
+                    SqliteMutationLog::new(&mut tx)
+                        .resolve(id, MutationState::Acked, Some(*seq_num))
+                        .await?;
+                    report.acked.push(id);
+                    continue;
+                }
+                Some(PushResult::Rejected { reason, .. }) => {
+                    SqliteMutationLog::new(&mut tx)
+                        .resolve(id, MutationState::Rejected, None)
+                        .await?;
+                    report.rejected.push((id, reason.clone()));
+                    continue;
+                }
+                // Unanswered, or acked beyond what was pulled (its deltas
+                // arrive next round): keep it applied locally.
+                _ => {}
+            }
+
+            // A savepoint per action, so one that no longer applies is rolled
+            // back on its own.
+            let mut savepoint = tx.begin().await.db_err()?;
+            match reapply(&mut savepoint, self.io.as_ref(), logged.mutation.action).await {
+                Ok(mx) => {
+                    savepoint.commit().await.db_err()?;
+                    SqliteMutationLog::new(&mut tx)
+                        .replace_changes(id, &mx.changes)
+                        .await?;
+                }
+                Err(DomainError::Rejected(reason)) => {
+                    savepoint.rollback().await.db_err()?;
+                    // Conflicts with the server state; the server would refuse
+                    // it too, so drop it rather than push it.
+                    warn!(%id, %reason, "local mutation no longer applies after rebase");
+                    SqliteMutationLog::new(&mut tx)
+                        .resolve(id, MutationState::Rejected, None)
+                        .await?;
+                    report.dropped.push(id);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        tx.commit().await.db_err()?;
+        info!(
+            acked = report.acked.len(),
+            rejected = report.rejected.len(),
+            dropped = report.dropped.len(),
+            pulled = report.pulled,
+            last_seen_server_seq = report.last_seen_server_seq,
+            "sync complete"
+        );
+        Ok(report)
+    }
+}
+
+/// Re-run an action through its mutator and apply the result. Foreign keys
+/// are deferred to commit, which in a rebase is only reached after every
+/// action has been re-run, so check them here: a violation means the action
+/// points at a row the server state no longer has.
+async fn reapply(conn: &mut SqliteConnection, io: &dyn Io, action: Action) -> Result<Mutation> {
+    let mx = run_mutator(&mut SqliteQueryExecutor::new(conn), io, action).await?;
+    let mut delta_executor = SqliteDeltaExecutor::new(conn);
+    for delta in mx.changes.iter().cloned() {
+        delta_executor.apply_any_delta(delta).await?;
+    }
+    let violations = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(&mut *conn)
+        .await
+        .db_err()?;
+    if let Some(violation) = violations.first() {
+        let table: String = violation.try_get("table").db_err()?;
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "{table} row references a missing row"
+        ))));
+    }
+    Ok(mx)
+}
@@ -0,0 +1,125 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use gv_core::{
+    actions::Action,
+    mutation_log::{LoggedMutation, MutationState},
+    mutators::Mutation,
+};
+use uuid::Uuid;
+
+/// How many mutations each actor can undo. Older entries fall off the bottom
+/// of the stack.
+const MAX_UNDO_DEPTH: usize = 100;
+
+/// Per-actor undo/redo history for a client session. Holds the mutations the
+/// actor applied (undo stack) and the ones they undid (redo stack); the
+/// `Undo`/`Redo` actions built from these entries re-check their deltas
+/// against the store, so the history itself never needs to be invalidated.
+///
+/// Held in memory and rebuilt from the local mutation log on startup
+/// (`restore`), so history survives a restart.
+#[derive(Debug, Clone, Default)]
+pub struct UndoStacks {
+    inner: Arc<Mutex<HashMap<Uuid, History>>>,
+}
+
+#[derive(Debug, Default)]
+struct History {
+    undo: Vec<Mutation>,
+    redo: Vec<Mutation>,
+}
+
+impl UndoStacks {
+    /// Record a mutation the actor just applied. A new action forks the
+    /// timeline, so the redo stack is cleared.
+    pub fn record(&self, actor_id: Uuid, mutation: Mutation) {
+        let mut inner = self.inner.lock().unwrap();
+        let history = inner.entry(actor_id).or_default();
+        history.undo.push(mutation);
+        if history.undo.len() > MAX_UNDO_DEPTH {
+            history.undo.remove(0);
+        }
+        history.redo.clear();
+    }
+
+    /// The mutation the next undo would revert.
+    pub fn peek_undo(&self, actor_id: Uuid) -> Option<Mutation> {
+        let inner = self.inner.lock().unwrap();
+        inner.get(&actor_id).and_then(|h| h.undo.last().cloned())
+    }
+
+    /// The mutation the next redo would re-apply.
+    pub fn peek_redo(&self, actor_id: Uuid) -> Option<Mutation> {
+        let inner = self.inner.lock().unwrap();
+        inner.get(&actor_id).and_then(|h| h.redo.last().cloned())
+    }
+
+    pub fn can_undo(&self, actor_id: Uuid) -> bool {
+        self.peek_undo(actor_id).is_some()
+    }
+
+    pub fn can_redo(&self, actor_id: Uuid) -> bool {
+        self.peek_redo(actor_id).is_some()
+    }
+
+    /// Move a mutation from the undo stack to the redo stack once its undo
+    /// has committed.
+    pub fn mark_undone(&self, actor_id: Uuid, mutation_id: Uuid) {
+        let mut inner = self.inner.lock().unwrap();
+        let history = inner.entry(actor_id).or_default();
+        if let Some(mutation) = take(&mut history.undo, mutation_id) {
+            history.redo.push(mutation);
+        }
+    }
+
+    /// Move a mutation from the redo stack back to the undo stack once its
+    /// redo has committed.
+    pub fn mark_redone(&self, actor_id: Uuid, mutation_id: Uuid) {
+        let mut inner = self.inner.lock().unwrap();
+        let history = inner.entry(actor_id).or_default();
+        if let Some(mutation) = take(&mut history.redo, mutation_id) {
+            history.undo.push(mutation);
+        }
+    }
+
+    /// Rebuild the stacks from the local mutation log, oldest first, the way
+    /// recording, undoing and redoing built them. Mutations the server
+    /// rejected were rolled back, so they're left out. A discarded mutation
+    /// comes back: the undo or redo that was rejected was never logged, and
+    /// it's discarded again if it's still stale.
+    pub fn restore(&self, log: impl IntoIterator<Item = LoggedMutation>) {
+        for logged in log {
+            if logged.state == MutationState::Rejected {
+                continue;
+            }
+            let mutation = logged.mutation;
+            let actor_id = mutation.action.actor_id();
+            match &mutation.action {
+                Action::Undo(undo) => self.mark_undone(actor_id, undo.mutation_id),
+                Action::Redo(redo) => self.mark_redone(actor_id, redo.mutation_id),
+                _ if mutation.is_undoable() => self.record(actor_id, mutation),
+                _ => {}
+            }
+        }
+    }
+
+    /// Drop a mutation from both stacks, e.g. after its undo or redo was
+    /// rejected because the rows it touched have since changed.
+    pub fn discard(&self, actor_id: Uuid, mutation_id: Uuid) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(history) = inner.get_mut(&actor_id) {
+            take(&mut history.undo, mutation_id);
+            take(&mut history.redo, mutation_id);
+        }
+    }
+}
+
+/// Remove a mutation from a stack by id. Looked up by id rather than popped so
+/// an action recorded concurrently with an undo can't be moved in its place.
+fn take(stack: &mut Vec<Mutation>, mutation_id: Uuid) -> Option<Mutation> {
+    let index = stack.iter().rposition(|m| m.id == mutation_id)?;
+    Some(stack.remove(index))
+}
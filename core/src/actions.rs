@@ -1,13 +1,21 @@
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::models::{
-    activity::Activity,
-    attribute::{Attribute, AttributeValue, LengthUnit, MassUnit, Value},
-    entry::{Entry, Position, Temporal},
-    user::User,
+use crate::{
+    models::{
+        activity::Activity,
+        attribute::{
+            Attribute, AttributeValue, DurationDisplay, LengthUnit, MassUnit, SpeedUnit, Value,
+        },
+        derived::DerivedExpr,
+        entry::{Entry, Position, Temporal},
+        grade::GradeScale,
+        grant::{GrantLevel, GrantScope},
+        user::User,
+    },
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Action {
     CreateUser(CreateUser),
     CreateActivity(CreateActivity),
@@ -25,6 +33,51 @@ pub enum Action {
     UpdateEntry(UpdateEntry),
     ConvertToSets(ConvertToSets),
     DuplicateEntry(DuplicateEntry),
+    Undo(Undo),
+    Redo(Redo),
+    GrantPermission(GrantPermission),
+    AcceptGrant(AcceptGrant),
+    RevokeGrant(RevokeGrant),
+    DeleteActivity(DeleteActivity),
+    ArchiveActivity(ArchiveActivity),
+    UpdateActivity(UpdateActivity),
+    AttributeMigration(AttributeMigration),
+    ConvertAttributeType(ConvertAttributeType),
+}
+
+impl Action {
+    /// The actor performing the action. `CreateUser` is performed by the user
+    /// being created.
+    pub fn actor_id(&self) -> Uuid {
+        match self {
+            Action::CreateUser(a) => a.user.actor_id,
+            Action::CreateActivity(a) => a.actor_id,
+            Action::CreateAttribute(a) => a.actor_id,
+            Action::CreateValue(a) => a.actor_id,
+            Action::AttachValue(a) => a.actor_id,
+            Action::DeleteAttributeValue(a) => a.actor_id,
+            Action::CreateEntry(a) => a.actor_id,
+            Action::CreateEntryFromActivity(a) => a.actor_id,
+            Action::DeleteEntryRecursive(a) => a.actor_id,
+            Action::MoveEntry(a) => a.actor_id,
+            Action::UpdateEntryCompletion(a) => a.actor_id,
+            Action::UpdateAttributeValue(a) => a.actor_id,
+            Action::UpdateAttribute(a) => a.actor_id,
+            Action::UpdateEntry(a) => a.actor_id,
+            Action::ConvertToSets(a) => a.actor_id,
+            Action::DuplicateEntry(a) => a.actor_id,
+            Action::Undo(a) => a.actor_id,
+            Action::Redo(a) => a.actor_id,
+            Action::GrantPermission(a) => a.actor_id,
+            Action::AcceptGrant(a) => a.actor_id,
+            Action::RevokeGrant(a) => a.actor_id,
+            Action::DeleteActivity(a) => a.actor_id,
+            Action::ArchiveActivity(a) => a.actor_id,
+            Action::UpdateActivity(a) => a.actor_id,
+            Action::AttributeMigration(a) => a.actor_id,
+            Action::ConvertAttributeType(a) => a.actor_id,
+        }
+    }
 }
 
 impl From<CreateUser> for Action {
@@ -81,14 +134,83 @@ impl From<DeleteAttributeValue> for Action {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateActivity {
     pub actor_id: Uuid,
     pub activity: Activity,
     pub template: Vec<Entry>,
 }
 
-#[derive(Debug, Clone)]
+/// Delete an activity along with its template tree and the template's values.
+/// Entries outside the template that are logged against the activity block
+/// the delete unless `referencing_entries` says what to do with them; the
+/// actor needs write access to every entry that changes or goes. Grants over
+/// the activity or any deleted entry are deleted with it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeleteActivity {
+    pub actor_id: Uuid,
+    pub activity_id: Uuid,
+    pub referencing_entries: Option<ReferencingEntries>,
+}
+
+impl From<DeleteActivity> for Action {
+    fn from(value: DeleteActivity) -> Self {
+        Action::DeleteActivity(value)
+    }
+}
+
+/// What `DeleteActivity` does with entries that reference the activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferencingEntries {
+    /// Keep them as anonymous entries, named after the activity unless they
+    /// already have a name.
+    Detach,
+    /// Delete each of them with its subtree and values.
+    DeleteRecursive,
+}
+
+/// Hide an activity from the library (or, with `archived: false`, restore
+/// it). Entries logged against it are untouched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveActivity {
+    pub actor_id: Uuid,
+    pub activity_id: Uuid,
+    pub archived: bool,
+}
+
+impl From<ArchiveActivity> for Action {
+    fn from(value: ArchiveActivity) -> Self {
+        Action::ArchiveActivity(value)
+    }
+}
+
+/// Edit an activity's name, description or source link, one field per
+/// action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateActivity {
+    pub actor_id: Uuid,
+    pub activity_id: Uuid,
+    pub change: ActivityChange,
+}
+
+impl From<UpdateActivity> for Action {
+    fn from(value: UpdateActivity) -> Self {
+        Action::UpdateActivity(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ActivityChange {
+    /// Rename. Validated (and trimmed) by `ActivityName::parse`.
+    SetName(String),
+    SetDescription(Option<String>),
+    /// Point the activity at the one it was copied from, or clear the link
+    /// with `None`. The source must be readable by the actor and must not
+    /// lead back to this activity.
+    SetSourceActivityId(Option<Uuid>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateUser {
     pub user: User,
 }
@@ -99,7 +221,7 @@ impl From<User> for CreateUser {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateEntry {
     pub actor_id: Uuid,
     pub entry: Entry,
@@ -125,7 +247,7 @@ impl From<Entry> for CreateEntry {
 /// NOTE: this API cannot express actor A creating an entry for actor B, eg a coach creating an
 /// entry for a client. That will need to be corrected, but deferring as the design for
 /// collaboration is nascent.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateEntryFromActivity {
     pub actor_id: Uuid,
     pub activity_id: Uuid,
@@ -140,7 +262,7 @@ impl From<CreateEntryFromActivity> for Action {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MoveEntry {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
@@ -155,7 +277,7 @@ pub struct MoveEntry {
 /// client-supplied (like `CreateEntry`'s client-built entry) so the caller can
 /// reference the new sequence before the mutation lands, e.g. to carry UI
 /// state across the swap.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConvertToSets {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
@@ -172,7 +294,7 @@ impl From<ConvertToSets> for Action {
 /// temporal, completion) with fresh entry ids, inserted immediately after the
 /// source among its siblings. A forest root duplicates as another root with
 /// the same temporal, landing adjacent in the day view.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DuplicateEntry {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
@@ -184,13 +306,13 @@ impl From<DuplicateEntry> for Action {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DeleteEntryRecursive {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateAttribute {
     pub actor_id: Uuid,
     pub attribute: Attribute,
@@ -205,7 +327,7 @@ impl From<Attribute> for CreateAttribute {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateValue {
     pub actor_id: Uuid,
     pub value: Value,
@@ -215,14 +337,14 @@ pub struct CreateValue {
 /// config default (both plan and actual). A no-op if a value for
 /// `(entry_id, attribute_id)` already exists. Unlike `CreateValue`, the caller
 /// passes only identifiers; the mutator resolves the default in core.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AttachValue {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
     pub attribute_id: Uuid,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UpdateEntryCompletion {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
@@ -235,13 +357,13 @@ impl From<UpdateEntryCompletion> for Action {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ValueField {
     Plan,
     Actual,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UpdateAttributeValue {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
@@ -258,7 +380,7 @@ impl From<UpdateAttributeValue> for Action {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DeleteAttributeValue {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
@@ -269,7 +391,7 @@ pub struct DeleteAttributeValue {
 /// user's intent as a single edit; the mutator validates it against the
 /// attribute's current type and (for type-specific edits) its config. Mirrors
 /// the Numeric/Select/Mass grouping used by `AttributeConfig`/`AttributePair`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UpdateAttribute {
     pub actor_id: Uuid,
     pub attribute_id: Uuid,
@@ -282,7 +404,7 @@ impl From<UpdateAttribute> for Action {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AttributeChange {
     // Common to all attribute types, freely editable.
     SetName(String),
@@ -291,42 +413,106 @@ pub enum AttributeChange {
     // attribute's config.
     Numeric(NumericChange),
     Select(SelectChange),
+    Grade(GradeChange),
+    Multiselect(MultiselectChange),
     Mass(MassChange),
     Length(LengthChange),
+    Speed(SpeedChange),
+    Duration(DurationChange),
     Text(TextChange),
+    Boolean(BooleanChange),
+    Derived(DerivedChange),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum NumericChange {
     /// Set (or clear, with `None`) the default value. Must respect the config's
     /// `integer`/`min`/`max` constraints.
     SetDefault(Option<f64>),
-    // Future additive edits: RaiseMax, LowerMin, SetInteger.
-}
-
-#[derive(Debug, Clone)]
+    /// Raise the upper bound, or remove it with `None`. Never lowers it, so
+    /// stored values stay within bounds.
+    RaiseMax(Option<f64>),
+    /// Lower the lower bound, or remove it with `None`. Never raises it.
+    LowerMin(Option<f64>),
+    /// Require whole numbers (`true`) or allow fractions (`false`). Requiring
+    /// them is rejected while any stored value, bound or default is
+    /// fractional.
+    SetInteger(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SelectChange {
     /// Set (or clear, with `None`) the default. A non-`None` default must be one
     /// of the config's existing options.
     SetDefault(Option<String>),
-    // Future additive edits: AddOption, RenameOption, SetOrdered.
+    /// Insert a new option at `position` in the option order (appended when
+    /// `None`). Existing options keep their relative order, so stored ranges
+    /// stay ordered.
+    AddOption {
+        option: String,
+        position: Option<u32>,
+    },
+    /// Rename an option. Stored values and the default selecting `from` are
+    /// rewritten to `to` in the same mutation.
+    RenameOption { from: String, to: String },
+    /// Make the options ordered (allowing range values) or unordered.
+    /// Unordering is rejected while any stored value is a range.
+    SetOrdered(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GradeChange {
+    /// Set (or clear, with `None`) the default. A non-`None` default must be a
+    /// grade on the config's scale. The scale itself is fixed: its grades are
+    /// the options every stored value picks from.
+    SetDefault(Option<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MultiselectChange {
+    /// Set (or clear, with `None`) the default selection. Every selected
+    /// option must be one of the config's options.
+    SetDefault(Option<Vec<String>>),
+    /// Insert a new option at `position` in the display order (appended when
+    /// `None`).
+    AddOption {
+        option: String,
+        position: Option<u32>,
+    },
+    /// Rename an option. Stored values and the default selecting `from` are
+    /// rewritten to `to` in the same mutation.
+    RenameOption { from: String, to: String },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MassChange {
     /// Replace the default unit. Not additive-constrained — stored values
     /// carry their own unit, so changing the default invalidates nothing.
     SetDefaultUnit(MassUnit),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LengthChange {
     /// Replace the default unit. Not additive-constrained — stored values
     /// carry their own unit, so changing the default invalidates nothing.
     SetDefaultUnit(LengthUnit),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpeedChange {
+    /// Replace the default unit. Not additive-constrained — stored values
+    /// carry their own unit, so changing the default invalidates nothing.
+    SetDefaultUnit(SpeedUnit),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DurationChange {
+    /// Replace the display. Presentation only — values are stored in seconds,
+    /// so changing it invalidates nothing.
+    SetDisplay(DurationDisplay),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TextChange {
     /// Set (or clear, with `None`) the default value. A non-`None` default must
     /// be a valid value (within the length cap).
@@ -336,11 +522,112 @@ pub enum TextChange {
     SetAutocomplete(bool),
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BooleanChange {
+    /// Set (or clear, with `None`) the default value.
+    SetDefault(Option<bool>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DerivedChange {
+    /// Replace the expression. Rejected when it would make the attribute
+    /// depend on itself through the derived attributes it references. Nothing
+    /// is stored for a derived attribute, so no values are rewritten.
+    SetExpression(DerivedExpr),
+}
+
+/// A non-additive attribute change: retire options or tighten bounds, with an
+/// explicit rule for every stored value the change would invalidate.
+/// `UpdateAttribute` only makes edits that keep stored values valid; this
+/// rewrites the affected values in the same mutation, so values still
+/// conform to their attribute afterwards (see `Mutation::values_changed`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeMigration {
+    pub actor_id: Uuid,
+    pub attribute_id: Uuid,
+    pub migration: ConfigMigration,
+}
+
+impl From<AttributeMigration> for Action {
+    fn from(value: AttributeMigration) -> Self {
+        Action::AttributeMigration(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConfigMigration {
+    /// Remove options from a select or multiselect attribute. Selections of a
+    /// removed option (in values and the default) move to its replacement,
+    /// which must be a remaining option, or are cleared.
+    RemoveOptions(Vec<OptionRemoval>),
+    /// Replace a numeric attribute's bounds. Values and the default that fall
+    /// outside the new bounds are clamped into them or cleared.
+    SetBounds {
+        min: Option<f64>,
+        max: Option<f64>,
+        out_of_range: OutOfRange,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionRemoval {
+    pub option: String,
+    /// `None` clears selections of the removed option.
+    pub replacement: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutOfRange {
+    Clamp,
+    Clear,
+}
+
+/// Move an attribute to another config type along a supported path,
+/// converting every stored value in the same mutation. Rejected when any value
+/// or the default has no lossless equivalent in the target type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConvertAttributeType {
+    pub actor_id: Uuid,
+    pub attribute_id: Uuid,
+    pub conversion: AttributeConversion,
+}
+
+impl From<ConvertAttributeType> for Action {
+    fn from(value: ConvertAttributeType) -> Self {
+        Action::ConvertAttributeType(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AttributeConversion {
+    /// Numeric -> Mass. Magnitudes are kept as-is, measured in `unit`, which
+    /// also becomes the default unit. Numeric bounds and the integer flag are
+    /// dropped; a numeric default has no mass form, so it must be cleared
+    /// first.
+    ToMass(MassUnit),
+    /// Numeric -> Length, as `ToMass`.
+    ToLength(LengthUnit),
+    /// Select -> Multiselect. Options carry over; each selection becomes a
+    /// one-option set. Select ranges have no set form.
+    ToMultiselect,
+    /// Select -> Text. Each selection becomes its option text, with
+    /// autocomplete on so prior selections are still offered. Select ranges
+    /// have no text form.
+    ToText,
+    /// Text -> Select. The options are the distinct stored texts (plus the
+    /// default), unordered, so every stored text stays selectable.
+    ToSelect,
+    /// Select -> Grade on `scale`, whose grades replace the options. Every
+    /// selection and the default must read as grades on the scale (see
+    /// `GradeScale::read`).
+    ToGrade(GradeScale),
+}
+
 /// Update an entry's structural/metadata fields. Deliberately excludes
 /// `position` and `temporal` — those are owned by `MoveEntry`, which enforces
 /// their cycle/parent/temporal constraints atomically. Completion has its own
 /// action (`UpdateEntryCompletion`) for now.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UpdateEntry {
     pub actor_id: Uuid,
     pub entry_id: Uuid,
@@ -353,7 +640,7 @@ impl From<UpdateEntry> for Action {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EntryChange {
     /// Toggle sequence/scalar. Becoming a scalar deletes all descendants (a
     /// scalar cannot contain children). Rejected while `display_as_sets` is
@@ -368,3 +655,80 @@ pub enum EntryChange {
     SetDisplayAsSets(bool),
     // Future: SetName(Option<String>), completion.
 }
+
+/// Revert one of the actor's previously applied mutations. The mutator reads
+/// that mutation's deltas from the replica's own log, inverts them and
+/// re-checks each one against the current state, rejecting the undo if a
+/// touched row has changed since (or if reverting would break a structural
+/// invariant).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Undo {
+    pub actor_id: Uuid,
+    /// Id of the mutation being reverted.
+    pub mutation_id: Uuid,
+}
+
+impl From<Undo> for Action {
+    fn from(value: Undo) -> Self {
+        Action::Undo(value)
+    }
+}
+
+/// Re-apply a mutation that was reverted by `Undo`. Its deltas come from the
+/// log and are re-checked against the current state exactly as an undo's are.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Redo {
+    pub actor_id: Uuid,
+    /// Id of the mutation being re-applied.
+    pub mutation_id: Uuid,
+}
+
+impl From<Redo> for Action {
+    fn from(value: Redo) -> Self {
+        Action::Redo(value)
+    }
+}
+
+/// Offer `grantee_id` access to one of the actor's resources. The grant is
+/// created pending and has no effect until the grantee accepts it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrantPermission {
+    pub actor_id: Uuid,
+    pub grant_id: Uuid,
+    pub grantee_id: Uuid,
+    pub scope: GrantScope,
+    pub level: GrantLevel,
+}
+
+impl From<GrantPermission> for Action {
+    fn from(value: GrantPermission) -> Self {
+        Action::GrantPermission(value)
+    }
+}
+
+/// Accept a pending grant offered to the actor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AcceptGrant {
+    pub actor_id: Uuid,
+    pub grant_id: Uuid,
+}
+
+impl From<AcceptGrant> for Action {
+    fn from(value: AcceptGrant) -> Self {
+        Action::AcceptGrant(value)
+    }
+}
+
+/// Delete a grant. The grantor revokes it; the grantee may also revoke one
+/// to decline it or give up access.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevokeGrant {
+    pub actor_id: Uuid,
+    pub grant_id: Uuid,
+}
+
+impl From<RevokeGrant> for Action {
+    fn from(value: RevokeGrant) -> Self {
+        Action::RevokeGrant(value)
+    }
+}
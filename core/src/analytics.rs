@@ -0,0 +1,793 @@
+//! Analytics over the log: pick out entries, bucket them by period or by an
+//! attribute's value, and aggregate each bucket.
+//!
+//! `evaluate` is a pure function of the rows involved, so every backend answers
+//! a query the same way: the SQL executors load the rows `AnalyticsQuery::pushdown`
+//! admits, and the in-memory model passes its own.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    actions::ValueField,
+    error::{Result, ValidationError},
+    models::{
+        attribute::{Attribute, AttributeConfig, AttributeValue, NumericValue, Value},
+        derived::DerivedScope,
+        entry::Entry,
+    },
+};
+
+/// A question about the log, e.g. "how many attempts on V8+ each week": the
+/// entries matching every filter, grouped, with one aggregate per group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsQuery {
+    pub filters: Vec<EntryFilter>,
+    /// `None` aggregates every matching entry into a single row.
+    pub group_by: Option<Grouping>,
+    pub aggregate: Aggregate,
+}
+
+// Queries key the client's subscription cache, but the AST carries floats
+// (inside attribute values), which have no `Hash`. The JSON encoding stands in.
+impl Eq for AnalyticsQuery {}
+
+impl Hash for AnalyticsQuery {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        serde_json::to_string(self)
+            .expect("analytics queries serialize")
+            .hash(state);
+    }
+}
+
+/// A condition an entry must meet to be counted. Template entries are never
+/// counted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EntryFilter {
+    /// The entry is an instance of the activity.
+    Activity {
+        activity_id: Uuid,
+    },
+    /// The entry is nested, at any depth, under an entry of the activity, e.g.
+    /// the problems of a bouldering session.
+    WithinActivity {
+        activity_id: Uuid,
+    },
+    /// The entry's time falls in `from..to`. An entry without a time of its own
+    /// takes its nearest timed ancestor's.
+    TimeWindow {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    Attribute(AttributePredicate),
+    Complete(bool),
+}
+
+/// Compares an entry's value for an attribute against `operand`, e.g.
+/// `V Grade >= V5` or `Outcome = Sent`. Both sides are read through the
+/// attribute's index columns: grades by difficulty, ordered selects by
+/// position, measures by SI magnitude, ranges by their lower bound. A derived
+/// attribute compares against a numeric operand. An entry without a value
+/// never matches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributePredicate {
+    pub target: AttributeField,
+    pub comparison: Comparison,
+    pub operand: AttributeValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// One side (plan or actual) of an attribute's values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeField {
+    pub attribute_id: Uuid,
+    pub field: ValueField,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Grouping {
+    /// Calendar periods in the local time `utc_offset_minutes` east of UTC.
+    /// Weeks start on Monday. Entries without a time are left out.
+    Period {
+        period: Period,
+        utc_offset_minutes: i32,
+    },
+    /// One group per distinct value; entries without a value are left out.
+    /// Values with the same index columns share a group.
+    Attribute(AttributeField),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Aggregate {
+    /// The number of matching entries.
+    Count,
+    /// The remaining aggregates read each entry's value as its float index
+    /// column and skip entries without one. A sum of nothing is zero; the
+    /// others have no value.
+    Sum(AttributeField),
+    Max(AttributeField),
+    Min(AttributeField),
+    Mean(AttributeField),
+}
+
+/// The filters of a query a SQL backend can apply while reading rows, so it
+/// reads only the entries that may match rather than the whole log. Every
+/// condition holds of each matching entry; `evaluate` still checks the full
+/// query over whatever the backend reads. The records, training load and
+/// series queries build one too, and the backends load their rows the same
+/// way.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pushdown {
+    /// The entry is the owner's.
+    pub owner_id: Option<Uuid>,
+    /// The entry has no parent.
+    pub top_level: bool,
+    /// The entry is an instance of each.
+    pub activity_ids: Vec<Uuid>,
+    pub complete: Vec<bool>,
+    /// The entry or one of its ancestors is timed in each `from..to`, which
+    /// holds both where an entry takes its nearest timed ancestor's time
+    /// (analytics) and where it takes its root's (series).
+    pub windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    pub predicates: Vec<IndexPredicate>,
+    /// Evaluation reads the matching entries' descendants too: a derived
+    /// attribute may sum over them.
+    pub descendants: bool,
+}
+
+/// An attribute predicate on an actual the entry stores, compared through
+/// the stored index columns: floats when both sides have one, strings
+/// otherwise, and no match when neither pair is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexPredicate {
+    pub attribute_id: Uuid,
+    pub comparison: Comparison,
+    pub float: Option<f64>,
+    pub string: Option<String>,
+}
+
+/// One group of a query's result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsRow {
+    pub group: GroupKey,
+    /// How many matching entries fell in the group.
+    pub entries: u32,
+    pub value: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GroupKey {
+    /// The only group of an ungrouped query.
+    All,
+    /// The start of the period, as a UTC instant.
+    Period { start: DateTime<Utc> },
+    /// The group's value, as first seen in time order. A derived attribute's
+    /// value is numeric.
+    Value(AttributeValue),
+}
+
+impl AnalyticsQuery {
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| ValidationError::InvalidAnalyticsQuery(msg.to_string()).into();
+        for filter in &self.filters {
+            if let EntryFilter::TimeWindow { from, to } = filter
+                && from > to
+            {
+                return Err(invalid("time window ends before it starts"));
+            }
+        }
+        if let Some(Grouping::Period {
+            utc_offset_minutes, ..
+        }) = &self.group_by
+            && offset(*utc_offset_minutes).is_none()
+        {
+            return Err(invalid("UTC offset out of range"));
+        }
+        Ok(())
+    }
+
+    /// What a SQL backend can apply of this query's filters, given the
+    /// `attributes` it reads (`query_executor::find_attributes`). Predicates
+    /// on plans and on derived attributes have no stored index to compare, so
+    /// they, and `WithinActivity`, are left to `evaluate`.
+    pub fn pushdown(&self, attributes: &[Attribute]) -> Pushdown {
+        let mut pushdown = Pushdown {
+            owner_id: None,
+            top_level: false,
+            activity_ids: Vec::new(),
+            complete: Vec::new(),
+            windows: Vec::new(),
+            predicates: Vec::new(),
+            descendants: attributes
+                .iter()
+                .any(|a| matches!(a.config, AttributeConfig::Derived(_))),
+        };
+        for filter in &self.filters {
+            match filter {
+                EntryFilter::Activity { activity_id } => pushdown.activity_ids.push(*activity_id),
+                EntryFilter::WithinActivity { .. } => {}
+                EntryFilter::TimeWindow { from, to } => pushdown.windows.push((*from, *to)),
+                EntryFilter::Attribute(p) => {
+                    let Some(attribute) = attributes.iter().find(|a| a.id == p.target.attribute_id)
+                    else {
+                        continue;
+                    };
+                    if p.target.field != ValueField::Actual
+                        || matches!(attribute.config, AttributeConfig::Derived(_))
+                    {
+                        continue;
+                    }
+                    let (float, string) = index(&attribute.config, &p.operand);
+                    pushdown.predicates.push(IndexPredicate {
+                        attribute_id: attribute.id,
+                        comparison: p.comparison.clone(),
+                        float,
+                        string,
+                    });
+                }
+                EntryFilter::Complete(complete) => pushdown.complete.push(*complete),
+            }
+        }
+        pushdown
+    }
+
+    /// Every attribute the query reads directly. Derived attributes read more.
+    pub fn attribute_ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        let filters = self.filters.iter().filter_map(|f| match f {
+            EntryFilter::Attribute(p) => Some(p.target.attribute_id),
+            _ => None,
+        });
+        let group = match &self.group_by {
+            Some(Grouping::Attribute(f)) => Some(f.attribute_id),
+            _ => None,
+        };
+        filters
+            .chain(group)
+            .chain(self.aggregate.target().map(|t| t.attribute_id))
+    }
+}
+
+impl Comparison {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Eq => ordering.is_eq(),
+            Comparison::Ne => ordering.is_ne(),
+            Comparison::Lt => ordering.is_lt(),
+            Comparison::Le => ordering.is_le(),
+            Comparison::Gt => ordering.is_gt(),
+            Comparison::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+impl Period {
+    /// The start of the period containing `instant`, in local time `offset`.
+    pub(crate) fn start(&self, instant: DateTime<Utc>, offset: FixedOffset) -> DateTime<Utc> {
+        let date = instant.with_timezone(&offset).date_naive();
+        let date = match self {
+            Period::Day => date,
+            Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Period::Month => date.with_day(1).expect("every month has a first day"),
+        };
+        date.and_time(chrono::NaiveTime::MIN)
+            .and_local_timezone(offset)
+            .single()
+            .expect("a fixed offset has no gaps")
+            .with_timezone(&Utc)
+    }
+}
+
+impl Aggregate {
+    fn target(&self) -> Option<&AttributeField> {
+        match self {
+            Aggregate::Count => None,
+            Aggregate::Sum(t) | Aggregate::Max(t) | Aggregate::Min(t) | Aggregate::Mean(t) => {
+                Some(t)
+            }
+        }
+    }
+}
+
+/// The time zone `utc_offset_minutes` east of UTC, or `None` when that is
+/// out of range. Shared by the queries that bucket by local calendar days.
+pub(crate) fn offset(utc_offset_minutes: i32) -> Option<FixedOffset> {
+    FixedOffset::east_opt(utc_offset_minutes.checked_mul(60)?)
+}
+
+/// Answer `query` over the given rows. `attributes` and `values` need only
+/// cover the attributes the query reads, transitively through derived ones;
+/// `entries` must include the ancestors of any entry that may match.
+pub fn evaluate(
+    query: &AnalyticsQuery,
+    attributes: Vec<Attribute>,
+    entries: Vec<Entry>,
+    values: Vec<Value>,
+) -> Vec<AnalyticsRow> {
+    let log = Log::new(attributes, entries, values);
+    let mut matching: Vec<(Option<DateTime<Utc>>, &Entry)> = log
+        .entries
+        .values()
+        .filter(|e| !e.is_template && query.filters.iter().all(|f| log.matches(f, e)))
+        .map(|e| (log.instant(e), e))
+        .collect();
+    // Time order (untimed last), so a group's label is its earliest value.
+    matching.sort_by_key(|(t, e)| (t.is_none(), *t, e.id));
+
+    let groups: Vec<(GroupKey, Vec<&Entry>)> = match &query.group_by {
+        None => vec![(
+            GroupKey::All,
+            matching.into_iter().map(|(_, e)| e).collect(),
+        )],
+        Some(Grouping::Period {
+            period,
+            utc_offset_minutes,
+        }) => {
+            let offset = offset(*utc_offset_minutes).expect("validated UTC offset");
+            let mut groups: Vec<(DateTime<Utc>, Vec<&Entry>)> = Vec::new();
+            for (instant, entry) in matching {
+                let Some(instant) = instant else { continue };
+                let start = period.start(instant, offset);
+                match groups.last_mut() {
+                    Some((s, members)) if *s == start => members.push(entry),
+                    _ => groups.push((start, vec![entry])),
+                }
+            }
+            groups
+                .into_iter()
+                .map(|(start, members)| (GroupKey::Period { start }, members))
+                .collect()
+        }
+        Some(Grouping::Attribute(target)) => {
+            let mut groups: Vec<(Index, AttributeValue, Vec<&Entry>)> = Vec::new();
+            for (_, entry) in matching {
+                let Some((value, index)) = log.read(target, entry.id) else {
+                    continue;
+                };
+                match groups.iter_mut().find(|(i, _, _)| *i == index) {
+                    Some((_, _, members)) => members.push(entry),
+                    None => groups.push((index, value, vec![entry])),
+                }
+            }
+            groups.sort_by(|(a, _, _), (b, _, _)| {
+                let float = match (a.0, b.0) {
+                    (Some(x), Some(y)) => x.total_cmp(&y),
+                    (x, y) => x.is_none().cmp(&y.is_none()),
+                };
+                float.then_with(|| a.1.cmp(&b.1))
+            });
+            groups
+                .into_iter()
+                .map(|(_, value, members)| (GroupKey::Value(value), members))
+                .collect()
+        }
+    };
+
+    groups
+        .into_iter()
+        .map(|(group, members)| AnalyticsRow {
+            group,
+            entries: members.len() as u32,
+            value: log.aggregate(&query.aggregate, &members),
+        })
+        .collect()
+}
+
+/// The rows an evaluation reads, indexed.
+pub(crate) struct Log {
+    attributes: HashMap<Uuid, Attribute>,
+    entries: HashMap<Uuid, Entry>,
+    values: HashMap<(Uuid, Uuid), Value>,
+    derived: DerivedScope,
+}
+
+impl Log {
+    pub(crate) fn new(attributes: Vec<Attribute>, entries: Vec<Entry>, values: Vec<Value>) -> Self {
+        Log {
+            derived: DerivedScope::new(attributes.clone(), entries.clone(), values.clone()),
+            attributes: attributes.into_iter().map(|a| (a.id, a)).collect(),
+            entries: entries.into_iter().map(|e| (e.id, e)).collect(),
+            values: values
+                .into_iter()
+                .map(|v| ((v.entry_id, v.attribute_id), v))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn ancestors<'a>(&'a self, entry: &'a Entry) -> impl Iterator<Item = &'a Entry> {
+        std::iter::successors(entry.parent_id(), |id| {
+            self.entries.get(id).and_then(|e| e.parent_id())
+        })
+        .filter_map(|id| self.entries.get(&id))
+    }
+
+    /// The entry's time, or its nearest timed ancestor's.
+    pub(crate) fn instant(&self, entry: &Entry) -> Option<DateTime<Utc>> {
+        std::iter::once(entry)
+            .chain(self.ancestors(entry))
+            .find_map(|e| e.temporal.canonical_instant())
+    }
+
+    fn matches(&self, filter: &EntryFilter, entry: &Entry) -> bool {
+        match filter {
+            EntryFilter::Activity { activity_id } => entry.activity_id == Some(*activity_id),
+            EntryFilter::WithinActivity { activity_id } => self
+                .ancestors(entry)
+                .any(|a| a.activity_id == Some(*activity_id)),
+            EntryFilter::TimeWindow { from, to } => self
+                .instant(entry)
+                .is_some_and(|t| (*from..*to).contains(&t)),
+            EntryFilter::Attribute(p) => {
+                let Some(attribute) = self.attributes.get(&p.target.attribute_id) else {
+                    return false;
+                };
+                let Some((_, lhs)) = self.read(&p.target, entry.id) else {
+                    return false;
+                };
+                let rhs = index(&attribute.config, &p.operand);
+                let ordering = match (lhs, rhs) {
+                    ((Some(a), _), (Some(b), _)) => a.partial_cmp(&b),
+                    ((_, Some(a)), (_, Some(b))) => Some(a.cmp(&b)),
+                    _ => None,
+                };
+                ordering.is_some_and(|o| p.comparison.holds(o))
+            }
+            EntryFilter::Complete(complete) => entry.is_complete == *complete,
+        }
+    }
+
+    /// The entry's value for `target` with its index columns. A derived
+    /// attribute's is computed.
+    pub(crate) fn read(
+        &self,
+        target: &AttributeField,
+        entry_id: Uuid,
+    ) -> Option<(AttributeValue, Index)> {
+        let attribute = self.attributes.get(&target.attribute_id)?;
+        let value = if let AttributeConfig::Derived(_) = attribute.config {
+            let (plan, actual) = self.derived.evaluate(attribute.id, entry_id);
+            let computed = match target.field {
+                ValueField::Plan => plan,
+                ValueField::Actual => actual,
+            };
+            AttributeValue::Numeric(NumericValue::Exact(computed?))
+        } else {
+            let stored = self.values.get(&(entry_id, attribute.id))?;
+            match target.field {
+                ValueField::Plan => stored.plan.clone(),
+                ValueField::Actual => stored.actual.clone(),
+            }?
+        };
+        let index = index(&attribute.config, &value);
+        Some((value, index))
+    }
+
+    fn aggregate(&self, aggregate: &Aggregate, members: &[&Entry]) -> Option<f64> {
+        let Some(target) = aggregate.target() else {
+            return Some(members.len() as f64);
+        };
+        let readings: Vec<f64> = members
+            .iter()
+            .filter_map(|e| self.read(target, e.id)?.1.0)
+            .collect();
+        match aggregate {
+            Aggregate::Count => unreachable!("count has no target"),
+            Aggregate::Sum(_) => Some(readings.iter().sum()),
+            Aggregate::Max(_) => readings.into_iter().reduce(f64::max),
+            Aggregate::Min(_) => readings.into_iter().reduce(f64::min),
+            Aggregate::Mean(_) => {
+                (!readings.is_empty()).then(|| readings.iter().sum::<f64>() / readings.len() as f64)
+            }
+        }
+    }
+}
+
+/// A value's `(index_float, index_string)`.
+type Index = (Option<f64>, Option<String>);
+
+/// The index columns of `value` as a value of an attribute with `config`. A
+/// derived attribute stores nothing to index, so its numbers index as
+/// themselves.
+fn index(config: &AttributeConfig, value: &AttributeValue) -> Index {
+    match (config, value) {
+        (AttributeConfig::Derived(_), AttributeValue::Numeric(n)) => match n {
+            NumericValue::Exact(v) => (Some(*v), None),
+            NumericValue::Range { min, .. } => (Some(*min), None),
+        },
+        _ => config.index_columns(Some(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{
+            attribute::{GradeConfig, NumericConfig, SelectConfig, SelectValue},
+            entry::Temporal,
+            grade::GradeScale,
+        },
+        test_log::{TestLog, at, attribute, children, entry},
+    };
+
+    struct Fixture {
+        session_activity: Uuid,
+        grade: Attribute,
+        outcome: Attribute,
+        attempts: Attribute,
+        log: TestLog,
+    }
+
+    /// Two bouldering sessions a week apart, the first of them on a Sunday
+    /// evening. Each problem records its grade, outcome and attempts.
+    fn fixture() -> Fixture {
+        let session_activity = Uuid::new_v4();
+        let grade = attribute(
+            "V Grade",
+            GradeConfig {
+                scale: GradeScale::VScale,
+                default: None,
+            }
+            .into(),
+        );
+        let outcome = attribute(
+            "Outcome",
+            SelectConfig {
+                options: vec!["Sent".to_string(), "Attempt".to_string()],
+                ordered: false,
+                default: None,
+            }
+            .into(),
+        );
+        let attempts = attribute(
+            "Attempts",
+            NumericConfig {
+                min: None,
+                max: None,
+                integer: true,
+                default: None,
+            }
+            .into(),
+        );
+        let mut fixture = Fixture {
+            session_activity,
+            grade,
+            outcome,
+            attempts,
+            log: TestLog::default(),
+        };
+        for (start, problems) in [
+            (
+                "2026-03-01T20:00:00Z",
+                vec![("V8", "Sent", 3.0), ("V5", "Sent", 1.0)],
+            ),
+            (
+                "2026-03-08T20:00:00Z",
+                vec![("V9", "Attempt", 6.0), ("V8", "Sent", 2.0)],
+            ),
+        ] {
+            let session = Entry {
+                is_sequence: true,
+                ..entry(
+                    Some(session_activity),
+                    None,
+                    Temporal::Start { start: at(start) },
+                )
+            };
+            for ((grade, outcome, attempts), position) in
+                problems.into_iter().zip(children(session.id))
+            {
+                let problem = entry(None, Some(position), Temporal::None);
+                for (attribute, actual) in [
+                    (
+                        &fixture.grade,
+                        AttributeValue::Grade(SelectValue::Exact(grade.to_string())),
+                    ),
+                    (
+                        &fixture.outcome,
+                        AttributeValue::Select(SelectValue::Exact(outcome.to_string())),
+                    ),
+                    (
+                        &fixture.attempts,
+                        AttributeValue::Numeric(NumericValue::Exact(attempts)),
+                    ),
+                ] {
+                    fixture.log.log(&problem, attribute, actual);
+                }
+                fixture.log.entries.push(problem);
+            }
+            fixture.log.entries.push(session);
+        }
+        fixture
+    }
+
+    fn actual(attribute: &Attribute) -> AttributeField {
+        AttributeField {
+            attribute_id: attribute.id,
+            field: ValueField::Actual,
+        }
+    }
+
+    fn run(f: &Fixture, query: &AnalyticsQuery) -> Vec<(GroupKey, u32, Option<f64>)> {
+        let attributes = vec![f.grade.clone(), f.outcome.clone(), f.attempts.clone()];
+        evaluate(
+            query,
+            attributes,
+            f.log.entries.clone(),
+            f.log.values.clone(),
+        )
+        .into_iter()
+        .map(|r| (r.group, r.entries, r.value))
+        .collect()
+    }
+
+    #[test]
+    fn attempts_on_v8_and_up_each_week() {
+        let f = fixture();
+        let query = |utc_offset_minutes| AnalyticsQuery {
+            filters: vec![
+                EntryFilter::WithinActivity {
+                    activity_id: f.session_activity,
+                },
+                EntryFilter::Attribute(AttributePredicate {
+                    target: actual(&f.grade),
+                    comparison: Comparison::Ge,
+                    operand: AttributeValue::Grade(SelectValue::Exact("V8".to_string())),
+                }),
+            ],
+            group_by: Some(Grouping::Period {
+                period: Period::Week,
+                utc_offset_minutes,
+            }),
+            aggregate: Aggregate::Sum(actual(&f.attempts)),
+        };
+        let week = |s| GroupKey::Period { start: at(s) };
+        // Both Sundays close their weeks in UTC...
+        assert_eq!(
+            run(&f, &query(0)),
+            vec![
+                (week("2026-02-23T00:00:00Z"), 1, Some(3.0)),
+                (week("2026-03-02T00:00:00Z"), 2, Some(8.0)),
+            ]
+        );
+        // ...but are already Monday five hours east.
+        assert_eq!(
+            run(&f, &query(300)),
+            vec![
+                (week("2026-03-01T19:00:00Z"), 1, Some(3.0)),
+                (week("2026-03-08T19:00:00Z"), 2, Some(8.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_by_value_and_aggregates() {
+        let f = fixture();
+        let sent = EntryFilter::Attribute(AttributePredicate {
+            target: actual(&f.outcome),
+            comparison: Comparison::Eq,
+            operand: AttributeValue::Select(SelectValue::Exact("Sent".to_string())),
+        });
+        let by_grade = AnalyticsQuery {
+            filters: vec![sent.clone()],
+            group_by: Some(Grouping::Attribute(actual(&f.grade))),
+            aggregate: Aggregate::Mean(actual(&f.attempts)),
+        };
+        let grade = |g: &str| GroupKey::Value(AttributeValue::Grade(SelectValue::Exact(g.into())));
+        // Grades group in difficulty order.
+        assert_eq!(
+            run(&f, &by_grade),
+            vec![(grade("V5"), 1, Some(1.0)), (grade("V8"), 2, Some(2.5))]
+        );
+
+        let hardest_send = AnalyticsQuery {
+            filters: vec![sent],
+            group_by: None,
+            aggregate: Aggregate::Max(actual(&f.grade)),
+        };
+        assert_eq!(run(&f, &hardest_send), vec![(GroupKey::All, 3, Some(8.0))]);
+
+        // Sessions have no grade: an ungrouped query still answers, with no max.
+        let sessions = AnalyticsQuery {
+            filters: vec![EntryFilter::Activity {
+                activity_id: f.session_activity,
+            }],
+            group_by: None,
+            aggregate: Aggregate::Max(actual(&f.grade)),
+        };
+        assert_eq!(run(&f, &sessions), vec![(GroupKey::All, 2, None)]);
+    }
+
+    #[test]
+    fn filters_by_time_window_and_completion() {
+        let f = fixture();
+        let count = |filters| AnalyticsQuery {
+            filters,
+            group_by: None,
+            aggregate: Aggregate::Count,
+        };
+        let window = EntryFilter::TimeWindow {
+            from: at("2026-03-05T00:00:00Z"),
+            to: at("2026-03-12T00:00:00Z"),
+        };
+        // The second session and its two problems, which inherit its time.
+        assert_eq!(
+            run(&f, &count(vec![window.clone()])),
+            vec![(GroupKey::All, 3, Some(3.0))]
+        );
+        assert_eq!(
+            run(&f, &count(vec![window, EntryFilter::Complete(false)])),
+            vec![(GroupKey::All, 0, Some(0.0))]
+        );
+
+        let backwards = count(vec![EntryFilter::TimeWindow {
+            from: at("2026-03-12T00:00:00Z"),
+            to: at("2026-03-05T00:00:00Z"),
+        }]);
+        assert!(backwards.validate().is_err());
+    }
+
+    #[test]
+    fn pushdown_keeps_filters_on_stored_columns() {
+        let f = fixture();
+        let predicate = |attribute: &Attribute, field, operand| {
+            EntryFilter::Attribute(AttributePredicate {
+                target: AttributeField {
+                    attribute_id: attribute.id,
+                    field,
+                },
+                comparison: Comparison::Ge,
+                operand,
+            })
+        };
+        let v8 = AttributeValue::Grade(SelectValue::Exact("V8".to_string()));
+        let query = AnalyticsQuery {
+            filters: vec![
+                EntryFilter::WithinActivity {
+                    activity_id: f.session_activity,
+                },
+                predicate(&f.grade, ValueField::Actual, v8.clone()),
+                predicate(&f.grade, ValueField::Plan, v8),
+                EntryFilter::Complete(true),
+            ],
+            group_by: None,
+            aggregate: Aggregate::Count,
+        };
+        let pushdown = query.pushdown(&[f.grade.clone(), f.outcome, f.attempts]);
+        assert!(pushdown.activity_ids.is_empty());
+        assert_eq!(pushdown.complete, vec![true]);
+        // Only the actual has stored index columns; V8 is difficulty 8.
+        assert_eq!(
+            pushdown.predicates,
+            vec![IndexPredicate {
+                attribute_id: f.grade.id,
+                comparison: Comparison::Ge,
+                float: Some(8.0),
+                string: None,
+            }]
+        );
+        assert!(!pushdown.descendants);
+    }
+}
@@ -1,12 +1,15 @@
+use serde::{Deserialize, Serialize};
+
 use crate::models::{
     activity::Activity,
     actor::Actor,
     attribute::{Attribute, Value},
     entry::Entry,
+    grant::Grant,
     user::User,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Delta<M> {
     Insert { new: M },
     Update { old: M, new: M },
@@ -17,11 +20,37 @@ impl<T> Delta<T> {
     pub fn insert(new: T) -> Self {
         Delta::Insert { new }
     }
+
+    /// The row state this delta expects to find: `None` for an insert.
+    pub fn before(&self) -> Option<&T> {
+        match self {
+            Delta::Insert { .. } => None,
+            Delta::Update { old, .. } | Delta::Delete { old } => Some(old),
+        }
+    }
+
+    /// The row state this delta leaves behind: `None` for a delete.
+    pub fn after(&self) -> Option<&T> {
+        match self {
+            Delta::Insert { new } | Delta::Update { new, .. } => Some(new),
+            Delta::Delete { .. } => None,
+        }
+    }
+
+    /// The delta that undoes this one: inserts become deletes, deletes become
+    /// inserts, and updates swap `old` and `new`.
+    pub fn inverse(self) -> Self {
+        match self {
+            Delta::Insert { new } => Delta::Delete { old: new },
+            Delta::Update { old, new } => Delta::Update { old: new, new: old },
+            Delta::Delete { old } => Delta::Insert { new: old },
+        }
+    }
 }
 
 // If we need to the PK for sync logic, this would be a good place to implement. Need an Id type of
 // some sort to represent both scalar and composite keys (values have a composite PK).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AnyDelta {
     User(Delta<User>),
     Actor(Delta<Actor>),
@@ -29,6 +58,27 @@ pub enum AnyDelta {
     Entry(Delta<Entry>),
     Attribute(Delta<Attribute>),
     Value(Delta<Value>),
+    Grant(Delta<Grant>),
+}
+
+impl AnyDelta {
+    pub fn inverse(self) -> Self {
+        match self {
+            AnyDelta::User(d) => AnyDelta::User(d.inverse()),
+            AnyDelta::Actor(d) => AnyDelta::Actor(d.inverse()),
+            AnyDelta::Activity(d) => AnyDelta::Activity(d.inverse()),
+            AnyDelta::Entry(d) => AnyDelta::Entry(d.inverse()),
+            AnyDelta::Attribute(d) => AnyDelta::Attribute(d.inverse()),
+            AnyDelta::Value(d) => AnyDelta::Value(d.inverse()),
+            AnyDelta::Grant(d) => AnyDelta::Grant(d.inverse()),
+        }
+    }
+}
+
+/// Invert a list of deltas: each delta is inverted and the order reversed, so
+/// applying `changes` followed by `inverse_changes(changes)` is a no-op.
+pub fn inverse_changes(changes: Vec<AnyDelta>) -> Vec<AnyDelta> {
+    changes.into_iter().rev().map(AnyDelta::inverse).collect()
 }
 
 /// Convert Delta<T> --> AnyDelta::T.
@@ -62,3 +112,8 @@ impl From<Delta<Value>> for AnyDelta {
         AnyDelta::Value(d)
     }
 }
+impl From<Delta<Grant>> for AnyDelta {
+    fn from(d: Delta<Grant>) -> Self {
+        AnyDelta::Grant(d)
+    }
+}
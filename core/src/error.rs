@@ -82,6 +82,12 @@ pub enum ValidationError {
     InvalidSelectConfig(String),
     #[error("Invalid multiselect config: {0}")]
     InvalidMultiselectConfig(String),
+    #[error("Invalid derived config: {0}")]
+    InvalidDerivedConfig(String),
+    #[error("Invalid analytics query: {0}")]
+    InvalidAnalyticsQuery(String),
+    #[error("Invalid query text: {0}")]
+    InvalidQueryText(crate::query_language::QueryParseError),
     #[error("Invalid value: {0}")]
     InvalidValue(String),
     #[error("Other: {0}")]
@@ -1,6 +1,9 @@
 pub mod actions;
+pub mod analytics;
 pub mod constants;
 pub mod queries;
+pub mod query_language;
+pub mod records;
 pub use constants::{DEFAULT_USER_ID, SYSTEM_ACTOR_ID};
 pub mod delta;
 pub mod delta_executor;
@@ -9,7 +12,15 @@ pub mod forest;
 pub mod instantiation;
 pub mod io;
 pub mod models;
+pub mod mutation_log;
 pub mod mutators;
+pub mod permissions;
 pub mod query_executor;
+pub mod series;
 pub mod std_lib;
+pub mod sync;
+#[cfg(test)]
+mod test_log;
+pub mod training_load;
 pub mod validation;
+pub mod wire;
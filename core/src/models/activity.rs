@@ -1,21 +1,28 @@
 use crate::{
     actions::CreateActivity,
+    delta::Delta,
     error::{Result, ValidationError},
     models::entry::{Entry, Temporal},
 };
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 // TODO: Activities can't currently be sequences! Need to add a field to the activity.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Activity {
     pub id: Uuid,
     pub owner_id: Uuid,
     pub source_activity_id: Option<Uuid>,
     pub name: ActivityName,
     pub description: Option<String>,
+    /// Hidden from the library (`AllActivities`); entries logged against it
+    /// keep their reference.
+    #[serde(default)]
+    pub archived: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct ActivityName(String);
 impl ActivityName {
     /// Maximum length, in characters, of a (trimmed) activity name.
@@ -35,6 +42,20 @@ impl ActivityName {
     }
 }
 
+impl TryFrom<String> for ActivityName {
+    type Error = crate::error::DomainError;
+
+    fn try_from(value: String) -> Result<Self> {
+        ActivityName::parse(value)
+    }
+}
+
+impl From<ActivityName> for String {
+    fn from(value: ActivityName) -> Self {
+        value.0
+    }
+}
+
 impl Activity {
     pub fn update(&self) -> ActivityUpdater {
         ActivityUpdater {
@@ -67,26 +88,37 @@ impl Activity {
 }
 
 #[derive(Debug)]
-#[allow(unused)]
 pub struct ActivityUpdater {
     old: Activity,
     new: Activity,
 }
 
-#[allow(unused)]
 impl ActivityUpdater {
-    fn source_activity_id(mut self, source_activity_id: Option<Uuid>) -> Self {
+    pub fn source_activity_id(mut self, source_activity_id: Option<Uuid>) -> Self {
         self.new.source_activity_id = source_activity_id;
         self
     }
 
-    fn name(mut self, name: ActivityName) -> Self {
+    pub fn name(mut self, name: ActivityName) -> Self {
         self.new.name = name;
         self
     }
 
-    fn description(mut self, description: Option<String>) -> Self {
+    pub fn description(mut self, description: Option<String>) -> Self {
         self.new.description = description;
         self
     }
+
+    pub fn archived(mut self, archived: bool) -> Self {
+        self.new.archived = archived;
+        self
+    }
+
+    pub fn to_delta(self) -> Delta<Activity> {
+        assert_eq!(self.old.id, self.new.id, "update should not mutate id");
+        Delta::Update {
+            old: self.old,
+            new: self.new,
+        }
+    }
 }
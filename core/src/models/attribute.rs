@@ -13,12 +13,16 @@
 //   serde_json's arbitrary_precision feature (enabled workspace-wide via ivm/dbsp).
 // - All config/value types derive Serialize + Deserialize.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::{DomainError, RejectReason, Result, ValidationError};
+use crate::models::derived::DerivedExpr;
+use crate::models::grade::GradeScale;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Attribute {
     pub id: Uuid,
     pub owner_id: Uuid,
@@ -49,6 +53,13 @@ impl Attribute {
         }
     }
 
+    pub fn expect_grade(&self) -> Result<&GradeConfig> {
+        match &self.config {
+            AttributeConfig::Grade(c) => Ok(c),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
     pub fn expect_mass(&self) -> Result<&MassConfig> {
         match &self.config {
             AttributeConfig::Mass(c) => Ok(c),
@@ -63,6 +74,34 @@ impl Attribute {
         }
     }
 
+    pub fn expect_speed(&self) -> Result<&SpeedConfig> {
+        match &self.config {
+            AttributeConfig::Speed(c) => Ok(c),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
+    pub fn expect_duration(&self) -> Result<&DurationConfig> {
+        match &self.config {
+            AttributeConfig::Duration(c) => Ok(c),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
+    pub fn expect_boolean(&self) -> Result<&BooleanConfig> {
+        match &self.config {
+            AttributeConfig::Boolean(c) => Ok(c),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
+    pub fn expect_derived(&self) -> Result<&DerivedConfig> {
+        match &self.config {
+            AttributeConfig::Derived(c) => Ok(c),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
     pub fn expect_text(&self) -> Result<&TextConfig> {
         match &self.config {
             AttributeConfig::Text(c) => Ok(c),
@@ -78,10 +117,12 @@ impl Attribute {
     }
 
     /// The config default mapped to an `AttributeValue`, if this type has one.
-    /// Numeric, Select, Text, and Multiselect carry a default; Mass and Length
-    /// have only `default_unit` and return `None` here (use `seed_value` to
-    /// build a measurement seed from the unit). An empty multiselect default is
-    /// treated as no default — multiselect never seeds a present-but-empty set.
+    /// Numeric, Select, Grade, Text, Multiselect, and Boolean carry a default;
+    /// Mass, Length, Speed and Duration have only a unit and return `None` here
+    /// (use `seed_value` to build a measurement seed). Derived values are
+    /// computed, never stored, so there is nothing to default. An empty
+    /// multiselect default is treated as no default — multiselect never seeds
+    /// a present-but-empty set.
     pub fn default_value(&self) -> Option<AttributeValue> {
         match &self.config {
             AttributeConfig::Numeric(c) => c
@@ -91,9 +132,17 @@ impl Attribute {
                 .default
                 .clone()
                 .map(|s| AttributeValue::Select(SelectValue::Exact(s))),
+            AttributeConfig::Grade(c) => c
+                .default
+                .clone()
+                .map(|s| AttributeValue::Grade(SelectValue::Exact(s))),
             AttributeConfig::Mass(_) => None,
             AttributeConfig::Length(_) => None,
+            AttributeConfig::Speed(_) => None,
+            AttributeConfig::Duration(_) => None,
             AttributeConfig::Text(c) => c.default.clone().map(AttributeValue::Text),
+            AttributeConfig::Boolean(c) => c.default.map(AttributeValue::Boolean),
+            AttributeConfig::Derived(_) => None,
             // An empty default is treated as no default: multiselect stores an
             // empty selection as `None` (the cleared state), never `Some([])`.
             AttributeConfig::Multiselect(c) => c
@@ -108,18 +157,23 @@ impl Attribute {
     /// the config type (numeric value on a numeric attribute, etc.), and the
     /// inner value must satisfy the config's constraints. Applied on every
     /// value write path (`CreateValue`, `UpdateAttributeValue`); configs are
-    /// additive-only (options never removed, bounds never shrunk), so values
-    /// admitted here stay conformant.
+    /// additive-only (options never removed, bounds never shrunk, option
+    /// renames rewrite stored values), so values admitted here stay
+    /// conformant.
     pub fn validate_value(&self, value: &AttributeValue) -> Result<()> {
         match (&self.config, value) {
             (AttributeConfig::Numeric(c), AttributeValue::Numeric(v)) => c.validate_value(v),
             (AttributeConfig::Select(c), AttributeValue::Select(v)) => c.validate_value(v),
+            (AttributeConfig::Grade(c), AttributeValue::Grade(v)) => c.validate_value(v),
             (AttributeConfig::Mass(c), AttributeValue::Mass(v)) => c.validate_value(v),
             (AttributeConfig::Length(c), AttributeValue::Length(v)) => c.validate_value(v),
+            (AttributeConfig::Speed(c), AttributeValue::Speed(v)) => c.validate_value(v),
+            (AttributeConfig::Duration(c), AttributeValue::Duration(v)) => c.validate_value(v),
             (AttributeConfig::Text(c), AttributeValue::Text(v)) => c.validate_value(v),
             (AttributeConfig::Multiselect(c), AttributeValue::Multiselect(v)) => {
                 c.validate_value(v)
             }
+            (AttributeConfig::Boolean(c), AttributeValue::Boolean(v)) => c.validate_value(*v),
             _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
         }
     }
@@ -127,7 +181,8 @@ impl Attribute {
     /// Build the seed `Value` used when attaching this attribute to an entry.
     /// Both `plan` and `actual` are set to the resolved default. Scalar types use
     /// `default_value`; Mass constructs a zero-magnitude `MassMeasurement` in the
-    /// config's `default_unit`. The composite key is `(entry_id, self.id)`.
+    /// config's `default_unit` (Length and Speed likewise), and Duration a
+    /// zero-second value. The composite key is `(entry_id, self.id)`.
     pub fn seed_value(&self, entry_id: Uuid) -> Value {
         let seed = match &self.config {
             AttributeConfig::Mass(c) => {
@@ -142,15 +197,35 @@ impl Attribute {
                     value: 0.0,
                 },
             ))),
+            AttributeConfig::Speed(c) => {
+                Some(AttributeValue::Speed(SpeedValue::Exact(SpeedMeasurement {
+                    unit: c.default_unit.clone(),
+                    value: 0.0,
+                })))
+            }
+            AttributeConfig::Duration(_) => {
+                Some(AttributeValue::Duration(DurationValue::Exact(0.0)))
+            }
             _ => self.default_value(),
         };
-        Value {
+        self.indexed(Value {
             entry_id,
             attribute_id: self.id,
             index_float: None,
             index_string: None,
             plan: seed.clone(),
             actual: seed,
+        })
+    }
+
+    /// `value` with its index columns derived from its `actual`. Applied on
+    /// every value write, so the columns never go stale.
+    pub fn indexed(&self, value: Value) -> Value {
+        let (index_float, index_string) = self.config.index_columns(value.actual.as_ref());
+        Value {
+            index_float,
+            index_string,
+            ..value
         }
     }
 }
@@ -161,10 +236,15 @@ impl Attribute {
 pub enum AttributeConfig {
     Numeric(NumericConfig),
     Select(SelectConfig),
+    Grade(GradeConfig),
     Multiselect(MultiselectConfig),
     Mass(MassConfig),
     Length(LengthConfig),
+    Speed(SpeedConfig),
+    Duration(DurationConfig),
     Text(TextConfig),
+    Boolean(BooleanConfig),
+    Derived(DerivedConfig),
 }
 
 impl From<NumericConfig> for AttributeConfig {
@@ -177,6 +257,11 @@ impl From<SelectConfig> for AttributeConfig {
         AttributeConfig::Select(value)
     }
 }
+impl From<GradeConfig> for AttributeConfig {
+    fn from(value: GradeConfig) -> Self {
+        AttributeConfig::Grade(value)
+    }
+}
 impl From<MultiselectConfig> for AttributeConfig {
     fn from(value: MultiselectConfig) -> Self {
         AttributeConfig::Multiselect(value)
@@ -192,13 +277,90 @@ impl From<LengthConfig> for AttributeConfig {
         AttributeConfig::Length(value)
     }
 }
+impl From<SpeedConfig> for AttributeConfig {
+    fn from(value: SpeedConfig) -> Self {
+        AttributeConfig::Speed(value)
+    }
+}
+impl From<DurationConfig> for AttributeConfig {
+    fn from(value: DurationConfig) -> Self {
+        AttributeConfig::Duration(value)
+    }
+}
 impl From<TextConfig> for AttributeConfig {
     fn from(value: TextConfig) -> Self {
         AttributeConfig::Text(value)
     }
 }
+impl From<BooleanConfig> for AttributeConfig {
+    fn from(value: BooleanConfig) -> Self {
+        AttributeConfig::Boolean(value)
+    }
+}
+impl From<DerivedConfig> for AttributeConfig {
+    fn from(value: DerivedConfig) -> Self {
+        AttributeConfig::Derived(value)
+    }
+}
 
 impl AttributeConfig {
+    /// The `(index_float, index_string)` columns for a value of this config whose
+    /// actual is `actual` (docs/attributes-design.md "Index Columns").
+    /// Measures index their SI-normalized magnitude (`Measure::normalized`;
+    /// speed in m/s), ordered selects their option ordinal, grades their
+    /// difficulty, and selects and text their string. A range indexes its
+    /// lower bound, so the index never overstates it. Multiselect is not
+    /// indexed.
+    pub fn index_columns(&self, actual: Option<&AttributeValue>) -> (Option<f64>, Option<String>) {
+        let Some(actual) = actual else {
+            return (None, None);
+        };
+        match (self, actual) {
+            (AttributeConfig::Numeric(_), AttributeValue::Numeric(n)) => match n {
+                NumericValue::Exact(v) => (Some(*v), None),
+                NumericValue::Range { min, .. } => (Some(*min), None),
+            },
+            (AttributeConfig::Select(c), AttributeValue::Select(s)) => {
+                let option = match s {
+                    SelectValue::Exact(o) => o,
+                    SelectValue::Range { min, .. } => min,
+                };
+                let ordinal = c
+                    .ordered
+                    .then(|| c.options.iter().position(|o| o == option))
+                    .flatten()
+                    .map(|i| i as f64);
+                (ordinal, Some(option.clone()))
+            }
+            (AttributeConfig::Grade(c), AttributeValue::Grade(s)) => {
+                let grade = match s {
+                    SelectValue::Exact(g) => g,
+                    SelectValue::Range { min, .. } => min,
+                };
+                (c.scale.difficulty(grade), None)
+            }
+            (AttributeConfig::Mass(_), AttributeValue::Mass(v)) => (Some(v.normalized()), None),
+            (AttributeConfig::Length(_), AttributeValue::Length(v)) => (Some(v.normalized()), None),
+            (AttributeConfig::Duration(_), AttributeValue::Duration(v)) => {
+                (Some(v.normalized()), None)
+            }
+            (AttributeConfig::Speed(_), AttributeValue::Speed(v)) => {
+                let mps = |unit: &SpeedUnit, m: f64| unit.convert(m, &SpeedUnit::MetersPerSecond);
+                // Pace inverts, so the slower end may be either endpoint.
+                let slowest = match v {
+                    SpeedValue::Exact(m) => mps(&m.unit, m.value),
+                    SpeedValue::Range { unit, min, max } => mps(unit, *min).min(mps(unit, *max)),
+                };
+                (Some(slowest), None)
+            }
+            (AttributeConfig::Text(_), AttributeValue::Text(t)) => (None, Some(t.clone())),
+            (AttributeConfig::Boolean(_), AttributeValue::Boolean(b)) => {
+                (Some(if *b { 1.0 } else { 0.0 }), None)
+            }
+            _ => (None, None),
+        }
+    }
+
     /// Validate the config itself (applied at `CreateAttribute`): bounds and
     /// defaults must be coherent, so values and seeds derived from the config
     /// pass `validate_value`. Per-field edits (`Set*Default`) re-check their
@@ -207,12 +369,18 @@ impl AttributeConfig {
         match self {
             AttributeConfig::Numeric(c) => c.validate(),
             AttributeConfig::Select(c) => c.validate(),
+            AttributeConfig::Grade(c) => c.validate(),
             AttributeConfig::Multiselect(c) => c.validate(),
-            // Mass and Length have no cross-field coherence to check: any
-            // single `default_unit` is valid.
+            // Mass, Length, Speed and Duration have no cross-field coherence
+            // to check: any single unit is valid.
             AttributeConfig::Mass(_) => Ok(()),
             AttributeConfig::Length(_) => Ok(()),
+            AttributeConfig::Speed(_) => Ok(()),
+            AttributeConfig::Duration(_) => Ok(()),
             AttributeConfig::Text(c) => c.validate(),
+            // Either default (or none) is a valid boolean.
+            AttributeConfig::Boolean(_) => Ok(()),
+            AttributeConfig::Derived(c) => c.validate(),
         }
     }
 
@@ -220,10 +388,15 @@ impl AttributeConfig {
         match self {
             AttributeConfig::Numeric(_) => "Numeric",
             AttributeConfig::Select(_) => "Select",
+            AttributeConfig::Grade(_) => "Grade",
             AttributeConfig::Multiselect(_) => "Multiselect",
             AttributeConfig::Mass(_) => "Mass",
             AttributeConfig::Length(_) => "Length",
+            AttributeConfig::Speed(_) => "Speed",
+            AttributeConfig::Duration(_) => "Duration",
             AttributeConfig::Text(_) => "Text",
+            AttributeConfig::Boolean(_) => "Boolean",
+            AttributeConfig::Derived(_) => "Derived",
         }
     }
 }
@@ -250,6 +423,27 @@ fn round_to_two_decimals(v: f64) -> f64 {
     }
 }
 
+/// A composite value must name at least one display unit, and each only once.
+fn check_composite_units<U: PartialEq>(units: &[U]) -> Result<()> {
+    if units.is_empty() {
+        return Err(ValidationError::InvalidValue(
+            "composite value needs display units".to_string(),
+        )
+        .into());
+    }
+    if units
+        .iter()
+        .enumerate()
+        .any(|(i, u)| units[..i].contains(u))
+    {
+        return Err(ValidationError::InvalidValue(
+            "composite value repeats a display unit".to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NumericConfig {
     pub min: Option<f64>,
@@ -432,6 +626,54 @@ impl SelectConfig {
     }
 }
 
+/// An ordered select whose options are the grades of a built-in
+/// `GradeScale`. Each option carries a numeric difficulty, so grades compare
+/// across scales and a range is ordered by difficulty rather than by a
+/// user-authored option list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradeConfig {
+    pub scale: GradeScale,
+    /// Must be a grade on `scale`.
+    pub default: Option<String>,
+}
+
+impl GradeConfig {
+    /// Validate the config itself: the default, when present, must be a grade
+    /// on the scale.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(d) = &self.default {
+            self.validate_value(&SelectValue::Exact(d.clone()))?;
+        }
+        Ok(())
+    }
+
+    /// Validate a grade value against this config: every grade must be on the
+    /// scale, and range endpoints ordered by difficulty (min <= max).
+    pub fn validate_value(&self, value: &SelectValue) -> Result<()> {
+        let difficulty = |s: &String| -> Result<f64> {
+            self.scale.difficulty(s).ok_or_else(|| {
+                ValidationError::InvalidValue(format!(
+                    "'{s}' is not a grade on the {:?} scale",
+                    self.scale
+                ))
+                .into()
+            })
+        };
+        match value {
+            SelectValue::Exact(s) => difficulty(s).map(|_| ()),
+            SelectValue::Range { min, max } => {
+                if difficulty(min)? > difficulty(max)? {
+                    return Err(ValidationError::InvalidValue(format!(
+                        "range min ('{min}') is harder than range max ('{max}')"
+                    ))
+                    .into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 /// Hard cap on the length of a multiselect option string (Unicode scalar
 /// count). A GV-level constraint, not user-authored: the user picks the option
 /// text but never a length bound (unlike a numeric attribute's min/max). Options
@@ -539,6 +781,36 @@ impl TextConfig {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BooleanConfig {
+    pub default: Option<bool>,
+}
+
+impl BooleanConfig {
+    /// Validate a boolean value. Both values are always valid; this exists so
+    /// every type is checked through the same `validate_value` path.
+    pub fn validate_value(&self, _value: bool) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A derived attribute's value is computed from other attributes on the same
+/// entry or its subtree (`DerivedScope`), never entered: every
+/// `AttributeValue` is a mismatch against it, so its stored values only mark
+/// where it is attached. References to other derived attributes must not form
+/// a cycle, checked against stored attributes in `create_attribute` and
+/// `update_attribute`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DerivedConfig {
+    pub expression: DerivedExpr,
+}
+
+impl DerivedConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.expression.validate()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MassConfig {
     /// Unit used for the attach-time seed value and for presenting an empty
@@ -549,7 +821,8 @@ pub struct MassConfig {
 impl MassConfig {
     /// Validate a mass value against this config: magnitudes must be finite
     /// with at most 2 decimal places, and range endpoints ordered (min <= max)
-    /// — both endpoints share one unit, so no conversion is involved. The unit
+    /// — both endpoints share one unit, so no conversion is involved. A
+    /// composite's display units must be non-empty and distinct. The unit
     /// itself is unconstrained; `default_unit` only picks the presentation
     /// default.
     pub fn validate_value(&self, value: &MassValue) -> Result<()> {
@@ -581,11 +854,15 @@ impl MassConfig {
                 }
                 Ok(())
             }
+            MassValue::Composite { value, units, .. } => {
+                check("mass value", *value)?;
+                check_composite_units(units)
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MassUnit {
     Gram,
     Kilogram,
@@ -654,11 +931,15 @@ impl LengthConfig {
                 }
                 Ok(())
             }
+            LengthValue::Composite { value, units, .. } => {
+                check("length value", *value)?;
+                check_composite_units(units)
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LengthUnit {
     Millimeter,
     Centimeter,
@@ -695,9 +976,178 @@ impl LengthUnit {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedConfig {
+    /// Unit used for the attach-time seed value and for presenting an empty
+    /// (cleared) value. A stored value carries its own unit and may differ.
+    pub default_unit: SpeedUnit,
+}
+
+impl SpeedConfig {
+    /// Validate a speed value against this config: magnitudes must be finite,
+    /// non-negative and have at most 2 decimal places, and range endpoints
+    /// ordered (min <= max) in the value's own unit. For a pace that means the
+    /// faster pace comes first. Mirrors `MassConfig::validate_value`.
+    pub fn validate_value(&self, value: &SpeedValue) -> Result<()> {
+        let check = |label: &str, v: f64| -> Result<()> {
+            if !v.is_finite() {
+                return Err(ValidationError::InvalidValue(format!(
+                    "{label} magnitude ({v}) must be finite"
+                ))
+                .into());
+            }
+            if v < 0.0 {
+                return Err(ValidationError::InvalidValue(format!(
+                    "{label} magnitude ({v}) must not be negative"
+                ))
+                .into());
+            }
+            if !at_most_two_decimals(v) {
+                return Err(ValidationError::InvalidValue(format!(
+                    "{label} magnitude ({v}) must have at most 2 decimal places"
+                ))
+                .into());
+            }
+            Ok(())
+        };
+        match value {
+            SpeedValue::Exact(m) => check("speed value", m.value),
+            SpeedValue::Range { unit: _, min, max } => {
+                check("speed range min", *min)?;
+                check("speed range max", *max)?;
+                if min > max {
+                    return Err(ValidationError::InvalidValue(format!(
+                        "range min ({min}) is above range max ({max})"
+                    ))
+                    .into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Speed units and pace units. A pace is the inverse of a speed (time per
+/// distance rather than distance per time), so converting between the two
+/// families inverts the magnitude.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    /// Pace: minutes per kilometer.
+    MinutesPerKilometer,
+    /// Pace: minutes per mile.
+    MinutesPerMile,
+}
+
+impl SpeedUnit {
+    pub fn is_pace(&self) -> bool {
+        matches!(
+            self,
+            SpeedUnit::MinutesPerKilometer | SpeedUnit::MinutesPerMile
+        )
+    }
+
+    /// Conversion factor to the SI base unit, m/s. For a speed unit it is m/s
+    /// per 1 of the unit; for a pace unit it is the speed that a pace of 1
+    /// equals, so a pace `p` is `factor / p` m/s. Exact by definition (1 mile
+    /// = 1609.344 m).
+    pub fn meters_per_second_factor(&self) -> f64 {
+        match self {
+            SpeedUnit::MetersPerSecond => 1.0,
+            SpeedUnit::KilometersPerHour => 1000.0 / 3600.0,
+            SpeedUnit::MilesPerHour => 1609.344 / 3600.0,
+            SpeedUnit::MinutesPerKilometer => 1000.0 / 60.0,
+            SpeedUnit::MinutesPerMile => 1609.344 / 60.0,
+        }
+    }
+
+    /// Convert a magnitude from `self` to `to` at full precision, routing
+    /// through the base unit (`self` → m/s → `to`) like `MassUnit::convert`.
+    /// Pace legs invert. Zero stays zero: a zero magnitude is an unset seed
+    /// (see `Attribute::seed_value`), and inverting it would be infinite.
+    pub fn convert(&self, value: f64, to: &SpeedUnit) -> f64 {
+        if value == 0.0 {
+            return 0.0;
+        }
+        let mps = if self.is_pace() {
+            self.meters_per_second_factor() / value
+        } else {
+            value * self.meters_per_second_factor()
+        };
+        if to.is_pace() {
+            to.meters_per_second_factor() / mps
+        } else {
+            mps / to.meters_per_second_factor()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DurationConfig {
+    /// How values are presented and entered. Values are always stored in
+    /// seconds, so changing it never touches stored values.
+    pub display: DurationDisplay,
+}
+
+impl DurationConfig {
+    /// Validate a duration value against this config: seconds must be finite,
+    /// non-negative and have at most 2 decimal places, and range endpoints
+    /// ordered (min <= max). `display` never constrains a value.
+    pub fn validate_value(&self, value: &DurationValue) -> Result<()> {
+        let check = |label: &str, v: f64| -> Result<()> {
+            if !v.is_finite() {
+                return Err(ValidationError::InvalidValue(format!(
+                    "{label} ({v}s) must be finite"
+                ))
+                .into());
+            }
+            if v < 0.0 {
+                return Err(ValidationError::InvalidValue(format!(
+                    "{label} ({v}s) must not be negative"
+                ))
+                .into());
+            }
+            if !at_most_two_decimals(v) {
+                return Err(ValidationError::InvalidValue(format!(
+                    "{label} ({v}s) must have at most 2 decimal places"
+                ))
+                .into());
+            }
+            Ok(())
+        };
+        match value {
+            DurationValue::Exact(v) => check("duration", *v),
+            DurationValue::Range { min, max } => {
+                check("duration range min", *min)?;
+                check("duration range max", *max)?;
+                if min > max {
+                    return Err(ValidationError::InvalidValue(format!(
+                        "range min ({min}s) is above range max ({max}s)"
+                    ))
+                    .into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Presentation of a duration. Unlike mass and length units this is not
+/// stored on values: a duration is always a number of seconds, and
+/// mixed-radix formats like hh:mm:ss are a view over that one magnitude.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DurationDisplay {
+    Seconds,
+    Minutes,
+    /// hh:mm:ss.
+    HoursMinutesSeconds,
+}
+
 ///// Values /////
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Value {
     // Identified by a composite key: (entry_id, attribute_id).
     pub entry_id: Uuid,
@@ -725,15 +1175,21 @@ impl Value {
 pub enum AttributeValue {
     Numeric(NumericValue),
     Select(SelectValue),
+    // A grade is a select over the scale's grades, so it shares `SelectValue`.
+    Grade(SelectValue),
     // The set of chosen options. Like `Text`, it has no exact/range axis, so it
     // carries a bare collection rather than a wrapping `*Value` enum. Order is a
     // display affordance taken from the config's option order, not data.
     Multiselect(Vec<String>),
     Mass(MassValue),
     Length(LengthValue),
+    Speed(SpeedValue),
+    Duration(DurationValue),
     // Free text has no exact/range axis, so it carries a bare `String` rather
     // than a wrapping `*Value` enum like the other types.
     Text(String),
+    // Yes/no, bare like `Text`: a boolean has no range.
+    Boolean(bool),
 }
 
 impl AttributeValue {
@@ -751,6 +1207,13 @@ impl AttributeValue {
         }
     }
 
+    pub fn expect_grade(self) -> Result<SelectValue> {
+        match self {
+            AttributeValue::Grade(v) => Ok(v),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
     pub fn expect_mass(self) -> Result<MassValue> {
         match self {
             AttributeValue::Mass(v) => Ok(v),
@@ -765,6 +1228,20 @@ impl AttributeValue {
         }
     }
 
+    pub fn expect_speed(self) -> Result<SpeedValue> {
+        match self {
+            AttributeValue::Speed(v) => Ok(v),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
+    pub fn expect_duration(self) -> Result<DurationValue> {
+        match self {
+            AttributeValue::Duration(v) => Ok(v),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
     pub fn expect_text(self) -> Result<String> {
         match self {
             AttributeValue::Text(s) => Ok(s),
@@ -772,12 +1249,131 @@ impl AttributeValue {
         }
     }
 
+    pub fn expect_boolean(self) -> Result<bool> {
+        match self {
+            AttributeValue::Boolean(b) => Ok(b),
+            _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+        }
+    }
+
     pub fn expect_multiselect(self) -> Result<Vec<String>> {
         match self {
             AttributeValue::Multiselect(v) => Ok(v),
             _ => Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
         }
     }
+
+    /// This value with every selection of option `from` replaced by `to`.
+    /// Only select and multiselect values name options; any other value is
+    /// returned as is.
+    pub fn with_option_renamed(self, from: &str, to: &str) -> AttributeValue {
+        let rename = |s: String| if s == from { to.to_string() } else { s };
+        match self {
+            AttributeValue::Select(SelectValue::Exact(s)) => {
+                AttributeValue::Select(SelectValue::Exact(rename(s)))
+            }
+            AttributeValue::Select(SelectValue::Range { min, max }) => {
+                AttributeValue::Select(SelectValue::Range {
+                    min: rename(min),
+                    max: rename(max),
+                })
+            }
+            AttributeValue::Multiselect(v) => {
+                AttributeValue::Multiselect(v.into_iter().map(rename).collect())
+            }
+            other => other,
+        }
+    }
+
+    /// This value with its selections of the options in `remap` replaced:
+    /// `Some(replacement)` substitutes, `None` drops the selection. A select
+    /// value that loses either endpoint, or a multiselect left with nothing
+    /// selected, becomes `None` (cleared). Values that name no options are
+    /// returned as is.
+    pub fn with_options_remapped(
+        self,
+        remap: &BTreeMap<String, Option<String>>,
+    ) -> Option<AttributeValue> {
+        let map = |s: String| match remap.get(&s) {
+            Some(replacement) => replacement.clone(),
+            None => Some(s),
+        };
+        match self {
+            AttributeValue::Select(SelectValue::Exact(s)) => {
+                map(s).map(|s| AttributeValue::Select(SelectValue::Exact(s)))
+            }
+            AttributeValue::Select(SelectValue::Range { min, max }) => {
+                Some(AttributeValue::Select(SelectValue::Range {
+                    min: map(min)?,
+                    max: map(max)?,
+                }))
+            }
+            AttributeValue::Multiselect(v) => {
+                let mut selected: Vec<String> = Vec::with_capacity(v.len());
+                for s in v.into_iter().filter_map(map) {
+                    if !selected.contains(&s) {
+                        selected.push(s);
+                    }
+                }
+                (!selected.is_empty()).then_some(AttributeValue::Multiselect(selected))
+            }
+            other => Some(other),
+        }
+    }
+
+    /// This value re-expressed as a value of `config`'s type, or `None` when
+    /// the type change has no lossless equivalent for it. Numeric magnitudes
+    /// take the config's default unit; a select option becomes a one-option
+    /// set or its option text, and text becomes the option of the same name.
+    /// A select range has no set or text form. A selection becomes the grade
+    /// (or span of grades) it reads as on a grade scale. Membership in the new config
+    /// is not checked here — that is `Attribute::validate_value`'s job.
+    pub fn converted_for(self, config: &AttributeConfig) -> Option<AttributeValue> {
+        match (self, config) {
+            (AttributeValue::Numeric(n), AttributeConfig::Mass(cfg)) => {
+                let unit = cfg.default_unit.clone();
+                Some(AttributeValue::Mass(match n {
+                    NumericValue::Exact(value) => MassValue::Exact(MassMeasurement { unit, value }),
+                    NumericValue::Range { min, max } => MassValue::Range { unit, min, max },
+                }))
+            }
+            (AttributeValue::Numeric(n), AttributeConfig::Length(cfg)) => {
+                let unit = cfg.default_unit.clone();
+                Some(AttributeValue::Length(match n {
+                    NumericValue::Exact(value) => {
+                        LengthValue::Exact(LengthMeasurement { unit, value })
+                    }
+                    NumericValue::Range { min, max } => LengthValue::Range { unit, min, max },
+                }))
+            }
+            (AttributeValue::Select(SelectValue::Exact(s)), AttributeConfig::Multiselect(_)) => {
+                Some(AttributeValue::Multiselect(vec![s]))
+            }
+            (AttributeValue::Select(SelectValue::Exact(s)), AttributeConfig::Text(_)) => {
+                Some(AttributeValue::Text(s))
+            }
+            (AttributeValue::Text(s), AttributeConfig::Select(_)) => {
+                Some(AttributeValue::Select(SelectValue::Exact(s)))
+            }
+            (AttributeValue::Select(v), AttributeConfig::Grade(cfg)) => {
+                let (min, max) = match v {
+                    SelectValue::Exact(s) => cfg.scale.read(&s)?,
+                    SelectValue::Range { min, max } => {
+                        (cfg.scale.read(&min)?.0, cfg.scale.read(&max)?.1)
+                    }
+                };
+                Some(AttributeValue::Grade(if min == max {
+                    SelectValue::Exact(min.to_string())
+                } else {
+                    SelectValue::Range {
+                        min: min.to_string(),
+                        max: max.to_string(),
+                    }
+                }))
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -786,15 +1382,33 @@ pub enum NumericValue {
     Range { min: f64, max: f64 },
 }
 
+impl NumericValue {
+    /// This value with each endpoint clamped into `min..=max` (either bound
+    /// may be open). Clamping keeps a range ordered.
+    pub fn clamped(self, min: Option<f64>, max: Option<f64>) -> NumericValue {
+        let clamp = |v: f64| {
+            let v = min.map_or(v, |min| v.max(min));
+            max.map_or(v, |max| v.min(max))
+        };
+        match self {
+            NumericValue::Exact(v) => NumericValue::Exact(clamp(v)),
+            NumericValue::Range { min, max } => NumericValue::Range {
+                min: clamp(min),
+                max: clamp(max),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SelectValue {
     Exact(String),
     Range { min: String, max: String },
 }
 
-/// One measurement in one unit. Composite formats (e.g. "5 lb 4 oz") are a
-/// presentation concern — see docs/attributes-design.md "Single measurement
-/// per value".
+/// One measurement in one unit, or one magnitude shown across several (e.g.
+/// "2 kg 500 g") — see docs/attributes-design.md "Single measurement per
+/// value".
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MassValue {
     Exact(MassMeasurement),
@@ -804,20 +1418,39 @@ pub enum MassValue {
         min: f64,
         max: f64,
     },
+    /// `value` in `unit`, entered and shown spread over `units`, largest
+    /// first (`Measure::to_parts`). Only the magnitude is a fact; `units` is
+    /// how it reads.
+    Composite {
+        unit: MassUnit,
+        value: f64,
+        units: Vec<MassUnit>,
+    },
 }
 
 impl MassValue {
     pub fn unit(&self) -> &MassUnit {
         match self {
             MassValue::Exact(m) => &m.unit,
-            MassValue::Range { unit, .. } => unit,
+            MassValue::Range { unit, .. } | MassValue::Composite { unit, .. } => unit,
         }
     }
 
-    /// A copy of this value re-expressed in `unit`. Magnitudes are rounded to
+    /// A composite value holding the sum of `parts` in the smallest of their
+    /// units, shown over those units: 2 kg + 500 g is 2500 g read as
+    /// 2 kg 500 g.
+    pub fn composite(parts: &[(MassUnit, f64)]) -> MassValue {
+        let units = Self::largest_first(parts.iter().map(|(u, _)| u.clone()).collect());
+        let unit = units.last().cloned().unwrap_or(MassUnit::Kilogram);
+        let value = round_to_two_decimals(Self::sum(parts) / unit.kilograms_per_unit());
+        MassValue::Composite { unit, value, units }
+    }
+
+    /// A copy of this value re-expressed in `unit`. Magnitudes are rounded to
     /// the 2-decimal cap so the result is writable as-is — `validate_value`
     /// would reject a full-precision conversion. Same-unit conversion returns
-    /// the value unchanged, so repeated re-selection never drifts.
+    /// the value unchanged, so repeated re-selection never drifts. A composite
+    /// in another unit collapses to one exact measurement.
     pub fn converted_to(&self, unit: MassUnit) -> MassValue {
         if *self.unit() == unit {
             return self.clone();
@@ -837,6 +1470,12 @@ impl MassValue {
                 max: round_to_two_decimals(from.convert(*max, &unit)),
                 unit,
             },
+            MassValue::Composite {
+                unit: from, value, ..
+            } => MassValue::Exact(MassMeasurement {
+                value: round_to_two_decimals(from.convert(*value, &unit)),
+                unit,
+            }),
         }
     }
 }
@@ -847,9 +1486,8 @@ pub struct MassMeasurement {
     pub value: f64,
 }
 
-/// One measurement in one unit, mirroring `MassValue`. Composite formats
-/// (e.g. "5 ft 4 in") are a presentation concern — see
-/// docs/attributes-design.md "Single measurement per value".
+/// One measurement in one unit, or one magnitude shown across several (e.g.
+/// "5 ft 10 in"), mirroring `MassValue`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LengthValue {
     Exact(LengthMeasurement),
@@ -859,16 +1497,32 @@ pub enum LengthValue {
         min: f64,
         max: f64,
     },
+    /// `value` in `unit`, shown spread over `units`, largest first. See
+    /// `MassValue::Composite`.
+    Composite {
+        unit: LengthUnit,
+        value: f64,
+        units: Vec<LengthUnit>,
+    },
 }
 
 impl LengthValue {
     pub fn unit(&self) -> &LengthUnit {
         match self {
             LengthValue::Exact(m) => &m.unit,
-            LengthValue::Range { unit, .. } => unit,
+            LengthValue::Range { unit, .. } | LengthValue::Composite { unit, .. } => unit,
         }
     }
 
+    /// A composite value holding the sum of `parts`; mirrors
+    /// `MassValue::composite`. 5 ft + 10 in is 70 in read as 5 ft 10 in.
+    pub fn composite(parts: &[(LengthUnit, f64)]) -> LengthValue {
+        let units = Self::largest_first(parts.iter().map(|(u, _)| u.clone()).collect());
+        let unit = units.last().cloned().unwrap_or(LengthUnit::Meter);
+        let value = round_to_two_decimals(Self::sum(parts) / unit.meters_per_unit());
+        LengthValue::Composite { unit, value, units }
+    }
+
     /// A copy of this value re-expressed in `unit`. Magnitudes are rounded to
     /// the 2-decimal cap so the result is writable as-is — `validate_value`
     /// would reject a full-precision conversion. Same-unit conversion returns
@@ -893,6 +1547,12 @@ impl LengthValue {
                 max: round_to_two_decimals(from.convert(*max, &unit)),
                 unit,
             },
+            LengthValue::Composite {
+                unit: from, value, ..
+            } => LengthValue::Exact(LengthMeasurement {
+                value: round_to_two_decimals(from.convert(*value, &unit)),
+                unit,
+            }),
         }
     }
 }
@@ -903,6 +1563,273 @@ pub struct LengthMeasurement {
     pub value: f64,
 }
 
+/// One measurement in one unit, mirroring `MassValue`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpeedValue {
+    Exact(SpeedMeasurement),
+    /// Both endpoints share one unit, so ordering needs no conversion.
+    Range {
+        unit: SpeedUnit,
+        min: f64,
+        max: f64,
+    },
+}
+
+impl SpeedValue {
+    pub fn unit(&self) -> &SpeedUnit {
+        match self {
+            SpeedValue::Exact(m) => &m.unit,
+            SpeedValue::Range { unit, .. } => unit,
+        }
+    }
+
+    /// A copy of this value re-expressed in `unit`, rounded to the 2-decimal
+    /// cap like `MassValue::converted_to`. Same-unit conversion returns the
+    /// value unchanged. Converting a range between pace and speed inverts
+    /// its endpoints, so they are swapped back into order.
+    pub fn converted_to(&self, unit: SpeedUnit) -> SpeedValue {
+        if *self.unit() == unit {
+            return self.clone();
+        }
+        match self {
+            SpeedValue::Exact(m) => SpeedValue::Exact(SpeedMeasurement {
+                value: round_to_two_decimals(m.unit.convert(m.value, &unit)),
+                unit,
+            }),
+            SpeedValue::Range {
+                unit: from,
+                min,
+                max,
+            } => {
+                let a = round_to_two_decimals(from.convert(*min, &unit));
+                let b = round_to_two_decimals(from.convert(*max, &unit));
+                SpeedValue::Range {
+                    min: a.min(b),
+                    max: a.max(b),
+                    unit,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedMeasurement {
+    pub unit: SpeedUnit,
+    pub value: f64,
+}
+
+/// A duration in seconds, independent of the entry's `Temporal` (e.g. the
+/// hang time of one set of repeaters).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DurationValue {
+    Exact(f64),
+    Range { min: f64, max: f64 },
+}
+
+///// Measures /////
+
+/// A quantity of one dimension (mass, length, time) that can be spread over
+/// several units at once — 5 ft 10 in, 1 h 23 min 10 s — and summed back into
+/// one magnitude. Composite forms are an entry and display concern: a stored
+/// value stays a single measurement (see docs/attributes-design.md "Single
+/// Measurement per Mass Value"), so parts are plain `(unit, magnitude)` pairs
+/// that collapse through `from_parts` before they're written.
+/// `(unit, magnitude)` parts of one quantity, largest unit first.
+pub type Parts<U> = Vec<(U, f64)>;
+
+pub trait Measure: Sized {
+    type Unit: Clone + PartialEq;
+
+    /// Every unit this measure can be expressed in.
+    fn defined_units() -> &'static [Self::Unit];
+
+    /// Size of one `unit` in the dimension's base unit (kg, m or s).
+    fn base_per_unit(unit: &Self::Unit) -> f64;
+
+    /// An exact value of `magnitude` in `unit`.
+    fn exact(unit: Self::Unit, magnitude: f64) -> Self;
+
+    /// This value's lower and upper bound in the base unit. An exact value
+    /// has equal bounds.
+    fn bounds(&self) -> (f64, f64);
+
+    /// This value in the base unit, for indexing. A range normalizes to its
+    /// lower bound, so an index never overstates it.
+    fn normalized(&self) -> f64 {
+        self.bounds().0
+    }
+
+    /// `units` with duplicates dropped, largest first.
+    fn largest_first(mut units: Vec<Self::Unit>) -> Vec<Self::Unit> {
+        units.sort_by(|a, b| Self::base_per_unit(b).total_cmp(&Self::base_per_unit(a)));
+        units.dedup();
+        units
+    }
+
+    /// The parts summed into one base-unit quantity.
+    fn sum(parts: &[(Self::Unit, f64)]) -> f64 {
+        parts
+            .iter()
+            .map(|(unit, v)| v * Self::base_per_unit(unit))
+            .sum()
+    }
+
+    /// Distribute a base-unit `quantity` over `units`, largest first: every
+    /// part but the smallest takes a whole count, and the smallest takes the
+    /// remainder at the 2-decimal cap. Adding or removing a unit is
+    /// `distribute(Self::sum(parts), new_units)`.
+    fn distribute(quantity: f64, units: &[Self::Unit]) -> Parts<Self::Unit> {
+        let units = Self::largest_first(units.to_vec());
+        let Some((smallest, larger)) = units.split_last() else {
+            return Vec::new();
+        };
+        let sign = if quantity < 0.0 { -1.0 } else { 1.0 };
+        let mut rest = quantity.abs();
+        let mut counts = Vec::with_capacity(units.len());
+        for unit in larger {
+            // The epsilon absorbs float error, so 72 in comes out as 6 ft
+            // rather than 5 ft 12 in.
+            let n = (rest / Self::base_per_unit(unit) + 1e-9).floor();
+            rest = (rest - n * Self::base_per_unit(unit)).max(0.0);
+            counts.push(n);
+        }
+        counts.push(round_to_two_decimals(rest / Self::base_per_unit(smallest)));
+        // Rounding can complete a whole larger unit (11.996 in → 12 in), so
+        // carry upward.
+        for i in (1..counts.len()).rev() {
+            let ratio = Self::base_per_unit(&units[i - 1]) / Self::base_per_unit(&units[i]);
+            if counts[i] >= ratio - 1e-9 {
+                counts[i] = round_to_two_decimals((counts[i] - ratio).max(0.0));
+                counts[i - 1] += 1.0;
+            }
+        }
+        units
+            .into_iter()
+            .zip(counts)
+            .map(|(unit, n)| (unit, sign * n))
+            .collect()
+    }
+
+    /// One exact value in `unit` holding the sum of `parts`, rounded to the
+    /// 2-decimal cap so it's writable as-is.
+    fn from_parts(parts: &[(Self::Unit, f64)], unit: Self::Unit) -> Self {
+        let magnitude = Self::sum(parts) / Self::base_per_unit(&unit);
+        Self::exact(unit, round_to_two_decimals(magnitude))
+    }
+
+    /// This value's lower and upper bound, each distributed over `units`.
+    /// An exact value gives the same parts twice.
+    fn to_parts(&self, units: &[Self::Unit]) -> (Parts<Self::Unit>, Parts<Self::Unit>) {
+        let (min, max) = self.bounds();
+        (Self::distribute(min, units), Self::distribute(max, units))
+    }
+}
+
+impl Measure for MassValue {
+    type Unit = MassUnit;
+
+    fn defined_units() -> &'static [MassUnit] {
+        &[MassUnit::Gram, MassUnit::Kilogram, MassUnit::Pound]
+    }
+
+    fn base_per_unit(unit: &MassUnit) -> f64 {
+        unit.kilograms_per_unit()
+    }
+
+    fn exact(unit: MassUnit, value: f64) -> Self {
+        MassValue::Exact(MassMeasurement { unit, value })
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        let kg = self.unit().kilograms_per_unit();
+        match self {
+            MassValue::Exact(MassMeasurement { value, .. })
+            | MassValue::Composite { value, .. } => (value * kg, value * kg),
+            MassValue::Range { min, max, .. } => (min * kg, max * kg),
+        }
+    }
+}
+
+impl Measure for LengthValue {
+    type Unit = LengthUnit;
+
+    fn defined_units() -> &'static [LengthUnit] {
+        // In the menu order used by the Swift picker.
+        &[
+            LengthUnit::Millimeter,
+            LengthUnit::Centimeter,
+            LengthUnit::Meter,
+            LengthUnit::Kilometer,
+            LengthUnit::Inch,
+            LengthUnit::Foot,
+            LengthUnit::Yard,
+            LengthUnit::Mile,
+        ]
+    }
+
+    fn base_per_unit(unit: &LengthUnit) -> f64 {
+        unit.meters_per_unit()
+    }
+
+    fn exact(unit: LengthUnit, value: f64) -> Self {
+        LengthValue::Exact(LengthMeasurement { unit, value })
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        let m = self.unit().meters_per_unit();
+        match self {
+            LengthValue::Exact(LengthMeasurement { value, .. })
+            | LengthValue::Composite { value, .. } => (value * m, value * m),
+            LengthValue::Range { min, max, .. } => (min * m, max * m),
+        }
+    }
+}
+
+/// Units a duration can be entered or shown in. Durations are stored in
+/// seconds, so a time unit only ever appears in parts, never on a value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+}
+
+impl TimeUnit {
+    pub fn seconds_per_unit(&self) -> f64 {
+        match self {
+            TimeUnit::Second => 1.0,
+            TimeUnit::Minute => 60.0,
+            TimeUnit::Hour => 3600.0,
+        }
+    }
+}
+
+impl Measure for DurationValue {
+    type Unit = TimeUnit;
+
+    fn defined_units() -> &'static [TimeUnit] {
+        &[TimeUnit::Second, TimeUnit::Minute, TimeUnit::Hour]
+    }
+
+    fn base_per_unit(unit: &TimeUnit) -> f64 {
+        unit.seconds_per_unit()
+    }
+
+    /// Stored in seconds whatever `unit` is. The product is re-rounded to shed
+    /// float noise (1.1 min is 66.00000000000001 s).
+    fn exact(unit: TimeUnit, value: f64) -> Self {
+        DurationValue::Exact(round_to_two_decimals(value * unit.seconds_per_unit()))
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        match self {
+            DurationValue::Exact(s) => (*s, *s),
+            DurationValue::Range { min, max } => (*min, *max),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1077,6 +2004,440 @@ mod tests {
         );
     }
 
+    #[test]
+    fn boolean_default_seeds_value() {
+        let a = |default| attr(BooleanConfig { default });
+        // `Some(false)` is a real default, distinct from no default.
+        assert_eq!(
+            a(Some(false)).seed_value(Uuid::nil()).actual,
+            Some(AttributeValue::Boolean(false))
+        );
+        assert_eq!(a(None).seed_value(Uuid::nil()).actual, None);
+        assert!(
+            a(None)
+                .validate_value(&AttributeValue::Boolean(true))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn measure_parts_round_trip() {
+        use LengthUnit::{Foot, Inch};
+        let height = LengthValue::from_parts(&[(Foot, 5.0), (Inch, 10.0)], Inch);
+        assert_eq!(height, LengthValue::exact(Inch, 70.0));
+        let five_ten = vec![(Foot, 5.0), (Inch, 10.0)];
+        assert_eq!(height.to_parts(&[Inch, Foot]), (five_ten.clone(), five_ten));
+        // A whole number of feet leaves no stray 12 inches.
+        assert_eq!(
+            LengthValue::exact(Inch, 72.0).to_parts(&[Foot, Inch]).0,
+            vec![(Foot, 6.0), (Inch, 0.0)]
+        );
+        // Rounding the smallest part up to a whole larger unit carries.
+        assert_eq!(
+            LengthValue::distribute(6.0 * 0.3048 - 1e-7, &[Foot, Inch]),
+            vec![(Foot, 6.0), (Inch, 0.0)]
+        );
+
+        use TimeUnit::{Hour, Minute, Second};
+        let time =
+            DurationValue::from_parts(&[(Hour, 1.0), (Minute, 23.0), (Second, 10.0)], Second);
+        assert_eq!(time, DurationValue::Exact(4990.0));
+        assert_eq!(
+            time.to_parts(&[Hour, Minute, Second]).1,
+            vec![(Hour, 1.0), (Minute, 23.0), (Second, 10.0)]
+        );
+        assert_eq!(
+            DurationValue::exact(Minute, 1.1),
+            DurationValue::Exact(66.0)
+        );
+
+        // Normalized to the base unit; a range to its lower bound.
+        assert_eq!(MassValue::exact(MassUnit::Gram, 2500.0).normalized(), 2.5);
+        assert_eq!(
+            MassValue::Range {
+                unit: MassUnit::Kilogram,
+                min: 20.0,
+                max: 30.0,
+            }
+            .normalized(),
+            20.0
+        );
+    }
+
+    #[test]
+    fn range_parts_keep_both_bounds() {
+        use TimeUnit::{Minute, Second};
+        let range = DurationValue::Range {
+            min: 90.0,
+            max: 150.0,
+        };
+        assert_eq!(
+            range.to_parts(&[Minute, Second]),
+            (
+                vec![(Minute, 1.0), (Second, 30.0)],
+                vec![(Minute, 2.0), (Second, 30.0)]
+            )
+        );
+        let range = LengthValue::Range {
+            unit: LengthUnit::Inch,
+            min: 66.0,
+            max: 74.0,
+        };
+        assert_eq!(
+            range.to_parts(&[LengthUnit::Foot, LengthUnit::Inch]).1,
+            vec![(LengthUnit::Foot, 6.0), (LengthUnit::Inch, 2.0)]
+        );
+    }
+
+    #[test]
+    fn composite_values_hold_one_magnitude() {
+        use LengthUnit::{Foot, Inch, Meter};
+        let height = LengthValue::composite(&[(Inch, 10.0), (Foot, 5.0)]);
+        assert_eq!(
+            height,
+            LengthValue::Composite {
+                unit: Inch,
+                value: 70.0,
+                units: vec![Foot, Inch],
+            }
+        );
+        assert_eq!(
+            height.normalized(),
+            LengthValue::exact(Inch, 70.0).normalized()
+        );
+        assert_eq!(
+            height.to_parts(&[Foot, Inch]).0,
+            vec![(Foot, 5.0), (Inch, 10.0)]
+        );
+        assert_eq!(height.converted_to(Meter), LengthValue::exact(Meter, 1.78));
+
+        let config = LengthConfig {
+            default_unit: Meter,
+        };
+        assert!(config.validate_value(&height).is_ok());
+        let with_units = |units| LengthValue::Composite {
+            unit: Inch,
+            value: 70.0,
+            units,
+        };
+        assert!(rejected_validation(
+            &config.validate_value(&with_units(vec![]))
+        ));
+        assert!(rejected_validation(
+            &config.validate_value(&with_units(vec![Foot, Foot]))
+        ));
+
+        let mass = MassValue::composite(&[(MassUnit::Kilogram, 2.0), (MassUnit::Gram, 500.0)]);
+        assert_eq!(mass.normalized(), 2.5);
+        assert!(
+            MassConfig {
+                default_unit: MassUnit::Kilogram
+            }
+            .validate_value(&mass)
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn index_columns_follow_actual() {
+        let select = |ordered| {
+            AttributeConfig::Select(SelectConfig {
+                options: vec!["Easy".to_string(), "Hard".to_string()],
+                ordered,
+                default: None,
+            })
+        };
+        let hard = AttributeValue::Select(SelectValue::Exact("Hard".to_string()));
+        assert_eq!(
+            select(true).index_columns(Some(&hard)),
+            (Some(1.0), Some("Hard".to_string()))
+        );
+        assert_eq!(
+            select(false).index_columns(Some(&hard)),
+            (None, Some("Hard".to_string()))
+        );
+        assert_eq!(select(true).index_columns(None), (None, None));
+
+        let grade = AttributeConfig::Grade(GradeConfig {
+            scale: GradeScale::French,
+            default: None,
+        });
+        let six_c = AttributeValue::Grade(SelectValue::Exact("6c".to_string()));
+        assert_eq!(grade.index_columns(Some(&six_c)), (Some(11.0), None));
+
+        let mass = AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram,
+        });
+        let plates = AttributeValue::Mass(MassValue::exact(MassUnit::Gram, 2500.0));
+        assert_eq!(mass.index_columns(Some(&plates)), (Some(2.5), None));
+
+        // A pace range indexes its slower end, which is its larger pace.
+        let speed = AttributeConfig::Speed(SpeedConfig {
+            default_unit: SpeedUnit::MinutesPerKilometer,
+        });
+        let pace = AttributeValue::Speed(SpeedValue::Range {
+            unit: SpeedUnit::MinutesPerKilometer,
+            min: 4.0,
+            max: 5.0,
+        });
+        let (mps, _) = speed.index_columns(Some(&pace));
+        assert!((mps.unwrap() - 1000.0 / 300.0).abs() < 1e-9);
+
+        let text = AttributeConfig::Text(TextConfig {
+            default: None,
+            autocomplete: false,
+        });
+        let note = AttributeValue::Text("felt strong".to_string());
+        assert_eq!(
+            text.index_columns(Some(&note)),
+            (None, Some("felt strong".to_string()))
+        );
+        // A value of the wrong type is left unindexed.
+        assert_eq!(mass.index_columns(Some(&note)), (None, None));
+    }
+
+    #[test]
+    fn grade_validation() {
+        let s = |v: &str| v.to_string();
+        let cfg = GradeConfig {
+            scale: GradeScale::French,
+            default: None,
+        };
+        assert!(cfg.validate_value(&SelectValue::Exact(s("6c+"))).is_ok());
+        // A grade from another scale isn't an option here.
+        assert!(rejected_validation(
+            &cfg.validate_value(&SelectValue::Exact(s("5.11a")))
+        ));
+        // Ranges are ordered by difficulty.
+        assert!(
+            cfg.validate_value(&SelectValue::Range {
+                min: s("6c"),
+                max: s("7a"),
+            })
+            .is_ok()
+        );
+        assert!(rejected_validation(&cfg.validate_value(
+            &SelectValue::Range {
+                min: s("7a"),
+                max: s("6c"),
+            }
+        )));
+        assert!(rejected_validation(
+            &GradeConfig {
+                scale: GradeScale::VScale,
+                default: Some(s("6A")),
+            }
+            .validate()
+        ));
+    }
+
+    #[test]
+    fn option_rename_rewrites_selections() {
+        let s = |v: &str| v.to_string();
+        let renamed = |v: AttributeValue| v.with_option_renamed("b", "B");
+        assert_eq!(
+            renamed(AttributeValue::Select(SelectValue::Range {
+                min: s("a"),
+                max: s("b"),
+            })),
+            AttributeValue::Select(SelectValue::Range {
+                min: s("a"),
+                max: s("B"),
+            })
+        );
+        assert_eq!(
+            renamed(AttributeValue::Multiselect(vec![s("b"), s("c")])),
+            AttributeValue::Multiselect(vec![s("B"), s("c")])
+        );
+        // Text never names an option, even when it matches one.
+        assert_eq!(
+            renamed(AttributeValue::Text(s("b"))),
+            AttributeValue::Text(s("b"))
+        );
+    }
+
+    #[test]
+    fn option_remap_replaces_or_clears() {
+        let s = |v: &str| v.to_string();
+        let remap = BTreeMap::from([(s("b"), Some(s("c"))), (s("x"), None)]);
+        let select = |v: &str| AttributeValue::Select(SelectValue::Exact(s(v)));
+        assert_eq!(select("b").with_options_remapped(&remap), Some(select("c")));
+        assert_eq!(select("x").with_options_remapped(&remap), None);
+        let range = |min: &str, max: &str| {
+            AttributeValue::Select(SelectValue::Range {
+                min: s(min),
+                max: s(max),
+            })
+        };
+        assert_eq!(
+            range("a", "b").with_options_remapped(&remap),
+            Some(range("a", "c"))
+        );
+        assert_eq!(range("a", "x").with_options_remapped(&remap), None);
+        // Replacements merge into an existing selection; an emptied set clears.
+        assert_eq!(
+            AttributeValue::Multiselect(vec![s("b"), s("c"), s("x")]).with_options_remapped(&remap),
+            Some(AttributeValue::Multiselect(vec![s("c")]))
+        );
+        assert_eq!(
+            AttributeValue::Multiselect(vec![s("x")]).with_options_remapped(&remap),
+            None
+        );
+    }
+
+    #[test]
+    fn numeric_clamp() {
+        assert_eq!(
+            NumericValue::Exact(120.0).clamped(Some(0.0), Some(100.0)),
+            NumericValue::Exact(100.0)
+        );
+        assert_eq!(
+            NumericValue::Range {
+                min: -5.0,
+                max: 50.0
+            }
+            .clamped(Some(0.0), None),
+            NumericValue::Range {
+                min: 0.0,
+                max: 50.0
+            }
+        );
+    }
+
+    #[test]
+    fn type_conversion_keeps_data_or_refuses() {
+        let s = |v: &str| v.to_string();
+        let mass: AttributeConfig = MassConfig {
+            default_unit: MassUnit::Kilogram,
+        }
+        .into();
+        assert_eq!(
+            AttributeValue::Numeric(NumericValue::Range {
+                min: 60.0,
+                max: 62.5
+            })
+            .converted_for(&mass),
+            Some(AttributeValue::Mass(MassValue::Range {
+                unit: MassUnit::Kilogram,
+                min: 60.0,
+                max: 62.5
+            }))
+        );
+        let multiselect: AttributeConfig = MultiselectConfig {
+            options: vec![s("a")],
+            default: None,
+        }
+        .into();
+        assert_eq!(
+            AttributeValue::Select(SelectValue::Exact(s("a"))).converted_for(&multiselect),
+            Some(AttributeValue::Multiselect(vec![s("a")]))
+        );
+        // A select range has no set form.
+        assert_eq!(
+            AttributeValue::Select(SelectValue::Range {
+                min: s("a"),
+                max: s("b")
+            })
+            .converted_for(&multiselect),
+            None
+        );
+        // Unsupported paths refuse rather than guess.
+        assert_eq!(AttributeValue::Text(s("a")).converted_for(&mass), None);
+    }
+
+    #[test]
+    fn speed_validation() {
+        let cfg = SpeedConfig {
+            default_unit: SpeedUnit::KilometersPerHour,
+        };
+        let m = |unit, value| SpeedValue::Exact(SpeedMeasurement { unit, value });
+        // The value's unit is free to differ from the config default.
+        assert!(
+            cfg.validate_value(&m(SpeedUnit::MinutesPerMile, 8.5))
+                .is_ok()
+        );
+        assert!(rejected_validation(
+            &cfg.validate_value(&m(SpeedUnit::KilometersPerHour, -3.0))
+        ));
+        assert!(rejected_validation(
+            &cfg.validate_value(&m(SpeedUnit::KilometersPerHour, 12.345))
+        ));
+        assert!(rejected_validation(&cfg.validate_value(
+            &SpeedValue::Range {
+                unit: SpeedUnit::MinutesPerKilometer,
+                min: 5.5,
+                max: 4.5, // inverted
+            }
+        )));
+    }
+
+    #[test]
+    fn speed_conversion() {
+        let m = |unit, value| SpeedValue::Exact(SpeedMeasurement { unit, value });
+        // Between speed units: a plain factor.
+        assert_eq!(
+            m(SpeedUnit::MetersPerSecond, 10.0).converted_to(SpeedUnit::KilometersPerHour),
+            m(SpeedUnit::KilometersPerHour, 36.0)
+        );
+        // Pace to speed inverts: 5 min/km is 12 km/h.
+        assert_eq!(
+            m(SpeedUnit::MinutesPerKilometer, 5.0).converted_to(SpeedUnit::KilometersPerHour),
+            m(SpeedUnit::KilometersPerHour, 12.0)
+        );
+        // 10 km/h = 9.656... min/mi, rounded to the 2-decimal cap.
+        assert_eq!(
+            m(SpeedUnit::KilometersPerHour, 10.0).converted_to(SpeedUnit::MinutesPerMile),
+            m(SpeedUnit::MinutesPerMile, 9.66)
+        );
+        // A zero seed stays zero rather than becoming infinite.
+        assert_eq!(
+            m(SpeedUnit::KilometersPerHour, 0.0).converted_to(SpeedUnit::MinutesPerKilometer),
+            m(SpeedUnit::MinutesPerKilometer, 0.0)
+        );
+        // Inverting a range swaps its endpoints back into order.
+        assert_eq!(
+            SpeedValue::Range {
+                unit: SpeedUnit::MinutesPerKilometer,
+                min: 4.0,
+                max: 5.0,
+            }
+            .converted_to(SpeedUnit::KilometersPerHour),
+            SpeedValue::Range {
+                unit: SpeedUnit::KilometersPerHour,
+                min: 12.0,
+                max: 15.0,
+            }
+        );
+    }
+
+    #[test]
+    fn duration_validation() {
+        let cfg = DurationConfig {
+            display: DurationDisplay::HoursMinutesSeconds,
+        };
+        assert!(cfg.validate_value(&DurationValue::Exact(7.5)).is_ok());
+        assert!(
+            cfg.validate_value(&DurationValue::Range {
+                min: 30.0,
+                max: 60.0
+            })
+            .is_ok()
+        );
+        // Negative, over-precise, and inverted durations are rejected.
+        assert!(rejected_validation(
+            &cfg.validate_value(&DurationValue::Exact(-1.0))
+        ));
+        assert!(rejected_validation(
+            &cfg.validate_value(&DurationValue::Exact(0.125))
+        ));
+        assert!(rejected_validation(&cfg.validate_value(
+            &DurationValue::Range {
+                min: 60.0,
+                max: 30.0
+            }
+        )));
+    }
+
     #[test]
     fn config_validation() {
         // Numeric: unordered bounds, out-of-bounds default, non-integer bound.
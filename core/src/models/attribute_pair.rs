@@ -1,22 +1,29 @@
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     error::DomainError,
     models::attribute::{
-        Attribute, AttributeConfig, LengthConfig, LengthUnit, LengthValue, MassConfig, MassUnit,
-        MassValue, MultiselectConfig, NumericConfig, NumericValue, SelectConfig, SelectValue,
-        TextConfig, Value,
+        Attribute, AttributeConfig, BooleanConfig, DerivedConfig, DurationConfig, DurationValue,
+        GradeConfig, LengthConfig, LengthUnit, LengthValue, MassConfig, MassUnit, MassValue,
+        MultiselectConfig, NumericConfig, NumericValue, SelectConfig, SelectValue, SpeedConfig,
+        SpeedUnit, SpeedValue, TextConfig, Value,
     },
 };
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AttributePair {
     Numeric(NumericAttributePair),
     Select(SelectAttributePair),
+    Grade(GradeAttributePair),
     Multiselect(MultiselectAttributePair),
     Mass(MassAttributePair),
     Length(LengthAttributePair),
+    Speed(SpeedAttributePair),
+    Duration(DurationAttributePair),
     Text(TextAttributePair),
+    Boolean(BooleanAttributePair),
+    Derived(DerivedAttributePair),
 }
 
 impl AttributePair {
@@ -24,10 +31,15 @@ impl AttributePair {
         match self {
             AttributePair::Numeric(p) => p.attr_id,
             AttributePair::Select(p) => p.attr_id,
+            AttributePair::Grade(p) => p.attr_id,
             AttributePair::Multiselect(p) => p.attr_id,
             AttributePair::Mass(p) => p.attr_id,
             AttributePair::Length(p) => p.attr_id,
+            AttributePair::Speed(p) => p.attr_id,
+            AttributePair::Duration(p) => p.attr_id,
             AttributePair::Text(p) => p.attr_id,
+            AttributePair::Boolean(p) => p.attr_id,
+            AttributePair::Derived(p) => p.attr_id,
         }
     }
 
@@ -35,10 +47,15 @@ impl AttributePair {
         match self {
             AttributePair::Numeric(p) => p.name.clone(),
             AttributePair::Select(p) => p.name.clone(),
+            AttributePair::Grade(p) => p.name.clone(),
             AttributePair::Multiselect(p) => p.name.clone(),
             AttributePair::Mass(p) => p.name.clone(),
             AttributePair::Length(p) => p.name.clone(),
+            AttributePair::Speed(p) => p.name.clone(),
+            AttributePair::Duration(p) => p.name.clone(),
             AttributePair::Text(p) => p.name.clone(),
+            AttributePair::Boolean(p) => p.name.clone(),
+            AttributePair::Derived(p) => p.name.clone(),
         }
     }
 }
@@ -75,6 +92,20 @@ impl TryFrom<(Attribute, Value)> for AttributePair {
                     actual,
                 }))
             }
+            (AttributeConfig::Grade(cfg), plan, actual) => {
+                let plan = plan.map(|v| v.expect_grade()).transpose()?;
+                let actual = actual.map(|v| v.expect_grade()).transpose()?;
+                Ok(AttributePair::Grade(GradeAttributePair {
+                    attr_id: attr.id,
+                    entry_id: val.entry_id,
+                    owner_id: attr.owner_id,
+                    name: attr.name,
+                    config: cfg,
+                    index_float: val.index_float,
+                    plan,
+                    actual,
+                }))
+            }
             (AttributeConfig::Multiselect(cfg), plan, actual) => {
                 let plan = plan.map(|v| v.expect_multiselect()).transpose()?;
                 let actual = actual.map(|v| v.expect_multiselect()).transpose()?;
@@ -117,6 +148,34 @@ impl TryFrom<(Attribute, Value)> for AttributePair {
                     actual,
                 }))
             }
+            (AttributeConfig::Speed(cfg), plan, actual) => {
+                let plan = plan.map(|v| v.expect_speed()).transpose()?;
+                let actual = actual.map(|v| v.expect_speed()).transpose()?;
+                Ok(AttributePair::Speed(SpeedAttributePair {
+                    attr_id: attr.id,
+                    entry_id: val.entry_id,
+                    owner_id: attr.owner_id,
+                    name: attr.name,
+                    config: cfg,
+                    index_float: val.index_float,
+                    plan,
+                    actual,
+                }))
+            }
+            (AttributeConfig::Duration(cfg), plan, actual) => {
+                let plan = plan.map(|v| v.expect_duration()).transpose()?;
+                let actual = actual.map(|v| v.expect_duration()).transpose()?;
+                Ok(AttributePair::Duration(DurationAttributePair {
+                    attr_id: attr.id,
+                    entry_id: val.entry_id,
+                    owner_id: attr.owner_id,
+                    name: attr.name,
+                    config: cfg,
+                    index_float: val.index_float,
+                    plan,
+                    actual,
+                }))
+            }
             (AttributeConfig::Text(cfg), plan, actual) => {
                 let plan = plan.map(|v| v.expect_text()).transpose()?;
                 let actual = actual.map(|v| v.expect_text()).transpose()?;
@@ -131,11 +190,38 @@ impl TryFrom<(Attribute, Value)> for AttributePair {
                     actual,
                 }))
             }
+            (AttributeConfig::Boolean(cfg), plan, actual) => {
+                let plan = plan.map(|v| v.expect_boolean()).transpose()?;
+                let actual = actual.map(|v| v.expect_boolean()).transpose()?;
+                Ok(AttributePair::Boolean(BooleanAttributePair {
+                    attr_id: attr.id,
+                    entry_id: val.entry_id,
+                    owner_id: attr.owner_id,
+                    name: attr.name,
+                    config: cfg,
+                    index_float: val.index_float,
+                    plan,
+                    actual,
+                }))
+            }
+            // Nothing is stored for a derived attribute; plan and actual are
+            // filled in by `DerivedScope::fill`.
+            (AttributeConfig::Derived(cfg), _, _) => {
+                Ok(AttributePair::Derived(DerivedAttributePair {
+                    attr_id: attr.id,
+                    entry_id: val.entry_id,
+                    owner_id: attr.owner_id,
+                    name: attr.name,
+                    config: cfg,
+                    plan: None,
+                    actual: None,
+                }))
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NumericAttributePair {
     pub attr_id: Uuid,
     pub entry_id: Uuid,
@@ -147,7 +233,7 @@ pub struct NumericAttributePair {
     pub actual: Option<NumericValue>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SelectAttributePair {
     pub attr_id: Uuid,
     pub entry_id: Uuid,
@@ -159,7 +245,20 @@ pub struct SelectAttributePair {
     pub actual: Option<SelectValue>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradeAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: GradeConfig,
+    /// The grade's difficulty (see `GradeScale::difficulty`).
+    pub index_float: Option<f64>,
+    pub plan: Option<SelectValue>,
+    pub actual: Option<SelectValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MultiselectAttributePair {
     pub attr_id: Uuid,
     pub entry_id: Uuid,
@@ -173,7 +272,7 @@ pub struct MultiselectAttributePair {
     pub actual: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MassAttributePair {
     pub attr_id: Uuid,
     pub entry_id: Uuid,
@@ -197,7 +296,7 @@ impl MassAttributePair {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LengthAttributePair {
     pub attr_id: Uuid,
     pub entry_id: Uuid,
@@ -221,7 +320,43 @@ impl LengthAttributePair {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: SpeedConfig,
+    pub index_float: Option<f64>,
+    pub plan: Option<SpeedValue>,
+    pub actual: Option<SpeedValue>,
+}
+
+impl SpeedAttributePair {
+    /// The unit this pair presents in: the actual value's unit, else the
+    /// plan's, else the config's `default_unit`. Mirrors `MassAttributePair`.
+    pub fn display_unit(&self) -> SpeedUnit {
+        self.actual
+            .as_ref()
+            .or(self.plan.as_ref())
+            .map(|v| v.unit().clone())
+            .unwrap_or_else(|| self.config.default_unit.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DurationAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: DurationConfig,
+    pub index_float: Option<f64>,
+    pub plan: Option<DurationValue>,
+    pub actual: Option<DurationValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextAttributePair {
     pub attr_id: Uuid,
     pub entry_id: Uuid,
@@ -234,3 +369,30 @@ pub struct TextAttributePair {
     pub plan: Option<String>,
     pub actual: Option<String>,
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BooleanAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: BooleanConfig,
+    pub index_float: Option<f64>,
+    // A boolean value is a bare `bool` (no exact/range axis), so plan/actual
+    // are `Option<bool>` directly.
+    pub plan: Option<bool>,
+    pub actual: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DerivedAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: DerivedConfig,
+    // Computed on read, so there is no index column. The value is a bare
+    // number: measures contribute their SI magnitude.
+    pub plan: Option<f64>,
+    pub actual: Option<f64>,
+}
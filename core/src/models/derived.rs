@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::ops::{Add, Div, Mul, Sub};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{Result, ValidationError};
+use crate::forest::Forest;
+use crate::models::attribute::{Attribute, AttributeConfig, Value};
+use crate::models::attribute_pair::AttributePair;
+use crate::models::entry::Entry;
+
+/// An arithmetic expression over other attributes, evaluated on an entry:
+/// volume is `Reps * Load`, tonnage is volume summed over a sets sequence.
+///
+/// Stored flat: each node refers to earlier nodes by index and the last node
+/// is the root. uniffi has no recursive types, so a boxed tree couldn't cross
+/// the FFI. Build expressions with the constructors and the arithmetic
+/// operators rather than by hand:
+/// `DerivedExpr::attribute(reps) * DerivedExpr::attribute(load)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DerivedExpr {
+    pub nodes: Vec<DerivedNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DerivedNode {
+    Constant(f64),
+    /// The attribute's value on the entry being evaluated. A derived attribute
+    /// is evaluated in turn; any other reads the number its value is indexed by
+    /// (`AttributeConfig::index_columns`), so measures read in SI units and a
+    /// range reads its lower bound.
+    Attribute(Uuid),
+    /// `lhs op rhs`, both indices of earlier nodes.
+    Binary {
+        op: DerivedOp,
+        lhs: u32,
+        rhs: u32,
+    },
+    /// Node `term` evaluated on every descendant of the entry, at any depth,
+    /// and summed. Descendants where `term` has no value are skipped.
+    SumDescendants {
+        term: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DerivedOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl DerivedExpr {
+    pub fn constant(value: f64) -> Self {
+        DerivedExpr {
+            nodes: vec![DerivedNode::Constant(value)],
+        }
+    }
+
+    pub fn attribute(attribute_id: Uuid) -> Self {
+        DerivedExpr {
+            nodes: vec![DerivedNode::Attribute(attribute_id)],
+        }
+    }
+
+    /// This expression summed over the entry's descendants.
+    pub fn sum_descendants(mut self) -> Self {
+        let term = self.root();
+        self.nodes.push(DerivedNode::SumDescendants { term });
+        self
+    }
+
+    /// Epley's one-rep max estimate: `load * (1 + reps / 30)`.
+    pub fn epley(load: Self, reps: Self) -> Self {
+        load * (Self::constant(1.0) + reps / Self::constant(30.0))
+    }
+
+    /// Brzycki's one-rep max estimate: `load * 36 / (37 - reps)`.
+    pub fn brzycki(load: Self, reps: Self) -> Self {
+        load * Self::constant(36.0) / (Self::constant(37.0) - reps)
+    }
+
+    /// Every attribute the expression reads directly. Derived attributes among
+    /// them read further attributes of their own.
+    pub fn references(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.nodes.iter().filter_map(|n| match n {
+            DerivedNode::Attribute(id) => Some(*id),
+            _ => None,
+        })
+    }
+
+    /// Validate the expression's shape: at least one node, every index refers
+    /// to an earlier node, and constants are finite.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| ValidationError::InvalidDerivedConfig(msg).into();
+        if self.nodes.is_empty() {
+            return Err(invalid("expression is empty".to_string()));
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            let operands = match node {
+                DerivedNode::Constant(c) if !c.is_finite() => {
+                    return Err(invalid(format!("constant {c} is not finite")));
+                }
+                DerivedNode::Constant(_) | DerivedNode::Attribute(_) => vec![],
+                DerivedNode::Binary { lhs, rhs, .. } => vec![*lhs, *rhs],
+                DerivedNode::SumDescendants { term } => vec![*term],
+            };
+            if let Some(bad) = operands.into_iter().find(|o| *o as usize >= i) {
+                return Err(invalid(format!(
+                    "node {i} refers to node {bad}, which does not precede it"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn root(&self) -> u32 {
+        self.nodes.len() as u32 - 1
+    }
+
+    fn binary(mut self, op: DerivedOp, rhs: Self) -> Self {
+        let lhs = self.root();
+        let offset = self.nodes.len() as u32;
+        self.nodes
+            .extend(rhs.nodes.into_iter().map(|n| n.shifted(offset)));
+        let rhs = self.root();
+        self.nodes.push(DerivedNode::Binary { op, lhs, rhs });
+        self
+    }
+}
+
+impl DerivedNode {
+    /// This node with its operand indices moved up by `offset`, for appending
+    /// one expression's nodes after another's.
+    fn shifted(self, offset: u32) -> Self {
+        match self {
+            DerivedNode::Binary { op, lhs, rhs } => DerivedNode::Binary {
+                op,
+                lhs: lhs + offset,
+                rhs: rhs + offset,
+            },
+            DerivedNode::SumDescendants { term } => DerivedNode::SumDescendants {
+                term: term + offset,
+            },
+            node => node,
+        }
+    }
+}
+
+impl Add for DerivedExpr {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        self.binary(DerivedOp::Add, rhs)
+    }
+}
+
+impl Sub for DerivedExpr {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self.binary(DerivedOp::Subtract, rhs)
+    }
+}
+
+impl Mul for DerivedExpr {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        self.binary(DerivedOp::Multiply, rhs)
+    }
+}
+
+impl Div for DerivedExpr {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        self.binary(DerivedOp::Divide, rhs)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Plan,
+    Actual,
+}
+
+/// Everything a derived attribute reads when evaluated on an entry: the
+/// entry's subtree, the values on it, and the attributes referenced,
+/// transitively. Plans are computed from plans and actuals from actuals; an
+/// expression with any operand missing has no value.
+pub struct DerivedScope {
+    attributes: HashMap<Uuid, Attribute>,
+    forest: Forest,
+    values: HashMap<(Uuid, Uuid), Value>,
+}
+
+impl DerivedScope {
+    pub fn new(attributes: Vec<Attribute>, subtree: Vec<Entry>, values: Vec<Value>) -> Self {
+        DerivedScope {
+            attributes: attributes.into_iter().map(|a| (a.id, a)).collect(),
+            forest: Forest::from(subtree),
+            values: values
+                .into_iter()
+                .map(|v| ((v.entry_id, v.attribute_id), v))
+                .collect(),
+        }
+    }
+
+    /// The computed `(plan, actual)` of `attribute_id` on `entry_id`.
+    pub fn evaluate(&self, attribute_id: Uuid, entry_id: Uuid) -> (Option<f64>, Option<f64>) {
+        (
+            self.attribute(attribute_id, entry_id, Side::Plan, &mut vec![]),
+            self.attribute(attribute_id, entry_id, Side::Actual, &mut vec![]),
+        )
+    }
+
+    /// Fill in the computed plan and actual of every derived pair.
+    pub fn fill(&self, pairs: &mut [AttributePair]) {
+        for pair in pairs {
+            if let AttributePair::Derived(p) = pair {
+                (p.plan, p.actual) = self.evaluate(p.attr_id, p.entry_id);
+            }
+        }
+    }
+
+    fn attribute(
+        &self,
+        attribute_id: Uuid,
+        entry_id: Uuid,
+        side: Side,
+        visiting: &mut Vec<Uuid>,
+    ) -> Option<f64> {
+        let attribute = self.attributes.get(&attribute_id)?;
+        if let AttributeConfig::Derived(c) = &attribute.config {
+            // Cycles are rejected on write; one that slips through has no
+            // value rather than recursing forever.
+            if visiting.contains(&attribute_id) {
+                return None;
+            }
+            visiting.push(attribute_id);
+            let root = c.expression.nodes.len().checked_sub(1);
+            let result = root.and_then(|r| self.node(&c.expression, r, entry_id, side, visiting));
+            visiting.pop();
+            return result;
+        }
+        let value = self.values.get(&(entry_id, attribute_id))?;
+        let field = match side {
+            Side::Plan => value.plan.as_ref(),
+            Side::Actual => value.actual.as_ref(),
+        };
+        attribute.config.index_columns(field).0
+    }
+
+    fn node(
+        &self,
+        expr: &DerivedExpr,
+        index: usize,
+        entry_id: Uuid,
+        side: Side,
+        visiting: &mut Vec<Uuid>,
+    ) -> Option<f64> {
+        // Operands must precede their node, which also rules out loops within
+        // an expression that skipped validation.
+        let operand = |i: u32| (i as usize) < index;
+        match &expr.nodes[index] {
+            DerivedNode::Constant(c) => Some(*c),
+            DerivedNode::Attribute(id) => self.attribute(*id, entry_id, side, visiting),
+            DerivedNode::Binary { op, lhs, rhs } if operand(*lhs) && operand(*rhs) => {
+                let lhs = self.node(expr, *lhs as usize, entry_id, side, visiting)?;
+                let rhs = self.node(expr, *rhs as usize, entry_id, side, visiting)?;
+                let result = match op {
+                    DerivedOp::Add => lhs + rhs,
+                    DerivedOp::Subtract => lhs - rhs,
+                    DerivedOp::Multiply => lhs * rhs,
+                    DerivedOp::Divide => lhs / rhs,
+                };
+                // Division by zero has no value.
+                result.is_finite().then_some(result)
+            }
+            DerivedNode::SumDescendants { term } if operand(*term) => self
+                .forest
+                .descendants(entry_id)
+                .into_iter()
+                .filter(|d| d.id != entry_id)
+                .filter_map(|d| self.node(expr, *term as usize, d.id, side, visiting))
+                .reduce(|a, b| a + b),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attribute::{
+        AttributeValue, DerivedConfig, MassConfig, MassUnit, MassValue, Measure, NumericConfig,
+        NumericValue,
+    };
+    use crate::models::entry::{Position, Temporal};
+    use fractional_index::FractionalIndex;
+
+    fn attribute(config: AttributeConfig) -> Attribute {
+        Attribute {
+            id: Uuid::new_v4(),
+            owner_id: Uuid::nil(),
+            name: "Attribute".to_string(),
+            description: None,
+            config,
+        }
+    }
+
+    fn derived(expression: DerivedExpr) -> Attribute {
+        attribute(AttributeConfig::Derived(DerivedConfig { expression }))
+    }
+
+    fn entry(parent: Option<&Entry>) -> Entry {
+        Entry {
+            id: Uuid::new_v4(),
+            activity_id: None,
+            owner_id: Uuid::nil(),
+            name: None,
+            position: parent.map(|p| Position {
+                parent_id: p.id,
+                frac_index: FractionalIndex::default(),
+            }),
+            is_template: false,
+            display_as_sets: false,
+            is_sequence: parent.is_none(),
+            is_complete: false,
+            temporal: Temporal::None,
+        }
+    }
+
+    fn value(entry: &Entry, attribute: &Attribute, actual: AttributeValue) -> Value {
+        Value {
+            entry_id: entry.id,
+            attribute_id: attribute.id,
+            index_float: None,
+            index_string: None,
+            plan: None,
+            actual: Some(actual),
+        }
+    }
+
+    #[test]
+    fn evaluates_volume_tonnage_and_one_rep_max() {
+        let reps = attribute(AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }));
+        let load = attribute(AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram,
+        }));
+        let volume = derived(DerivedExpr::attribute(reps.id) * DerivedExpr::attribute(load.id));
+        let tonnage = derived(DerivedExpr::attribute(volume.id).sum_descendants());
+        let epley = derived(DerivedExpr::epley(
+            DerivedExpr::attribute(load.id),
+            DerivedExpr::attribute(reps.id),
+        ));
+        let brzycki = derived(DerivedExpr::brzycki(
+            DerivedExpr::attribute(load.id),
+            DerivedExpr::attribute(reps.id),
+        ));
+
+        let sets = entry(None);
+        let first = entry(Some(&sets));
+        let second = entry(Some(&sets));
+        let n = |v: f64| AttributeValue::Numeric(NumericValue::Exact(v));
+        let values = vec![
+            value(&first, &reps, n(5.0)),
+            value(
+                &first,
+                &load,
+                AttributeValue::Mass(MassValue::exact(MassUnit::Kilogram, 100.0)),
+            ),
+            value(&second, &reps, n(3.0)),
+            value(
+                &second,
+                &load,
+                AttributeValue::Mass(MassValue::exact(MassUnit::Gram, 110_000.0)),
+            ),
+        ];
+        let scope = DerivedScope::new(
+            vec![
+                reps,
+                load,
+                volume.clone(),
+                tonnage.clone(),
+                epley.clone(),
+                brzycki.clone(),
+            ],
+            vec![sets.clone(), first.clone(), second.clone()],
+            values,
+        );
+
+        assert_eq!(scope.evaluate(volume.id, first.id), (None, Some(500.0)));
+        assert_eq!(scope.evaluate(tonnage.id, sets.id), (None, Some(830.0)));
+        // Nothing to sum below a leaf, and nothing planned anywhere.
+        assert_eq!(scope.evaluate(tonnage.id, first.id), (None, None));
+        let (_, epley_1rm) = scope.evaluate(epley.id, first.id);
+        assert!((epley_1rm.unwrap() - 100.0 * (1.0 + 5.0 / 30.0)).abs() < 1e-9);
+        let (_, brzycki_1rm) = scope.evaluate(brzycki.id, first.id);
+        assert!((brzycki_1rm.unwrap() - 100.0 * 36.0 / 32.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn missing_operands_division_by_zero_and_cycles_have_no_value() {
+        let reps = attribute(AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }));
+        let per_rep = derived(DerivedExpr::constant(1.0) / DerivedExpr::attribute(reps.id));
+        let mut a = derived(DerivedExpr::constant(0.0));
+        let b = derived(DerivedExpr::attribute(a.id));
+        a.config = AttributeConfig::Derived(DerivedConfig {
+            expression: DerivedExpr::attribute(b.id),
+        });
+
+        let leaf = entry(None);
+        let zero = value(
+            &leaf,
+            &reps,
+            AttributeValue::Numeric(NumericValue::Exact(0.0)),
+        );
+        let scope = DerivedScope::new(
+            vec![reps, per_rep.clone(), a.clone(), b],
+            vec![leaf.clone()],
+            vec![zero],
+        );
+        assert_eq!(scope.evaluate(per_rep.id, leaf.id), (None, None));
+        assert_eq!(scope.evaluate(a.id, leaf.id), (None, None));
+    }
+
+    #[test]
+    fn operators_build_a_valid_flat_expression() {
+        let (x, y) = (Uuid::new_v4(), Uuid::new_v4());
+        let expr = (DerivedExpr::attribute(x) + DerivedExpr::constant(2.0))
+            * DerivedExpr::attribute(y).sum_descendants();
+        assert_eq!(
+            expr.nodes,
+            vec![
+                DerivedNode::Attribute(x),
+                DerivedNode::Constant(2.0),
+                DerivedNode::Binary {
+                    op: DerivedOp::Add,
+                    lhs: 0,
+                    rhs: 1
+                },
+                DerivedNode::Attribute(y),
+                DerivedNode::SumDescendants { term: 3 },
+                DerivedNode::Binary {
+                    op: DerivedOp::Multiply,
+                    lhs: 2,
+                    rhs: 4
+                },
+            ]
+        );
+        assert!(expr.validate().is_ok());
+        assert_eq!(expr.references().collect::<Vec<_>>(), vec![x, y]);
+
+        let forward = DerivedExpr {
+            nodes: vec![DerivedNode::SumDescendants { term: 0 }],
+        };
+        assert!(forward.validate().is_err());
+        assert!(DerivedExpr { nodes: vec![] }.validate().is_err());
+    }
+}
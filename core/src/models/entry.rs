@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use fractional_index::FractionalIndex;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
@@ -7,7 +8,7 @@ use crate::{
     error::{DomainError, RejectReason, Result, ValidationError},
 };
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Entry {
     pub id: Uuid,
     pub activity_id: Option<Uuid>,
@@ -61,9 +62,10 @@ impl Entry {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     pub parent_id: Uuid,
+    #[serde(with = "fractional_index::stringify")]
     pub frac_index: FractionalIndex,
 }
 
@@ -94,7 +96,7 @@ impl Position {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Temporal {
     None,
     Start {
@@ -1,10 +1,12 @@
+use serde::{Deserialize, Serialize};
+
 use super::activity::Activity;
 use super::entry::Entry;
 use crate::models::attribute_pair::AttributePair;
 
 /// Domain model representing an Entry with all its joined relations.
 /// Currently includes the optional Activity and Attribute-Value pairs.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EntryJoin {
     pub entry: Entry,
     pub activity: Option<Activity>,
@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+
+/// A built-in climbing grade scale. Each grade carries a numeric difficulty on
+/// an axis shared by every scale of the same discipline, so grades compare and
+/// convert across scales (a 5.11a and a French 6c are both 11.0).
+///
+/// Route axis: the YDS number, with the letter grades in quarters (5.10a =
+/// 10.0, 5.10b = 10.25, ... 5.11a = 11.0) and the plus/minus grades between
+/// the letters they span (5.10- = 10.125, 5.10 = 10.375, 5.10+ = 10.625).
+/// Boulder axis: the V number (V4 =
+/// 4.0). Route and boulder grades measure different things and never convert
+/// into each other.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GradeScale {
+    /// Yosemite Decimal System (routes), 5.0 through 5.15d, with letter or
+    /// plus/minus grades from 5.10 up.
+    Yds,
+    /// French sport grades (routes), 4a through 9c.
+    French,
+    /// Hueco V-scale (boulders), V0 through V17.
+    VScale,
+    /// Fontainebleau (boulders), 4 through 9A.
+    Font,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GradeDiscipline {
+    Route,
+    Boulder,
+}
+
+const YDS: &[(&str, f64)] = &[
+    ("5.0", 0.0),
+    ("5.1", 1.0),
+    ("5.2", 2.0),
+    ("5.3", 3.0),
+    ("5.4", 4.0),
+    ("5.5", 5.0),
+    ("5.6", 6.0),
+    ("5.7", 7.0),
+    ("5.8", 8.0),
+    ("5.9", 9.0),
+    ("5.10a", 10.0),
+    ("5.10-", 10.125),
+    ("5.10b", 10.25),
+    ("5.10", 10.375),
+    ("5.10c", 10.5),
+    ("5.10+", 10.625),
+    ("5.10d", 10.75),
+    ("5.11a", 11.0),
+    ("5.11-", 11.125),
+    ("5.11b", 11.25),
+    ("5.11", 11.375),
+    ("5.11c", 11.5),
+    ("5.11+", 11.625),
+    ("5.11d", 11.75),
+    ("5.12a", 12.0),
+    ("5.12-", 12.125),
+    ("5.12b", 12.25),
+    ("5.12", 12.375),
+    ("5.12c", 12.5),
+    ("5.12+", 12.625),
+    ("5.12d", 12.75),
+    ("5.13a", 13.0),
+    ("5.13-", 13.125),
+    ("5.13b", 13.25),
+    ("5.13", 13.375),
+    ("5.13c", 13.5),
+    ("5.13+", 13.625),
+    ("5.13d", 13.75),
+    ("5.14a", 14.0),
+    ("5.14-", 14.125),
+    ("5.14b", 14.25),
+    ("5.14", 14.375),
+    ("5.14c", 14.5),
+    ("5.14+", 14.625),
+    ("5.14d", 14.75),
+    ("5.15a", 15.0),
+    ("5.15-", 15.125),
+    ("5.15b", 15.25),
+    ("5.15", 15.375),
+    ("5.15c", 15.5),
+    ("5.15+", 15.625),
+    ("5.15d", 15.75),
+];
+
+const FRENCH: &[(&str, f64)] = &[
+    ("4a", 5.0),
+    ("4b", 6.0),
+    ("4c", 7.0),
+    ("5a", 8.0),
+    ("5b", 9.0),
+    ("5c", 9.5),
+    ("6a", 10.0),
+    ("6a+", 10.25),
+    ("6b", 10.5),
+    ("6b+", 10.75),
+    ("6c", 11.0),
+    // Straddles 5.11b and 5.11c.
+    ("6c+", 11.375),
+    ("7a", 11.75),
+    ("7a+", 12.0),
+    ("7b", 12.25),
+    ("7b+", 12.5),
+    ("7c", 12.75),
+    ("7c+", 13.0),
+    ("8a", 13.25),
+    ("8a+", 13.5),
+    ("8b", 13.75),
+    ("8b+", 14.0),
+    ("8c", 14.25),
+    ("8c+", 14.5),
+    ("9a", 14.75),
+    ("9a+", 15.0),
+    ("9b", 15.25),
+    ("9b+", 15.5),
+    ("9c", 15.75),
+];
+
+const V_SCALE: &[(&str, f64)] = &[
+    ("V0", 0.0),
+    ("V1", 1.0),
+    ("V2", 2.0),
+    ("V3", 3.0),
+    ("V4", 4.0),
+    ("V5", 5.0),
+    ("V6", 6.0),
+    ("V7", 7.0),
+    ("V8", 8.0),
+    ("V9", 9.0),
+    ("V10", 10.0),
+    ("V11", 11.0),
+    ("V12", 12.0),
+    ("V13", 13.0),
+    ("V14", 14.0),
+    ("V15", 15.0),
+    ("V16", 16.0),
+    ("V17", 17.0),
+];
+
+const FONT: &[(&str, f64)] = &[
+    ("4", 0.0),
+    ("4+", 0.5),
+    ("5", 1.0),
+    ("5+", 2.0),
+    ("6A", 3.0),
+    ("6A+", 3.5),
+    ("6B", 4.0),
+    ("6B+", 4.5),
+    ("6C", 5.0),
+    ("6C+", 5.5),
+    ("7A", 6.0),
+    ("7A+", 7.0),
+    ("7B", 8.0),
+    ("7B+", 8.5),
+    ("7C", 9.0),
+    ("7C+", 10.0),
+    ("8A", 11.0),
+    ("8A+", 12.0),
+    ("8B", 13.0),
+    ("8B+", 14.0),
+    ("8C", 15.0),
+    ("8C+", 16.0),
+    ("9A", 17.0),
+];
+
+impl GradeScale {
+    pub fn discipline(&self) -> GradeDiscipline {
+        match self {
+            GradeScale::Yds | GradeScale::French => GradeDiscipline::Route,
+            GradeScale::VScale | GradeScale::Font => GradeDiscipline::Boulder,
+        }
+    }
+
+    /// Every grade on the scale with its difficulty, easiest first.
+    /// Difficulties are strictly increasing.
+    pub fn grades(&self) -> &'static [(&'static str, f64)] {
+        match self {
+            GradeScale::Yds => YDS,
+            GradeScale::French => FRENCH,
+            GradeScale::VScale => V_SCALE,
+            GradeScale::Font => FONT,
+        }
+    }
+
+    /// The grade labels in order, easiest first — the options a value picks
+    /// from.
+    pub fn options(&self) -> Vec<String> {
+        self.grades().iter().map(|(g, _)| g.to_string()).collect()
+    }
+
+    /// The difficulty of `grade` on the discipline's shared axis, or `None`
+    /// when `grade` isn't on this scale.
+    pub fn difficulty(&self, grade: &str) -> Option<f64> {
+        self.grades()
+            .iter()
+            .find(|(g, _)| *g == grade)
+            .map(|(_, d)| *d)
+    }
+
+    /// The grades `label` names on this scale, easiest and hardest: a grade of
+    /// the scale names itself twice. YDS also reads the labels of the std
+    /// lib's YDS select from before grades had their own type: "11+" is
+    /// 5.11+, and "<5.5" spans 5.0 to 5.4.
+    pub fn read(&self, label: &str) -> Option<(&'static str, &'static str)> {
+        let grade = |g: &str| self.grades().iter().find(|(known, _)| *known == g);
+        if let Some((g, _)) = grade(label) {
+            return Some((g, g));
+        }
+        match self {
+            GradeScale::Yds if label == "<5.5" => Some(("5.0", "5.4")),
+            // The old select's short forms: 10- through 13+.
+            GradeScale::Yds if label.len() > 1 && label.starts_with('1') => {
+                grade(&format!("5.{label}")).map(|(g, _)| (*g, *g))
+            }
+            _ => None,
+        }
+    }
+
+    /// The grade on this scale closest in difficulty to `difficulty`. An exact
+    /// tie goes to the easier grade, so a conversion never overstates a send.
+    pub fn nearest(&self, difficulty: f64) -> &'static str {
+        let mut best = self.grades()[0];
+        for entry in self.grades() {
+            if (entry.1 - difficulty).abs() < (best.1 - difficulty).abs() {
+                best = *entry;
+            }
+        }
+        best.0
+    }
+
+    /// Convert `grade` from this scale to the closest grade on `to`. `None`
+    /// when `grade` isn't on this scale or `to` is a different discipline.
+    pub fn convert(&self, grade: &str, to: &GradeScale) -> Option<&'static str> {
+        if self.discipline() != to.discipline() {
+            return None;
+        }
+        self.difficulty(grade).map(|d| to.nearest(d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [GradeScale; 4] = [
+        GradeScale::Yds,
+        GradeScale::French,
+        GradeScale::VScale,
+        GradeScale::Font,
+    ];
+
+    #[test]
+    fn difficulties_strictly_increase() {
+        for scale in ALL {
+            for pair in scale.grades().windows(2) {
+                assert!(pair[0].1 < pair[1].1, "{scale:?}: {pair:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn converts_within_a_discipline() {
+        assert_eq!(
+            GradeScale::Yds.convert("5.11a", &GradeScale::French),
+            Some("6c")
+        );
+        // 6c+ straddles 5.11b and 5.11c, as 5.11 does.
+        assert_eq!(
+            GradeScale::French.convert("6c+", &GradeScale::Yds),
+            Some("5.11")
+        );
+        assert_eq!(
+            GradeScale::Yds.convert("5.12+", &GradeScale::French),
+            Some("7b+")
+        );
+        assert_eq!(
+            GradeScale::Font.convert("7A", &GradeScale::VScale),
+            Some("V6")
+        );
+        // Halfway between V3 and V4: the tie goes to the easier grade.
+        assert_eq!(
+            GradeScale::Font.convert("6A+", &GradeScale::VScale),
+            Some("V3")
+        );
+        // Every grade converts to itself on its own scale.
+        for scale in ALL {
+            for (g, _) in scale.grades() {
+                assert_eq!(scale.convert(g, &scale), Some(*g));
+            }
+        }
+    }
+
+    #[test]
+    fn reads_the_old_yds_select_labels() {
+        let yds = GradeScale::Yds;
+        assert_eq!(yds.read("5.11+"), Some(("5.11+", "5.11+")));
+        assert_eq!(yds.read("11+"), Some(("5.11+", "5.11+")));
+        assert_eq!(yds.read("10-"), Some(("5.10-", "5.10-")));
+        assert_eq!(yds.read("13"), Some(("5.13", "5.13")));
+        assert_eq!(yds.read("5.7"), Some(("5.7", "5.7")));
+        assert_eq!(yds.read("<5.5"), Some(("5.0", "5.4")));
+        assert_eq!(yds.read("16"), None);
+        assert_eq!(GradeScale::French.read("11+"), None);
+        assert!(yds.difficulty("5.11-").unwrap() < yds.difficulty("5.11").unwrap());
+        assert!(yds.difficulty("5.11+").unwrap() < yds.difficulty("5.11d").unwrap());
+    }
+
+    #[test]
+    fn refuses_across_disciplines_and_unknown_grades() {
+        assert_eq!(GradeScale::Yds.convert("5.10a", &GradeScale::VScale), None);
+        assert_eq!(GradeScale::VScale.convert("5.10a", &GradeScale::Font), None);
+    }
+}
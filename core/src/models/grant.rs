@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use uuid::Uuid;
+
+use crate::error::{DomainError, Result};
+
+/// Permission an owner (`grantor_id`) has extended to another actor
+/// (`grantee_id`) over one of their resources. A grant has no effect until the
+/// grantee accepts it. See `docs/permissions.md` and `crate::permissions` for
+/// how a grant propagates beyond its scope.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grant {
+    pub id: Uuid,
+    pub grantor_id: Uuid,
+    pub grantee_id: Uuid,
+    pub scope: GrantScope,
+    pub level: GrantLevel,
+    pub accepted: bool,
+}
+
+/// The resource a grant is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GrantScope {
+    Entry(Uuid),
+    Activity(Uuid),
+    Attribute(Uuid),
+    /// The grantor's log, by their actor id. Only a `RootWrite` grant is over
+    /// a log, and a `RootWrite` grant is only over their log.
+    Log(Uuid),
+}
+
+impl GrantScope {
+    pub fn id(&self) -> Uuid {
+        match self {
+            GrantScope::Entry(id)
+            | GrantScope::Activity(id)
+            | GrantScope::Attribute(id)
+            | GrantScope::Log(id) => *id,
+        }
+    }
+
+    /// The `scope_kind` column value.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GrantScope::Entry(_) => "entry",
+            GrantScope::Activity(_) => "activity",
+            GrantScope::Attribute(_) => "attribute",
+            GrantScope::Log(_) => "log",
+        }
+    }
+
+    pub fn parse(kind: &str, id: Uuid) -> Result<GrantScope> {
+        match kind {
+            "entry" => Ok(GrantScope::Entry(id)),
+            "activity" => Ok(GrantScope::Activity(id)),
+            "attribute" => Ok(GrantScope::Attribute(id)),
+            "log" => Ok(GrantScope::Log(id)),
+            other => Err(DomainError::Database(
+                format!("unknown grant scope_kind: {other}").into(),
+            )),
+        }
+    }
+}
+
+/// What a grant allows. `Write` implies `Read`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GrantLevel {
+    Read,
+    Write,
+    /// Add root entries to the grantor's log (`GrantScope::Log`), and no
+    /// more: it reads nothing. Each root entry the grantee adds comes with an
+    /// accepted `Write` grant over it, so they can read and write what they
+    /// logged, and whatever is later logged under it.
+    RootWrite,
+}
+
+impl GrantLevel {
+    /// Whether a grant at this level allows access at `level`.
+    pub fn allows(self, level: GrantLevel) -> bool {
+        matches!(
+            (self, level),
+            (GrantLevel::Read | GrantLevel::Write, GrantLevel::Read)
+                | (GrantLevel::Write, GrantLevel::Write)
+                | (GrantLevel::RootWrite, GrantLevel::RootWrite)
+        )
+    }
+
+    pub fn parse(s: &str) -> Result<GrantLevel> {
+        match s {
+            "read" => Ok(GrantLevel::Read),
+            "write" => Ok(GrantLevel::Write),
+            "root_write" => Ok(GrantLevel::RootWrite),
+            other => Err(DomainError::Database(
+                format!("unknown grant level: {other}").into(),
+            )),
+        }
+    }
+}
+
+impl Display for GrantLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrantLevel::Read => write!(f, "read"),
+            GrantLevel::Write => write!(f, "write"),
+            GrantLevel::RootWrite => write!(f, "root_write"),
+        }
+    }
+}
@@ -2,6 +2,9 @@ pub mod activity;
 pub mod actor;
 pub mod attribute;
 pub mod attribute_pair;
+pub mod derived;
 pub mod entry;
 pub mod entry_join;
+pub mod grade;
+pub mod grant;
 pub mod user;
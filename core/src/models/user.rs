@@ -3,9 +3,10 @@ use crate::{
     delta::Delta,
     validation::{Email, Username},
 };
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct User {
     pub actor_id: Uuid,
     pub username: Username,
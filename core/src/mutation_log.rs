@@ -0,0 +1,102 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    delta::AnyDelta,
+    error::{DomainError, Result},
+    mutators::Mutation,
+};
+
+/// Where a locally applied mutation is in its trip to the server.
+///
+/// - `Pending`: applied locally, not yet sent.
+/// - `Sent`: pushed to the server, awaiting a verdict.
+/// - `Acked`: the server accepted it; it is part of the shared history.
+/// - `Rejected`: the server refused it; its local effects must be reverted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MutationState {
+    Pending,
+    Sent,
+    Acked,
+    Rejected,
+}
+
+impl MutationState {
+    pub fn parse(s: &str) -> Result<MutationState> {
+        match s {
+            "pending" => Ok(MutationState::Pending),
+            "sent" => Ok(MutationState::Sent),
+            "acked" => Ok(MutationState::Acked),
+            "rejected" => Ok(MutationState::Rejected),
+            other => Err(DomainError::Database(
+                format!("unknown mutation state: {other}").into(),
+            )),
+        }
+    }
+}
+
+impl Display for MutationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MutationState::Pending => write!(f, "pending"),
+            MutationState::Sent => write!(f, "sent"),
+            MutationState::Acked => write!(f, "acked"),
+            MutationState::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// A mutation as recorded in the client's local mutation log. `seq` is the
+/// client-local order the mutation was applied in; it is unrelated to the
+/// server's sequence numbers. `server_seq_num` is set once the server acks it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedMutation {
+    pub seq: i64,
+    pub state: MutationState,
+    pub server_seq_num: Option<i64>,
+    pub mutation: Mutation,
+}
+
+/// A delta's position in the server's global history: the sequence number of
+/// the transaction that committed it and its index within that transaction.
+/// Ordered lexicographically, so sorting offsets replays history in commit
+/// order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SyncOffset {
+    pub seq_num: i64,
+    pub op_offset: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StampedDelta {
+    pub offset: SyncOffset,
+    pub delta: AnyDelta,
+}
+
+/// A mutation as recorded in the server's mutation log. `seq_num` is the
+/// global sequence number assigned to the transaction that committed it;
+/// sequence numbers increase in commit order with no gaps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommittedMutation {
+    pub seq_num: i64,
+    pub mutation: Mutation,
+}
+
+impl CommittedMutation {
+    /// The mutation's deltas, each stamped with its `(seq_num, op_offset)`.
+    pub fn stamped_changes(&self) -> Vec<StampedDelta> {
+        self.mutation
+            .changes
+            .iter()
+            .enumerate()
+            .map(|(i, delta)| StampedDelta {
+                offset: SyncOffset {
+                    seq_num: self.seq_num,
+                    op_offset: i as u32,
+                },
+                delta: delta.clone(),
+            })
+            .collect()
+    }
+}
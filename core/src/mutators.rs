@@ -1,32 +1,47 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
 use chrono::{DateTime, Utc};
 use fractional_index::FractionalIndex;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     actions::{
-        Action, AttachValue, AttributeChange, ConvertToSets, CreateActivity, CreateAttribute,
-        CreateEntry, CreateEntryFromActivity, CreateUser, CreateValue, DeleteAttributeValue,
-        DeleteEntryRecursive, DuplicateEntry, EntryChange, LengthChange, MassChange, MoveEntry,
-        NumericChange, SelectChange, TextChange, UpdateAttribute, UpdateAttributeValue,
+        AcceptGrant, Action, ActivityChange, ArchiveActivity, AttachValue, AttributeChange,
+        AttributeConversion, AttributeMigration, BooleanChange, ConfigMigration,
+        ConvertAttributeType, ConvertToSets, CreateActivity, CreateAttribute, CreateEntry,
+        CreateEntryFromActivity, CreateUser, CreateValue, DeleteActivity, DeleteAttributeValue,
+        DeleteEntryRecursive, DerivedChange, DuplicateEntry, DurationChange, EntryChange,
+        GradeChange, GrantPermission, LengthChange, MassChange, MoveEntry, MultiselectChange,
+        NumericChange, OutOfRange, Redo, ReferencingEntries, RevokeGrant, SelectChange,
+        SpeedChange, TextChange, Undo, UpdateActivity, UpdateAttribute, UpdateAttributeValue,
         UpdateEntry, UpdateEntryCompletion, ValueField,
     },
-    delta::{AnyDelta, Delta},
-    error::{DomainError, RejectReason, Result},
+    delta::{AnyDelta, Delta, inverse_changes},
+    error::{DomainError, RejectReason, Result, ValidationError},
     forest::Forest,
     instantiation::{duplicate_subtree, instantiate_subtree},
     io::Io,
     models::{
+        activity::ActivityName,
         actor::{Actor, ActorKind},
-        attribute::{AttributeConfig, NumericValue, SelectValue, Value},
+        attribute::{
+            Attribute, AttributeConfig, AttributeValue, GradeConfig, LengthConfig, MassConfig,
+            MultiselectConfig, NumericValue, SelectConfig, SelectValue, TextConfig, Value,
+        },
         entry::{Entry, Position, Temporal},
+        grant::{Grant, GrantLevel, GrantScope},
         user::User,
     },
+    permissions::{Resource, adds_root, require_read, require_write},
     queries::{
-        FindActivityById, FindActivityTemplateRoot, FindAncestors, FindAttributeById,
-        FindDescendants, FindEntryById, FindUserById, FindUserByUsername, FindValueByKey,
-        FindValuesForEntries, IsEmailRegistered,
+        AllAttributes, FindActivitiesBySource, FindActivityById, FindActivityTemplateRoot,
+        FindAncestors, FindAttributeById, FindDescendants, FindEntriesByActivity, FindEntryById,
+        FindGrantById, FindGrantsByGrantor, FindMutationById, FindUserById, FindUserByUsername,
+        FindValueByKey, FindValuesForAttribute, FindValuesForEntries, FindValuesForEntry,
+        IsEmailRegistered, distinct_text_values,
     },
-    query_executor::AnyQueryExecutor,
+    query_executor::{AnyQueryExecutor, QueryExecutor},
 };
 
 // TODO: make randomness/time deterministic in mutations.
@@ -36,7 +51,7 @@ use crate::{
  * - All mutators must be capable of running in a transaction.
  */
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Mutation {
     pub id: Uuid,
     pub timestamp: DateTime<Utc>,
@@ -44,6 +59,35 @@ pub struct Mutation {
     pub changes: Vec<AnyDelta>,
 }
 
+impl Mutation {
+    /// The deltas that revert this mutation (see `delta::inverse_changes`).
+    pub fn inverse_changes(&self) -> Vec<AnyDelta> {
+        inverse_changes(self.changes.clone())
+    }
+
+    /// Whether this mutation belongs on an undo stack: it changed something,
+    /// isn't itself an undo/redo, and touches no user, actor or grant rows
+    /// (account and sharing changes are rejected by `undo`).
+    pub fn is_undoable(&self) -> bool {
+        !self.changes.is_empty()
+            && !matches!(self.action, Action::Undo(_) | Action::Redo(_))
+            && !self.changes.iter().any(|d| {
+                matches!(
+                    d,
+                    AnyDelta::User(_) | AnyDelta::Actor(_) | AnyDelta::Grant(_)
+                )
+            })
+    }
+
+    /// How many attribute values this mutation created, rewrote or removed.
+    pub fn values_changed(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|d| matches!(d, AnyDelta::Value(_)))
+            .count()
+    }
+}
+
 /// Template entries live "outside the timeline": they may carry a duration but
 /// never a start or end time. Shared by `create_entry` and `move_entry` so the
 /// invariant is enforced on every write path that sets a template's temporal.
@@ -71,6 +115,66 @@ async fn load_subtree_forest(
     ))
 }
 
+/// Re-derive the index columns of copied values (instantiation, duplication)
+/// from their attributes, so a copy never inherits a stale or missing index.
+async fn reindex_copies(
+    executor: &mut impl AnyQueryExecutor,
+    values: Vec<Value>,
+) -> Result<Vec<Value>> {
+    let attribute_ids: HashSet<Uuid> = values.iter().map(|v| v.attribute_id).collect();
+    let mut attributes = HashMap::new();
+    for attribute_id in attribute_ids {
+        let attribute = executor
+            .execute(FindAttributeById { attribute_id })
+            .await?
+            .ok_or_else(|| DomainError::InvariantViolation {
+                invariant: "value references an existing attribute",
+                context: format!("attribute '{attribute_id}'"),
+            })?;
+        attributes.insert(attribute_id, attribute);
+    }
+    Ok(values
+        .into_iter()
+        .map(|v| attributes[&v.attribute_id].indexed(v))
+        .collect())
+}
+
+/// Reject a derived attribute whose expression reaches back to itself
+/// through the derived attributes it references, or references one that
+/// doesn't exist. Attributes are read as `changes` (a replay, or nothing)
+/// leave them. Every stored attribute passed this check, so a walk from
+/// `attribute` finds any new cycle.
+async fn reject_derived_cycle(
+    executor: &mut impl AnyQueryExecutor,
+    changes: &[AnyDelta],
+    attribute: &crate::models::attribute::Attribute,
+) -> Result<()> {
+    let AttributeConfig::Derived(cfg) = &attribute.config else {
+        return Ok(());
+    };
+    let mut pending: Vec<Uuid> = cfg.expression.references().collect();
+    let mut seen = HashSet::new();
+    while let Some(attribute_id) = pending.pop() {
+        if attribute_id == attribute.id {
+            return Err(DomainError::Rejected(RejectReason::Precondition(
+                "derived attribute would depend on itself",
+            )));
+        }
+        if !seen.insert(attribute_id) {
+            continue;
+        }
+        let Some(referenced) = replayed_attribute(executor, changes, attribute_id).await? else {
+            return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+                "referenced attribute '{attribute_id}' does not exist"
+            ))));
+        };
+        if let AttributeConfig::Derived(c) = &referenced.config {
+            pending.extend(c.expression.references());
+        }
+    }
+    Ok(())
+}
+
 /// The activity constraint a sets sequence imposes on an incoming member:
 /// `None` when the sequence has no members yet (unconstrained), otherwise
 /// `Some(activity)` — the value every member shares (`Some(id)` for an
@@ -209,17 +313,13 @@ pub async fn create_user(
 }
 
 pub async fn create_activity(
-    _executor: &mut impl AnyQueryExecutor,
+    executor: &mut impl AnyQueryExecutor,
     io: &dyn Io,
     action: CreateActivity,
 ) -> Result<Mutation> {
     let activity = action.activity.clone();
-    if action.actor_id != activity.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not authorized to create activities for owner '{}'",
-            action.actor_id, activity.owner_id
-        ))));
-    }
+    // A new activity has no grants yet, so only its owner can write it.
+    require_write(executor, action.actor_id, Resource::Activity(&activity)).await?;
 
     // Templates must form a tree.
     let template_forest = Forest::from(action.template.clone());
@@ -267,22 +367,295 @@ pub async fn create_activity(
     })
 }
 
-pub async fn create_entry(
+/// Delete an activity (see `DeleteActivity`). Its template tree and the
+/// template's values go with it; entries elsewhere that reference it are
+/// detached or deleted as `referencing_entries` says, and block the delete
+/// without it. Copies of the activity lose their `source_activity_id` link.
+pub async fn delete_activity(
     executor: &mut impl AnyQueryExecutor,
     io: &dyn Io,
-    action: CreateEntry,
+    action: DeleteActivity,
 ) -> Result<Mutation> {
-    // Check if actor has permission to create entry at the given position.
-    // For now, only allow the owner to create.
-    if action.actor_id != action.entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not authorized to create entry for owner '{}' in parent entry '{:?}'",
-            action.actor_id,
-            action.entry.owner_id,
-            action.entry.position.map(|p| p.parent_id)
+    let Some(activity) = executor
+        .execute(FindActivityById {
+            id: action.activity_id,
+        })
+        .await?
+    else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "delete activity failed, activity '{}' not found",
+            action.activity_id
         ))));
+    };
+    require_write(executor, action.actor_id, Resource::Activity(&activity)).await?;
+
+    let mut deleted = match executor
+        .execute(FindActivityTemplateRoot {
+            activity_id: activity.id,
+        })
+        .await?
+    {
+        Some(root) => {
+            executor
+                .execute(FindDescendants { entry_id: root.id })
+                .await?
+        }
+        None => vec![],
+    };
+    let template_ids: HashSet<Uuid> = deleted.iter().map(|e| e.id).collect();
+    let referencing: Vec<Entry> = executor
+        .execute(FindEntriesByActivity {
+            activity_id: activity.id,
+        })
+        .await?
+        .into_iter()
+        .filter(|e| !template_ids.contains(&e.id))
+        .collect();
+
+    let mut detached: Vec<AnyDelta> = vec![];
+    match action.referencing_entries {
+        _ if referencing.is_empty() => {}
+        None => {
+            return Err(DomainError::Rejected(RejectReason::Precondition(
+                "activity is still referenced by entries: detach or delete them",
+            )));
+        }
+        Some(ReferencingEntries::Detach) => {
+            for entry in referencing {
+                require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
+                let name = entry
+                    .name
+                    .clone()
+                    .filter(|n| !n.is_empty())
+                    .unwrap_or_else(|| activity.name.to_string());
+                detached.push(
+                    entry
+                        .update()
+                        .activity_id(None)
+                        .name(Some(name))
+                        .to_delta()
+                        .into(),
+                );
+            }
+        }
+        Some(ReferencingEntries::DeleteRecursive) => {
+            let mut deleted_ids = template_ids.clone();
+            for entry in referencing {
+                if deleted_ids.contains(&entry.id) {
+                    continue;
+                }
+                // Sets members all share the activity, so the whole sets
+                // sequence goes rather than being left empty.
+                let mut root_id = entry.id;
+                if let Some(parent_id) = entry.parent_id() {
+                    let parent = executor
+                        .execute(FindEntryById {
+                            entry_id: parent_id,
+                        })
+                        .await?;
+                    if parent.is_some_and(|p| p.display_as_sets) {
+                        root_id = parent_id;
+                    }
+                }
+                let subtree = executor
+                    .execute(FindDescendants { entry_id: root_id })
+                    .await?;
+                // Every entry that goes, not just the referencing one: the
+                // subtree may reach entries the actor was never granted.
+                for e in subtree {
+                    if deleted_ids.insert(e.id) {
+                        require_write(executor, action.actor_id, Resource::Entry(&e)).await?;
+                        deleted.push(e);
+                    }
+                }
+            }
+        }
+    }
+
+    // Grants over a deleted row would outlive it; their grantors are the
+    // rows' owners.
+    let mut grantors: Vec<Uuid> = deleted.iter().map(|e| e.owner_id).collect();
+    grantors.push(activity.owner_id);
+    grantors.sort();
+    grantors.dedup();
+    let deleted_ids: HashSet<Uuid> = deleted.iter().map(|e| e.id).collect();
+    let mut grants = vec![];
+    for grantor_id in grantors {
+        grants.extend(
+            executor
+                .execute(FindGrantsByGrantor { grantor_id })
+                .await?
+                .into_iter()
+                .filter(|g| match g.scope {
+                    GrantScope::Entry(id) => deleted_ids.contains(&id),
+                    GrantScope::Activity(id) => id == activity.id,
+                    GrantScope::Attribute(_) | GrantScope::Log(_) => false,
+                }),
+        );
     }
 
+    let values = executor
+        .execute(FindValuesForEntries {
+            entry_ids: deleted.iter().map(|e| e.id).collect(),
+        })
+        .await?;
+    // Copies may belong to other actors; unlinking them only drops a
+    // reference to a row that is going away.
+    let copies = executor
+        .execute(FindActivitiesBySource {
+            source_activity_id: activity.id,
+        })
+        .await?;
+
+    // Referencing rows change before the rows they reference are deleted.
+    let mut deltas: Vec<AnyDelta> = values
+        .into_iter()
+        .map(|v| Delta::Delete { old: v }.into())
+        .collect();
+    deltas.extend(detached);
+    deltas.extend(
+        grants
+            .into_iter()
+            .map(|g| AnyDelta::from(Delta::Delete { old: g })),
+    );
+    deltas.extend(
+        deleted
+            .into_iter()
+            .map(|e| AnyDelta::from(Delta::Delete { old: e })),
+    );
+    deltas.extend(
+        copies
+            .into_iter()
+            .map(|a| a.update().source_activity_id(None).to_delta().into()),
+    );
+    deltas.push(Delta::Delete { old: activity }.into());
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: action.into(),
+        changes: deltas,
+    })
+}
+
+/// Archive or restore an activity (see `ArchiveActivity`). Already in the
+/// requested state is a no-op.
+pub async fn archive_activity(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: ArchiveActivity,
+) -> Result<Mutation> {
+    let Some(activity) = executor
+        .execute(FindActivityById {
+            id: action.activity_id,
+        })
+        .await?
+    else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "archive activity failed, activity '{}' not found",
+            action.activity_id
+        ))));
+    };
+    require_write(executor, action.actor_id, Resource::Activity(&activity)).await?;
+
+    let changes = if activity.archived == action.archived {
+        vec![]
+    } else {
+        vec![
+            activity
+                .update()
+                .archived(action.archived)
+                .to_delta()
+                .into(),
+        ]
+    };
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: action.into(),
+        changes,
+    })
+}
+
+/// Edit an activity's name, description or source link. A change that
+/// leaves the activity as it was is a no-op.
+pub async fn update_activity(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: UpdateActivity,
+) -> Result<Mutation> {
+    let Some(activity) = executor
+        .execute(FindActivityById {
+            id: action.activity_id,
+        })
+        .await?
+    else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(
+            "activity does not exist".to_string(),
+        )));
+    };
+    require_write(executor, action.actor_id, Resource::Activity(&activity)).await?;
+
+    let updater = match &action.change {
+        ActivityChange::SetName(name) => activity.update().name(ActivityName::parse(name.clone())?),
+        ActivityChange::SetDescription(description) => {
+            activity.update().description(description.clone())
+        }
+        ActivityChange::SetSourceActivityId(source_activity_id) => {
+            // The new source must be readable, and following source links
+            // from it must never come back around to this activity. A chain
+            // that loops without reaching it is already broken in the store.
+            let mut next = *source_activity_id;
+            let mut visited = HashSet::new();
+            while let Some(id) = next {
+                if id == activity.id {
+                    return Err(DomainError::Rejected(RejectReason::Precondition(
+                        "activity cannot be its own source",
+                    )));
+                }
+                if !visited.insert(id) {
+                    return Err(DomainError::InvariantViolation {
+                        invariant: "activity source links are acyclic",
+                        context: format!("activity '{}'", id),
+                    });
+                }
+                let Some(source) = executor.execute(FindActivityById { id }).await? else {
+                    return Err(DomainError::Rejected(RejectReason::NotFound(
+                        "source activity does not exist".to_string(),
+                    )));
+                };
+                if Some(id) == *source_activity_id {
+                    require_read(executor, action.actor_id, Resource::Activity(&source)).await?;
+                }
+                next = source.source_activity_id;
+            }
+            activity.update().source_activity_id(*source_activity_id)
+        }
+    };
+
+    let delta = updater.to_delta();
+    let changes = match &delta {
+        Delta::Update { old, new } if old == new => vec![],
+        _ => vec![delta.into()],
+    };
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: action.into(),
+        changes,
+    })
+}
+
+pub async fn create_entry(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: CreateEntry,
+) -> Result<Mutation> {
+    // Check if actor has permission to create entry at the given position: a
+    // root entry by its owner or by a root-write grant over their log, a child
+    // by anyone who can write the parent's tree.
+    require_write(executor, action.actor_id, Resource::Entry(&action.entry)).await?;
+
     // display_as_sets is earned, not born: a fresh entry has no members, so
     // it cannot satisfy the sets shape (>=1 member). The flag is set later
     // via ConvertToSets / UpdateEntry(SetDisplayAsSets).
@@ -292,18 +665,18 @@ pub async fn create_entry(
         )));
     }
 
-    // Check if referenced activity exists.
+    // Check if referenced activity exists and is readable by the actor.
     if let Some(activity_id) = action.entry.activity_id {
-        if executor
+        let Some(activity) = executor
             .execute(FindActivityById { id: activity_id })
             .await?
-            .is_none()
-        {
+        else {
             return Err(DomainError::Rejected(RejectReason::NotFound(format!(
                 "create entry failed, activity '{}' not found",
                 activity_id
             ))));
-        }
+        };
+        require_read(executor, action.actor_id, Resource::Activity(&activity)).await?;
     };
 
     // A child must match its parent's template/log kind — a template tree and a
@@ -322,6 +695,13 @@ pub async fn create_entry(
                 )));
             }
 
+            // Every entry tree is owned by a single actor.
+            if parent.owner_id != action.entry.owner_id {
+                return Err(DomainError::Rejected(RejectReason::Precondition(
+                    "child entry must have its parent's owner",
+                )));
+            }
+
             // Joining a sets sequence: the new member must match the members'
             // shared activity (or shared anonymity).
             if parent.display_as_sets {
@@ -344,15 +724,40 @@ pub async fn create_entry(
     let insert_entry = Delta::Insert {
         new: action.entry.clone(),
     };
+    let mut changes = vec![insert_entry.into()];
+    changes.extend(read_your_writes(executor, io, action.actor_id, &action.entry).await?);
 
     Ok(Mutation {
         id: io.uuid(),
         timestamp: io.current_time_wall_clock(),
         action: Action::CreateEntry(action),
-        changes: vec![insert_entry.into()],
+        changes,
     })
 }
 
+/// When the actor adds `root` to another actor's log by a `RootWrite` grant,
+/// the accepted `Write` grant over it that lets them keep reading and writing
+/// it (see `permissions::adds_root`).
+async fn read_your_writes(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    actor_id: Uuid,
+    root: &Entry,
+) -> Result<Option<AnyDelta>> {
+    if !adds_root(executor, actor_id, root).await? {
+        return Ok(None);
+    }
+    let grant = Grant {
+        id: io.uuid(),
+        grantor_id: root.owner_id,
+        grantee_id: actor_id,
+        scope: GrantScope::Entry(root.id),
+        level: GrantLevel::Write,
+        accepted: true,
+    };
+    Ok(Some(Delta::Insert { new: grant }.into()))
+}
+
 /// Instantiate an activity's template into a fresh log subtree. Finds the
 /// activity's template root, deep-copies the subtree (entries + values) with new
 /// ids and `is_template` cleared, and places the instantiated root at the given
@@ -375,13 +780,10 @@ pub async fn create_entry_from_activity(
             )))
         })?;
 
-    // Only the owner can instantiate their activities (for now).
-    if action.actor_id != activity.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not authorized to instantiate activity owned by '{}'",
-            action.actor_id, activity.owner_id
-        ))));
-    }
+    // The instantiated entries belong to the activity's owner, so the actor
+    // must be able to read the activity and write where the copy lands (see
+    // below, once the root is built).
+    require_read(executor, action.actor_id, Resource::Activity(&activity)).await?;
 
     // Placement validation mirrors move_entry, applied to the instantiated root.
     if let Some(position) = &action.position {
@@ -407,6 +809,11 @@ pub async fn create_entry_from_activity(
                 "instantiated subtree must match its parent's template/log kind",
             )));
         }
+        if parent.owner_id != activity.owner_id {
+            return Err(DomainError::Rejected(RejectReason::Precondition(
+                "instantiated subtree must have its parent's owner",
+            )));
+        }
 
         // Joining a sets sequence: the instantiated root is an entry of this
         // activity, which must match the members' shared activity.
@@ -468,12 +875,29 @@ pub async fn create_entry_from_activity(
         action.temporal.clone(),
         action.is_template,
     );
+    let values = reindex_copies(executor, values).await?;
+
+    let instantiated_root = entries
+        .iter()
+        .find(|e| e.position == action.position)
+        .ok_or_else(|| DomainError::InvariantViolation {
+            invariant: "instantiation yields a root at the requested position",
+            context: format!("activity '{}'", action.activity_id),
+        })?;
+    require_write(
+        executor,
+        action.actor_id,
+        Resource::Entry(instantiated_root),
+    )
+    .await?;
+    let grant = read_your_writes(executor, io, action.actor_id, instantiated_root).await?;
 
     let mut deltas: Vec<AnyDelta> = entries
         .into_iter()
         .map(|e| Delta::Insert { new: e }.into())
         .collect();
     deltas.extend(values.into_iter().map(|v| Delta::Insert { new: v }.into()));
+    deltas.extend(grant);
 
     Ok(Mutation {
         id: io.uuid(),
@@ -505,6 +929,15 @@ pub async fn move_entry(
         )));
     };
 
+    // The actor must be able to write the entry where it is and where it
+    // lands.
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
+    let moved = Entry {
+        position: action.position.clone(),
+        ..entry.clone()
+    };
+    require_write(executor, action.actor_id, Resource::Entry(&moved)).await?;
+
     if let Some(position) = &action.position {
         // Root template entries must remain at the root.
         if entry.is_template && entry.position.is_none() {
@@ -572,6 +1005,13 @@ pub async fn move_entry(
             )));
         }
 
+        // Every entry tree is owned by a single actor.
+        if parent.owner_id != entry.owner_id {
+            return Err(DomainError::Rejected(RejectReason::Precondition(
+                "cannot move entry into another owner's tree",
+            )));
+        }
+
         // Joining a sets sequence: the incoming member must match the
         // members' shared activity (or shared anonymity). A same-parent
         // reorder passes trivially — the mover is one of the members the
@@ -668,11 +1108,7 @@ pub async fn delete_entry_recursive(
     };
 
     // Check if actor has permission to delete.
-    if action.actor_id != root.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(
-            "delete_entry_recursive actor is not the owner of the deleting entry".to_string(),
-        )));
-    }
+    require_write(executor, action.actor_id, Resource::Entry(root)).await?;
 
     // The last member of a sets sequence cannot be deleted (display_as_sets
     // requires >=1 member — break out or delete the sequence itself instead).
@@ -735,12 +1171,7 @@ pub async fn convert_to_sets(
         )));
     };
 
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not the owner of entry '{}'",
-            action.actor_id, entry.id
-        ))));
-    }
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     // An activity template's root must keep activity_id == activity.id, and
     // the wrap sequence is anonymous — converting the root would break the
@@ -856,12 +1287,7 @@ pub async fn duplicate_entry(
         )));
     };
 
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not the owner of entry '{}'",
-            action.actor_id, entry.id
-        ))));
-    }
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     // Each activity has exactly one template root; duplicating it would mint
     // a second.
@@ -902,6 +1328,7 @@ pub async fn duplicate_entry(
         .await?;
 
     let (entries, values) = duplicate_subtree(io, entry.id, &subtree, &values, root_position);
+    let values = reindex_copies(executor, values).await?;
 
     let mut deltas: Vec<AnyDelta> = entries
         .into_iter()
@@ -918,21 +1345,17 @@ pub async fn duplicate_entry(
 }
 
 pub async fn create_attribute(
-    _executor: &mut impl AnyQueryExecutor,
+    executor: &mut impl AnyQueryExecutor,
     io: &dyn Io,
     action: CreateAttribute,
 ) -> Result<Mutation> {
     let attribute = action.attribute.clone();
 
-    // Only the owner can create attributes for themselves (for now).
-    if action.actor_id != attribute.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not authorized to create attributes for owner '{}'",
-            action.actor_id, attribute.owner_id
-        ))));
-    }
+    // A new attribute has no grants yet, so only its owner can write it.
+    require_write(executor, action.actor_id, Resource::Attribute(&attribute)).await?;
 
     attribute.config.validate()?;
+    reject_derived_cycle(executor, &[], &attribute).await?;
 
     let insert_attribute = Delta::Insert { new: attribute };
 
@@ -960,13 +1383,7 @@ pub async fn update_entry_completion(
         )));
     };
 
-    // Only the owner may complete their own entries.
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not the owner of entry '{}'",
-            action.actor_id, entry.id
-        ))));
-    }
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     // Template entries represent activity definitions, not logged events.
     if entry.is_template {
@@ -1012,13 +1429,8 @@ pub async fn create_value(
             )))
         })?;
 
-    // Only the entry owner can create values on it.
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not authorized to create values on entry owned by '{}'",
-            action.actor_id, entry.owner_id
-        ))));
-    }
+    // Values follow their entry.
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     // The attribute must exist and be owned by the same actor as the entry.
     let attribute = executor
@@ -1039,6 +1451,7 @@ pub async fn create_value(
             attribute.owner_id, entry.owner_id
         ))));
     }
+    require_read(executor, action.actor_id, Resource::Attribute(&attribute)).await?;
 
     if let Some(plan) = &value.plan {
         attribute.validate_value(plan)?;
@@ -1065,7 +1478,10 @@ pub async fn create_value(
         });
     }
 
-    let insert_value = Delta::Insert { new: value };
+    // The index columns are derived here, never taken from the action.
+    let insert_value = Delta::Insert {
+        new: attribute.indexed(value),
+    };
 
     Ok(Mutation {
         id: io.uuid(),
@@ -1095,13 +1511,8 @@ pub async fn attach_value(
             )))
         })?;
 
-    // Only the entry owner can attach values to it.
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not authorized to attach values on entry owned by '{}'",
-            action.actor_id, entry.owner_id
-        ))));
-    }
+    // Values follow their entry.
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     let attribute = executor
         .execute(FindAttributeById {
@@ -1121,6 +1532,7 @@ pub async fn attach_value(
             attribute.owner_id, entry.owner_id
         ))));
     }
+    require_read(executor, action.actor_id, Resource::Attribute(&attribute)).await?;
 
     // No-op if already attached.
     if executor
@@ -1188,13 +1600,8 @@ pub async fn delete_attribute_value(
             }
         })?;
 
-    // Only the entry owner can detach values from it.
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not the owner of entry '{}'",
-            action.actor_id, entry.id
-        ))));
-    }
+    // Values follow their entry.
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     Ok(Mutation {
         id: io.uuid(),
@@ -1220,12 +1627,7 @@ pub async fn update_attribute_value(
         )));
     };
 
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not the owner of entry '{}'",
-            action.actor_id, entry.id
-        ))));
-    }
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     let Some(attribute) = executor
         .execute(FindAttributeById {
@@ -1255,7 +1657,7 @@ pub async fn update_attribute_value(
         )));
     };
 
-    let new = match action.field {
+    let new = attribute.indexed(match action.field {
         ValueField::Plan => Value {
             plan: action.value.clone(),
             ..old.clone()
@@ -1264,7 +1666,7 @@ pub async fn update_attribute_value(
             actual: action.value.clone(),
             ..old.clone()
         },
-    };
+    });
 
     Ok(Mutation {
         id: io.uuid(),
@@ -1278,7 +1680,10 @@ pub async fn update_attribute_value(
 /// description) are unconstrained; type-specific edits are rejected if the
 /// variant doesn't match the attribute's config type, and `Set*Default` edits
 /// are validated against the config (select option membership, numeric
-/// integer/min/max). A change that leaves the attribute unchanged is a no-op.
+/// integer/min/max). Edits to bounds, options or flags must leave every
+/// stored value of the attribute valid; an option rename rewrites the values
+/// that select it in the same mutation. A change that leaves the attribute
+/// unchanged is a no-op.
 pub async fn update_attribute(
     executor: &mut impl AnyQueryExecutor,
     io: &dyn Io,
@@ -1295,15 +1700,16 @@ pub async fn update_attribute(
         )));
     };
 
-    // Only the owner can modify their attributes.
-    if action.actor_id != old.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not the owner of attribute '{}'",
-            action.actor_id, old.id
-        ))));
-    }
+    require_write(executor, action.actor_id, Resource::Attribute(&old)).await?;
 
     let mut new = old.clone();
+    // Edits that can invalidate stored values: re-check them all against the
+    // new config, after applying any option rename.
+    let mut recheck_values = false;
+    let mut renamed_option: Option<(&str, &str)> = None;
+    // Edits that shift a select's option ordinals, which ordered selects
+    // index: re-derive every stored value's index columns.
+    let mut reindex_values = false;
     match &action.change {
         AttributeChange::SetName(name) => new.name = name.clone(),
         AttributeChange::SetDescription(description) => new.description = description.clone(),
@@ -1319,6 +1725,39 @@ pub async fn update_attribute(
                     }
                     cfg.default = *default;
                 }
+                NumericChange::RaiseMax(max) => {
+                    let lowered = match (cfg.max, max) {
+                        (Some(old_max), Some(max)) => *max < old_max,
+                        (None, Some(_)) => true,
+                        (_, None) => false,
+                    };
+                    if lowered {
+                        return Err(DomainError::Rejected(RejectReason::Precondition(
+                            "attribute max can only be raised",
+                        )));
+                    }
+                    cfg.max = *max;
+                    cfg.validate()?;
+                }
+                NumericChange::LowerMin(min) => {
+                    let raised = match (cfg.min, min) {
+                        (Some(old_min), Some(min)) => *min > old_min,
+                        (None, Some(_)) => true,
+                        (_, None) => false,
+                    };
+                    if raised {
+                        return Err(DomainError::Rejected(RejectReason::Precondition(
+                            "attribute min can only be lowered",
+                        )));
+                    }
+                    cfg.min = *min;
+                    cfg.validate()?;
+                }
+                NumericChange::SetInteger(integer) => {
+                    cfg.integer = *integer;
+                    cfg.validate()?;
+                    recheck_values = *integer;
+                }
             }
         }
         AttributeChange::Select(change) => {
@@ -1333,6 +1772,53 @@ pub async fn update_attribute(
                     }
                     cfg.default = default.clone();
                 }
+                SelectChange::AddOption { option, position } => {
+                    insert_option(&mut cfg.options, option, *position);
+                    cfg.validate()?;
+                    reindex_values = cfg.ordered;
+                }
+                SelectChange::RenameOption { from, to } => {
+                    rename_option(&mut cfg.options, from, to)?;
+                    cfg.default = cfg
+                        .default
+                        .take()
+                        .map(|d| if d == *from { to.clone() } else { d });
+                    cfg.validate()?;
+                    renamed_option = Some((from.as_str(), to.as_str()));
+                }
+                SelectChange::SetOrdered(ordered) => {
+                    cfg.ordered = *ordered;
+                    recheck_values = !*ordered;
+                    reindex_values = true;
+                }
+            }
+        }
+        AttributeChange::Multiselect(change) => {
+            let AttributeConfig::Multiselect(cfg) = &mut new.config else {
+                return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
+            };
+            match change {
+                MultiselectChange::SetDefault(default) => {
+                    // Same rule as a value write: option membership, no repeats.
+                    if let Some(selected) = default {
+                        cfg.validate_value(selected)?;
+                    }
+                    cfg.default = default.clone();
+                }
+                MultiselectChange::AddOption { option, position } => {
+                    insert_option(&mut cfg.options, option, *position);
+                    cfg.validate()?;
+                }
+                MultiselectChange::RenameOption { from, to } => {
+                    rename_option(&mut cfg.options, from, to)?;
+                    cfg.default = cfg.default.take().map(|d| {
+                        d.into_iter()
+                            .map(|s| if s == *from { to.clone() } else { s })
+                            .collect()
+                    });
+                    cfg.validate()?;
+                    renamed_option = Some((from.as_str(), to.as_str()));
+                }
             }
         }
         AttributeChange::Mass(change) => {
@@ -1355,6 +1841,26 @@ pub async fn update_attribute(
                 }
             }
         }
+        AttributeChange::Speed(change) => {
+            let AttributeConfig::Speed(cfg) = &mut new.config else {
+                return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
+            };
+            match change {
+                SpeedChange::SetDefaultUnit(unit) => {
+                    cfg.default_unit = unit.clone();
+                }
+            }
+        }
+        AttributeChange::Duration(change) => {
+            let AttributeConfig::Duration(cfg) = &mut new.config else {
+                return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
+            };
+            match change {
+                DurationChange::SetDisplay(display) => {
+                    cfg.display = display.clone();
+                }
+            }
+        }
         AttributeChange::Text(change) => {
             let AttributeConfig::Text(cfg) = &mut new.config else {
                 return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
@@ -1372,11 +1878,47 @@ pub async fn update_attribute(
                 }
             }
         }
-    }
-
-    // No-op if the change left the attribute unchanged.
-    if new == old {
-        return Ok(Mutation {
+        AttributeChange::Grade(change) => {
+            let AttributeConfig::Grade(cfg) = &mut new.config else {
+                return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
+            };
+            match change {
+                GradeChange::SetDefault(default) => {
+                    // Same rule as a value write: the grade is on the scale.
+                    if let Some(s) = default {
+                        cfg.validate_value(&SelectValue::Exact(s.clone()))?;
+                    }
+                    cfg.default = default.clone();
+                }
+            }
+        }
+        AttributeChange::Boolean(change) => {
+            let AttributeConfig::Boolean(cfg) = &mut new.config else {
+                return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
+            };
+            match change {
+                BooleanChange::SetDefault(default) => {
+                    cfg.default = *default;
+                }
+            }
+        }
+        AttributeChange::Derived(change) => {
+            let AttributeConfig::Derived(cfg) = &mut new.config else {
+                return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
+            };
+            match change {
+                DerivedChange::SetExpression(expression) => {
+                    cfg.expression = expression.clone();
+                    cfg.validate()?;
+                    reject_derived_cycle(executor, &[], &new).await?;
+                }
+            }
+        }
+    }
+
+    // No-op if the change left the attribute unchanged.
+    if new == old {
+        return Ok(Mutation {
             id: io.uuid(),
             timestamp: io.current_time_wall_clock(),
             action: Action::UpdateAttribute(action),
@@ -1384,11 +1926,366 @@ pub async fn update_attribute(
         });
     }
 
+    let mut value_changes: Vec<AnyDelta> = vec![];
+    if recheck_values || reindex_values || renamed_option.is_some() {
+        // Every stored value, including those on entries the actor can't
+        // see: a value that stops conforming is broken for its owner too.
+        let values = executor
+            .execute(FindValuesForAttribute {
+                attribute_id: new.id,
+            })
+            .await?;
+        for value in values {
+            let mut updated = value.clone();
+            if let Some((from, to)) = renamed_option {
+                updated.plan = updated.plan.map(|v| v.with_option_renamed(from, to));
+                updated.actual = updated.actual.map(|v| v.with_option_renamed(from, to));
+            }
+            let updated = new.indexed(updated);
+            for field in [&updated.plan, &updated.actual].into_iter().flatten() {
+                if new.validate_value(field).is_err() {
+                    return Err(DomainError::Rejected(RejectReason::Precondition(
+                        "stored values would no longer fit the attribute",
+                    )));
+                }
+            }
+            if updated != value {
+                value_changes.push(
+                    Delta::<Value>::Update {
+                        old: value,
+                        new: updated,
+                    }
+                    .into(),
+                );
+            }
+        }
+    }
+
+    let mut changes: Vec<AnyDelta> =
+        vec![Delta::<crate::models::attribute::Attribute>::Update { old, new }.into()];
+    changes.extend(value_changes);
     Ok(Mutation {
         id: io.uuid(),
         timestamp: io.current_time_wall_clock(),
         action: Action::UpdateAttribute(action),
-        changes: vec![Delta::<crate::models::attribute::Attribute>::Update { old, new }.into()],
+        changes,
+    })
+}
+
+/// Insert `option` at `position` (clamped to the end), or append it.
+fn insert_option(options: &mut Vec<String>, option: &str, position: Option<u32>) {
+    let index = position.map_or(options.len(), |p| (p as usize).min(options.len()));
+    options.insert(index, option.to_string());
+}
+
+/// Rename option `from` in place. `to` must not already be an option (the
+/// caller's config validation catches the duplicate).
+fn rename_option(options: &mut [String], from: &str, to: &str) -> Result<()> {
+    let Some(option) = options.iter_mut().find(|o| *o == from) else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "option '{from}' does not exist"
+        ))));
+    };
+    *option = to.to_string();
+    Ok(())
+}
+
+/// Apply an `AttributeMigration`: change the config non-additively and
+/// rewrite every stored value (on any entry, like `update_attribute`'s
+/// option rename) that the new config would reject, per the migration's
+/// rule. A rewritten value that still doesn't fit — e.g. a select range
+/// whose replacement endpoints end up out of order — rejects the migration.
+pub async fn migrate_attribute(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: AttributeMigration,
+) -> Result<Mutation> {
+    let Some(old) = executor
+        .execute(FindAttributeById {
+            attribute_id: action.attribute_id,
+        })
+        .await?
+    else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(
+            "attribute does not exist".to_string(),
+        )));
+    };
+    require_write(executor, action.actor_id, Resource::Attribute(&old)).await?;
+
+    let mut new = old.clone();
+    // Removed option -> replacement (`None` clears); empty for bounds changes.
+    let mut remap: BTreeMap<String, Option<String>> = BTreeMap::new();
+    match &action.migration {
+        ConfigMigration::RemoveOptions(removals) => {
+            let options = match &mut new.config {
+                AttributeConfig::Select(cfg) => &mut cfg.options,
+                AttributeConfig::Multiselect(cfg) => &mut cfg.options,
+                _ => return Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+            };
+            for removal in removals {
+                let Some(index) = options.iter().position(|o| *o == removal.option) else {
+                    return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+                        "option '{}' does not exist",
+                        removal.option
+                    ))));
+                };
+                options.remove(index);
+                remap.insert(removal.option.clone(), removal.replacement.clone());
+            }
+            for replacement in removals.iter().filter_map(|r| r.replacement.as_ref()) {
+                if !options.contains(replacement) {
+                    return Err(ValidationError::InvalidValue(format!(
+                        "replacement '{replacement}' is not one of the remaining options"
+                    ))
+                    .into());
+                }
+            }
+            match &mut new.config {
+                AttributeConfig::Select(cfg) => {
+                    cfg.default = cfg
+                        .default
+                        .take()
+                        .and_then(|d| remap.get(&d).cloned().unwrap_or(Some(d)));
+                }
+                AttributeConfig::Multiselect(cfg) => {
+                    cfg.default = cfg.default.take().and_then(|d| {
+                        AttributeValue::Multiselect(d)
+                            .with_options_remapped(&remap)
+                            .and_then(|d| d.expect_multiselect().ok())
+                    });
+                }
+                _ => unreachable!("checked above"),
+            }
+        }
+        ConfigMigration::SetBounds {
+            min,
+            max,
+            out_of_range,
+        } => {
+            let AttributeConfig::Numeric(cfg) = &mut new.config else {
+                return Err(DomainError::Rejected(RejectReason::AttributeMismatch));
+            };
+            cfg.min = *min;
+            cfg.max = *max;
+            if let Some(d) = cfg.default
+                && cfg.validate_value(&NumericValue::Exact(d)).is_err()
+            {
+                cfg.default = match out_of_range {
+                    OutOfRange::Clamp => match NumericValue::Exact(d).clamped(*min, *max) {
+                        NumericValue::Exact(d) => Some(d),
+                        NumericValue::Range { .. } => {
+                            unreachable!("clamping keeps exact values exact")
+                        }
+                    },
+                    OutOfRange::Clear => None,
+                };
+            }
+        }
+    }
+    new.config.validate()?;
+
+    let migrate = |v: AttributeValue| match &action.migration {
+        ConfigMigration::RemoveOptions(_) => v.with_options_remapped(&remap),
+        ConfigMigration::SetBounds {
+            min,
+            max,
+            out_of_range,
+        } => match v {
+            AttributeValue::Numeric(n)
+                if new
+                    .validate_value(&AttributeValue::Numeric(n.clone()))
+                    .is_err() =>
+            {
+                match out_of_range {
+                    OutOfRange::Clamp => Some(AttributeValue::Numeric(n.clamped(*min, *max))),
+                    OutOfRange::Clear => None,
+                }
+            }
+            other => Some(other),
+        },
+    };
+    let mut changes: Vec<AnyDelta> = vec![];
+    for value in executor
+        .execute(FindValuesForAttribute {
+            attribute_id: new.id,
+        })
+        .await?
+    {
+        let migrated = new.indexed(Value {
+            plan: value.plan.clone().and_then(migrate),
+            actual: value.actual.clone().and_then(migrate),
+            ..value.clone()
+        });
+        for field in [&migrated.plan, &migrated.actual].into_iter().flatten() {
+            if new.validate_value(field).is_err() {
+                return Err(DomainError::Rejected(RejectReason::Precondition(
+                    "migrated value would not fit the attribute",
+                )));
+            }
+        }
+        if migrated != value {
+            changes.push(
+                Delta::<Value>::Update {
+                    old: value,
+                    new: migrated,
+                }
+                .into(),
+            );
+        }
+    }
+    if new != old {
+        changes.insert(
+            0,
+            Delta::<crate::models::attribute::Attribute>::Update { old, new }.into(),
+        );
+    }
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: action.into(),
+        changes,
+    })
+}
+
+/// Apply a `ConvertAttributeType`: replace the attribute's config with one of
+/// the target type and convert every stored value (on any entry, like
+/// `migrate_attribute`) with `AttributeValue::converted_for`. All or nothing —
+/// a value or default that would lose data rejects the whole conversion.
+pub async fn convert_attribute_type(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: ConvertAttributeType,
+) -> Result<Mutation> {
+    let Some(old) = executor
+        .execute(FindAttributeById {
+            attribute_id: action.attribute_id,
+        })
+        .await?
+    else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(
+            "attribute does not exist".to_string(),
+        )));
+    };
+    require_write(executor, action.actor_id, Resource::Attribute(&old)).await?;
+
+    let values = executor
+        .execute(FindValuesForAttribute {
+            attribute_id: old.id,
+        })
+        .await?;
+    let numeric_default_lost = Err(DomainError::Rejected(RejectReason::Precondition(
+        "a numeric default has no unit-typed equivalent; clear it first",
+    )));
+    let config: AttributeConfig = match (&old.config, &action.conversion) {
+        (AttributeConfig::Numeric(cfg), AttributeConversion::ToMass(unit)) => {
+            if cfg.default.is_some() {
+                return numeric_default_lost;
+            }
+            MassConfig {
+                default_unit: unit.clone(),
+            }
+            .into()
+        }
+        (AttributeConfig::Numeric(cfg), AttributeConversion::ToLength(unit)) => {
+            if cfg.default.is_some() {
+                return numeric_default_lost;
+            }
+            LengthConfig {
+                default_unit: unit.clone(),
+            }
+            .into()
+        }
+        (AttributeConfig::Select(cfg), AttributeConversion::ToMultiselect) => MultiselectConfig {
+            options: cfg.options.clone(),
+            default: cfg.default.clone().map(|d| vec![d]),
+        }
+        .into(),
+        (AttributeConfig::Select(cfg), AttributeConversion::ToText) => TextConfig {
+            default: cfg.default.clone(),
+            autocomplete: true,
+        }
+        .into(),
+        (AttributeConfig::Text(cfg), AttributeConversion::ToSelect) => {
+            let mut options = distinct_text_values(&values);
+            if let Some(d) = &cfg.default
+                && !options.contains(d)
+            {
+                options.push(d.clone());
+            }
+            SelectConfig {
+                options,
+                ordered: false,
+                default: cfg.default.clone(),
+            }
+            .into()
+        }
+        (AttributeConfig::Select(cfg), AttributeConversion::ToGrade(scale)) => {
+            let default = match &cfg.default {
+                None => None,
+                Some(d) => match scale.read(d) {
+                    Some((min, max)) if min == max => Some(min.to_string()),
+                    _ => {
+                        return Err(DomainError::Rejected(RejectReason::Precondition(
+                            "the default is not one grade on the target scale",
+                        )));
+                    }
+                },
+            };
+            GradeConfig {
+                scale: scale.clone(),
+                default,
+            }
+            .into()
+        }
+        _ => return Err(DomainError::Rejected(RejectReason::AttributeMismatch)),
+    };
+    config.validate()?;
+    let new = crate::models::attribute::Attribute {
+        config,
+        ..old.clone()
+    };
+
+    let convert = |field: Option<AttributeValue>| -> Result<Option<AttributeValue>> {
+        let Some(v) = field else {
+            return Ok(None);
+        };
+        match v.converted_for(&new.config) {
+            Some(converted) if new.validate_value(&converted).is_ok() => Ok(Some(converted)),
+            _ => Err(DomainError::Rejected(RejectReason::Precondition(
+                "a stored value has no equivalent in the target type",
+            ))),
+        }
+    };
+    let mut changes: Vec<AnyDelta> = vec![
+        Delta::<crate::models::attribute::Attribute>::Update {
+            old,
+            new: new.clone(),
+        }
+        .into(),
+    ];
+    for value in values {
+        let converted = new.indexed(Value {
+            plan: convert(value.plan.clone())?,
+            actual: convert(value.actual.clone())?,
+            ..value.clone()
+        });
+        if converted != value {
+            changes.push(
+                Delta::<Value>::Update {
+                    old: value,
+                    new: converted,
+                }
+                .into(),
+            );
+        }
+    }
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: action.into(),
+        changes,
     })
 }
 
@@ -1412,12 +2309,7 @@ pub async fn update_entry(
         )));
     };
 
-    if action.actor_id != entry.owner_id {
-        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
-            "actor '{}' is not the owner of entry '{}'",
-            action.actor_id, entry.id
-        ))));
-    }
+    require_write(executor, action.actor_id, Resource::Entry(&entry)).await?;
 
     let mut deltas: Vec<AnyDelta> = vec![];
 
@@ -1512,3 +2404,609 @@ pub async fn update_entry(
         changes: deltas,
     })
 }
+
+/// Offer another actor access to one of the actor's entries, activities or
+/// attributes, or root-write access to their log (see `GrantPermission`).
+/// Only the owner can share a resource; access gained through a grant can't
+/// be passed on.
+pub async fn grant_permission(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: GrantPermission,
+) -> Result<Mutation> {
+    if matches!(action.scope, GrantScope::Log(_)) != (action.level == GrantLevel::RootWrite) {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "a log is granted root-write access, and only a log is",
+        )));
+    }
+
+    let owner_id = match action.scope {
+        GrantScope::Entry(entry_id) => executor
+            .execute(FindEntryById { entry_id })
+            .await?
+            .map(|e| e.owner_id),
+        GrantScope::Activity(id) => executor
+            .execute(FindActivityById { id })
+            .await?
+            .map(|a| a.owner_id),
+        GrantScope::Attribute(attribute_id) => executor
+            .execute(FindAttributeById { attribute_id })
+            .await?
+            .map(|a| a.owner_id),
+        GrantScope::Log(actor_id) => executor
+            .execute(FindUserById { actor_id })
+            .await?
+            .map(|u| u.actor_id),
+    };
+    let Some(owner_id) = owner_id else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "{} '{}' not found",
+            action.scope.kind(),
+            action.scope.id()
+        ))));
+    };
+
+    if action.actor_id != owner_id {
+        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
+            "actor '{}' is not the owner of {} '{}'",
+            action.actor_id,
+            action.scope.kind(),
+            action.scope.id()
+        ))));
+    }
+
+    if action.grantee_id == action.actor_id {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "cannot grant permission to yourself",
+        )));
+    }
+
+    if executor
+        .execute(FindUserById {
+            actor_id: action.grantee_id,
+        })
+        .await?
+        .is_none()
+    {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "grantee '{}' not found",
+            action.grantee_id
+        ))));
+    }
+
+    if executor
+        .execute(FindGrantById {
+            grant_id: action.grant_id,
+        })
+        .await?
+        .is_some()
+    {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "grant_id already in use",
+        )));
+    }
+
+    let grant = Grant {
+        id: action.grant_id,
+        grantor_id: action.actor_id,
+        grantee_id: action.grantee_id,
+        scope: action.scope,
+        level: action.level,
+        accepted: false,
+    };
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: Action::GrantPermission(action),
+        changes: vec![Delta::Insert { new: grant }.into()],
+    })
+}
+
+/// Accept a grant offered to the actor (see `AcceptGrant`). Accepting an
+/// already accepted grant is a no-op.
+pub async fn accept_grant(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: AcceptGrant,
+) -> Result<Mutation> {
+    let Some(grant) = executor
+        .execute(FindGrantById {
+            grant_id: action.grant_id,
+        })
+        .await?
+    else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "grant '{}' not found",
+            action.grant_id
+        ))));
+    };
+
+    if action.actor_id != grant.grantee_id {
+        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
+            "actor '{}' is not the grantee of grant '{}'",
+            action.actor_id, grant.id
+        ))));
+    }
+
+    if grant.accepted {
+        return Ok(Mutation {
+            id: io.uuid(),
+            timestamp: io.current_time_wall_clock(),
+            action: Action::AcceptGrant(action),
+            changes: vec![],
+        });
+    }
+
+    let new = Grant {
+        accepted: true,
+        ..grant.clone()
+    };
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: Action::AcceptGrant(action),
+        changes: vec![Delta::Update { old: grant, new }.into()],
+    })
+}
+
+/// Delete a grant (see `RevokeGrant`). Either side may end it: the grantor to
+/// revoke access, the grantee to decline or give it up.
+pub async fn revoke_grant(
+    executor: &mut impl AnyQueryExecutor,
+    io: &dyn Io,
+    action: RevokeGrant,
+) -> Result<Mutation> {
+    let Some(grant) = executor
+        .execute(FindGrantById {
+            grant_id: action.grant_id,
+        })
+        .await?
+    else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "grant '{}' not found",
+            action.grant_id
+        ))));
+    };
+
+    if action.actor_id != grant.grantor_id && action.actor_id != grant.grantee_id {
+        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
+            "actor '{}' is not a party to grant '{}'",
+            action.actor_id, grant.id
+        ))));
+    }
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: Action::RevokeGrant(action),
+        changes: vec![Delta::Delete { old: grant }.into()],
+    })
+}
+
+/// The state of a row as left by the last delta in `staged` that touches it:
+/// `None` when no staged delta touches the row (read the store instead),
+/// `Some(None)` when the row was staged for deletion.
+fn staged_row<'a, M: Clone + 'a>(
+    staged: impl DoubleEndedIterator<Item = &'a Delta<M>>,
+    is_row: impl Fn(&M) -> bool,
+) -> Option<Option<M>> {
+    staged
+        .rev()
+        .find(|d| d.before().or(d.after()).is_some_and(&is_row))
+        .map(|d| d.after().cloned())
+}
+
+fn entry_deltas(changes: &[AnyDelta]) -> impl DoubleEndedIterator<Item = &Delta<Entry>> {
+    changes.iter().filter_map(|d| match d {
+        AnyDelta::Entry(d) => Some(d),
+        _ => None,
+    })
+}
+
+fn attribute_deltas(changes: &[AnyDelta]) -> impl DoubleEndedIterator<Item = &Delta<Attribute>> {
+    changes.iter().filter_map(|d| match d {
+        AnyDelta::Attribute(d) => Some(d),
+        _ => None,
+    })
+}
+
+/// An attribute as `changes` leave it: staged by them, or else as stored.
+async fn replayed_attribute(
+    executor: &mut impl AnyQueryExecutor,
+    changes: &[AnyDelta],
+    attribute_id: Uuid,
+) -> Result<Option<Attribute>> {
+    match staged_row(attribute_deltas(changes), |a| a.id == attribute_id) {
+        Some(state) => Ok(state),
+        None => executor.execute(FindAttributeById { attribute_id }).await,
+    }
+}
+
+/// Reject removing `attribute_id` while a derived attribute, as `changes`
+/// leave it, still reads it.
+async fn reject_referenced_attribute(
+    executor: &mut impl AnyQueryExecutor,
+    changes: &[AnyDelta],
+    attribute_id: Uuid,
+) -> Result<()> {
+    let mut ids: HashSet<Uuid> = executor
+        .execute(AllAttributes)
+        .await?
+        .into_iter()
+        .map(|a| a.id)
+        .collect();
+    ids.extend(attribute_deltas(changes).filter_map(|d| d.after().map(|a| a.id)));
+    for id in ids {
+        let Some(attribute) = replayed_attribute(executor, changes, id).await? else {
+            continue;
+        };
+        if let AttributeConfig::Derived(cfg) = &attribute.config
+            && cfg.expression.references().any(|r| r == attribute_id)
+        {
+            return Err(DomainError::Rejected(RejectReason::Precondition(
+                "attribute is read by a derived attribute",
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Every delta in a replay must find its row exactly as it left it: an
+/// insert expects no row, an update or delete expects `old`.
+fn expect_current<M: PartialEq>(delta: &Delta<M>, current: Option<&M>) -> Result<()> {
+    if delta.before() != current {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "a row has changed since the mutation was applied",
+        )));
+    }
+    Ok(())
+}
+
+/// The entry whose access governs a replayed entry row. Parents restored by
+/// the same replay aren't in the store yet, so their ancestors can't be looked
+/// up; walk up through them to the topmost restored entry instead. Entry trees
+/// have a single owner, so access there is access to the whole chain.
+fn replay_access_root<'a>(changes: &'a [AnyDelta], entry: &'a Entry) -> &'a Entry {
+    let mut current = entry;
+    // Bounded by the number of deltas, so a malformed replay with a parent
+    // cycle can't loop forever.
+    for _ in 0..changes.len() {
+        let Some(parent_id) = current.parent_id() else {
+            break;
+        };
+        match entry_deltas(changes)
+            .filter_map(|d| d.after())
+            .find(|e| e.id == parent_id)
+        {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+    current
+}
+
+/// Check that `changes` — the deltas of an undo or redo — can be applied on
+/// top of the current state. Each delta's `old` row must still be what the
+/// store holds (accounting for earlier deltas in the list), so a row edited
+/// since the original mutation blocks the replay instead of being clobbered.
+/// Restored rows are re-validated against the rest of the store, which may
+/// have moved on: an entry's restored parent must still be a compatible
+/// sequence without creating a cycle, a deleted entry must take its children
+/// and values with it, restored values must still conform to their
+/// attribute, a restored derived attribute must still reference existing
+/// attributes without a cycle, and a removed attribute must not be read by a
+/// derived one. The actor must still have write access to every touched row:
+/// a revoked grant blocks undoing what it allowed.
+async fn validate_replay(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    changes: &[AnyDelta],
+) -> Result<()> {
+    let deleted_entries: HashSet<Uuid> = changes
+        .iter()
+        .filter_map(|d| match d {
+            AnyDelta::Entry(Delta::Delete { old }) => Some(old.id),
+            _ => None,
+        })
+        .collect();
+    let deleted_values: HashSet<(Uuid, Uuid)> = changes
+        .iter()
+        .filter_map(|d| match d {
+            AnyDelta::Value(Delta::Delete { old }) => Some((old.entry_id, old.attribute_id)),
+            _ => None,
+        })
+        .collect();
+
+    for (i, delta) in changes.iter().enumerate() {
+        let staged = &changes[..i];
+        match delta {
+            AnyDelta::User(_) | AnyDelta::Actor(_) => {
+                return Err(DomainError::Rejected(RejectReason::Precondition(
+                    "user and actor changes cannot be undone",
+                )));
+            }
+            AnyDelta::Grant(_) => {
+                return Err(DomainError::Rejected(RejectReason::Precondition(
+                    "grant changes cannot be undone",
+                )));
+            }
+            AnyDelta::Activity(d) => {
+                let Some(row) = d.before().or(d.after()) else {
+                    continue;
+                };
+                let id = row.id;
+                let staged_activities = staged.iter().filter_map(|d| match d {
+                    AnyDelta::Activity(d) => Some(d),
+                    _ => None,
+                });
+                let current = match staged_row(staged_activities, |a| a.id == id) {
+                    Some(state) => state,
+                    None => executor.execute(FindActivityById { id }).await?,
+                };
+                expect_current(d, current.as_ref())?;
+                for activity in d.before().into_iter().chain(d.after()) {
+                    require_write(executor, actor_id, Resource::Activity(activity)).await?;
+                }
+            }
+            AnyDelta::Attribute(d) => {
+                let Some(row) = d.before().or(d.after()) else {
+                    continue;
+                };
+                let attribute_id = row.id;
+                let current = match staged_row(attribute_deltas(staged), |a| a.id == attribute_id) {
+                    Some(state) => state,
+                    None => executor.execute(FindAttributeById { attribute_id }).await?,
+                };
+                expect_current(d, current.as_ref())?;
+                for attribute in d.before().into_iter().chain(d.after()) {
+                    require_write(executor, actor_id, Resource::Attribute(attribute)).await?;
+                }
+                // Checked against the store as the whole replay leaves it,
+                // the way `create_attribute` and `update_attribute` check.
+                match d.after() {
+                    Some(attribute) => {
+                        attribute.config.validate()?;
+                        reject_derived_cycle(executor, changes, attribute).await?;
+                    }
+                    None => reject_referenced_attribute(executor, changes, attribute_id).await?,
+                }
+            }
+            AnyDelta::Entry(d) => {
+                let Some(row) = d.before().or(d.after()) else {
+                    continue;
+                };
+                let entry_id = row.id;
+                let current = match staged_row(entry_deltas(staged), |e| e.id == entry_id) {
+                    Some(state) => state,
+                    None => executor.execute(FindEntryById { entry_id }).await?,
+                };
+                expect_current(d, current.as_ref())?;
+                for entry in d.before().into_iter().chain(d.after()) {
+                    let root = replay_access_root(changes, entry);
+                    require_write(executor, actor_id, Resource::Entry(root)).await?;
+                }
+
+                match d.after() {
+                    Some(entry) => validate_replayed_entry(executor, changes, entry).await?,
+                    None => {
+                        // A deleted entry must not strand children or values
+                        // added since the original mutation.
+                        let subtree = executor.execute(FindDescendants { entry_id }).await?;
+                        if subtree.iter().any(|e| !deleted_entries.contains(&e.id)) {
+                            return Err(DomainError::Rejected(RejectReason::Precondition(
+                                "entry has gained children since the mutation was applied",
+                            )));
+                        }
+                        let values = executor.execute(FindValuesForEntry { entry_id }).await?;
+                        if values
+                            .iter()
+                            .any(|v| !deleted_values.contains(&(v.entry_id, v.attribute_id)))
+                        {
+                            return Err(DomainError::Rejected(RejectReason::Precondition(
+                                "entry has gained attribute values since the mutation was applied",
+                            )));
+                        }
+                    }
+                }
+            }
+            AnyDelta::Value(d) => {
+                let Some(row) = d.before().or(d.after()) else {
+                    continue;
+                };
+                let (entry_id, attribute_id) = (row.entry_id, row.attribute_id);
+                let staged_values = staged.iter().filter_map(|d| match d {
+                    AnyDelta::Value(d) => Some(d),
+                    _ => None,
+                });
+                let current = match staged_row(staged_values, |v| {
+                    v.entry_id == entry_id && v.attribute_id == attribute_id
+                }) {
+                    Some(state) => state,
+                    None => {
+                        executor
+                            .execute(FindValueByKey {
+                                entry_id,
+                                attribute_id,
+                            })
+                            .await?
+                    }
+                };
+                expect_current(d, current.as_ref())?;
+
+                let Some(value) = d.after() else {
+                    continue;
+                };
+                // The value's entry and attribute are checked as the whole
+                // replay leaves them: the replay may restore them too.
+                let entry = match staged_row(entry_deltas(changes), |e| e.id == entry_id) {
+                    Some(state) => state,
+                    None => executor.execute(FindEntryById { entry_id }).await?,
+                };
+                let Some(entry) = entry else {
+                    return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+                        "entry '{}' no longer exists",
+                        entry_id
+                    ))));
+                };
+                let root = replay_access_root(changes, &entry);
+                require_write(executor, actor_id, Resource::Entry(root)).await?;
+                let replayed_attributes = changes.iter().filter_map(|d| match d {
+                    AnyDelta::Attribute(d) => Some(d),
+                    _ => None,
+                });
+                let attribute = match staged_row(replayed_attributes, |a| a.id == attribute_id) {
+                    Some(state) => state,
+                    None => executor.execute(FindAttributeById { attribute_id }).await?,
+                };
+                let Some(attribute) = attribute else {
+                    return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+                        "attribute '{}' no longer exists",
+                        attribute_id
+                    ))));
+                };
+                for field in [&value.plan, &value.actual].into_iter().flatten() {
+                    attribute.validate_value(field)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Re-check the placement of an entry restored by a replay against the tree
+/// as it stands now — the same rules `move_entry` enforces on a move. The
+/// parent is looked up as the whole replay leaves it, since undoing a
+/// recursive delete restores parents and children together.
+async fn validate_replayed_entry(
+    executor: &mut impl AnyQueryExecutor,
+    changes: &[AnyDelta],
+    entry: &Entry,
+) -> Result<()> {
+    validate_template_temporal(entry.is_template, &entry.temporal)?;
+
+    let Some(position) = &entry.position else {
+        if !entry.is_template && entry.temporal.start().is_none() && entry.temporal.end().is_none()
+        {
+            return Err(DomainError::Rejected(RejectReason::Precondition(
+                "root entry must have defined start or end time",
+            )));
+        }
+        return Ok(());
+    };
+
+    let replayed_parent = staged_row(entry_deltas(changes), |e| e.id == position.parent_id);
+    let parent_in_store = replayed_parent.is_none();
+    let parent = match replayed_parent {
+        Some(state) => state,
+        None => {
+            executor
+                .execute(FindEntryById {
+                    entry_id: position.parent_id,
+                })
+                .await?
+        }
+    };
+    let Some(parent) = parent else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "parent entry '{}' no longer exists",
+            position.parent_id
+        ))));
+    };
+    if !parent.is_sequence {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "cannot move entry into a non-sequence entry",
+        )));
+    }
+    if entry.is_template != parent.is_template {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "template and log entries cannot share a tree",
+        )));
+    }
+    if entry.owner_id != parent.owner_id {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "cannot move entry into another owner's tree",
+        )));
+    }
+    // A parent restored by the replay is checked through its own delta; one
+    // already in the store must not have come to descend from the entry.
+    if parent_in_store {
+        let parent_ancestors = executor
+            .execute(FindAncestors {
+                entry_id: position.parent_id,
+            })
+            .await?;
+        if parent_ancestors.contains(&entry.id) {
+            return Err(DomainError::Rejected(RejectReason::Precondition(
+                "restoring the entry's position would create a cycle",
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The deltas of the mutation an undo or redo replays, as the log holds them.
+/// Only the actor's own mutations can be replayed, and never an undo or redo
+/// itself: those are replayed through the mutation they refer to.
+async fn logged_changes(
+    executor: &mut (impl AnyQueryExecutor + QueryExecutor<FindMutationById>),
+    actor_id: Uuid,
+    mutation_id: Uuid,
+) -> Result<Vec<AnyDelta>> {
+    let Some(logged) = executor.execute(FindMutationById { mutation_id }).await? else {
+        return Err(DomainError::Rejected(RejectReason::NotFound(format!(
+            "mutation '{}' not found",
+            mutation_id
+        ))));
+    };
+    if logged.action.actor_id() != actor_id {
+        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
+            "mutation '{}' was made by another actor",
+            mutation_id
+        ))));
+    }
+    if matches!(logged.action, Action::Undo(_) | Action::Redo(_)) {
+        return Err(DomainError::Rejected(RejectReason::Precondition(
+            "undo and redo mutations cannot themselves be replayed",
+        )));
+    }
+    Ok(logged.changes)
+}
+
+/// Revert a mutation (see `Undo`): apply the inverse of its logged deltas,
+/// provided every touched row is still as the mutation left it.
+pub async fn undo(
+    executor: &mut (impl AnyQueryExecutor + QueryExecutor<FindMutationById>),
+    io: &dyn Io,
+    action: Undo,
+) -> Result<Mutation> {
+    let changes =
+        inverse_changes(logged_changes(executor, action.actor_id, action.mutation_id).await?);
+    validate_replay(executor, action.actor_id, &changes).await?;
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: Action::Undo(action),
+        changes,
+    })
+}
+
+/// Re-apply an undone mutation (see `Redo`): apply its original logged
+/// deltas, provided every touched row is still as the undo left it.
+pub async fn redo(
+    executor: &mut (impl AnyQueryExecutor + QueryExecutor<FindMutationById>),
+    io: &dyn Io,
+    action: Redo,
+) -> Result<Mutation> {
+    let changes = logged_changes(executor, action.actor_id, action.mutation_id).await?;
+    validate_replay(executor, action.actor_id, &changes).await?;
+
+    Ok(Mutation {
+        id: io.uuid(),
+        timestamp: io.current_time_wall_clock(),
+        action: Action::Redo(action),
+        changes,
+    })
+}
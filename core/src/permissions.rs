@@ -0,0 +1,301 @@
+//! Read/write authorization for entries, activities and attributes. See
+//! `docs/permissions.md`.
+//!
+//! An owner can always read and write their own resources. Anyone else needs
+//! an accepted grant from the owner, and a grant reaches beyond its scope:
+//!
+//! - An entry grant covers the entry's subtree, the activities those entries
+//!   are logged against, and the attributes their values use.
+//! - An activity grant covers the activity's template tree and the attributes
+//!   its template values use.
+//! - An attribute grant covers just the attribute.
+//! - A root-write grant over the owner's log covers adding a root entry to
+//!   it, and nothing else. The mutator adding it grants the actor `Write`
+//!   over the new entry along with it (`adds_root`), so they read and write
+//!   what they logged, read-your-writes.
+//!
+//! A value has no access of its own; it follows its entry. Resources owned by
+//! the system actor are a shared library: anyone can read them.
+//!
+//! Every mutator calls `require_read` / `require_write` for the resources it
+//! reads from or writes to before producing deltas. Reads made on an actor's
+//! behalf go through a `ScopedQueryExecutor`, whose executor reads only the
+//! rows the actor can see.
+
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::{
+    SYSTEM_ACTOR_ID,
+    error::{DomainError, RejectReason, Result},
+    models::{
+        activity::Activity,
+        attribute::Attribute,
+        entry::Entry,
+        grant::{Grant, GrantLevel, GrantScope},
+    },
+    queries::{
+        FindActivityTemplateRoot, FindAncestors, FindDescendants, FindEntryById,
+        FindGrantsForGrantee, FindValuesForEntries,
+    },
+    query_executor::AnyQueryExecutor,
+};
+
+/// Everything an actor can read, by id: the same rule as `can_read`, computed
+/// for all rows at once by the `VisibleTo` query.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Visibility {
+    pub entries: HashSet<Uuid>,
+    pub activities: HashSet<Uuid>,
+    pub attributes: HashSet<Uuid>,
+}
+
+/// Something an actor can be granted access to.
+#[derive(Debug, Clone, Copy)]
+pub enum Resource<'a> {
+    Entry(&'a Entry),
+    Activity(&'a Activity),
+    Attribute(&'a Attribute),
+}
+
+impl Resource<'_> {
+    pub fn owner_id(&self) -> Uuid {
+        match self {
+            Resource::Entry(e) => e.owner_id,
+            Resource::Activity(a) => a.owner_id,
+            Resource::Attribute(a) => a.owner_id,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Resource::Entry(e) => format!("entry '{}'", e.id),
+            Resource::Activity(a) => format!("activity '{}'", a.id),
+            Resource::Attribute(a) => format!("attribute '{}'", a.id),
+        }
+    }
+}
+
+pub async fn can_read(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    resource: Resource<'_>,
+) -> Result<bool> {
+    has_access(executor, actor_id, resource, GrantLevel::Read).await
+}
+
+pub async fn can_write(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    resource: Resource<'_>,
+) -> Result<bool> {
+    has_access(executor, actor_id, resource, GrantLevel::Write).await
+}
+
+/// `can_read`, rejecting the action as `Unauthorized` when it doesn't hold.
+pub async fn require_read(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    resource: Resource<'_>,
+) -> Result<()> {
+    require(executor, actor_id, resource, GrantLevel::Read).await
+}
+
+/// `can_write`, rejecting the action as `Unauthorized` when it doesn't hold.
+pub async fn require_write(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    resource: Resource<'_>,
+) -> Result<()> {
+    require(executor, actor_id, resource, GrantLevel::Write).await
+}
+
+async fn require(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    resource: Resource<'_>,
+    level: GrantLevel,
+) -> Result<()> {
+    if !has_access(executor, actor_id, resource, level).await? {
+        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
+            "actor '{}' does not have {} access to {}",
+            actor_id,
+            level,
+            resource.describe()
+        ))));
+    }
+    Ok(())
+}
+
+async fn has_access(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    resource: Resource<'_>,
+    level: GrantLevel,
+) -> Result<bool> {
+    let owner_id = resource.owner_id();
+    if actor_id == owner_id || (owner_id == SYSTEM_ACTOR_ID && level == GrantLevel::Read) {
+        return Ok(true);
+    }
+    if let Resource::Entry(entry) = resource
+        && level == GrantLevel::Write
+        && adds_root(executor, actor_id, entry).await?
+    {
+        return Ok(true);
+    }
+
+    // Only accepted grants from the resource's owner, at a sufficient level,
+    // can apply.
+    let grants: Vec<Grant> = executor
+        .execute(FindGrantsForGrantee {
+            grantee_id: actor_id,
+        })
+        .await?
+        .into_iter()
+        .filter(|g| g.accepted && g.grantor_id == owner_id && g.level.allows(level))
+        .collect();
+    if grants.is_empty() {
+        return Ok(false);
+    }
+
+    match resource {
+        Resource::Entry(entry) => entry_access(executor, entry, &grants).await,
+        Resource::Activity(activity) => activity_access(executor, activity, &grants).await,
+        Resource::Attribute(attribute) => attribute_access(executor, attribute, &grants).await,
+    }
+}
+
+/// Whether `actor_id` is adding `entry` as a new root of another actor's log
+/// by an accepted `RootWrite` grant from its owner. Such an entry comes with
+/// an accepted `Write` grant over it for the actor.
+pub async fn adds_root(
+    executor: &mut impl AnyQueryExecutor,
+    actor_id: Uuid,
+    entry: &Entry,
+) -> Result<bool> {
+    if actor_id == entry.owner_id || entry.position.is_some() || entry.is_template {
+        return Ok(false);
+    }
+    let granted = executor
+        .execute(FindGrantsForGrantee {
+            grantee_id: actor_id,
+        })
+        .await?
+        .into_iter()
+        .any(|g| {
+            g.accepted
+                && g.grantor_id == entry.owner_id
+                && g.level == GrantLevel::RootWrite
+                && g.scope == GrantScope::Log(entry.owner_id)
+        });
+    if !granted {
+        return Ok(false);
+    }
+    Ok(executor
+        .execute(FindEntryById { entry_id: entry.id })
+        .await?
+        .is_none())
+}
+
+/// An entry is covered by a grant on itself or any ancestor, or by a grant on
+/// the activity whose template tree it belongs to. The entry need not exist
+/// yet: a new child is covered through its parent.
+async fn entry_access(
+    executor: &mut impl AnyQueryExecutor,
+    entry: &Entry,
+    grants: &[Grant],
+) -> Result<bool> {
+    let mut ancestors = vec![entry.id];
+    if let Some(parent_id) = entry.parent_id() {
+        ancestors.extend(
+            executor
+                .execute(FindAncestors {
+                    entry_id: parent_id,
+                })
+                .await?,
+        );
+    }
+    if grants
+        .iter()
+        .any(|g| matches!(g.scope, GrantScope::Entry(id) if ancestors.contains(&id)))
+    {
+        return Ok(true);
+    }
+
+    if !entry.is_template {
+        return Ok(false);
+    }
+    let root_id = *ancestors.last().expect("ancestors include the entry");
+    let root = if root_id == entry.id {
+        Some(entry.clone())
+    } else {
+        executor
+            .execute(FindEntryById { entry_id: root_id })
+            .await?
+    };
+    let Some(activity_id) = root.and_then(|r| r.activity_id) else {
+        return Ok(false);
+    };
+    Ok(grants
+        .iter()
+        .any(|g| g.scope == GrantScope::Activity(activity_id)))
+}
+
+/// An activity is covered by a grant on itself, or by an entry grant whose
+/// subtree logs against it.
+async fn activity_access(
+    executor: &mut impl AnyQueryExecutor,
+    activity: &Activity,
+    grants: &[Grant],
+) -> Result<bool> {
+    for grant in grants {
+        match grant.scope {
+            GrantScope::Activity(id) if id == activity.id => return Ok(true),
+            GrantScope::Entry(entry_id) => {
+                let subtree = executor.execute(FindDescendants { entry_id }).await?;
+                if subtree.iter().any(|e| e.activity_id == Some(activity.id)) {
+                    return Ok(true);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+/// An attribute is covered by a grant on itself, or by an entry or activity
+/// grant whose entries hold a value for it.
+async fn attribute_access(
+    executor: &mut impl AnyQueryExecutor,
+    attribute: &Attribute,
+    grants: &[Grant],
+) -> Result<bool> {
+    for grant in grants {
+        let root_id = match grant.scope {
+            GrantScope::Attribute(id) if id == attribute.id => return Ok(true),
+            GrantScope::Attribute(_) | GrantScope::Log(_) => continue,
+            GrantScope::Entry(entry_id) => entry_id,
+            GrantScope::Activity(activity_id) => {
+                match executor
+                    .execute(FindActivityTemplateRoot { activity_id })
+                    .await?
+                {
+                    Some(root) => root.id,
+                    None => continue,
+                }
+            }
+        };
+        let entry_ids = executor
+            .execute(FindDescendants { entry_id: root_id })
+            .await?
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        let values = executor.execute(FindValuesForEntries { entry_ids }).await?;
+        if values.iter().any(|v| v.attribute_id == attribute.id) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
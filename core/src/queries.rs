@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
 use crate::{
+    actions::ValueField,
+    analytics::{AnalyticsQuery, AnalyticsRow},
     models::{
         activity::Activity,
         actor::Actor,
@@ -10,8 +13,15 @@ use crate::{
         attribute_pair::AttributePair,
         entry::Entry,
         entry_join::EntryJoin,
+        grant::Grant,
         user::User,
     },
+    mutation_log::{CommittedMutation, LoggedMutation},
+    mutators::Mutation,
+    permissions::Visibility,
+    records::PersonalRecord,
+    series::{SeriesAggregate, SeriesBucket, SeriesPoint, SeriesRange, SeriesUnit},
+    training_load::{LoadWindow, TrainingLoadReport},
     validation::{Email, Username},
 };
 
@@ -29,7 +39,7 @@ macro_rules! define_query {
         $vis:vis struct $name:ident $body:tt => $response:ty
     ) => {
         $(#[$meta])*
-        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
         $vis struct $name $body
 
         impl sealed::Sealed for $name {}
@@ -39,7 +49,7 @@ macro_rules! define_query {
     };
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AnyQuery {
     // Auth
     IsEmailRegistered(IsEmailRegistered),
@@ -50,6 +60,8 @@ pub enum AnyQuery {
     FindActivityById(FindActivityById),
     AllActivities(AllActivities),
     FindActivityTemplateRoot(FindActivityTemplateRoot),
+    FindEntriesByActivity(FindEntriesByActivity),
+    FindActivitiesBySource(FindActivitiesBySource),
     // Entry
     AllEntries(AllEntries),
     EntriesRootedInTimeInterval(EntriesRootedInTimeInterval),
@@ -65,11 +77,21 @@ pub enum AnyQuery {
     FindValueByKey(FindValueByKey),
     FindValuesForEntry(FindValuesForEntry),
     FindValuesForEntries(FindValuesForEntries),
+    FindValuesForAttribute(FindValuesForAttribute),
     FindAttributePairsForEntry(FindAttributePairsForEntry),
     DistinctTextValuesForAttribute(DistinctTextValuesForAttribute),
-}
-
-#[derive(Clone, Debug, PartialEq)]
+    // Analytics
+    RunAnalytics(RunAnalytics),
+    PersonalRecords(PersonalRecords),
+    TrainingLoad(TrainingLoad),
+    AttributeSeries(AttributeSeries),
+    // Grant
+    FindGrantById(FindGrantById),
+    FindGrantsForGrantee(FindGrantsForGrantee),
+    FindGrantsByGrantor(FindGrantsByGrantor),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AnyQueryResponse {
     // Auth
     IsEmailRegistered(bool),
@@ -80,6 +102,8 @@ pub enum AnyQueryResponse {
     FindActivityById(Option<Activity>),
     AllActivities(Vec<Activity>),
     FindActivityTemplateRoot(Option<Entry>),
+    FindEntriesByActivity(Vec<Entry>),
+    FindActivitiesBySource(Vec<Activity>),
     // Entry
     AllEntries(Vec<Entry>),
     EntriesRootedInTimeInterval(Vec<Entry>),
@@ -95,8 +119,18 @@ pub enum AnyQueryResponse {
     FindValueByKey(Option<Value>),
     FindValuesForEntry(Vec<Value>),
     FindValuesForEntries(Vec<Value>),
+    FindValuesForAttribute(Vec<Value>),
     FindAttributePairsForEntry(Vec<AttributePair>),
     DistinctTextValuesForAttribute(Vec<String>),
+    // Analytics
+    RunAnalytics(Vec<AnalyticsRow>),
+    PersonalRecords(Vec<PersonalRecord>),
+    TrainingLoad(TrainingLoadReport),
+    AttributeSeries(Vec<SeriesPoint>),
+    // Grant
+    FindGrantById(Option<Grant>),
+    FindGrantsForGrantee(Vec<Grant>),
+    FindGrantsByGrantor(Vec<Grant>),
 }
 
 impl From<IsEmailRegistered> for AnyQuery {
@@ -141,6 +175,18 @@ impl From<FindActivityTemplateRoot> for AnyQuery {
     }
 }
 
+impl From<FindEntriesByActivity> for AnyQuery {
+    fn from(value: FindEntriesByActivity) -> Self {
+        AnyQuery::FindEntriesByActivity(value)
+    }
+}
+
+impl From<FindActivitiesBySource> for AnyQuery {
+    fn from(value: FindActivitiesBySource) -> Self {
+        AnyQuery::FindActivitiesBySource(value)
+    }
+}
+
 impl From<AllEntries> for AnyQuery {
     fn from(value: AllEntries) -> Self {
         AnyQuery::AllEntries(value)
@@ -213,6 +259,12 @@ impl From<FindValuesForEntries> for AnyQuery {
     }
 }
 
+impl From<FindValuesForAttribute> for AnyQuery {
+    fn from(value: FindValuesForAttribute) -> Self {
+        AnyQuery::FindValuesForAttribute(value)
+    }
+}
+
 impl From<FindAttributePairsForEntry> for AnyQuery {
     fn from(value: FindAttributePairsForEntry) -> Self {
         AnyQuery::FindAttributePairsForEntry(value)
@@ -225,6 +277,48 @@ impl From<DistinctTextValuesForAttribute> for AnyQuery {
     }
 }
 
+impl From<RunAnalytics> for AnyQuery {
+    fn from(value: RunAnalytics) -> Self {
+        AnyQuery::RunAnalytics(value)
+    }
+}
+
+impl From<PersonalRecords> for AnyQuery {
+    fn from(value: PersonalRecords) -> Self {
+        AnyQuery::PersonalRecords(value)
+    }
+}
+
+impl From<TrainingLoad> for AnyQuery {
+    fn from(value: TrainingLoad) -> Self {
+        AnyQuery::TrainingLoad(value)
+    }
+}
+
+impl From<AttributeSeries> for AnyQuery {
+    fn from(value: AttributeSeries) -> Self {
+        AnyQuery::AttributeSeries(value)
+    }
+}
+
+impl From<FindGrantById> for AnyQuery {
+    fn from(value: FindGrantById) -> Self {
+        AnyQuery::FindGrantById(value)
+    }
+}
+
+impl From<FindGrantsForGrantee> for AnyQuery {
+    fn from(value: FindGrantsForGrantee) -> Self {
+        AnyQuery::FindGrantsForGrantee(value)
+    }
+}
+
+impl From<FindGrantsByGrantor> for AnyQuery {
+    fn from(value: FindGrantsByGrantor) -> Self {
+        AnyQuery::FindGrantsByGrantor(value)
+    }
+}
+
 // --- Auth ---
 
 define_query! {
@@ -250,9 +344,20 @@ define_query! {
 }
 
 define_query! {
+    /// The library: every activity that isn't archived.
     pub struct AllActivities; => Vec<Activity>
 }
 
+define_query! {
+    /// Every entry, log or template, whose `activity_id` is `activity_id`.
+    pub struct FindEntriesByActivity { pub activity_id: Uuid } => Vec<Entry>
+}
+
+define_query! {
+    /// Activities copied from `source_activity_id`.
+    pub struct FindActivitiesBySource { pub source_activity_id: Uuid } => Vec<Activity>
+}
+
 define_query! {
     /// The root template entry for an activity (parentless, `is_template`,
     /// matching `activity_id`). `CreateActivity` guarantees exactly one.
@@ -325,6 +430,12 @@ define_query! {
     pub struct FindValuesForEntries { pub entry_ids: Vec<Uuid> } => Vec<Value>
 }
 
+define_query! {
+    /// Every stored value of an attribute, across all entries. Config edits
+    /// check (and rewrite) these to keep stored values conformant.
+    pub struct FindValuesForAttribute { pub attribute_id: Uuid } => Vec<Value>
+}
+
 define_query! {
     pub struct FindAttributePairsForEntry { pub entry_id: Uuid } => Vec<AttributePair>
 }
@@ -354,6 +465,142 @@ pub fn distinct_text_values(values: &[Value]) -> Vec<String> {
     set.into_iter().collect()
 }
 
+// --- Analytics ---
+
+define_query! {
+    /// One row per group, in group order: periods by time, values by their
+    /// index columns. See `analytics::evaluate`.
+    pub struct RunAnalytics { pub query: AnalyticsQuery } => Vec<AnalyticsRow>
+}
+
+define_query! {
+    /// `owner_id`'s personal records on one activity, or on every activity
+    /// when `None`, ordered by activity, attribute and load. See
+    /// `records::personal_records`.
+    pub struct PersonalRecords {
+        pub owner_id: Uuid,
+        pub activity_id: Option<Uuid>,
+    } => Vec<PersonalRecord>
+}
+
+define_query! {
+    /// `owner_id`'s session-RPE load over `window`, with RPE read from
+    /// `rpe_attribute_id`. See `training_load::training_load`.
+    pub struct TrainingLoad {
+        pub owner_id: Uuid,
+        pub rpe_attribute_id: Uuid,
+        pub window: LoadWindow,
+    } => TrainingLoadReport
+}
+
+define_query! {
+    /// `attribute_id`'s values on the entries of `activity_id`, as time-ordered
+    /// points in `unit` (the attribute's default when `None`). `bucket`
+    /// aggregates each period's values; without one, `roll_up_sets`
+    /// aggregates a sets sequence's members into one point. See
+    /// `series::attribute_series`.
+    pub struct AttributeSeries {
+        pub activity_id: Uuid,
+        pub attribute_id: Uuid,
+        pub field: ValueField,
+        pub bucket: Option<SeriesBucket>,
+        pub aggregate: SeriesAggregate,
+        pub range: Option<SeriesRange>,
+        pub unit: Option<SeriesUnit>,
+        pub roll_up_sets: bool,
+    } => Vec<SeriesPoint>
+}
+
+// --- Grant ---
+
+define_query! {
+    pub struct FindGrantById { pub grant_id: Uuid } => Option<Grant>
+}
+
+define_query! {
+    /// Every grant offered to `grantee_id`, pending or accepted.
+    pub struct FindGrantsForGrantee { pub grantee_id: Uuid } => Vec<Grant>
+}
+
+define_query! {
+    /// Every grant `grantor_id` has offered, pending or accepted.
+    pub struct FindGrantsByGrantor { pub grantor_id: Uuid } => Vec<Grant>
+}
+
+// --- Read scope ---
+//
+// Scopes sync pulls; the SQL executors' scoped reads share its SQL.
+// Implemented by both SQL executors, and not part of `AnyQuery`: clients ask
+// for rows, not for what they may see.
+
+define_query! {
+    /// Ids of every entry, activity and attribute `actor_id` can read: their
+    /// own, the system actor's, and whatever their accepted grants cover.
+    pub struct VisibleTo { pub actor_id: Uuid } => Visibility
+}
+
+// --- Mutation log ---
+//
+// The mutation log is client-local, so these queries are implemented only by
+// the SQLite executor and are not part of `AnyQuery`.
+
+define_query! {
+    /// Mutations applied locally but not yet sent to the server, oldest first.
+    pub struct PendingMutations; => Vec<LoggedMutation>
+}
+
+define_query! {
+    /// The `limit` most recent logged mutations in any state, newest first.
+    pub struct MutationHistory { pub limit: u32 } => Vec<LoggedMutation>
+}
+
+define_query! {
+    /// Mutations whose effects exist only locally (pending or sent, not yet
+    /// acked or rejected), oldest first. These are the mutations a sync
+    /// rebases.
+    pub struct UnackedMutations; => Vec<LoggedMutation>
+}
+
+define_query! {
+    /// The latest server `seq_num` this client has applied, or 0 before the
+    /// first sync.
+    pub struct LastSeenServerSeq; => i64
+}
+
+// The server's mutation log is the shared history clients catch up from.
+// Implemented only by the Postgres executor.
+
+define_query! {
+    /// Up to `limit` committed mutations with `seq_num > after_seq_num`, in
+    /// commit order.
+    pub struct MutationsSince {
+        pub after_seq_num: i64,
+        pub limit: u32,
+    } => Vec<CommittedMutation>
+}
+
+define_query! {
+    /// The sequence number of the most recently committed mutation, or 0 if
+    /// none has been committed.
+    pub struct LatestSeqNum; => i64
+}
+
+define_query! {
+    /// The `seq_num` a mutation was committed under, if it was. Makes pushes
+    /// idempotent: a retried mutation is acked rather than applied twice.
+    pub struct FindSeqNumByMutationId { pub mutation_id: Uuid } => Option<i64>
+}
+
+// Implemented by both executors, each over its own log.
+
+define_query! {
+    /// A mutation whose effects this replica holds, with the deltas it
+    /// applied here: on a client, a logged mutation that wasn't rejected; on
+    /// the server, a committed one. `undo` and `redo` replay these deltas, so
+    /// what they write never comes from the request.
+    pub struct FindMutationById { pub mutation_id: Uuid } => Option<Mutation>
+}
+
 // --- Simulation ---
 
 // SnapshotAll is used to read *every* row from the database, regardless of auth, to bootstrap a
@@ -370,6 +617,7 @@ pub struct Snapshot {
     pub attributes: Vec<Attribute>,
     pub entries: Vec<Entry>,
     pub values: Vec<Value>,
+    pub grants: Vec<Grant>,
 }
 
 #[cfg(test)]
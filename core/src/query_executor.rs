@@ -1,11 +1,23 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
 use crate::{
     error::Result,
+    models::{
+        attribute::{Attribute, AttributeConfig},
+        attribute_pair::AttributePair,
+        derived::DerivedScope,
+    },
     queries::{
-        AllActivities, AllActorIds, AllAttributes, AllEntries, DistinctTextValuesForAttribute,
-        EntriesRootedInTimeInterval, FindActivityById, FindActivityTemplateRoot, FindAncestors,
+        AllActivities, AllActorIds, AllAttributes, AllEntries, AnyQuery, AnyQueryResponse,
+        AttributeSeries, DistinctTextValuesForAttribute, EntriesRootedInTimeInterval,
+        FindActivitiesBySource, FindActivityById, FindActivityTemplateRoot, FindAncestors,
         FindAttributeById, FindAttributePairsForEntry, FindAttributesByOwner, FindDescendants,
-        FindEntryById, FindEntryJoinById, FindUserById, FindUserByUsername, FindValueByKey,
-        FindValuesForEntries, FindValuesForEntry, IsEmailRegistered, Query,
+        FindEntriesByActivity, FindEntryById, FindEntryJoinById, FindGrantById,
+        FindGrantsByGrantor, FindGrantsForGrantee, FindUserById, FindUserByUsername,
+        FindValueByKey, FindValuesForAttribute, FindValuesForEntries, FindValuesForEntry,
+        IsEmailRegistered, PersonalRecords, Query, RunAnalytics, TrainingLoad,
     },
 };
 
@@ -28,6 +40,8 @@ pub trait AnyQueryExecutor:
     + QueryExecutor<FindActivityById>
     + QueryExecutor<AllActivities>
     + QueryExecutor<FindActivityTemplateRoot>
+    + QueryExecutor<FindEntriesByActivity>
+    + QueryExecutor<FindActivitiesBySource>
     + QueryExecutor<AllEntries>
     + QueryExecutor<EntriesRootedInTimeInterval>
     + QueryExecutor<FindAncestors>
@@ -40,8 +54,16 @@ pub trait AnyQueryExecutor:
     + QueryExecutor<FindValueByKey>
     + QueryExecutor<FindValuesForEntry>
     + QueryExecutor<FindValuesForEntries>
+    + QueryExecutor<FindValuesForAttribute>
     + QueryExecutor<FindAttributePairsForEntry>
     + QueryExecutor<DistinctTextValuesForAttribute>
+    + QueryExecutor<RunAnalytics>
+    + QueryExecutor<PersonalRecords>
+    + QueryExecutor<TrainingLoad>
+    + QueryExecutor<AttributeSeries>
+    + QueryExecutor<FindGrantById>
+    + QueryExecutor<FindGrantsForGrantee>
+    + QueryExecutor<FindGrantsByGrantor>
 {
 }
 
@@ -53,6 +75,8 @@ impl<T> AnyQueryExecutor for T where
         + QueryExecutor<FindActivityById>
         + QueryExecutor<AllActivities>
         + QueryExecutor<FindActivityTemplateRoot>
+        + QueryExecutor<FindEntriesByActivity>
+        + QueryExecutor<FindActivitiesBySource>
         + QueryExecutor<AllEntries>
         + QueryExecutor<EntriesRootedInTimeInterval>
         + QueryExecutor<FindAncestors>
@@ -65,7 +89,210 @@ impl<T> AnyQueryExecutor for T where
         + QueryExecutor<FindValueByKey>
         + QueryExecutor<FindValuesForEntry>
         + QueryExecutor<FindValuesForEntries>
+        + QueryExecutor<FindValuesForAttribute>
         + QueryExecutor<FindAttributePairsForEntry>
         + QueryExecutor<DistinctTextValuesForAttribute>
+        + QueryExecutor<RunAnalytics>
+        + QueryExecutor<PersonalRecords>
+        + QueryExecutor<TrainingLoad>
+        + QueryExecutor<AttributeSeries>
+        + QueryExecutor<FindGrantById>
+        + QueryExecutor<FindGrantsForGrantee>
+        + QueryExecutor<FindGrantsByGrantor>
 {
 }
+
+/// Run a type-erased query against any executor, wrapping the result in the
+/// matching `AnyQueryResponse` variant. Used where queries arrive as data
+/// (e.g. over HTTP) rather than as typed values.
+pub async fn execute_any_query(
+    executor: &mut impl AnyQueryExecutor,
+    query: AnyQuery,
+) -> Result<AnyQueryResponse> {
+    Ok(match query {
+        // Auth
+        AnyQuery::IsEmailRegistered(q) => {
+            AnyQueryResponse::IsEmailRegistered(executor.execute(q).await?)
+        }
+        AnyQuery::FindUserById(q) => AnyQueryResponse::FindUserById(executor.execute(q).await?),
+        AnyQuery::FindUserByUsername(q) => {
+            AnyQueryResponse::FindUserByUsername(executor.execute(q).await?)
+        }
+        AnyQuery::AllActorIds(q) => AnyQueryResponse::AllActorIds(executor.execute(q).await?),
+        // Activity
+        AnyQuery::FindActivityById(q) => {
+            AnyQueryResponse::FindActivityById(executor.execute(q).await?)
+        }
+        AnyQuery::AllActivities(q) => AnyQueryResponse::AllActivities(executor.execute(q).await?),
+        AnyQuery::FindActivityTemplateRoot(q) => {
+            AnyQueryResponse::FindActivityTemplateRoot(executor.execute(q).await?)
+        }
+        AnyQuery::FindEntriesByActivity(q) => {
+            AnyQueryResponse::FindEntriesByActivity(executor.execute(q).await?)
+        }
+        AnyQuery::FindActivitiesBySource(q) => {
+            AnyQueryResponse::FindActivitiesBySource(executor.execute(q).await?)
+        }
+        // Entry
+        AnyQuery::AllEntries(q) => AnyQueryResponse::AllEntries(executor.execute(q).await?),
+        AnyQuery::EntriesRootedInTimeInterval(q) => {
+            AnyQueryResponse::EntriesRootedInTimeInterval(executor.execute(q).await?)
+        }
+        AnyQuery::FindAncestors(q) => AnyQueryResponse::FindAncestors(executor.execute(q).await?),
+        AnyQuery::FindEntryById(q) => AnyQueryResponse::FindEntryById(executor.execute(q).await?),
+        AnyQuery::FindEntryJoinById(q) => {
+            AnyQueryResponse::FindEntryJoinById(executor.execute(q).await?)
+        }
+        AnyQuery::FindDescendants(q) => {
+            AnyQueryResponse::FindDescendants(executor.execute(q).await?)
+        }
+        // Attribute
+        AnyQuery::FindAttributeById(q) => {
+            AnyQueryResponse::FindAttributeById(executor.execute(q).await?)
+        }
+        AnyQuery::AllAttributes(q) => AnyQueryResponse::AllAttributes(executor.execute(q).await?),
+        AnyQuery::FindAttributesByOwner(q) => {
+            AnyQueryResponse::FindAttributesByOwner(executor.execute(q).await?)
+        }
+        // Value
+        AnyQuery::FindValueByKey(q) => AnyQueryResponse::FindValueByKey(executor.execute(q).await?),
+        AnyQuery::FindValuesForEntry(q) => {
+            AnyQueryResponse::FindValuesForEntry(executor.execute(q).await?)
+        }
+        AnyQuery::FindValuesForEntries(q) => {
+            AnyQueryResponse::FindValuesForEntries(executor.execute(q).await?)
+        }
+        AnyQuery::FindValuesForAttribute(q) => {
+            AnyQueryResponse::FindValuesForAttribute(executor.execute(q).await?)
+        }
+        AnyQuery::FindAttributePairsForEntry(q) => {
+            AnyQueryResponse::FindAttributePairsForEntry(executor.execute(q).await?)
+        }
+        AnyQuery::DistinctTextValuesForAttribute(q) => {
+            AnyQueryResponse::DistinctTextValuesForAttribute(executor.execute(q).await?)
+        }
+        // Analytics
+        AnyQuery::RunAnalytics(q) => AnyQueryResponse::RunAnalytics(executor.execute(q).await?),
+        AnyQuery::PersonalRecords(q) => {
+            AnyQueryResponse::PersonalRecords(executor.execute(q).await?)
+        }
+        AnyQuery::TrainingLoad(q) => AnyQueryResponse::TrainingLoad(executor.execute(q).await?),
+        AnyQuery::AttributeSeries(q) => {
+            AnyQueryResponse::AttributeSeries(executor.execute(q).await?)
+        }
+        // Grant
+        AnyQuery::FindGrantById(q) => AnyQueryResponse::FindGrantById(executor.execute(q).await?),
+        AnyQuery::FindGrantsForGrantee(q) => {
+            AnyQueryResponse::FindGrantsForGrantee(executor.execute(q).await?)
+        }
+        AnyQuery::FindGrantsByGrantor(q) => {
+            AnyQueryResponse::FindGrantsByGrantor(executor.execute(q).await?)
+        }
+    })
+}
+
+/// Fill in the computed plan and actual of the derived pairs among `pairs`,
+/// the stored pairs of `entry_id`. Loads the entry's subtree, its values, and
+/// every attribute the expressions reach; a no-op when no pair is derived.
+/// Shared by the SQLite and Postgres `FindAttributePairsForEntry`, so an
+/// `EntryJoin` carries the computed values too.
+pub async fn evaluate_derived_pairs<E>(
+    executor: &mut E,
+    entry_id: Uuid,
+    mut pairs: Vec<AttributePair>,
+) -> Result<Vec<AttributePair>>
+where
+    E: QueryExecutor<FindAttributeById>
+        + QueryExecutor<FindDescendants>
+        + QueryExecutor<FindValuesForEntries>,
+{
+    let mut pending: Vec<Uuid> = pairs
+        .iter()
+        .filter_map(|p| match p {
+            AttributePair::Derived(d) => Some(d.attr_id),
+            _ => None,
+        })
+        .collect();
+    if pending.is_empty() {
+        return Ok(pairs);
+    }
+    let mut seen = HashSet::new();
+    let mut attributes = Vec::new();
+    while let Some(attribute_id) = pending.pop() {
+        if !seen.insert(attribute_id) {
+            continue;
+        }
+        if let Some(attribute) = executor.execute(FindAttributeById { attribute_id }).await? {
+            if let AttributeConfig::Derived(c) = &attribute.config {
+                pending.extend(c.expression.references());
+            }
+            attributes.push(attribute);
+        }
+    }
+    let subtree = executor.execute(FindDescendants { entry_id }).await?;
+    let values = executor
+        .execute(FindValuesForEntries {
+            entry_ids: subtree.iter().map(|e| e.id).collect(),
+        })
+        .await?;
+    DerivedScope::new(attributes, subtree, values).fill(&mut pairs);
+    Ok(pairs)
+}
+
+/// The attributes `pending` names, following derived attributes to the
+/// attributes they read.
+pub async fn find_attributes<E>(executor: &mut E, mut pending: Vec<Uuid>) -> Result<Vec<Attribute>>
+where
+    E: QueryExecutor<FindAttributeById>,
+{
+    let mut seen = HashSet::new();
+    let mut attributes = Vec::new();
+    while let Some(attribute_id) = pending.pop() {
+        if !seen.insert(attribute_id) {
+            continue;
+        }
+        if let Some(attribute) = executor.execute(FindAttributeById { attribute_id }).await? {
+            if let AttributeConfig::Derived(c) = &attribute.config {
+                pending.extend(c.expression.references());
+            }
+            attributes.push(attribute);
+        }
+    }
+    Ok(attributes)
+}
+
+/// An executor that can restrict its reads to the rows one actor can see. The
+/// SQLite and Postgres executors apply the restriction in their SQL, by the
+/// rule `VisibleTo` reads.
+pub trait ReadScope {
+    /// Restrict every later read to what `actor_id` can see.
+    fn read_as(&mut self, actor_id: Uuid);
+}
+
+/// Runs queries on behalf of `actor_id`, so only the rows the actor can read
+/// come back. The inner executor restricts its own reads (`ReadScope`), so
+/// counts, analytics and lookups never load what the actor can't see. The
+/// user directory and the device-local mutation log aren't restricted.
+/// Mutators keep using unscoped executors and make their own permission
+/// checks.
+pub struct ScopedQueryExecutor<E> {
+    inner: E,
+    actor_id: Uuid,
+}
+
+impl<E: ReadScope> ScopedQueryExecutor<E> {
+    pub fn new(mut inner: E, actor_id: Uuid) -> Self {
+        inner.read_as(actor_id);
+        ScopedQueryExecutor { inner, actor_id }
+    }
+
+    pub fn actor_id(&self) -> Uuid {
+        self.actor_id
+    }
+}
+
+impl<Q: Query, E: QueryExecutor<Q>> QueryExecutor<Q> for ScopedQueryExecutor<E> {
+    async fn execute(&mut self, query: Q) -> Result<Q::Response> {
+        self.inner.execute(query).await
+    }
+}
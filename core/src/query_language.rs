@@ -0,0 +1,1031 @@
+//! A small textual language for analytics queries, e.g.
+//!
+//! ```text
+//! count Boulder where "V Grade" >= V8 and Outcome != Sent group by week since 2026-01-01
+//! ```
+//!
+//! Keywords are case-insensitive. A name or value containing spaces, quotes or
+//! comparison characters, or spelled like a keyword, is double-quoted
+//! (`\"` and `\\` escape inside quotes).
+//!
+//! ```text
+//! query     := aggregate [activity] clause*
+//! aggregate := "count" | ("sum" | "max" | "min" | "mean") field
+//! field     := ["plan" | "actual"] attribute
+//! clause    := "where" condition ("and" condition)*
+//!            | "within" activity
+//!            | "group" "by" ("day" | "week" | "month" | field)
+//!            | "since" date
+//!            | "until" date
+//! condition := field op value | "complete" | "incomplete"
+//! op        := "=" | "!=" | "<" | "<=" | ">" | ">="
+//! date      := YYYY-MM-DD (local midnight) | RFC 3339 instant
+//! ```
+//!
+//! A field reads the actual side unless it says `plan`. A value is read as its
+//! attribute's type: a number, an option or grade, `true`/`false`, text, or a
+//! measure with an optional unit suffix (`100kg`, `5km`, `90s`, `12km/h`); a
+//! bare measure takes the attribute's default unit, and durations seconds.
+
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use crate::{
+    actions::ValueField,
+    analytics::{
+        Aggregate, AnalyticsQuery, AttributeField, AttributePredicate, Comparison, EntryFilter,
+        Grouping, Period,
+    },
+    models::{
+        activity::Activity,
+        attribute::{
+            Attribute, AttributeConfig, AttributeValue, DurationValue, LengthUnit, LengthValue,
+            MassUnit, MassValue, Measure, NumericValue, SelectValue, SpeedMeasurement, SpeedUnit,
+            SpeedValue, TimeUnit,
+        },
+    },
+    std_lib::StandardLibrary,
+};
+
+const KEYWORDS: &[&str] = &[
+    "count",
+    "sum",
+    "max",
+    "min",
+    "mean",
+    "plan",
+    "actual",
+    "where",
+    "and",
+    "within",
+    "group",
+    "by",
+    "day",
+    "week",
+    "month",
+    "since",
+    "until",
+    "complete",
+    "incomplete",
+];
+
+const MASS_UNITS: &[(&str, MassUnit)] = &[
+    ("g", MassUnit::Gram),
+    ("kg", MassUnit::Kilogram),
+    ("lb", MassUnit::Pound),
+    ("lbs", MassUnit::Pound),
+];
+
+const LENGTH_UNITS: &[(&str, LengthUnit)] = &[
+    ("mm", LengthUnit::Millimeter),
+    ("cm", LengthUnit::Centimeter),
+    ("m", LengthUnit::Meter),
+    ("km", LengthUnit::Kilometer),
+    ("in", LengthUnit::Inch),
+    ("ft", LengthUnit::Foot),
+    ("yd", LengthUnit::Yard),
+    ("mi", LengthUnit::Mile),
+];
+
+const SPEED_UNITS: &[(&str, SpeedUnit)] = &[
+    ("m/s", SpeedUnit::MetersPerSecond),
+    ("km/h", SpeedUnit::KilometersPerHour),
+    ("mph", SpeedUnit::MilesPerHour),
+    ("min/km", SpeedUnit::MinutesPerKilometer),
+    ("min/mi", SpeedUnit::MinutesPerMile),
+];
+
+const TIME_UNITS: &[(&str, TimeUnit)] = &[
+    ("s", TimeUnit::Second),
+    ("min", TimeUnit::Minute),
+    ("h", TimeUnit::Hour),
+];
+
+/// Why query text didn't parse, and where: `start..end` is a byte range of
+/// the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryParseError {
+    pub message: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {}..{})", self.message, self.start, self.end)
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+impl QueryParseError {
+    fn at(start: usize, end: usize, message: impl Into<String>) -> Self {
+        QueryParseError {
+            message: message.into(),
+            start: start as u32,
+            end: end as u32,
+        }
+    }
+
+    /// The query text with the error's span underlined and the message after
+    /// it, for a terminal.
+    pub fn render(&self, text: &str) -> String {
+        let start = (self.start as usize).min(text.len());
+        let end = (self.end as usize).clamp(start, text.len());
+        let pad = text[..start].chars().count();
+        let width = text[start..end].chars().count().max(1);
+        format!(
+            "{text}\n{}{} {}",
+            " ".repeat(pad),
+            "^".repeat(width),
+            self.message
+        )
+    }
+}
+
+/// The activities and attributes query names resolve against: the actor's
+/// library plus the standard library. Names match case-insensitively, and an
+/// item's id (as text) matches it too. When a name matches several items, the
+/// actor's own wins.
+pub struct Library {
+    actor_id: Uuid,
+    activities: Vec<Activity>,
+    attributes: Vec<Attribute>,
+}
+
+impl Library {
+    pub fn new(actor_id: Uuid, activities: Vec<Activity>, attributes: Vec<Attribute>) -> Self {
+        let mut library = Library {
+            actor_id,
+            activities,
+            attributes,
+        };
+        for std in StandardLibrary::activities() {
+            if !library.activities.iter().any(|a| a.id == std.activity.id) {
+                library.activities.push(std.activity);
+            }
+        }
+        for std in StandardLibrary::attributes() {
+            if !library.attributes.iter().any(|a| a.id == std.id) {
+                library.attributes.push(std);
+            }
+        }
+        library
+    }
+
+    fn activity(&self, token: &Token) -> Result<&Activity, QueryParseError> {
+        resolve(&self.activities, "activity", token, self.actor_id, |a| {
+            (a.id, a.owner_id, a.name.to_string())
+        })
+    }
+
+    fn attribute(&self, token: &Token) -> Result<&Attribute, QueryParseError> {
+        resolve(&self.attributes, "attribute", token, self.actor_id, |a| {
+            (a.id, a.owner_id, a.name.clone())
+        })
+    }
+
+    /// How to write the activity in a query: its name, or its id when the
+    /// library doesn't have it.
+    fn activity_name(&self, id: Uuid) -> String {
+        self.activities
+            .iter()
+            .find(|a| a.id == id)
+            .map_or_else(|| id.to_string(), |a| a.name.to_string())
+    }
+
+    fn attribute_name(&self, id: Uuid) -> String {
+        self.attributes
+            .iter()
+            .find(|a| a.id == id)
+            .map_or_else(|| id.to_string(), |a| a.name.clone())
+    }
+}
+
+/// Find the one item `token` names; `describe` gives an item's id, owner and
+/// name.
+fn resolve<'a, T>(
+    items: &'a [T],
+    kind: &str,
+    token: &Token,
+    actor_id: Uuid,
+    describe: impl Fn(&T) -> (Uuid, Uuid, String),
+) -> Result<&'a T, QueryParseError> {
+    let text = token.text.trim();
+    if let Ok(id) = Uuid::parse_str(text)
+        && let Some(item) = items.iter().find(|i| describe(i).0 == id)
+    {
+        return Ok(item);
+    }
+    let matches: Vec<&T> = items
+        .iter()
+        .filter(|i| describe(i).2.eq_ignore_ascii_case(text))
+        .collect();
+    let own: Vec<&T> = matches
+        .iter()
+        .copied()
+        .filter(|i| describe(i).1 == actor_id)
+        .collect();
+    match (matches.as_slice(), own.as_slice()) {
+        ([item], _) | (_, [item]) => Ok(item),
+        ([], _) => {
+            let suggestion = items
+                .iter()
+                .map(|i| describe(i).2)
+                .map(|name| (edit_distance(&name, text), name))
+                .filter(|(d, _)| *d <= 2.max(text.chars().count() / 3))
+                .min_by_key(|(d, _)| *d);
+            Err(token.error(match suggestion {
+                Some((_, name)) => format!("unknown {kind} '{text}'; did you mean '{name}'?"),
+                None => format!("unknown {kind} '{text}'"),
+            }))
+        }
+        _ => Err(token.error(format!(
+            "{} {kind}s are named '{text}'; use the id of the one you mean",
+            matches.len()
+        ))),
+    }
+}
+
+/// Case-insensitive Levenshtein distance.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diagonal + usize::from(ca != cb))
+                .min(row[j] + 1)
+                .min(above + 1);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Word,
+    Quoted,
+    Op(Comparison),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn error(&self, message: impl Into<String>) -> QueryParseError {
+        QueryParseError::at(self.start, self.end, message)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == Kind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    /// A word that isn't a keyword, or any quoted string.
+    fn is_name(&self) -> bool {
+        match self.kind {
+            Kind::Word => !KEYWORDS.iter().any(|k| self.is_keyword(k)),
+            Kind::Quoted => true,
+            Kind::Op(_) => false,
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '"' | '=' | '!' | '<' | '>')
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, QueryParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let mut next_is = |want: char| {
+            chars
+                .next_if(|(_, c)| *c == want)
+                .map(|(i, c)| end = i + c.len_utf8())
+                .is_some()
+        };
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '=' => Kind::Op(Comparison::Eq),
+            '!' if next_is('=') => Kind::Op(Comparison::Ne),
+            '!' => return Err(QueryParseError::at(start, end, "expected '=' after '!'")),
+            '<' if next_is('=') => Kind::Op(Comparison::Le),
+            '<' => Kind::Op(Comparison::Lt),
+            '>' if next_is('=') => Kind::Op(Comparison::Ge),
+            '>' => Kind::Op(Comparison::Gt),
+            '"' => {
+                let mut quoted = String::new();
+                loop {
+                    let c = match chars.next() {
+                        Some((i, '"')) => {
+                            end = i + 1;
+                            break;
+                        }
+                        Some((_, '\\')) => chars.next(),
+                        next => next,
+                    };
+                    match c {
+                        Some((_, c)) => quoted.push(c),
+                        // The quote runs to the end of the source, escapes and all.
+                        None => {
+                            return Err(QueryParseError::at(
+                                start,
+                                text.len(),
+                                "unterminated quote",
+                            ));
+                        }
+                    }
+                }
+                tokens.push(Token {
+                    kind: Kind::Quoted,
+                    text: quoted,
+                    start,
+                    end,
+                });
+                continue;
+            }
+            _ => {
+                while let Some((i, c)) = chars.next_if(|(_, c)| is_word_char(*c)) {
+                    end = i + c.len_utf8();
+                }
+                Kind::Word
+            }
+        };
+        tokens.push(Token {
+            kind,
+            text: text[start..end].to_string(),
+            start,
+            end,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Parse query text, resolving names against `library`. Dates and period
+/// groups are in the local time `utc_offset_minutes` east of UTC.
+pub fn parse(
+    text: &str,
+    library: &Library,
+    utc_offset_minutes: i32,
+) -> Result<AnalyticsQuery, QueryParseError> {
+    let offset = utc_offset_minutes
+        .checked_mul(60)
+        .and_then(FixedOffset::east_opt)
+        .ok_or_else(|| QueryParseError::at(0, 0, "UTC offset out of range"))?;
+    Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+        text_len: text.len(),
+        library,
+        offset,
+        utc_offset_minutes,
+    }
+    .query()
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    text_len: usize,
+    library: &'a Library,
+    offset: FixedOffset,
+    utc_offset_minutes: i32,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// The next token, or an error naming what was `expected` at the end.
+    fn next(&mut self, expected: &str) -> Result<Token, QueryParseError> {
+        let token = self.tokens.get(self.pos).cloned().ok_or_else(|| {
+            QueryParseError::at(self.text_len, self.text_len, format!("expected {expected}"))
+        })?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, keyword: &str) -> bool {
+        let found = self.peek().is_some_and(|t| t.is_keyword(keyword));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn name(&mut self, expected: &str) -> Result<Token, QueryParseError> {
+        let token = self.next(expected)?;
+        if !token.is_name() {
+            return Err(token.error(format!(
+                "expected {expected}, found '{}'{}",
+                token.text,
+                if token.kind == Kind::Word {
+                    " (quote names spelled like keywords)"
+                } else {
+                    ""
+                }
+            )));
+        }
+        Ok(token)
+    }
+
+    fn query(mut self) -> Result<AnalyticsQuery, QueryParseError> {
+        const AGGREGATES: &str = "an aggregate (count, sum, max, min or mean)";
+        let first = self.next(AGGREGATES)?;
+        let aggregate = match first.text.to_ascii_lowercase().as_str() {
+            _ if first.kind != Kind::Word => {
+                return Err(first.error(format!("expected {AGGREGATES}")));
+            }
+            "count" => Aggregate::Count,
+            "sum" => Aggregate::Sum(self.field()?),
+            "max" => Aggregate::Max(self.field()?),
+            "min" => Aggregate::Min(self.field()?),
+            "mean" => Aggregate::Mean(self.field()?),
+            _ => return Err(first.error(format!("expected {AGGREGATES}"))),
+        };
+
+        let mut subject = None;
+        if self.peek().is_some_and(|t| t.is_name()) {
+            let token = self.name("an activity")?;
+            subject = Some(EntryFilter::Activity {
+                activity_id: self.library.activity(&token)?.id,
+            });
+        }
+        let mut within = Vec::new();
+        let mut conditions = Vec::new();
+        let mut group_by = None;
+        let mut since: Option<(Token, DateTime<Utc>)> = None;
+        let mut until: Option<(Token, DateTime<Utc>)> = None;
+        while let Some(token) = self.peek().cloned() {
+            self.pos += 1;
+            let duplicate = |clause: &str| token.error(format!("more than one '{clause}' clause"));
+            match token.text.to_ascii_lowercase().as_str() {
+                _ if token.kind != Kind::Word => {
+                    return Err(token.error(
+                        "expected where, within, group by, since or until \
+                         (quote names that contain spaces)",
+                    ));
+                }
+                "where" => loop {
+                    conditions.push(self.condition()?);
+                    if !self.eat("and") {
+                        break;
+                    }
+                },
+                "within" => {
+                    let name = self.name("an activity")?;
+                    within.push(EntryFilter::WithinActivity {
+                        activity_id: self.library.activity(&name)?.id,
+                    });
+                }
+                "group" => {
+                    if group_by.is_some() {
+                        return Err(duplicate("group by"));
+                    }
+                    let by = self.next("'by'")?;
+                    if !by.is_keyword("by") {
+                        return Err(by.error("expected 'by'"));
+                    }
+                    group_by = Some(self.grouping()?);
+                }
+                "since" if since.is_some() => return Err(duplicate("since")),
+                "since" => since = Some((token, self.date()?)),
+                "until" if until.is_some() => return Err(duplicate("until")),
+                "until" => until = Some((token, self.date()?)),
+                _ => {
+                    return Err(token.error(
+                        "expected where, within, group by, since or until \
+                         (quote names that contain spaces)",
+                    ));
+                }
+            }
+        }
+
+        let mut filters: Vec<EntryFilter> = subject.into_iter().chain(within).collect();
+        filters.extend(conditions);
+        if since.is_some() || until.is_some() {
+            let from = since.as_ref().map_or(DateTime::<Utc>::MIN_UTC, |s| s.1);
+            let to = until.as_ref().map_or(DateTime::<Utc>::MAX_UTC, |u| u.1);
+            if let (Some(_), Some((token, _))) = (&since, &until)
+                && from > to
+            {
+                return Err(token.error("'until' is before 'since'"));
+            }
+            filters.push(EntryFilter::TimeWindow { from, to });
+        }
+        Ok(AnalyticsQuery {
+            filters,
+            group_by,
+            aggregate,
+        })
+    }
+
+    fn field(&mut self) -> Result<AttributeField, QueryParseError> {
+        Ok(self.field_attribute()?.0)
+    }
+
+    fn field_attribute(&mut self) -> Result<(AttributeField, &'a Attribute), QueryParseError> {
+        let field = if self.eat("plan") {
+            ValueField::Plan
+        } else {
+            self.eat("actual");
+            ValueField::Actual
+        };
+        let token = self.name("an attribute")?;
+        let attribute = self.library.attribute(&token)?;
+        Ok((
+            AttributeField {
+                attribute_id: attribute.id,
+                field,
+            },
+            attribute,
+        ))
+    }
+
+    fn condition(&mut self) -> Result<EntryFilter, QueryParseError> {
+        if self.eat("complete") {
+            return Ok(EntryFilter::Complete(true));
+        }
+        if self.eat("incomplete") {
+            return Ok(EntryFilter::Complete(false));
+        }
+        let (target, attribute) = self.field_attribute()?;
+        const OPS: &str = "a comparison (=, !=, <, <=, >, >=)";
+        let op = self.next(OPS)?;
+        let Kind::Op(comparison) = op.kind.clone() else {
+            return Err(op.error(format!("expected {OPS}")));
+        };
+        let value = self.next(&format!("a value for {}", attribute.name))?;
+        if let Kind::Op(_) = value.kind {
+            return Err(value.error(format!("expected a value for {}", attribute.name)));
+        }
+        Ok(EntryFilter::Attribute(AttributePredicate {
+            target,
+            comparison,
+            operand: operand(attribute, &value)?,
+        }))
+    }
+
+    fn grouping(&mut self) -> Result<Grouping, QueryParseError> {
+        let period = if self.eat("day") {
+            Period::Day
+        } else if self.eat("week") {
+            Period::Week
+        } else if self.eat("month") {
+            Period::Month
+        } else {
+            return Ok(Grouping::Attribute(self.field()?));
+        };
+        Ok(Grouping::Period {
+            period,
+            utc_offset_minutes: self.utc_offset_minutes,
+        })
+    }
+
+    fn date(&mut self) -> Result<DateTime<Utc>, QueryParseError> {
+        let token = self.next("a date like 2026-01-01")?;
+        if let Ok(date) = NaiveDate::parse_from_str(&token.text, "%Y-%m-%d") {
+            return Ok(date
+                .and_time(NaiveTime::MIN)
+                .and_local_timezone(self.offset)
+                .single()
+                .expect("a fixed offset has no gaps")
+                .with_timezone(&Utc));
+        }
+        DateTime::parse_from_rfc3339(&token.text)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|_| token.error("expected a date like 2026-01-01"))
+    }
+}
+
+/// Read `token` as a value of `attribute`.
+fn operand(attribute: &Attribute, token: &Token) -> Result<AttributeValue, QueryParseError> {
+    let text = token.text.as_str();
+    let name = &attribute.name;
+    let option = |options: &[String]| {
+        options
+            .iter()
+            .find(|o| o.eq_ignore_ascii_case(text))
+            .map(|o| SelectValue::Exact(o.clone()))
+    };
+    match &attribute.config {
+        AttributeConfig::Numeric(_) | AttributeConfig::Derived(_) => number(text)
+            .map(|n| AttributeValue::Numeric(NumericValue::Exact(n)))
+            .ok_or_else(|| token.error(format!("expected a number for {name}"))),
+        AttributeConfig::Select(c) => {
+            option(&c.options)
+                .map(AttributeValue::Select)
+                .ok_or_else(|| {
+                    token.error(format!(
+                        "{name} has no option '{text}'; options are {}",
+                        c.options.join(", ")
+                    ))
+                })
+        }
+        AttributeConfig::Grade(c) => option(&c.scale.options())
+            .map(AttributeValue::Grade)
+            .ok_or_else(|| {
+                let grades = c.scale.grades();
+                token.error(format!(
+                    "{name} has no grade '{text}'; grades run {} to {}",
+                    grades[0].0,
+                    grades[grades.len() - 1].0
+                ))
+            }),
+        AttributeConfig::Multiselect(_) => {
+            Err(token.error(format!("{name} is a multiselect, which can't be compared")))
+        }
+        AttributeConfig::Mass(c) => measure(text, MASS_UNITS, &c.default_unit)
+            .map(|(unit, v)| AttributeValue::Mass(MassValue::exact(unit, v)))
+            .ok_or_else(|| {
+                token.error(format!(
+                    "expected a mass for {name}, e.g. 100kg (units: {})",
+                    units(MASS_UNITS)
+                ))
+            }),
+        AttributeConfig::Length(c) => measure(text, LENGTH_UNITS, &c.default_unit)
+            .map(|(unit, v)| AttributeValue::Length(LengthValue::exact(unit, v)))
+            .ok_or_else(|| {
+                token.error(format!(
+                    "expected a length for {name}, e.g. 5km (units: {})",
+                    units(LENGTH_UNITS)
+                ))
+            }),
+        AttributeConfig::Speed(c) => measure(text, SPEED_UNITS, &c.default_unit)
+            .map(|(unit, value)| {
+                AttributeValue::Speed(SpeedValue::Exact(SpeedMeasurement { unit, value }))
+            })
+            .ok_or_else(|| {
+                token.error(format!(
+                    "expected a speed or pace for {name}, e.g. 12km/h (units: {})",
+                    units(SPEED_UNITS)
+                ))
+            }),
+        AttributeConfig::Duration(_) => measure(text, TIME_UNITS, &TimeUnit::Second)
+            .map(|(unit, v)| {
+                AttributeValue::Duration(DurationValue::Exact(v * unit.seconds_per_unit()))
+            })
+            .ok_or_else(|| {
+                token.error(format!(
+                    "expected a duration for {name}, e.g. 90s (units: {})",
+                    units(TIME_UNITS)
+                ))
+            }),
+        AttributeConfig::Text(_) => Ok(AttributeValue::Text(text.to_string())),
+        AttributeConfig::Boolean(_) => match text.to_ascii_lowercase().as_str() {
+            "true" | "yes" => Ok(AttributeValue::Boolean(true)),
+            "false" | "no" => Ok(AttributeValue::Boolean(false)),
+            _ => Err(token.error(format!("expected true or false for {name}"))),
+        },
+    }
+}
+
+fn units<U>(table: &[(&str, U)]) -> String {
+    let symbols: Vec<&str> = table.iter().map(|(s, _)| *s).collect();
+    symbols.join(", ")
+}
+
+fn number(text: &str) -> Option<f64> {
+    text.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// A number with an optional unit suffix from `units`; no suffix means
+/// `default`.
+fn measure<U: Clone>(text: &str, units: &[(&str, U)], default: &U) -> Option<(U, f64)> {
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+')))
+        .unwrap_or(text.len());
+    let (magnitude, suffix) = text.split_at(split);
+    let unit = if suffix.is_empty() {
+        default.clone()
+    } else {
+        let suffix = suffix.to_ascii_lowercase();
+        units.iter().find(|(s, _)| *s == suffix)?.1.clone()
+    };
+    Some((unit, number(magnitude)?))
+}
+
+/// Write `query` in the query language, naming activities and attributes from
+/// `library` (or by id when it lacks them). Dates are written in the local time
+/// `utc_offset_minutes` east of UTC. Anything `parse` produces reads back
+/// unchanged; the language has no spelling for a second activity filter, a
+/// second time window or a range value (written as its lower bound).
+pub fn format(query: &AnalyticsQuery, library: &Library, utc_offset_minutes: i32) -> String {
+    let offset = utc_offset_minutes
+        .checked_mul(60)
+        .and_then(FixedOffset::east_opt)
+        .unwrap_or(FixedOffset::east_opt(0).expect("UTC is a valid offset"));
+    let field = |f: &AttributeField| {
+        let name = quote(&library.attribute_name(f.attribute_id));
+        match f.field {
+            ValueField::Plan => format!("plan {name}"),
+            ValueField::Actual => name,
+        }
+    };
+    let date = |t: &DateTime<Utc>| {
+        let local = t.with_timezone(&offset);
+        if local.time() == NaiveTime::MIN {
+            local.format("%Y-%m-%d").to_string()
+        } else {
+            t.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+        }
+    };
+
+    let mut out = match &query.aggregate {
+        Aggregate::Count => "count".to_string(),
+        Aggregate::Sum(f) => format!("sum {}", field(f)),
+        Aggregate::Max(f) => format!("max {}", field(f)),
+        Aggregate::Min(f) => format!("min {}", field(f)),
+        Aggregate::Mean(f) => format!("mean {}", field(f)),
+    };
+    let mut conditions = Vec::new();
+    let mut window = String::new();
+    let mut subject = true;
+    for filter in &query.filters {
+        match filter {
+            EntryFilter::Activity { activity_id } if subject => {
+                subject = false;
+                out += &format!(" {}", quote(&library.activity_name(*activity_id)));
+            }
+            EntryFilter::Activity { .. } => {}
+            EntryFilter::WithinActivity { activity_id } => {
+                out += &format!(" within {}", quote(&library.activity_name(*activity_id)));
+            }
+            EntryFilter::TimeWindow { from, to } => {
+                if *from != DateTime::<Utc>::MIN_UTC {
+                    window += &format!(" since {}", date(from));
+                }
+                if *to != DateTime::<Utc>::MAX_UTC {
+                    window += &format!(" until {}", date(to));
+                }
+            }
+            EntryFilter::Attribute(p) => {
+                let op = match p.comparison {
+                    Comparison::Eq => "=",
+                    Comparison::Ne => "!=",
+                    Comparison::Lt => "<",
+                    Comparison::Le => "<=",
+                    Comparison::Gt => ">",
+                    Comparison::Ge => ">=",
+                };
+                conditions.push(format!(
+                    "{} {op} {}",
+                    field(&p.target),
+                    format_value(&p.operand)
+                ));
+            }
+            EntryFilter::Complete(true) => conditions.push("complete".to_string()),
+            EntryFilter::Complete(false) => conditions.push("incomplete".to_string()),
+        }
+    }
+    if !conditions.is_empty() {
+        out += &format!(" where {}", conditions.join(" and "));
+    }
+    match &query.group_by {
+        None => {}
+        Some(Grouping::Period { period, .. }) => {
+            out += match period {
+                Period::Day => " group by day",
+                Period::Week => " group by week",
+                Period::Month => " group by month",
+            }
+        }
+        Some(Grouping::Attribute(f)) => out += &format!(" group by {}", field(f)),
+    }
+    out + &window
+}
+
+fn format_value(value: &AttributeValue) -> String {
+    fn symbol<U: PartialEq>(units: &[(&'static str, U)], unit: &U) -> &'static str {
+        units.iter().find(|(_, u)| u == unit).map_or("", |(s, _)| s)
+    }
+    match value {
+        AttributeValue::Numeric(NumericValue::Exact(v) | NumericValue::Range { min: v, .. }) => {
+            v.to_string()
+        }
+        AttributeValue::Select(s) | AttributeValue::Grade(s) => match s {
+            SelectValue::Exact(o) | SelectValue::Range { min: o, .. } => quote(o),
+        },
+        AttributeValue::Multiselect(options) => quote(&options.join(", ")),
+        AttributeValue::Mass(m) => match m {
+            MassValue::Exact(m) => format!("{}{}", m.value, symbol(MASS_UNITS, &m.unit)),
+            MassValue::Range { unit, min: v, .. } | MassValue::Composite { unit, value: v, .. } => {
+                format!("{v}{}", symbol(MASS_UNITS, unit))
+            }
+        },
+        AttributeValue::Length(l) => match l {
+            LengthValue::Exact(l) => format!("{}{}", l.value, symbol(LENGTH_UNITS, &l.unit)),
+            LengthValue::Range { unit, min: v, .. }
+            | LengthValue::Composite { unit, value: v, .. } => {
+                format!("{v}{}", symbol(LENGTH_UNITS, unit))
+            }
+        },
+        AttributeValue::Speed(s) => match s {
+            SpeedValue::Exact(s) => format!("{}{}", s.value, symbol(SPEED_UNITS, &s.unit)),
+            SpeedValue::Range { unit, min, .. } => format!("{min}{}", symbol(SPEED_UNITS, unit)),
+        },
+        AttributeValue::Duration(DurationValue::Exact(s) | DurationValue::Range { min: s, .. }) => {
+            format!("{s}s")
+        }
+        AttributeValue::Text(t) => quote(t),
+        AttributeValue::Boolean(b) => b.to_string(),
+    }
+}
+
+/// `text` as a single word when it reads back as one, quoted otherwise.
+fn quote(text: &str) -> String {
+    let bare = !text.is_empty()
+        && text.chars().all(is_word_char)
+        && !KEYWORDS.iter().any(|k| text.eq_ignore_ascii_case(k));
+    if bare {
+        text.to_string()
+    } else {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attribute::NumericConfig;
+
+    fn std_lib() -> Library {
+        Library::new(Uuid::new_v4(), vec![], vec![])
+    }
+
+    fn std_attribute(name: &str) -> Uuid {
+        StandardLibrary::attributes()
+            .into_iter()
+            .find(|a| a.name == name)
+            .unwrap()
+            .id
+    }
+
+    fn err(text: &str) -> QueryParseError {
+        parse(text, &std_lib(), 0).unwrap_err()
+    }
+
+    #[test]
+    fn parses_against_the_standard_library() {
+        let text = r#"count Boulder where "V Grade" >= V8 and Outcome != Sent group by week since 2026-01-01"#;
+        let query = parse(text, &std_lib(), -300).unwrap();
+        let boulder = StandardLibrary::activities()
+            .into_iter()
+            .find(|a| a.activity.name.to_string() == "Boulder")
+            .unwrap()
+            .activity
+            .id;
+        let actual = |name| AttributeField {
+            attribute_id: std_attribute(name),
+            field: ValueField::Actual,
+        };
+        assert_eq!(
+            query,
+            AnalyticsQuery {
+                filters: vec![
+                    EntryFilter::Activity {
+                        activity_id: boulder
+                    },
+                    EntryFilter::Attribute(AttributePredicate {
+                        target: actual("V Grade"),
+                        comparison: Comparison::Ge,
+                        operand: AttributeValue::Grade(SelectValue::Exact("V8".to_string())),
+                    }),
+                    EntryFilter::Attribute(AttributePredicate {
+                        target: actual("Outcome"),
+                        comparison: Comparison::Ne,
+                        operand: AttributeValue::Select(SelectValue::Exact("Sent".to_string())),
+                    }),
+                    EntryFilter::TimeWindow {
+                        from: "2026-01-01T05:00:00Z".parse().unwrap(),
+                        to: DateTime::<Utc>::MAX_UTC,
+                    },
+                ],
+                group_by: Some(Grouping::Period {
+                    period: Period::Week,
+                    utc_offset_minutes: -300,
+                }),
+                aggregate: Aggregate::Count,
+            }
+        );
+        // Canonical text reads back as itself.
+        assert_eq!(format(&query, &std_lib(), -300), text);
+    }
+
+    #[test]
+    fn formats_what_it_parses() {
+        let library = std_lib();
+        for text in [
+            "sum plan Load \"Bench Press\" where Reps >= 5 and complete group by day",
+            "mean Load within Repeaters where Load > 20.5lb group by plan \"Grip Type\"",
+            "max \"YDS Grade\" where Outcome = Flash and incomplete since 2026-02-01 until 2026-03-01",
+            "min Distance where Distance < 5km until 2026-03-01T12:30:00Z",
+            "count where Notes = \"felt \\\"strong\\\"\" group by month",
+        ] {
+            let query = parse(text, &library, 0).unwrap();
+            assert_eq!(format(&query, &library, 0), text);
+        }
+        // Spelling differences normalize.
+        let query = parse("COUNT boulder WHERE actual \"v grade\">=v8", &library, 0).unwrap();
+        assert_eq!(
+            format(&query, &library, 0),
+            "count Boulder where \"V Grade\" >= V8"
+        );
+    }
+
+    #[test]
+    fn own_names_shadow_the_standard_library() {
+        let actor_id = Uuid::new_v4();
+        let load = Attribute {
+            id: Uuid::new_v4(),
+            owner_id: actor_id,
+            name: "Load".to_string(),
+            description: None,
+            config: NumericConfig {
+                min: None,
+                max: None,
+                integer: false,
+                default: None,
+            }
+            .into(),
+        };
+        let library = Library::new(actor_id, vec![], vec![load.clone()]);
+        let query = parse("sum Load", &library, 0).unwrap();
+        assert_eq!(
+            query.aggregate,
+            Aggregate::Sum(AttributeField {
+                attribute_id: load.id,
+                field: ValueField::Actual,
+            })
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let text = "count where \"V Grdae\" >= V8";
+        let e = parse(text, &std_lib(), 0).unwrap_err();
+        assert_eq!(
+            e.message,
+            "unknown attribute 'V Grdae'; did you mean 'V Grade'?"
+        );
+        assert_eq!(&text[e.start as usize..e.end as usize], "\"V Grdae\"");
+        assert_eq!(
+            e.render(text),
+            format!("{text}\n            ^^^^^^^^^ {}", e.message)
+        );
+
+        assert_eq!(
+            err("count where \"V Grade\" >= V18").message,
+            "V Grade has no grade 'V18'; grades run V0 to V17"
+        );
+        assert_eq!(
+            err("count where Outcome = Sen").message,
+            "Outcome has no option 'Sen'; options are Sent, Flash, Onsight, Attempt, Working"
+        );
+        assert_eq!(
+            err("count where Load > heavy").message,
+            "expected a mass for Load, e.g. 100kg (units: g, kg, lb, lbs)"
+        );
+        assert_eq!(err("count where Reps >").start, 18);
+        assert_eq!(
+            err("count where Reps ! 5").message,
+            "expected '=' after '!'"
+        );
+        assert_eq!(err("count \"Boulder").message, "unterminated quote");
+        let trailing_escape = err(r#"count "Boulder\"#);
+        assert_eq!(trailing_escape.message, "unterminated quote");
+        assert_eq!((trailing_escape.start, trailing_escape.end), (6, 15));
+        let escaped = err(r#"count "Bo\"ulder"#);
+        assert_eq!((escaped.start, escaped.end), (6, 16));
+        assert_eq!(
+            err("total Reps").message,
+            "expected an aggregate (count, sum, max, min or mean)"
+        );
+        assert_eq!(
+            err("count Boulder Problem").message,
+            "expected where, within, group by, since or until (quote names that contain spaces)"
+        );
+        assert_eq!(
+            err("count since 2026-03-01 until 2026-02-01").message,
+            "'until' is before 'since'"
+        );
+        assert_eq!(
+            err("count group by week group by day").message,
+            "more than one 'group by' clause"
+        );
+    }
+}
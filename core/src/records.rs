@@ -0,0 +1,443 @@
+//! Personal records: the best actual value an attribute has reached on an
+//! actor's entries of an activity, and each time that best was raised.
+//!
+//! What counts as best depends on the attribute:
+//! - mass and length: the largest, compared in base units, so pounds rank
+//!   against kilograms and miles against kilometers;
+//! - the std-lib Reps: the most at each load, on entries that also carry a
+//!   mass, otherwise the most outright;
+//! - grades and ordered selects: the hardest sent, on entries whose std-lib
+//!   Outcome is Sent, Flash or Onsight.
+//!
+//! Other attributes (durations, speeds, text, other numerics, ...) have no
+//! one direction of better and set no records: fewer is better for a count
+//! of falls. Only entries with a time of their own or an ancestor's count,
+//! so every mark has a date; templates never count, and neither do entries
+//! shared by other actors.
+//!
+//! Values rank by their stored `index_float`, the same number analytics
+//! compares. Like `analytics::evaluate`, this is a pure function of the rows,
+//! so every backend reports the same records.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    analytics::{Log, Pushdown},
+    models::{
+        attribute::{Attribute, AttributeConfig, AttributeValue, SelectValue, Value},
+        entry::Entry,
+    },
+    queries::PersonalRecords,
+    std_lib::{OUTCOME_ID, REPS_ID},
+};
+
+/// Outcomes that make a climb a send.
+const SENDS: [&str; 3] = ["Sent", "Flash", "Onsight"];
+
+/// One attribute's record on one activity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonalRecord {
+    pub activity_id: Uuid,
+    pub attribute_id: Uuid,
+    pub kind: RecordKind,
+    /// The standing record.
+    pub best: RecordMark,
+    /// Every mark that raised the record, oldest first; the last is `best`.
+    pub history: Vec<RecordMark>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordKind {
+    /// The largest value: heaviest load, longest distance, most reps.
+    Max,
+    /// The most at one load, e.g. most reps at 100kg. `load` is the first
+    /// value of `load_attribute_id` logged at that weight; loads are equal
+    /// when they weigh the same to the gram, whatever their units.
+    MaxAt {
+        load_attribute_id: Uuid,
+        load: AttributeValue,
+    },
+    /// The hardest grade sent.
+    HardestSend,
+}
+
+/// A value that set (or raised) a record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordMark {
+    pub entry_id: Uuid,
+    pub at: DateTime<Utc>,
+    pub value: AttributeValue,
+}
+
+/// Which record a value competes for: an attribute on an activity, at a load
+/// (mass attribute and grams) for `RecordKind::MaxAt`.
+type RecordKey = (Uuid, Uuid, Option<(Uuid, i64)>);
+
+/// A record in progress: its kind, best score so far and marks.
+type Standing = (RecordKind, f64, Vec<RecordMark>);
+
+impl PersonalRecords {
+    /// The entries a SQL backend reads: the owner's, of the activity when
+    /// one is named. It loads their ancestors too, for their times.
+    pub fn pushdown(&self) -> Pushdown {
+        Pushdown {
+            owner_id: Some(self.owner_id),
+            activity_ids: self.activity_id.into_iter().collect(),
+            ..Pushdown::default()
+        }
+    }
+}
+
+/// Every record `owner_id` holds over the given rows, for one activity or
+/// (`None`) all of them, ordered by activity, attribute and load. `entries`
+/// must include the ancestors of the activity's entries, which may carry
+/// their times.
+pub fn personal_records(
+    owner_id: Uuid,
+    activity_id: Option<Uuid>,
+    attributes: Vec<Attribute>,
+    entries: Vec<Entry>,
+    values: Vec<Value>,
+) -> Vec<PersonalRecord> {
+    let configs: HashMap<Uuid, AttributeConfig> = attributes
+        .iter()
+        .map(|a| (a.id, a.config.clone()))
+        .collect();
+    let mut by_entry: HashMap<Uuid, Vec<Value>> = HashMap::new();
+    for value in &values {
+        by_entry
+            .entry(value.entry_id)
+            .or_default()
+            .push(value.clone());
+    }
+    let log = Log::new(attributes, entries.clone(), values);
+
+    let mut logged: Vec<(DateTime<Utc>, &Entry)> = entries
+        .iter()
+        .filter(|e| e.owner_id == owner_id && !e.is_template && e.activity_id.is_some())
+        .filter(|e| activity_id.is_none_or(|id| e.activity_id == Some(id)))
+        .filter_map(|e| Some((log.instant(e)?, e)))
+        .collect();
+    // Siblings logged together share a time; their order breaks the tie.
+    logged.sort_by_key(|(t, e)| (*t, e.position.as_ref().map(|p| p.frac_index.clone()), e.id));
+
+    let mut records: HashMap<RecordKey, Standing> = HashMap::new();
+    for (at, entry) in logged {
+        let Some(activity_id) = entry.activity_id else {
+            continue;
+        };
+        let values = by_entry.get(&entry.id).map_or(&[][..], Vec::as_slice);
+        let actuals: Vec<(&Value, &AttributeValue, &AttributeConfig)> = values
+            .iter()
+            .filter_map(|v| Some((v, v.actual.as_ref()?, configs.get(&v.attribute_id)?)))
+            .collect();
+        let sent = actuals.iter().any(|(v, actual, _)| match actual {
+            AttributeValue::Select(SelectValue::Exact(o)) => {
+                v.attribute_id == OUTCOME_ID && SENDS.contains(&o.as_str())
+            }
+            _ => false,
+        });
+        let loads: Vec<(Uuid, &AttributeValue, i64)> = actuals
+            .iter()
+            .filter(|(_, _, config)| matches!(config, AttributeConfig::Mass(_)))
+            .filter_map(|(v, actual, _)| {
+                let kilograms = v.index_float?;
+                Some((v.attribute_id, *actual, (kilograms * 1000.0).round() as i64))
+            })
+            .collect();
+
+        for (value, actual, config) in &actuals {
+            let Some(score) = value.index_float else {
+                continue;
+            };
+            let contests: Vec<(Option<(Uuid, i64)>, RecordKind)> = match config {
+                AttributeConfig::Mass(_) | AttributeConfig::Length(_) => {
+                    vec![(None, RecordKind::Max)]
+                }
+                AttributeConfig::Numeric(_) if value.attribute_id != REPS_ID => vec![],
+                AttributeConfig::Numeric(_) if loads.is_empty() => vec![(None, RecordKind::Max)],
+                AttributeConfig::Numeric(_) => loads
+                    .iter()
+                    .map(|(load_attribute_id, load, grams)| {
+                        (
+                            Some((*load_attribute_id, *grams)),
+                            RecordKind::MaxAt {
+                                load_attribute_id: *load_attribute_id,
+                                load: (*load).clone(),
+                            },
+                        )
+                    })
+                    .collect(),
+                AttributeConfig::Grade(_) if sent => vec![(None, RecordKind::HardestSend)],
+                AttributeConfig::Select(c) if c.ordered && sent => {
+                    vec![(None, RecordKind::HardestSend)]
+                }
+                _ => vec![],
+            };
+            for (load, kind) in contests {
+                let mark = RecordMark {
+                    entry_id: entry.id,
+                    at,
+                    value: (*actual).clone(),
+                };
+                let key = (activity_id, value.attribute_id, load);
+                match records.get_mut(&key) {
+                    Some((_, best, history)) => {
+                        // A tie doesn't raise the record; the first to reach it keeps it.
+                        if score > *best {
+                            *best = score;
+                            history.push(mark);
+                        }
+                    }
+                    None => {
+                        records.insert(key, (kind, score, vec![mark]));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut records: Vec<(RecordKey, Standing)> = records.into_iter().collect();
+    records.sort_by_key(|(key, _)| *key);
+    records
+        .into_iter()
+        .map(
+            |((activity_id, attribute_id, _), (kind, _, history))| PersonalRecord {
+                activity_id,
+                attribute_id,
+                kind,
+                best: history.last().expect("a record has a mark").clone(),
+                history,
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{
+            attribute::{
+                GradeConfig, LengthConfig, LengthUnit, LengthValue, MassConfig, MassUnit,
+                MassValue, Measure, NumericConfig, NumericValue, SelectConfig,
+            },
+            entry::Temporal,
+            grade::GradeScale,
+        },
+        test_log::{TestLog, at, attribute, children, entry},
+    };
+
+    fn logged(activity_id: Uuid, start: &str) -> Entry {
+        entry(
+            Some(activity_id),
+            None,
+            Temporal::Start { start: at(start) },
+        )
+    }
+
+    fn history(record: &PersonalRecord) -> Vec<(Uuid, AttributeValue)> {
+        record
+            .history
+            .iter()
+            .map(|m| (m.entry_id, m.value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn heaviest_load_and_most_reps_at_each_load_across_units() {
+        let bench = Uuid::new_v4();
+        let load = attribute(
+            "Load",
+            MassConfig {
+                default_unit: MassUnit::Kilogram,
+            }
+            .into(),
+        );
+        let count = NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        };
+        let reps = Attribute {
+            id: REPS_ID,
+            ..attribute("Reps", count.clone().into())
+        };
+        // Fewer is better for some counts, so other numerics set no record.
+        let misses = attribute("Missed Reps", count.into());
+        let kg = |v| AttributeValue::Mass(MassValue::exact(MassUnit::Kilogram, v));
+        let lb = |v| AttributeValue::Mass(MassValue::exact(MassUnit::Pound, v));
+        let n = |v| AttributeValue::Numeric(NumericValue::Exact(v));
+        let sets = [
+            ("2026-03-01T10:00:00Z", kg(100.0), 5.0),
+            // 225 lb is 102.1 kg: heavier, and its own load.
+            ("2026-03-02T10:00:00Z", lb(225.0), 3.0),
+            // 95 kg is lighter than 225 lb, though the number is smaller too.
+            ("2026-03-03T10:00:00Z", kg(95.0), 8.0),
+            ("2026-03-04T10:00:00Z", kg(100.0), 6.0),
+        ];
+        let mut log = TestLog::default();
+        for (start, weight, count) in sets {
+            let set = logged(bench, start);
+            log.log(&set, &load, weight);
+            log.log(&set, &reps, n(count));
+            log.log(&set, &misses, n(1.0));
+            log.entries.push(set);
+        }
+        // A template never sets a record.
+        let mut template = logged(bench, "2026-03-05T10:00:00Z");
+        template.is_template = true;
+        log.log(&template, &load, kg(500.0));
+        log.entries.push(template);
+        // Nor does an entry someone else shared.
+        let theirs = Entry {
+            owner_id: Uuid::new_v4(),
+            ..logged(bench, "2026-03-06T10:00:00Z")
+        };
+        log.log(&theirs, &load, kg(300.0));
+        log.entries.push(theirs);
+
+        let entries = log.entries.clone();
+        let records = personal_records(
+            Uuid::nil(),
+            Some(bench),
+            vec![load.clone(), reps.clone(), misses.clone()],
+            log.entries,
+            log.values,
+        );
+        let heaviest = records.iter().find(|r| r.attribute_id == load.id).unwrap();
+        assert!(records.iter().all(|r| r.attribute_id != misses.id));
+        assert_eq!(heaviest.kind, RecordKind::Max);
+        assert_eq!(
+            history(heaviest),
+            vec![(entries[0].id, kg(100.0)), (entries[1].id, lb(225.0))]
+        );
+        assert_eq!(heaviest.best.at, at("2026-03-02T10:00:00Z"));
+
+        let rep_maxes: Vec<_> = records
+            .iter()
+            .filter(|r| r.attribute_id == reps.id)
+            .map(|r| (r.kind.clone(), history(r)))
+            .collect();
+        let at = |weight| RecordKind::MaxAt {
+            load_attribute_id: load.id,
+            load: weight,
+        };
+        assert_eq!(
+            rep_maxes,
+            vec![
+                (at(kg(95.0)), vec![(entries[2].id, n(8.0))]),
+                (
+                    at(kg(100.0)),
+                    vec![(entries[0].id, n(5.0)), (entries[3].id, n(6.0))]
+                ),
+                (at(lb(225.0)), vec![(entries[1].id, n(3.0))]),
+            ]
+        );
+    }
+
+    #[test]
+    fn hardest_send_and_longest_distance() {
+        let boulder = Uuid::new_v4();
+        let grade = attribute(
+            "V Grade",
+            GradeConfig {
+                scale: GradeScale::VScale,
+                default: None,
+            }
+            .into(),
+        );
+        let outcome = Attribute {
+            id: OUTCOME_ID,
+            ..attribute(
+                "Outcome",
+                SelectConfig {
+                    options: ["Sent", "Flash", "Onsight", "Attempt"]
+                        .map(String::from)
+                        .to_vec(),
+                    ordered: false,
+                    default: None,
+                }
+                .into(),
+            )
+        };
+        let distance = attribute(
+            "Distance",
+            LengthConfig {
+                default_unit: LengthUnit::Meter,
+            }
+            .into(),
+        );
+        let v = |g: &str| AttributeValue::Grade(SelectValue::Exact(g.to_string()));
+        let result = |o: &str| AttributeValue::Select(SelectValue::Exact(o.to_string()));
+        let session = Entry {
+            is_sequence: true,
+            ..entry(
+                None,
+                None,
+                Temporal::Start {
+                    start: at("2026-04-01T18:00:00Z"),
+                },
+            )
+        };
+        let mut log = TestLog::default();
+        log.entries.push(session.clone());
+        let problems = [("V5", "Sent"), ("V9", "Attempt"), ("V7", "Flash")];
+        for ((g, o), position) in problems.into_iter().zip(children(session.id)) {
+            // Untimed problems take the session's time.
+            let problem = entry(Some(boulder), Some(position), Temporal::None);
+            log.log(&problem, &grade, v(g));
+            log.log(&problem, &outcome, result(o));
+            log.entries.push(problem);
+        }
+        let run = Uuid::new_v4();
+        let mile = logged(run, "2026-04-02T07:00:00Z");
+        let five_k = logged(run, "2026-04-03T07:00:00Z");
+        log.log(
+            &mile,
+            &distance,
+            AttributeValue::Length(LengthValue::exact(LengthUnit::Mile, 1.0)),
+        );
+        log.log(
+            &five_k,
+            &distance,
+            AttributeValue::Length(LengthValue::exact(LengthUnit::Kilometer, 5.0)),
+        );
+        log.entries.extend([mile.clone(), five_k.clone()]);
+
+        let records = personal_records(
+            Uuid::nil(),
+            None,
+            vec![grade, outcome, distance],
+            log.entries,
+            log.values,
+        );
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.activity_id, r.kind.clone(), r.best.value.clone()))
+            .collect();
+        let mut expected = vec![
+            (boulder, RecordKind::HardestSend, v("V7")),
+            (
+                run,
+                RecordKind::Max,
+                AttributeValue::Length(LengthValue::exact(LengthUnit::Kilometer, 5.0)),
+            ),
+        ];
+        expected.sort_by_key(|(activity_id, _, _)| *activity_id);
+        assert_eq!(summary, expected);
+        let hardest = records
+            .iter()
+            .find(|r| r.kind == RecordKind::HardestSend)
+            .unwrap();
+        assert_eq!(hardest.history.len(), 2);
+        assert_eq!(hardest.best.at, at("2026-04-01T18:00:00Z"));
+        let longest = records.iter().find(|r| r.activity_id == run).unwrap();
+        assert_eq!(longest.history[0].entry_id, mile.id);
+    }
+}
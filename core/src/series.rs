@@ -0,0 +1,468 @@
+//! One attribute's values over time, for charts: "load on Bench Press", "distance
+//! per run by week".
+//!
+//! A point's time is its entry's canonical instant or, for an entry with no
+//! time of its own, its root ancestor's. Values are read through the
+//! attribute's index columns like analytics (a range by its lower bound, a
+//! speed by its slower end) and then converted to the series' unit. Entries
+//! without a value, or whose value has no magnitude (text, unordered
+//! selects), contribute no point.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    analytics::{AttributeField, Log, Period, Pushdown, offset},
+    error::{Result, ValidationError},
+    models::{
+        attribute::{Attribute, AttributeConfig, LengthUnit, MassUnit, SpeedUnit, TimeUnit, Value},
+        entry::Entry,
+    },
+    queries::AttributeSeries,
+};
+
+/// Calendar periods in the local time `utc_offset_minutes` east of UTC, as in
+/// `Grouping::Period`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SeriesBucket {
+    pub period: Period,
+    pub utc_offset_minutes: i32,
+}
+
+/// How the values behind one point combine into its value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SeriesAggregate {
+    Sum,
+    Max,
+    Min,
+    Mean,
+    /// The number of values.
+    Count,
+}
+
+/// Points whose time falls in `from..to`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SeriesRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+/// The unit a measure attribute's series is in. It must be of the attribute's
+/// dimension.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SeriesUnit {
+    Mass(MassUnit),
+    Length(LengthUnit),
+    Speed(SpeedUnit),
+    Duration(TimeUnit),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    /// For a bucket, its start.
+    pub time: DateTime<Utc>,
+    pub value: f64,
+    /// The entry, or the sets sequence it was rolled up to; `None` for a
+    /// bucket.
+    pub entry_id: Option<Uuid>,
+    /// How many values the point combines.
+    pub values: u32,
+}
+
+impl AttributeSeries {
+    pub fn validate(&self) -> Result<()> {
+        let invalid =
+            |reason: &str| Err(ValidationError::InvalidAnalyticsQuery(reason.into()).into());
+        if let Some(bucket) = &self.bucket
+            && offset(bucket.utc_offset_minutes).is_none()
+        {
+            return invalid("UTC offset out of range");
+        }
+        if let Some(range) = &self.range
+            && range.from > range.to
+        {
+            return invalid("time range ends before it starts");
+        }
+        Ok(())
+    }
+
+    /// What a SQL backend can apply of this query, given the `attributes` it
+    /// reads (`query_executor::find_attributes`): the activity's entries
+    /// timed, or under an ancestor timed, in the range. Their ancestors load
+    /// with them, and their descendants when a derived attribute reads them.
+    pub fn pushdown(&self, attributes: &[Attribute]) -> Pushdown {
+        Pushdown {
+            activity_ids: vec![self.activity_id],
+            windows: self.range.iter().map(|r| (r.from, r.to)).collect(),
+            descendants: attributes
+                .iter()
+                .any(|a| matches!(a.config, AttributeConfig::Derived(_))),
+            ..Pushdown::default()
+        }
+    }
+
+    /// The unit points are in: the requested one, else the attribute's
+    /// default (seconds for a duration). `None` for an attribute that isn't a
+    /// measure.
+    fn unit_for(&self, config: &AttributeConfig) -> Result<Option<SeriesUnit>> {
+        let unit = match (config, &self.unit) {
+            (AttributeConfig::Mass(_), Some(u @ SeriesUnit::Mass(_)))
+            | (AttributeConfig::Length(_), Some(u @ SeriesUnit::Length(_)))
+            | (AttributeConfig::Speed(_), Some(u @ SeriesUnit::Speed(_)))
+            | (AttributeConfig::Duration(_), Some(u @ SeriesUnit::Duration(_))) => u.clone(),
+            (_, Some(_)) => {
+                return Err(ValidationError::InvalidAnalyticsQuery(
+                    "unit doesn't match the attribute's kind".into(),
+                )
+                .into());
+            }
+            (AttributeConfig::Mass(c), None) => SeriesUnit::Mass(c.default_unit.clone()),
+            (AttributeConfig::Length(c), None) => SeriesUnit::Length(c.default_unit.clone()),
+            (AttributeConfig::Speed(c), None) => SeriesUnit::Speed(c.default_unit.clone()),
+            (AttributeConfig::Duration(_), None) => SeriesUnit::Duration(TimeUnit::Second),
+            (_, None) => return Ok(None),
+        };
+        Ok(Some(unit))
+    }
+}
+
+impl SeriesUnit {
+    /// `magnitude`, in the dimension's base unit (kg, m, m/s or s), in this
+    /// unit.
+    fn express(&self, magnitude: f64) -> f64 {
+        match self {
+            SeriesUnit::Mass(u) => MassUnit::Kilogram.convert(magnitude, u),
+            SeriesUnit::Length(u) => LengthUnit::Meter.convert(magnitude, u),
+            SeriesUnit::Speed(u) => SpeedUnit::MetersPerSecond.convert(magnitude, u),
+            SeriesUnit::Duration(u) => magnitude / u.seconds_per_unit(),
+        }
+    }
+}
+
+impl SeriesAggregate {
+    fn apply(&self, values: &[f64]) -> f64 {
+        match self {
+            SeriesAggregate::Sum => values.iter().sum(),
+            SeriesAggregate::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            SeriesAggregate::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            SeriesAggregate::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            SeriesAggregate::Count => values.len() as f64,
+        }
+    }
+}
+
+/// A point's time and entry; a bucket's start and no entry.
+type PointKey = (DateTime<Utc>, Option<Uuid>);
+
+/// The series `query` asks for, in time order. Without a bucket there is one
+/// point per logged entry of the activity, or, with `roll_up_sets`, one per
+/// sets sequence for its members. With a bucket there is one point per
+/// period that has values, aggregating all of them, so rolling up changes
+/// nothing. `attributes` and `values` need only cover the attribute,
+/// transitively through derived ones; `entries` must include the ancestors
+/// of the activity's entries.
+pub fn attribute_series(
+    query: &AttributeSeries,
+    attributes: Vec<Attribute>,
+    entries: Vec<Entry>,
+    values: Vec<Value>,
+) -> Result<Vec<SeriesPoint>> {
+    query.validate()?;
+    let Some(attribute) = attributes.iter().find(|a| a.id == query.attribute_id) else {
+        return Ok(vec![]);
+    };
+    let unit = query.unit_for(&attribute.config)?;
+    let target = AttributeField {
+        attribute_id: query.attribute_id,
+        field: query.field.clone(),
+    };
+    let log = Log::new(attributes, entries.clone(), values);
+    let time = |entry: &Entry| {
+        entry
+            .temporal
+            .canonical_instant()
+            .or_else(|| log.ancestors(entry).last()?.temporal.canonical_instant())
+    };
+
+    let mut readings = Vec::new();
+    for entry in &entries {
+        if entry.is_template || entry.activity_id != Some(query.activity_id) {
+            continue;
+        }
+        let Some((_, (Some(magnitude), _))) = log.read(&target, entry.id) else {
+            continue;
+        };
+        let value = unit.as_ref().map_or(magnitude, |u| u.express(magnitude));
+        let owner = match log.ancestors(entry).next() {
+            Some(sets) if query.roll_up_sets && sets.display_as_sets => sets,
+            _ => entry,
+        };
+        let Some(at) = time(owner) else { continue };
+        if query
+            .range
+            .as_ref()
+            .is_some_and(|r| !(r.from..r.to).contains(&at))
+        {
+            continue;
+        }
+        let frac_index = owner.position.as_ref().map(|p| p.frac_index.clone());
+        readings.push(((at, frac_index, owner.id), value));
+    }
+    // Time order, then log order among siblings, so points that share a time
+    // (the sets of one untimed workout) keep the order they were logged in.
+    readings.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    // Each value under the point it belongs to, keyed by that point's time and
+    // entry (or, in a bucket, its start).
+    let mut points: Vec<(PointKey, Vec<f64>)> = Vec::new();
+    let mut slots = HashMap::new();
+    for ((at, _, owner_id), value) in readings {
+        let key = match &query.bucket {
+            None => (at, Some(owner_id)),
+            Some(bucket) => {
+                let offset = offset(bucket.utc_offset_minutes).expect("validated UTC offset");
+                (bucket.period.start(at, offset), None)
+            }
+        };
+        let slot = *slots.entry(key).or_insert_with(|| {
+            points.push((key, vec![]));
+            points.len() - 1
+        });
+        points[slot].1.push(value);
+    }
+
+    Ok(points
+        .into_iter()
+        .map(|((time, entry_id), values)| SeriesPoint {
+            time,
+            value: query.aggregate.apply(&values),
+            entry_id,
+            values: values.len() as u32,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::ValueField,
+        models::{
+            attribute::{AttributeValue, MassConfig, MassMeasurement, MassValue, TextConfig},
+            entry::Temporal,
+        },
+        test_log::{TestLog, at, attribute, children, entry},
+    };
+
+    struct Fixture {
+        bench: Uuid,
+        load: Attribute,
+        log: TestLog,
+    }
+
+    /// Two timed workouts, each holding an untimed sets sequence of bench
+    /// press (the second logged in pounds), plus a one-off timed set.
+    fn fixture() -> Fixture {
+        let bench = Uuid::new_v4();
+        let load = attribute(
+            "Load",
+            MassConfig {
+                default_unit: MassUnit::Kilogram,
+            }
+            .into(),
+        );
+        let mut f = Fixture {
+            bench,
+            load,
+            log: TestLog::default(),
+        };
+        let log_set = |f: &mut Fixture, position, temporal, unit: MassUnit, value| {
+            let set = entry(Some(bench), position, temporal);
+            let mass = AttributeValue::Mass(MassValue::Exact(MassMeasurement { unit, value }));
+            f.log.log(&set, &f.load, mass);
+            f.log.entries.push(set);
+        };
+        for (start, unit, loads) in [
+            ("2026-03-02T18:00:00Z", MassUnit::Kilogram, [80.0, 90.0]),
+            ("2026-03-04T18:00:00Z", MassUnit::Pound, [220.0, 225.0]),
+        ] {
+            let workout = entry(None, None, Temporal::Start { start: at(start) });
+            let sets = Entry {
+                display_as_sets: true,
+                is_sequence: true,
+                ..entry(Some(bench), children(workout.id).next(), Temporal::None)
+            };
+            for (load, position) in loads.into_iter().zip(children(sets.id)) {
+                log_set(&mut f, Some(position), Temporal::None, unit.clone(), load);
+            }
+            f.log.entries.extend([workout, sets]);
+        }
+        log_set(
+            &mut f,
+            None,
+            Temporal::Start {
+                start: at("2026-03-10T07:00:00Z"),
+            },
+            MassUnit::Kilogram,
+            100.0,
+        );
+        f
+    }
+
+    fn query(f: &Fixture) -> AttributeSeries {
+        AttributeSeries {
+            activity_id: f.bench,
+            attribute_id: f.load.id,
+            field: ValueField::Actual,
+            bucket: None,
+            aggregate: SeriesAggregate::Max,
+            range: None,
+            unit: None,
+            roll_up_sets: false,
+        }
+    }
+
+    fn run(f: &Fixture, query: &AttributeSeries) -> Vec<(DateTime<Utc>, f64, u32)> {
+        attribute_series(
+            query,
+            vec![f.load.clone()],
+            f.log.entries.clone(),
+            f.log.values.clone(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|p| (p.time, (p.value * 100.0).round() / 100.0, p.values))
+        .collect()
+    }
+
+    #[test]
+    fn sets_take_their_root_time_and_roll_up() {
+        let f = fixture();
+        let first = at("2026-03-02T18:00:00Z");
+        let second = at("2026-03-04T18:00:00Z");
+        let third = at("2026-03-10T07:00:00Z");
+        // One point per set, timed by its workout, in the attribute's kg.
+        assert_eq!(
+            run(&f, &query(&f)),
+            vec![
+                (first, 80.0, 1),
+                (first, 90.0, 1),
+                (second, 99.79, 1),
+                (second, 102.06, 1),
+                (third, 100.0, 1),
+            ]
+        );
+        let rolled = AttributeSeries {
+            roll_up_sets: true,
+            unit: Some(SeriesUnit::Mass(MassUnit::Pound)),
+            ..query(&f)
+        };
+        assert_eq!(
+            run(&f, &rolled),
+            vec![(first, 198.42, 2), (second, 225.0, 2), (third, 220.46, 1)]
+        );
+    }
+
+    #[test]
+    fn buckets_by_week_within_range() {
+        let f = fixture();
+        let weekly = AttributeSeries {
+            bucket: Some(SeriesBucket {
+                period: Period::Week,
+                utc_offset_minutes: 0,
+            }),
+            aggregate: SeriesAggregate::Count,
+            ..query(&f)
+        };
+        assert_eq!(
+            run(&f, &weekly),
+            vec![
+                (at("2026-03-02T00:00:00Z"), 4.0, 4),
+                (at("2026-03-09T00:00:00Z"), 1.0, 1),
+            ]
+        );
+        let ranged = AttributeSeries {
+            range: Some(SeriesRange {
+                from: at("2026-03-03T00:00:00Z"),
+                to: at("2026-03-31T00:00:00Z"),
+            }),
+            aggregate: SeriesAggregate::Sum,
+            ..weekly
+        };
+        assert_eq!(
+            run(&f, &ranged),
+            vec![
+                (at("2026-03-02T00:00:00Z"), 201.85, 2),
+                (at("2026-03-09T00:00:00Z"), 100.0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_a_unit_of_another_dimension() {
+        let f = fixture();
+        let metres = AttributeSeries {
+            unit: Some(SeriesUnit::Length(LengthUnit::Meter)),
+            ..query(&f)
+        };
+        let result = attribute_series(&metres, vec![f.load.clone()], vec![], vec![]);
+        assert!(result.is_err());
+
+        // Text has no magnitude to plot.
+        let notes = attribute(
+            "Notes",
+            TextConfig {
+                default: None,
+                autocomplete: false,
+            }
+            .into(),
+        );
+        let set = f
+            .log
+            .entries
+            .iter()
+            .find(|e| e.position.is_none() && e.activity_id.is_some());
+        let value = notes.indexed(Value {
+            entry_id: set.unwrap().id,
+            attribute_id: notes.id,
+            index_float: None,
+            index_string: None,
+            plan: None,
+            actual: Some(AttributeValue::Text("heavy".to_string())),
+        });
+        let notes_series = AttributeSeries {
+            attribute_id: notes.id,
+            ..query(&f)
+        };
+        let points = attribute_series(
+            &notes_series,
+            vec![notes],
+            f.log.entries.clone(),
+            vec![value],
+        )
+        .unwrap();
+        assert!(points.is_empty());
+    }
+
+    #[test]
+    fn pushdown_reads_the_activity_in_range() {
+        let f = fixture();
+        let range = SeriesRange {
+            from: at("2026-03-03T00:00:00Z"),
+            to: at("2026-03-31T00:00:00Z"),
+        };
+        let ranged = AttributeSeries {
+            range: Some(range.clone()),
+            ..query(&f)
+        };
+        let pushdown = ranged.pushdown(std::slice::from_ref(&f.load));
+        assert_eq!(pushdown.activity_ids, vec![f.bench]);
+        assert_eq!(pushdown.windows, vec![(range.from, range.to)]);
+        assert_eq!(pushdown.owner_id, None);
+        assert!(!pushdown.descendants);
+        assert!(query(&f).pushdown(&[f.load]).windows.is_empty());
+    }
+}
@@ -6,11 +6,12 @@ use crate::{
     models::{
         activity::{Activity, ActivityName},
         attribute::{
-            Attribute, AttributeValue, LengthConfig, LengthUnit, MassConfig, MassMeasurement,
-            MassUnit, MassValue, MultiselectConfig, NumericConfig, NumericValue, SelectConfig,
-            TextConfig, Value,
+            Attribute, AttributeValue, DurationConfig, DurationDisplay, GradeConfig, LengthConfig,
+            LengthUnit, MassConfig, MassMeasurement, MassUnit, MassValue, MultiselectConfig,
+            NumericConfig, NumericValue, SelectConfig, TextConfig, Value,
         },
         entry::{Entry, Position, Temporal},
+        grade::GradeScale,
     },
 };
 
@@ -27,9 +28,9 @@ use crate::{
 // before distributing a build.
 // -----------------------------------------------------------------------------
 
-const REPS_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000001");
+pub const REPS_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000001");
 const LOAD_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000002");
-const OUTCOME_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000003");
+pub const OUTCOME_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000003");
 const YDS_GRADE_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000004");
 const RPE_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000005");
 const V_GRADE_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000006");
@@ -39,6 +40,7 @@ const DISTANCE_ID: Uuid = uuid!("00000000-0000-4000-8000-000000000009");
 const NOTES_ID: Uuid = uuid!("00000000-0000-4000-8000-00000000000a");
 const LOCATION_ID: Uuid = uuid!("00000000-0000-4000-8000-00000000000b");
 const CLIMB_TAG_ID: Uuid = uuid!("00000000-0000-4000-8000-00000000000c");
+const HOLD_TIME_ID: Uuid = uuid!("00000000-0000-4000-8000-00000000000d");
 
 /// Stable ids for one std-lib activity: its own id plus the ids of its template
 /// entries. Grouping keeps each activity's ids together and lets call sites read
@@ -123,6 +125,11 @@ const REAR_ELEVATED_SPLIT_SQUAT: ActivityIds = ActivityIds::new(
     uuid!("00000000-0000-4000-8000-000000000392"),
 );
 
+const PLANK: ActivityIds = ActivityIds::new(
+    uuid!("00000000-0000-4000-8000-0000000003a1"),
+    uuid!("00000000-0000-4000-8000-0000000003a2"),
+);
+
 /// A std-lib activity bundled with its template subtree and any values to attach
 /// to template entries. The client seeds these as a `CreateActivity` followed by
 /// a `CreateValue` per `template_values` entry.
@@ -182,36 +189,14 @@ impl StandardLibrary {
             .into(),
         };
 
-        // This is a good example where you want something like an equivalence, so you can use 10-
-        // but map that to 10a or 10b if someone else uses that, or map to French grades.
         let yds_grade = Attribute {
             id: YDS_GRADE_ID,
             owner_id: DEFAULT_USER_ID,
             name: "YDS Grade".to_string(),
             description: Some("Yosemite Decimal System climbing grade".to_string()),
-            config: SelectConfig {
-                options: vec![
-                    "<5.5".to_string(),
-                    "5.5".to_string(),
-                    "5.6".to_string(),
-                    "5.7".to_string(),
-                    "5.8".to_string(),
-                    "5.9".to_string(),
-                    "10-".to_string(),
-                    "10".to_string(),
-                    "10+".to_string(),
-                    "11-".to_string(),
-                    "11".to_string(),
-                    "11+".to_string(),
-                    "12-".to_string(),
-                    "12".to_string(),
-                    "12+".to_string(),
-                    "13-".to_string(),
-                    "13".to_string(),
-                    "13+".to_string(),
-                ],
+            config: GradeConfig {
+                scale: GradeScale::Yds,
                 default: None,
-                ordered: true,
             }
             .into(),
         };
@@ -235,10 +220,9 @@ impl StandardLibrary {
             owner_id: DEFAULT_USER_ID,
             name: "V Grade".to_string(),
             description: Some("Hueco V-scale bouldering grade".to_string()),
-            config: SelectConfig {
-                options: (0..=12).map(|n| format!("V{n}")).collect(),
+            config: GradeConfig {
+                scale: GradeScale::VScale,
                 default: None,
-                ordered: true,
             }
             .into(),
         };
@@ -349,9 +333,24 @@ impl StandardLibrary {
             .into(),
         };
 
+        let hold_time = Attribute {
+            id: HOLD_TIME_ID,
+            owner_id: DEFAULT_USER_ID,
+            name: "Hold Time".to_string(),
+            description: Some(
+                "How long a position is held — a fingerboard hang or a plank. Independent of \
+                 the entry's time, so each set can carry its own."
+                    .to_string(),
+            ),
+            config: DurationConfig {
+                display: DurationDisplay::Seconds,
+            }
+            .into(),
+        };
+
         vec![
             reps, load, outcome, yds_grade, rpe, v_grade, grip_type, hold_size, distance, notes,
-            location, climb_tag,
+            location, climb_tag, hold_time,
         ]
     }
 
@@ -398,7 +397,7 @@ impl StandardLibrary {
                 &REPEATERS,
                 "Repeaters",
                 "Fingerboard training protocol usually targeting power-endurance. Alternate reps (~5-10 seconds) with short rest (~3 seconds).",
-                &[GRIP_TYPE_ID, RPE_ID, LOAD_ID, HOLD_SIZE_ID],
+                &[GRIP_TYPE_ID, RPE_ID, LOAD_ID, HOLD_SIZE_ID, HOLD_TIME_ID],
             ),
             scalar_activity(
                 &DUMBBELL_OVERHEAD_PRESS,
@@ -424,6 +423,12 @@ impl StandardLibrary {
                 "Split squat with the rear foot elevated on a surface like a bench or box. Good for building stability and supporting muscles used in running and climbing. Common loading mechanisms are handheld dumbbells, barbell, or a kettlebell in goblet grip.",
                 &[REPS_ID, LOAD_ID],
             ),
+            scalar_activity(
+                &PLANK,
+                "Plank",
+                "An isometric core hold on the forearms and toes, keeping the body in a straight line.",
+                &[HOLD_TIME_ID, RPE_ID],
+            ),
             // Seeded last: its set members reference the activities above, which
             // must already be committed (each activity is its own transaction).
             Self::strength_workout(),
@@ -444,6 +449,7 @@ impl StandardLibrary {
                 "General strength workout targeting a mix of pull, push, and single-leg."
                     .to_string(),
             ),
+            archived: false,
         };
 
         let root = template_entry(
@@ -651,6 +657,7 @@ fn scalar_activity(
         source_activity_id: None,
         name: ActivityName::parse(name.to_string()).expect("valid std-lib name"),
         description: Some(description.to_string()),
+        archived: false,
     };
     let root = template_entry(ids.template.id, Some(ids.id), None, false);
     let template_values = attribute_ids
@@ -0,0 +1,131 @@
+//! Write-path sync protocol between a client replica and the authoritative
+//! server (see `docs/sync.md`).
+//!
+//! - Push: the client sends its unacknowledged mutations in the order it
+//!   applied them. The server re-runs each action's mutator against its own
+//!   state and either commits it under a global `seq_num` (ack) or rejects it.
+//! - Pull: the client asks for every mutation committed after the last
+//!   `seq_num` it has seen, then rebases its local-only mutations on top. It
+//!   only gets what its actor may read (see [`scope_pulled`]).
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    delta::{AnyDelta, Delta},
+    error::Result,
+    mutation_log::CommittedMutation,
+    mutators::Mutation,
+    permissions::Visibility,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PushRequest {
+    /// The latest server `seq_num` the client had applied when it pushed:
+    /// where in the server timeline these mutations were authored.
+    pub last_seen_server_seq: i64,
+    pub mutations: Vec<Mutation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PushResponse {
+    /// One result per pushed mutation, in push order.
+    pub results: Vec<PushResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PushResult {
+    /// The server committed the mutation as `seq_num`. Pushing an already
+    /// committed mutation again acks it with its original `seq_num`.
+    Acked { mutation_id: Uuid, seq_num: i64 },
+    /// The server's mutator refused the action against the server state.
+    Rejected { mutation_id: Uuid, reason: String },
+}
+
+impl PushResult {
+    pub fn mutation_id(&self) -> Uuid {
+        match self {
+            PushResult::Acked { mutation_id, .. } => *mutation_id,
+            PushResult::Rejected { mutation_id, .. } => *mutation_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub after_seq_num: i64,
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PullResponse {
+    /// Committed mutations with `seq_num > after_seq_num` that the actor may
+    /// see, in commit order.
+    pub mutations: Vec<CommittedMutation>,
+    /// The last `seq_num` this page covers, including mutations left out of
+    /// it: where the next pull starts. A page that covers nothing new means
+    /// the client is caught up. Defaults to 0 for servers that predate it,
+    /// whose pages were never filtered.
+    #[serde(default)]
+    pub through_seq_num: i64,
+}
+
+/// `mutations` as `actor_id` may see them, given `visible`, what they can
+/// read as of the pull (the `VisibleTo` scope the query executors use).
+///
+/// The actor's own mutations arrive whole. Anyone else's carry only their
+/// deltas to rows the actor can read, and are left out when none remain:
+/// users and actors are a public directory, values follow their entry, and a
+/// grant is visible to its two parties.
+///
+/// Visibility is as of the pull, not of each mutation: a row that stops
+/// being readable (its grant revoked) is not retracted from a replica that
+/// already has it, and one that becomes readable after a replica's cursor
+/// passed its insert is not sent to that replica (see `docs/sync.md`).
+pub fn scope_pulled(
+    actor_id: Uuid,
+    visible: &Visibility,
+    mutations: Vec<CommittedMutation>,
+) -> Vec<CommittedMutation> {
+    mutations
+        .into_iter()
+        .filter_map(|mut committed| {
+            if committed.mutation.action.actor_id() == actor_id {
+                return Some(committed);
+            }
+            committed
+                .mutation
+                .changes
+                .retain(|delta| delta_visible(actor_id, visible, delta));
+            (!committed.mutation.changes.is_empty()).then_some(committed)
+        })
+        .collect()
+}
+
+fn delta_visible(actor_id: Uuid, visible: &Visibility, delta: &AnyDelta) -> bool {
+    fn touches<T>(delta: &Delta<T>, f: impl Fn(&T) -> bool) -> bool {
+        delta.before().into_iter().chain(delta.after()).any(f)
+    }
+    match delta {
+        AnyDelta::User(_) | AnyDelta::Actor(_) => true,
+        AnyDelta::Activity(d) => touches(d, |a| {
+            a.owner_id == actor_id || visible.activities.contains(&a.id)
+        }),
+        AnyDelta::Entry(d) => touches(d, |e| {
+            e.owner_id == actor_id || visible.entries.contains(&e.id)
+        }),
+        AnyDelta::Attribute(d) => touches(d, |a| {
+            a.owner_id == actor_id || visible.attributes.contains(&a.id)
+        }),
+        AnyDelta::Value(d) => touches(d, |v| visible.entries.contains(&v.entry_id)),
+        AnyDelta::Grant(d) => touches(d, |g| g.grantor_id == actor_id || g.grantee_id == actor_id),
+    }
+}
+
+/// The server side of the sync protocol. Implemented by the Postgres server
+/// directly, so a client can sync in-process with no network in between.
+#[allow(async_fn_in_trait)]
+pub trait SyncServer {
+    async fn push(&self, request: PushRequest) -> Result<PushResponse>;
+    async fn pull(&self, request: PullRequest) -> Result<PullResponse>;
+}
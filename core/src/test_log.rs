@@ -0,0 +1,78 @@
+//! Building blocks for the logs the analytics, records, training load and
+//! series unit tests evaluate: attributes and entries owned by the nil actor
+//! and the indexed values on them.
+
+use chrono::{DateTime, Utc};
+use fractional_index::FractionalIndex;
+use uuid::Uuid;
+
+use crate::models::{
+    attribute::{Attribute, AttributeConfig, AttributeValue, Value},
+    entry::{Entry, Position, Temporal},
+};
+
+pub(crate) fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+pub(crate) fn attribute(name: &str, config: AttributeConfig) -> Attribute {
+    Attribute {
+        id: Uuid::new_v4(),
+        owner_id: Uuid::nil(),
+        name: name.to_string(),
+        description: None,
+        config,
+    }
+}
+
+/// A completed, non-template entry.
+pub(crate) fn entry(
+    activity_id: Option<Uuid>,
+    position: Option<Position>,
+    temporal: Temporal,
+) -> Entry {
+    Entry {
+        id: Uuid::new_v4(),
+        activity_id,
+        owner_id: Uuid::nil(),
+        name: None,
+        position,
+        is_template: false,
+        display_as_sets: false,
+        is_sequence: false,
+        is_complete: true,
+        temporal,
+    }
+}
+
+/// Positions for the children of `parent_id`, in order.
+pub(crate) fn children(parent_id: Uuid) -> impl Iterator<Item = Position> {
+    std::iter::successors(Some(FractionalIndex::default()), |f| {
+        Some(FractionalIndex::new_after(f))
+    })
+    .map(move |frac_index| Position {
+        parent_id,
+        frac_index,
+    })
+}
+
+/// Entries and the values logged on them.
+#[derive(Default)]
+pub(crate) struct TestLog {
+    pub entries: Vec<Entry>,
+    pub values: Vec<Value>,
+}
+
+impl TestLog {
+    /// Record `actual` for `attribute` on `entry`, with its index columns.
+    pub(crate) fn log(&mut self, entry: &Entry, attribute: &Attribute, actual: AttributeValue) {
+        self.values.push(attribute.indexed(Value {
+            entry_id: entry.id,
+            attribute_id: attribute.id,
+            index_float: None,
+            index_string: None,
+            plan: None,
+            actual: Some(actual),
+        }));
+    }
+}
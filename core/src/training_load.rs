@@ -0,0 +1,501 @@
+//! Training load by the session-RPE method: a session's load is its RPE times
+//! its duration in minutes, in arbitrary units.
+//!
+//! A session is a timed, top-level entry of the owner's log. Its RPE is the
+//! actual value of the RPE attribute on that entry or, when it has none, the
+//! mean of the RPEs logged on the entries under it (the std lib puts RPE on
+//! each exercise, not on the workout). Its duration comes from
+//! `Temporal::infer_duration_ms`. A session missing either has an unknown
+//! load: it is counted in its day's and week's `unknown`, never as zero.
+//!
+//! From daily totals come the acute:chronic workload ratio (each day's last 7
+//! days against the weekly mean of its last 28) and, per week, monotony (mean
+//! daily load over its standard deviation) and strain (weekly load times
+//! monotony). Rest days count as zero load in both.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    analytics::{Pushdown, offset},
+    error::{Result, ValidationError},
+    models::{
+        attribute::{AttributeValue, NumericValue, Value},
+        entry::Entry,
+    },
+    queries::TrainingLoad,
+};
+
+/// Longest window a report covers, in days.
+const MAX_WINDOW_DAYS: i64 = 3660;
+
+/// Days of load the chronic side of the ratio reads, ending with the day.
+const CHRONIC_DAYS: i64 = 28;
+
+/// The span a report covers: every local day (and Monday-start week) that
+/// overlaps `from..to`, in the time zone `utc_offset_minutes` east of UTC.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LoadWindow {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub utc_offset_minutes: i32,
+}
+
+/// Load over a window, as daily and weekly time series.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingLoadReport {
+    /// The window's sessions in time order, including those of unknown load.
+    pub sessions: Vec<SessionLoad>,
+    pub days: Vec<DayLoad>,
+    pub weeks: Vec<WeekLoad>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionLoad {
+    pub entry_id: Uuid,
+    pub at: DateTime<Utc>,
+    /// A range's midpoint.
+    pub rpe: Option<f64>,
+    pub duration_minutes: Option<f64>,
+    /// `rpe × duration_minutes`, or `None` when either is unknown.
+    pub load: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayLoad {
+    /// Local midnight.
+    pub start: DateTime<Utc>,
+    /// Total of the day's sessions of known load.
+    pub load: f64,
+    pub sessions: u32,
+    /// Sessions of unknown load, left out of `load`.
+    pub unknown: u32,
+    /// Load over the 7 days ending with this one against the weekly mean of
+    /// the 28 ending with it; `None` while that mean is zero.
+    pub acute_chronic_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeekLoad {
+    /// Local midnight on Monday.
+    pub start: DateTime<Utc>,
+    pub load: f64,
+    pub sessions: u32,
+    pub unknown: u32,
+    /// Mean daily load over its standard deviation; `None` when every day's
+    /// load is the same.
+    pub monotony: Option<f64>,
+    /// `load × monotony`.
+    pub strain: Option<f64>,
+}
+
+impl LoadWindow {
+    pub fn validate(&self) -> Result<()> {
+        let invalid =
+            |reason: &str| Err(ValidationError::InvalidAnalyticsQuery(reason.into()).into());
+        if offset(self.utc_offset_minutes).is_none() {
+            return invalid("UTC offset out of range");
+        }
+        if self.from >= self.to {
+            return invalid("training load window must end after it starts");
+        }
+        if self.to - self.from > TimeDelta::days(MAX_WINDOW_DAYS) {
+            return invalid("training load window is longer than ten years");
+        }
+        Ok(())
+    }
+}
+
+impl TrainingLoad {
+    /// The entries a SQL backend reads: the owner's top-level entries, with
+    /// everything under them for their RPEs, timed from the chronic period
+    /// before the window to a week after it (the last week is reported
+    /// whole).
+    pub fn pushdown(&self) -> Pushdown {
+        let LoadWindow { from, to, .. } = self.window;
+        let from = from.checked_sub_signed(TimeDelta::days(CHRONIC_DAYS));
+        let to = to.checked_add_signed(TimeDelta::days(7));
+        Pushdown {
+            owner_id: Some(self.owner_id),
+            top_level: true,
+            windows: vec![(
+                from.unwrap_or(DateTime::<Utc>::MIN_UTC),
+                to.unwrap_or(DateTime::<Utc>::MAX_UTC),
+            )],
+            descendants: true,
+            ..Pushdown::default()
+        }
+    }
+}
+
+/// The day's and week's totals a session counts toward.
+#[derive(Default, Clone, Copy)]
+struct Tally {
+    load: f64,
+    sessions: u32,
+    unknown: u32,
+}
+
+impl Tally {
+    fn add(&mut self, other: Tally) {
+        self.load += other.load;
+        self.sessions += other.sessions;
+        self.unknown += other.unknown;
+    }
+}
+
+/// Report `owner_id`'s load in `window` from the log's `entries` and the
+/// values of the RPE attribute (`rpe_attribute_id`). The window must be valid.
+pub fn training_load(
+    owner_id: Uuid,
+    rpe_attribute_id: Uuid,
+    window: &LoadWindow,
+    entries: Vec<Entry>,
+    values: Vec<Value>,
+) -> TrainingLoadReport {
+    let offset = offset(window.utc_offset_minutes).expect("validated UTC offset");
+    let date = |t: DateTime<Utc>| t.with_timezone(&offset).date_naive();
+    let midnight = |d: NaiveDate| {
+        d.and_time(NaiveTime::MIN)
+            .and_local_timezone(offset)
+            .single()
+            .expect("a fixed offset has no gaps")
+            .with_timezone(&Utc)
+    };
+    let owned: HashMap<Uuid, &Entry> = entries
+        .iter()
+        .filter(|e| e.owner_id == owner_id && !e.is_template)
+        .map(|e| (e.id, e))
+        .collect();
+    // The top-level entry `id` sits under, if the owner's entries reach one.
+    let session_of = |mut id: Uuid| {
+        let mut seen = HashSet::new();
+        loop {
+            match owned.get(&id)?.parent_id() {
+                None => return Some(id),
+                // A stored cycle has no session.
+                Some(_) if !seen.insert(id) => return None,
+                Some(parent_id) => id = parent_id,
+            }
+        }
+    };
+    let mut own_rpes: HashMap<Uuid, f64> = HashMap::new();
+    let mut nested_rpes: HashMap<Uuid, Vec<f64>> = HashMap::new();
+    for v in values {
+        if v.attribute_id != rpe_attribute_id {
+            continue;
+        }
+        let rpe = match v.actual {
+            Some(AttributeValue::Numeric(NumericValue::Exact(rpe))) => rpe,
+            Some(AttributeValue::Numeric(NumericValue::Range { min, max })) => (min + max) / 2.0,
+            _ => continue,
+        };
+        match session_of(v.entry_id) {
+            Some(session_id) if session_id == v.entry_id => {
+                own_rpes.insert(session_id, rpe);
+            }
+            Some(session_id) => nested_rpes.entry(session_id).or_default().push(rpe),
+            None => {}
+        }
+    }
+
+    let mut sessions: Vec<SessionLoad> = owned
+        .values()
+        .filter(|e| e.position.is_none())
+        .filter_map(|e| {
+            let at = e.temporal.canonical_instant()?;
+            let rpe = own_rpes.get(&e.id).copied().or_else(|| {
+                let nested = nested_rpes.get(&e.id)?;
+                Some(nested.iter().sum::<f64>() / nested.len() as f64)
+            });
+            let duration_minutes = e
+                .temporal
+                .infer_duration_ms()
+                .filter(|ms| *ms >= 0)
+                .map(|ms| ms as f64 / 60_000.0);
+            Some(SessionLoad {
+                entry_id: e.id,
+                at,
+                rpe,
+                duration_minutes,
+                load: rpe.zip(duration_minutes).map(|(r, m)| r * m),
+            })
+        })
+        .collect();
+    sessions.sort_by_key(|s| (s.at, s.entry_id));
+
+    let mut daily: HashMap<NaiveDate, Tally> = HashMap::new();
+    for session in &sessions {
+        daily.entry(date(session.at)).or_default().add(Tally {
+            load: session.load.unwrap_or(0.0),
+            sessions: 1,
+            unknown: u32::from(session.load.is_none()),
+        });
+    }
+    let day = |d: NaiveDate| daily.get(&d).copied().unwrap_or_default();
+    // The loads of the `n` days ending with `d`.
+    let trailing = |d: NaiveDate, n: u64| (0..n).map(move |back| day(d - Days::new(back)).load);
+
+    let first = date(window.from);
+    let last = date(window.to - TimeDelta::nanoseconds(1));
+    let days: Vec<DayLoad> = first
+        .iter_days()
+        .take_while(|d| *d <= last)
+        .map(|d| {
+            let tally = day(d);
+            let acute: f64 = trailing(d, 7).sum();
+            let chronic = trailing(d, CHRONIC_DAYS as u64).sum::<f64>() / 4.0;
+            DayLoad {
+                start: midnight(d),
+                load: tally.load,
+                sessions: tally.sessions,
+                unknown: tally.unknown,
+                acute_chronic_ratio: (chronic > 0.0).then(|| acute / chronic),
+            }
+        })
+        .collect();
+
+    let monday = first - Days::new(u64::from(first.weekday().num_days_from_monday()));
+    let weeks: Vec<WeekLoad> = monday
+        .iter_weeks()
+        .take_while(|w| *w <= last)
+        .map(|w| {
+            let mut tally = Tally::default();
+            let loads: Vec<f64> = w
+                .iter_days()
+                .take(7)
+                .map(|d| {
+                    tally.add(day(d));
+                    day(d).load
+                })
+                .collect();
+            let mean = loads.iter().sum::<f64>() / 7.0;
+            let sd = (loads.iter().map(|l| (l - mean).powi(2)).sum::<f64>() / 7.0).sqrt();
+            let monotony = (sd > 0.0).then(|| mean / sd);
+            WeekLoad {
+                start: midnight(w),
+                load: tally.load,
+                sessions: tally.sessions,
+                unknown: tally.unknown,
+                monotony,
+                strain: monotony.map(|m| tally.load * m),
+            }
+        })
+        .collect();
+
+    sessions.retain(|s| (first..=last).contains(&date(s.at)));
+    TrainingLoadReport {
+        sessions,
+        days,
+        weeks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{
+            attribute::{Attribute, NumericConfig},
+            entry::Temporal,
+        },
+        test_log::{TestLog, at, attribute, children, entry},
+    };
+
+    fn session(start: &str, minutes: Option<u32>) -> Entry {
+        let start = at(start);
+        let temporal = match minutes {
+            Some(m) => Temporal::StartAndDuration {
+                start,
+                duration_ms: m * 60_000,
+            },
+            None => Temporal::Start { start },
+        };
+        entry(None, None, temporal)
+    }
+
+    fn rpe() -> Attribute {
+        attribute(
+            "RPE",
+            NumericConfig {
+                min: Some(0.0),
+                max: Some(10.0),
+                integer: false,
+                default: None,
+            }
+            .into(),
+        )
+    }
+
+    fn exact(rpe: f64) -> AttributeValue {
+        AttributeValue::Numeric(NumericValue::Exact(rpe))
+    }
+
+    fn window(from: &str, to: &str) -> LoadWindow {
+        LoadWindow {
+            from: at(from),
+            to: at(to),
+            utc_offset_minutes: 0,
+        }
+    }
+
+    #[test]
+    fn session_loads_and_unknown_buckets() {
+        let rpe = rpe();
+        let easy = session("2026-06-01T08:00:00Z", Some(60));
+        let hard = session("2026-06-01T18:00:00Z", Some(30));
+        let untimed = session("2026-06-02T18:00:00Z", None);
+        let unrated = session("2026-06-03T18:00:00Z", Some(45));
+        let mut log = TestLog::default();
+        log.log(&easy, &rpe, exact(3.0));
+        log.log(
+            &hard,
+            &rpe,
+            AttributeValue::Numeric(NumericValue::Range { min: 7.0, max: 9.0 }),
+        );
+        log.log(&untimed, &rpe, exact(5.0));
+        log.entries = vec![easy.clone(), hard.clone(), untimed.clone(), unrated.clone()];
+        let report = training_load(
+            Uuid::nil(),
+            rpe.id,
+            &window("2026-06-01T00:00:00Z", "2026-06-04T00:00:00Z"),
+            log.entries,
+            log.values,
+        );
+
+        let loads: Vec<_> = report
+            .sessions
+            .iter()
+            .map(|s| (s.entry_id, s.load))
+            .collect();
+        assert_eq!(
+            loads,
+            vec![
+                (easy.id, Some(180.0)),
+                (hard.id, Some(240.0)),
+                (untimed.id, None),
+                (unrated.id, None),
+            ]
+        );
+        let days: Vec<_> = report
+            .days
+            .iter()
+            .map(|d| (d.load, d.sessions, d.unknown))
+            .collect();
+        assert_eq!(days, vec![(420.0, 2, 0), (0.0, 1, 1), (0.0, 1, 1)]);
+        // June 1, 2026 is a Monday: one week, rest days included.
+        assert_eq!(report.weeks.len(), 1);
+        let week = &report.weeks[0];
+        assert_eq!((week.load, week.sessions, week.unknown), (420.0, 4, 2));
+        assert_eq!(week.start, at("2026-06-01T00:00:00Z"));
+        // Daily loads [420, 0, 0, 0, 0, 0, 0]: mean 60, sd 60 × √6.
+        let monotony = week.monotony.unwrap();
+        assert!((monotony - 1.0 / 6f64.sqrt()).abs() < 1e-9);
+        assert!((week.strain.unwrap() - 420.0 * monotony).abs() < 1e-9);
+    }
+
+    #[test]
+    fn acute_chronic_ratio_reads_history_before_the_window() {
+        let rpe = rpe();
+        // Four weeks of 100 a day, then a 300 day.
+        let mut log = TestLog::default();
+        let first: NaiveDate = "2026-05-04".parse().unwrap();
+        for (i, d) in first.iter_days().take(29).enumerate() {
+            let s = session(&format!("{d}T12:00:00Z"), Some(20));
+            let r = if i == 28 { 15.0 } else { 5.0 };
+            log.log(&s, &rpe, exact(r));
+            log.entries.push(s);
+        }
+        let report = training_load(
+            Uuid::nil(),
+            rpe.id,
+            &window("2026-05-31T00:00:00Z", "2026-06-02T00:00:00Z"),
+            log.entries,
+            log.values,
+        );
+        let ratios: Vec<_> = report
+            .days
+            .iter()
+            .map(|d| d.acute_chronic_ratio.unwrap())
+            .collect();
+        // Steady load is a ratio of 1; the spike day's 7 days are 900 against
+        // a chronic weekly mean of (2700 + 300) / 4 = 750.
+        assert!((ratios[0] - 1.0).abs() < 1e-9);
+        assert!((ratios[1] - 900.0 / 750.0).abs() < 1e-9);
+        // The window's two days span two weeks, each reported whole.
+        assert_eq!(report.weeks.len(), 2);
+        assert_eq!(report.weeks[0].monotony, None);
+        assert_eq!(report.sessions.len(), 2);
+    }
+
+    #[test]
+    fn sessions_take_the_mean_rpe_of_their_exercises() {
+        let rpe = rpe();
+        let exercise = |parent: &Entry| entry(None, children(parent.id).next(), Temporal::None);
+        let workout = session("2026-06-01T08:00:00Z", Some(60));
+        let bench = exercise(&workout);
+        let bench_set = exercise(&bench);
+        let rows = exercise(&workout);
+        // A session's own RPE wins over its exercises'.
+        let rated = session("2026-06-02T08:00:00Z", Some(30));
+        let rated_exercise = exercise(&rated);
+        // Someone else's session, shared with the owner, isn't theirs to count.
+        let shared = Entry {
+            owner_id: Uuid::new_v4(),
+            ..session("2026-06-03T08:00:00Z", Some(30))
+        };
+        let mut log = TestLog::default();
+        log.log(&bench_set, &rpe, exact(8.0));
+        log.log(
+            &rows,
+            &rpe,
+            AttributeValue::Numeric(NumericValue::Range { min: 5.0, max: 7.0 }),
+        );
+        log.log(&rated, &rpe, exact(4.0));
+        log.log(&rated_exercise, &rpe, exact(9.0));
+        log.log(&shared, &rpe, exact(5.0));
+        log.entries = vec![
+            workout.clone(),
+            bench,
+            bench_set,
+            rows,
+            rated.clone(),
+            rated_exercise,
+            shared,
+        ];
+        let report = training_load(
+            Uuid::nil(),
+            rpe.id,
+            &window("2026-06-01T00:00:00Z", "2026-06-04T00:00:00Z"),
+            log.entries,
+            log.values,
+        );
+        let rpes: Vec<_> = report
+            .sessions
+            .iter()
+            .map(|s| (s.entry_id, s.rpe, s.load))
+            .collect();
+        assert_eq!(
+            rpes,
+            vec![
+                (workout.id, Some(7.0), Some(420.0)),
+                (rated.id, Some(4.0), Some(120.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_windows() {
+        let backwards = window("2026-06-02T00:00:00Z", "2026-06-01T00:00:00Z");
+        assert!(backwards.validate().is_err());
+        let forever = LoadWindow {
+            from: DateTime::<Utc>::MIN_UTC,
+            to: DateTime::<Utc>::MAX_UTC,
+            utc_offset_minutes: 0,
+        };
+        assert!(forever.validate().is_err());
+    }
+}
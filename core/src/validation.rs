@@ -1,9 +1,12 @@
+use serde::{Deserialize, Serialize};
+
 use crate::error::{DomainError, ValidationError};
 
 // NOTE: Mostly AI generated.
 
 /// A very naive type representing an email. Not production ready, but good enough for now.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct Email(String);
 impl Email {
     pub fn parse(email: String) -> Result<Self, DomainError> {
@@ -56,7 +59,8 @@ impl Email {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct Username(String);
 
 impl Username {
@@ -84,3 +88,31 @@ impl Username {
         &self.0
     }
 }
+
+impl TryFrom<String> for Email {
+    type Error = DomainError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Email::parse(value)
+    }
+}
+
+impl From<Email> for String {
+    fn from(value: Email) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<String> for Username {
+    type Error = DomainError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Username::parse(value)
+    }
+}
+
+impl From<Username> for String {
+    fn from(value: Username) -> Self {
+        value.0
+    }
+}
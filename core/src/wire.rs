@@ -0,0 +1,60 @@
+//! Versioned JSON wire format for data that outlives the build that wrote it:
+//! actions, deltas and mutations in the mutation logs, and anything sent
+//! between client and server. See `docs/wire-format.md` for the
+//! representation of each type.
+//!
+//! Encoded values are wrapped in an envelope recording the format version:
+//!
+//! ```json
+//! {"version": 1, "payload": {"CreateUser": {"user": {...}}}}
+//! ```
+//!
+//! When a model change would alter how existing JSON decodes (a renamed
+//! field, a new required field, a reshaped enum), bump [`WIRE_VERSION`] and
+//! add an arm to `upgrade` that rewrites the previous version's JSON into the
+//! new shape. Payloads from older builds then keep decoding; payloads from
+//! newer builds are refused rather than misread.
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// The version this build writes, and the newest it can read.
+pub const WIRE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub version: u32,
+    pub payload: T,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WireError {
+    #[error("unsupported wire version {0} (this build reads up to {WIRE_VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("malformed payload: {0}")]
+    Malformed(#[from] serde_json::Error),
+}
+
+/// Encode `value` at the current [`WIRE_VERSION`].
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<String, WireError> {
+    Ok(serde_json::to_string(&Versioned {
+        version: WIRE_VERSION,
+        payload: value,
+    })?)
+}
+
+/// Decode a value written by [`encode`] at this or any earlier version.
+pub fn decode<T: DeserializeOwned>(s: &str) -> Result<T, WireError> {
+    let envelope: Versioned<serde_json::Value> = serde_json::from_str(s)?;
+    let payload = upgrade(envelope.version, envelope.payload)?;
+    Ok(serde_json::from_value(payload)?)
+}
+
+/// Rewrite a payload written at `version` into the current version's shape,
+/// one version at a time.
+fn upgrade(version: u32, payload: serde_json::Value) -> Result<serde_json::Value, WireError> {
+    match version {
+        WIRE_VERSION => Ok(payload),
+        // e.g. `1 => upgrade(2, v1_to_v2(payload))` once version 2 exists.
+        other => Err(WireError::UnsupportedVersion(other)),
+    }
+}
@@ -6,14 +6,18 @@ use uuid::Uuid;
 use crate::{Arbitrary, GenerationContext, arbitrary_actor_id, gen_random_text, maybe, pick};
 use gv_core::{
     actions::{
-        Action, AttachValue, AttributeChange, ConvertToSets, CreateActivity, CreateAttribute,
-        CreateEntry, CreateEntryFromActivity, CreateUser, CreateValue, DeleteAttributeValue,
-        DuplicateEntry, EntryChange, LengthChange, MassChange, MoveEntry, NumericChange,
-        SelectChange, TextChange, UpdateAttribute, UpdateEntry, UpdateEntryCompletion,
+        Action, AttachValue, AttributeChange, BooleanChange, ConvertToSets, CreateActivity,
+        CreateAttribute, CreateEntry, CreateEntryFromActivity, CreateUser, CreateValue,
+        DeleteAttributeValue, DerivedChange, DuplicateEntry, DurationChange, EntryChange,
+        GradeChange, LengthChange, MassChange, MoveEntry, NumericChange, SelectChange, SpeedChange,
+        TextChange, UpdateAttribute, UpdateEntry, UpdateEntryCompletion,
     },
     models::{
         activity::Activity,
-        attribute::{Attribute, AttributeConfig, LengthUnit, MassUnit, Value},
+        attribute::{
+            Attribute, AttributeConfig, DerivedConfig, DurationDisplay, LengthUnit, MassUnit,
+            SpeedUnit, Value,
+        },
         entry::{Entry, Position, Temporal},
         user::User,
     },
@@ -351,6 +355,12 @@ impl Arbitrary for UpdateAttribute {
                     };
                     AttributeChange::Select(SelectChange::SetDefault(default))
                 }
+                AttributeConfig::Grade(cfg) => {
+                    let default = maybe(rng, 0.7, |rng| {
+                        pick(&cfg.scale.options(), rng).unwrap().clone()
+                    });
+                    AttributeChange::Grade(GradeChange::SetDefault(default))
+                }
                 AttributeConfig::Multiselect(_) => {
                     // No type-specific config edit exists for multiselect yet
                     // (config editing is deferred), so fall back to a common
@@ -378,6 +388,26 @@ impl Arbitrary for UpdateAttribute {
                     let unit = pick(&all[..], rng).unwrap().clone();
                     AttributeChange::Length(LengthChange::SetDefaultUnit(unit))
                 }
+                AttributeConfig::Speed(_) => {
+                    let all = [
+                        SpeedUnit::MetersPerSecond,
+                        SpeedUnit::KilometersPerHour,
+                        SpeedUnit::MilesPerHour,
+                        SpeedUnit::MinutesPerKilometer,
+                        SpeedUnit::MinutesPerMile,
+                    ];
+                    let unit = pick(&all[..], rng).unwrap().clone();
+                    AttributeChange::Speed(SpeedChange::SetDefaultUnit(unit))
+                }
+                AttributeConfig::Duration(_) => {
+                    let all = [
+                        DurationDisplay::Seconds,
+                        DurationDisplay::Minutes,
+                        DurationDisplay::HoursMinutesSeconds,
+                    ];
+                    let display = pick(&all[..], rng).unwrap().clone();
+                    AttributeChange::Duration(DurationChange::SetDisplay(display))
+                }
                 AttributeConfig::Text(_) => {
                     if rng.random_bool(0.5) {
                         let default = maybe(rng, 0.7, |rng| gen_random_text(rng, 1..6));
@@ -386,6 +416,16 @@ impl Arbitrary for UpdateAttribute {
                         AttributeChange::Text(TextChange::SetAutocomplete(rng.random_bool(0.5)))
                     }
                 }
+                AttributeConfig::Boolean(_) => {
+                    let default = maybe(rng, 0.7, |rng| rng.random_bool(0.5));
+                    AttributeChange::Boolean(BooleanChange::SetDefault(default))
+                }
+                AttributeConfig::Derived(_) => {
+                    // May reference the attribute itself or a derived one
+                    // that reads it, which the mutator rejects as a cycle.
+                    let config = DerivedConfig::arbitrary(rng, context);
+                    AttributeChange::Derived(DerivedChange::SetExpression(config.expression))
+                }
             },
         };
         UpdateAttribute {
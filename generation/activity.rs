@@ -31,6 +31,7 @@ impl Arbitrary for Activity {
             source_activity_id: None,
             name: ActivityName::arbitrary(rng, context),
             description: desc,
+            archived: false,
         }
     }
 }
@@ -1,11 +1,14 @@
 use gv_core::models::{
     attribute::{
-        Attribute, AttributeConfig, AttributeValue, LengthConfig, LengthMeasurement, LengthUnit,
-        LengthValue, MAX_MULTISELECT_OPTION_LEN, MassConfig, MassMeasurement, MassUnit, MassValue,
-        MultiselectConfig, NumericConfig, NumericValue, SelectConfig, SelectValue, TextConfig,
-        Value,
+        Attribute, AttributeConfig, AttributeValue, BooleanConfig, DerivedConfig, DurationConfig,
+        DurationDisplay, DurationValue, GradeConfig, LengthConfig, LengthMeasurement, LengthValue,
+        MAX_MULTISELECT_OPTION_LEN, MassConfig, MassMeasurement, MassValue, Measure,
+        MultiselectConfig, NumericConfig, NumericValue, SelectConfig, SelectValue, SpeedConfig,
+        SpeedMeasurement, SpeedUnit, SpeedValue, TextConfig, Value,
     },
+    derived::DerivedExpr,
     entry::Entry,
+    grade::GradeScale,
 };
 use uuid::Uuid;
 
@@ -34,12 +37,17 @@ impl Arbitrary for Attribute {
 
 impl Arbitrary for AttributeConfig {
     fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, context: &C) -> Self {
-        match rng.random_range(0..=5) {
+        match rng.random_range(0..=10) {
             0 => AttributeConfig::Numeric(NumericConfig::arbitrary(rng, context)),
             1 => AttributeConfig::Select(SelectConfig::arbitrary(rng, context)),
             2 => AttributeConfig::Multiselect(MultiselectConfig::arbitrary(rng, context)),
             3 => AttributeConfig::Mass(MassConfig::arbitrary(rng, context)),
             4 => AttributeConfig::Length(LengthConfig::arbitrary(rng, context)),
+            5 => AttributeConfig::Duration(DurationConfig::arbitrary(rng, context)),
+            6 => AttributeConfig::Boolean(BooleanConfig::arbitrary(rng, context)),
+            7 => AttributeConfig::Speed(SpeedConfig::arbitrary(rng, context)),
+            8 => AttributeConfig::Grade(GradeConfig::arbitrary(rng, context)),
+            9 => AttributeConfig::Derived(DerivedConfig::arbitrary(rng, context)),
             _ => AttributeConfig::Text(TextConfig::arbitrary(rng, context)),
         }
     }
@@ -100,6 +108,52 @@ impl Arbitrary for SelectConfig {
     }
 }
 
+const ALL_GRADE_SCALES: [GradeScale; 4] = [
+    GradeScale::Yds,
+    GradeScale::French,
+    GradeScale::VScale,
+    GradeScale::Font,
+];
+
+impl Arbitrary for GradeConfig {
+    fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, _context: &C) -> Self {
+        let scale = pick(&ALL_GRADE_SCALES[..], rng).unwrap().clone();
+        let default = maybe(rng, 0.5, |rng| pick(&scale.options(), rng).unwrap().clone());
+        GradeConfig { scale, default }
+    }
+}
+
+impl Arbitrary for DerivedConfig {
+    fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, context: &C) -> Self {
+        let lhs = arbitrary_operand(rng, context);
+        let rhs = arbitrary_operand(rng, context);
+        let expression = match rng.random_range(0..4) {
+            0 => lhs * rhs,
+            1 => lhs / rhs,
+            2 => (lhs * rhs).sum_descendants(),
+            _ => DerivedExpr::epley(lhs, rhs),
+        };
+        DerivedConfig { expression }
+    }
+}
+
+/// A derived expression operand: usually one of the model's attributes (else
+/// a fabricated id, which the mutator rejects as missing), sometimes a
+/// constant. Referencing a derived attribute can't close a cycle, since the
+/// attribute being generated is new.
+fn arbitrary_operand<R: RngExt, C: GenerationContext>(rng: &mut R, context: &C) -> DerivedExpr {
+    if rng.random_bool(0.2) {
+        return DerivedExpr::constant(rng.random_range(1..=100) as f64);
+    }
+    let attribute_id = context
+        .model()
+        .attributes()
+        .choose(rng)
+        .map(|a| a.id)
+        .unwrap_or_else(|| Uuid::arbitrary(rng, context));
+    DerivedExpr::attribute(attribute_id)
+}
+
 impl Arbitrary for MultiselectConfig {
     fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, _context: &C) -> Self {
         let n = rng.random_range(0..=8);
@@ -129,29 +183,47 @@ impl Arbitrary for MultiselectConfig {
 
 impl Arbitrary for MassConfig {
     fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, _context: &C) -> Self {
-        let all_units = [MassUnit::Gram, MassUnit::Kilogram, MassUnit::Pound];
         MassConfig {
-            default_unit: pick(&all_units[..], rng).unwrap().clone(),
+            default_unit: pick(MassValue::defined_units(), rng).unwrap().clone(),
         }
     }
 }
 
-/// All length units, in the menu order used by the Swift picker.
-const ALL_LENGTH_UNITS: [LengthUnit; 8] = [
-    LengthUnit::Millimeter,
-    LengthUnit::Centimeter,
-    LengthUnit::Meter,
-    LengthUnit::Kilometer,
-    LengthUnit::Inch,
-    LengthUnit::Foot,
-    LengthUnit::Yard,
-    LengthUnit::Mile,
-];
-
 impl Arbitrary for LengthConfig {
     fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, _context: &C) -> Self {
         LengthConfig {
-            default_unit: pick(&ALL_LENGTH_UNITS[..], rng).unwrap().clone(),
+            default_unit: pick(LengthValue::defined_units(), rng).unwrap().clone(),
+        }
+    }
+}
+
+/// All duration displays, in the menu order used by the Swift picker.
+const ALL_SPEED_UNITS: [SpeedUnit; 5] = [
+    SpeedUnit::MetersPerSecond,
+    SpeedUnit::KilometersPerHour,
+    SpeedUnit::MilesPerHour,
+    SpeedUnit::MinutesPerKilometer,
+    SpeedUnit::MinutesPerMile,
+];
+
+impl Arbitrary for SpeedConfig {
+    fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, _context: &C) -> Self {
+        SpeedConfig {
+            default_unit: pick(&ALL_SPEED_UNITS[..], rng).unwrap().clone(),
+        }
+    }
+}
+
+const ALL_DURATION_DISPLAYS: [DurationDisplay; 3] = [
+    DurationDisplay::Seconds,
+    DurationDisplay::Minutes,
+    DurationDisplay::HoursMinutesSeconds,
+];
+
+impl Arbitrary for DurationConfig {
+    fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, _context: &C) -> Self {
+        DurationConfig {
+            display: pick(&ALL_DURATION_DISPLAYS[..], rng).unwrap().clone(),
         }
     }
 }
@@ -165,6 +237,14 @@ impl Arbitrary for TextConfig {
     }
 }
 
+impl Arbitrary for BooleanConfig {
+    fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, _context: &C) -> Self {
+        BooleanConfig {
+            default: maybe(rng, 0.5, |rng| rng.random_bool(0.5)),
+        }
+    }
+}
+
 impl Arbitrary for Value {
     fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, context: &C) -> Self {
         let model = context.model();
@@ -210,12 +290,12 @@ impl Arbitrary for Value {
         let actual = maybe(rng, 0.5, |rng| {
             AttributeValue::arbitrary_from(rng, context, &config)
         });
-        // TODO: generate appropriate index_float / index_string based on attribute config.
+        let (index_float, index_string) = config.index_columns(actual.as_ref());
         Value {
             entry_id,
             attribute_id,
-            index_float: None,
-            index_string: None,
+            index_float,
+            index_string,
             plan,
             actual,
         }
@@ -235,6 +315,9 @@ impl ArbitraryFrom<&AttributeConfig> for AttributeValue {
             AttributeConfig::Select(c) => {
                 AttributeValue::Select(SelectValue::arbitrary_from(rng, context, c))
             }
+            AttributeConfig::Grade(c) => {
+                AttributeValue::Grade(SelectValue::arbitrary_from(rng, context, c))
+            }
             // A multiselect value is a random subset of the options, kept in
             // option order so it validates (membership + no duplicates). The
             // value carries no `*Value` type, so build the `Vec<String>` here.
@@ -251,9 +334,19 @@ impl ArbitraryFrom<&AttributeConfig> for AttributeValue {
             AttributeConfig::Length(c) => {
                 AttributeValue::Length(LengthValue::arbitrary_from(rng, context, c))
             }
+            AttributeConfig::Speed(c) => {
+                AttributeValue::Speed(SpeedValue::arbitrary_from(rng, context, c))
+            }
+            AttributeConfig::Duration(c) => {
+                AttributeValue::Duration(DurationValue::arbitrary_from(rng, context, c))
+            }
             // Text has no `*Value` type or config constraint on the value, so
             // generate a bare string directly (well under the length cap).
             AttributeConfig::Text(_) => AttributeValue::Text(gen_random_text(rng, 1..8)),
+            AttributeConfig::Boolean(_) => AttributeValue::Boolean(rng.random_bool(0.5)),
+            // No value fits a derived attribute (it is computed, never
+            // entered); any value exercises the mismatch rejection.
+            AttributeConfig::Derived(_) => AttributeValue::Boolean(rng.random_bool(0.5)),
         }
     }
 }
@@ -315,25 +408,46 @@ impl ArbitraryFrom<&SelectConfig> for SelectValue {
     }
 }
 
+impl ArbitraryFrom<&GradeConfig> for SelectValue {
+    fn arbitrary_from<R: RngExt, C: GenerationContext>(
+        rng: &mut R,
+        _context: &C,
+        config: &GradeConfig,
+    ) -> Self {
+        // Grades are listed easiest first, so index order is difficulty order.
+        let grades = config.scale.options();
+        let a = rng.random_range(0..grades.len());
+        if rng.random_bool(0.5) {
+            let b = rng.random_range(0..grades.len());
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            SelectValue::Range {
+                min: grades[lo].clone(),
+                max: grades[hi].clone(),
+            }
+        } else {
+            SelectValue::Exact(grades[a].clone())
+        }
+    }
+}
+
 impl ArbitraryFrom<&MassConfig> for MassValue {
     fn arbitrary_from<R: RngExt, C: GenerationContext>(
         rng: &mut R,
         _context: &C,
         _config: &MassConfig,
     ) -> Self {
-        let all_units = [MassUnit::Gram, MassUnit::Kilogram, MassUnit::Pound];
-        let rand_unit = |rng: &mut R| pick(&all_units[..], rng).unwrap().clone();
+        let rand_unit = |rng: &mut R| pick(MassValue::defined_units(), rng).unwrap().clone();
         let rand_magnitude = |rng: &mut R| -> f64 {
             let v: f64 = rng.random_range(0.0..500.0);
             (v * 100.0).round() / 100.0
         };
 
-        match rng.random_range(0..=1) {
+        match rng.random_range(0..=2) {
             0 => MassValue::Exact(MassMeasurement {
                 unit: rand_unit(rng),
                 value: rand_magnitude(rng),
             }),
-            _ => {
+            1 => {
                 let a = rand_magnitude(rng);
                 let b = rand_magnitude(rng);
                 let (min, max) = if a <= b { (a, b) } else { (b, a) };
@@ -343,6 +457,22 @@ impl ArbitraryFrom<&MassConfig> for MassValue {
                     max,
                 }
             }
+            _ => {
+                let mut units: Vec<_> = MassValue::defined_units()
+                    .iter()
+                    .filter(|_| rng.random_bool(0.5))
+                    .cloned()
+                    .collect();
+                if units.is_empty() {
+                    units.push(rand_unit(rng));
+                }
+                let units = MassValue::largest_first(units);
+                MassValue::Composite {
+                    unit: units.last().unwrap().clone(),
+                    value: rand_magnitude(rng),
+                    units,
+                }
+            }
         }
     }
 }
@@ -353,18 +483,18 @@ impl ArbitraryFrom<&LengthConfig> for LengthValue {
         _context: &C,
         _config: &LengthConfig,
     ) -> Self {
-        let rand_unit = |rng: &mut R| pick(&ALL_LENGTH_UNITS[..], rng).unwrap().clone();
+        let rand_unit = |rng: &mut R| pick(LengthValue::defined_units(), rng).unwrap().clone();
         let rand_magnitude = |rng: &mut R| -> f64 {
             let v: f64 = rng.random_range(0.0..500.0);
             (v * 100.0).round() / 100.0
         };
 
-        match rng.random_range(0..=1) {
+        match rng.random_range(0..=2) {
             0 => LengthValue::Exact(LengthMeasurement {
                 unit: rand_unit(rng),
                 value: rand_magnitude(rng),
             }),
-            _ => {
+            1 => {
                 let a = rand_magnitude(rng);
                 let b = rand_magnitude(rng);
                 let (min, max) = if a <= b { (a, b) } else { (b, a) };
@@ -374,6 +504,77 @@ impl ArbitraryFrom<&LengthConfig> for LengthValue {
                     max,
                 }
             }
+            _ => {
+                let mut units: Vec<_> = LengthValue::defined_units()
+                    .iter()
+                    .filter(|_| rng.random_bool(0.5))
+                    .cloned()
+                    .collect();
+                if units.is_empty() {
+                    units.push(rand_unit(rng));
+                }
+                let units = LengthValue::largest_first(units);
+                LengthValue::Composite {
+                    unit: units.last().unwrap().clone(),
+                    value: rand_magnitude(rng),
+                    units,
+                }
+            }
+        }
+    }
+}
+
+impl ArbitraryFrom<&SpeedConfig> for SpeedValue {
+    fn arbitrary_from<R: RngExt, C: GenerationContext>(
+        rng: &mut R,
+        _context: &C,
+        _config: &SpeedConfig,
+    ) -> Self {
+        let rand_unit = |rng: &mut R| pick(&ALL_SPEED_UNITS[..], rng).unwrap().clone();
+        let rand_magnitude = |rng: &mut R| -> f64 {
+            let v: f64 = rng.random_range(0.0..60.0);
+            (v * 100.0).round() / 100.0
+        };
+
+        match rng.random_range(0..=1) {
+            0 => SpeedValue::Exact(SpeedMeasurement {
+                unit: rand_unit(rng),
+                value: rand_magnitude(rng),
+            }),
+            _ => {
+                let a = rand_magnitude(rng);
+                let b = rand_magnitude(rng);
+                let (min, max) = if a <= b { (a, b) } else { (b, a) };
+                SpeedValue::Range {
+                    unit: rand_unit(rng),
+                    min,
+                    max,
+                }
+            }
+        }
+    }
+}
+
+impl ArbitraryFrom<&DurationConfig> for DurationValue {
+    fn arbitrary_from<R: RngExt, C: GenerationContext>(
+        rng: &mut R,
+        _context: &C,
+        _config: &DurationConfig,
+    ) -> Self {
+        // Up to an hour, on the 2-decimal grid.
+        let rand_seconds = |rng: &mut R| -> f64 {
+            let v: f64 = rng.random_range(0.0..3600.0);
+            (v * 100.0).round() / 100.0
+        };
+
+        match rng.random_range(0..=1) {
+            0 => DurationValue::Exact(rand_seconds(rng)),
+            _ => {
+                let a = rand_seconds(rng);
+                let b = rand_seconds(rng);
+                let (min, max) = if a <= b { (a, b) } else { (b, a) };
+                DurationValue::Range { min, max }
+            }
         }
     }
 }
@@ -54,7 +54,7 @@ impl Arbitrary for Entry {
             is_sequence,
             is_complete,
             is_template: false,
-            position: Option::<Position>::arbitrary(rng, context),
+            position: arbitrary_position(rng, context, Some(owner_id)),
             temporal: Temporal::arbitrary(rng, context),
         }
     }
@@ -107,26 +107,40 @@ impl ArbitraryFrom<&[FractionalIndex]> for FractionalIndex {
 
 impl Arbitrary for Option<Position> {
     fn arbitrary<R: RngExt, C: GenerationContext>(rng: &mut R, context: &C) -> Self {
-        let model = context.model();
-        if rng.random_bool(0.5) {
-            // Choose a root position half the time.
-            return None;
-        }
-        // TODO: consider using p_valid to choose invalid, non-sequence parents.
-        let parent = model.entries().filter(|e| e.is_sequence).choose(rng)?;
-        // Choose a child position.
-        let entries: Vec<Entry> = model.entries().cloned().collect();
-        let forest = Forest::from(entries);
-        let sibling_findices: Vec<FractionalIndex> = forest
-            .children(parent.id)
-            .iter()
-            .filter_map(|e| e.frac_index().cloned())
-            .collect();
-        Some(Position {
-            parent_id: parent.id,
-            frac_index: FractionalIndex::arbitrary_from(rng, context, &sibling_findices),
-        })
+        arbitrary_position(rng, context, None)
+    }
+}
+
+/// A root position half the time, otherwise a child position under a random
+/// sequence — one owned by `owner_id` when given, since an entry tree has a
+/// single owner.
+fn arbitrary_position<R: RngExt, C: GenerationContext>(
+    rng: &mut R,
+    context: &C,
+    owner_id: Option<Uuid>,
+) -> Option<Position> {
+    let model = context.model();
+    if rng.random_bool(0.5) {
+        // Choose a root position half the time.
+        return None;
     }
+    // TODO: consider using p_valid to choose invalid, non-sequence parents.
+    let parent = model
+        .entries()
+        .filter(|e| e.is_sequence && owner_id.is_none_or(|o| e.owner_id == o))
+        .choose(rng)?;
+    // Choose a child position.
+    let entries: Vec<Entry> = model.entries().cloned().collect();
+    let forest = Forest::from(entries);
+    let sibling_findices: Vec<FractionalIndex> = forest
+        .children(parent.id)
+        .iter()
+        .filter_map(|e| e.frac_index().cloned())
+        .collect();
+    Some(Position {
+        parent_id: parent.id,
+        frac_index: FractionalIndex::arbitrary_from(rng, context, &sibling_findices),
+    })
 }
 
 /// Generate a random duration in milliseconds by sampling from a random distribution with mean
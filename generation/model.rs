@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use gv_core::{
     SYSTEM_ACTOR_ID,
+    analytics::{self, AnalyticsQuery, AnalyticsRow},
     delta::{AnyDelta, Delta},
     delta_executor::AnyDeltaExecutor,
     models::{
@@ -8,11 +9,15 @@ use gv_core::{
         actor::{Actor, ActorKind},
         attribute::{Attribute, Value},
         entry::Entry,
+        grant::Grant,
         user::User,
     },
     mutators::Mutation,
-    queries::Snapshot,
+    queries::{AttributeSeries, Snapshot},
+    records::{self, PersonalRecord},
+    series::{self, SeriesPoint},
     std_lib::StandardLibrary,
+    training_load::{self, LoadWindow, TrainingLoadReport},
 };
 use rand::RngExt;
 use rustc_hash::FxHashMap as HashMap;
@@ -30,6 +35,7 @@ pub struct Model {
     activities: HashMap<Uuid, Activity>,
     attributes: HashMap<Uuid, Attribute>,
     values: HashMap<ValuePrimaryKey, Value>,
+    grants: HashMap<Uuid, Grant>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -47,6 +53,7 @@ impl Model {
             activities: HashMap::default(),
             attributes: HashMap::default(),
             values: HashMap::default(),
+            grants: HashMap::default(),
         }
     }
 
@@ -75,9 +82,76 @@ impl Model {
         self.attributes.values()
     }
 
+    pub fn grants(&self) -> impl Iterator<Item = &Grant> {
+        self.grants.values()
+    }
+
     pub fn values(&self) -> impl Iterator<Item = &Value> {
         self.values.values()
     }
+
+    /// Answer an analytics query over the whole model, as the SQL executors'
+    /// `RunAnalytics` does over a database.
+    pub fn run_analytics(
+        &self,
+        query: &AnalyticsQuery,
+    ) -> gv_core::error::Result<Vec<AnalyticsRow>> {
+        query.validate()?;
+        Ok(analytics::evaluate(
+            query,
+            self.attributes().cloned().collect(),
+            self.entries().cloned().collect(),
+            self.values().cloned().collect(),
+        ))
+    }
+
+    /// `owner_id`'s personal records over the whole model, as the SQL
+    /// executors' `PersonalRecords` finds them in a database.
+    pub fn personal_records(
+        &self,
+        owner_id: Uuid,
+        activity_id: Option<Uuid>,
+    ) -> Vec<PersonalRecord> {
+        records::personal_records(
+            owner_id,
+            activity_id,
+            self.attributes().cloned().collect(),
+            self.entries().cloned().collect(),
+            self.values().cloned().collect(),
+        )
+    }
+
+    /// `owner_id`'s training load over the whole model, as the SQL executors'
+    /// `TrainingLoad` reports it from a database.
+    pub fn training_load(
+        &self,
+        owner_id: Uuid,
+        rpe_attribute_id: Uuid,
+        window: &LoadWindow,
+    ) -> gv_core::error::Result<TrainingLoadReport> {
+        window.validate()?;
+        Ok(training_load::training_load(
+            owner_id,
+            rpe_attribute_id,
+            window,
+            self.entries().cloned().collect(),
+            self.values().cloned().collect(),
+        ))
+    }
+
+    /// An attribute's series over the whole model, as the SQL executors'
+    /// `AttributeSeries` plots it from a database.
+    pub fn attribute_series(
+        &self,
+        query: &AttributeSeries,
+    ) -> gv_core::error::Result<Vec<SeriesPoint>> {
+        series::attribute_series(
+            query,
+            self.attributes().cloned().collect(),
+            self.entries().cloned().collect(),
+            self.values().cloned().collect(),
+        )
+    }
 }
 
 impl Model {
@@ -97,6 +171,7 @@ impl Model {
             source_activity_id: None,
             name: ActivityName::parse("Pull Ups".to_string()).unwrap(),
             description: None,
+            archived: false,
         }];
         let attributes_seed = StandardLibrary::attributes();
 
@@ -123,6 +198,7 @@ impl Model {
             attributes: Self::map_of(snapshot.attributes),
             entries: Self::map_of(snapshot.entries),
             values: Self::map_of(snapshot.values),
+            grants: Self::map_of(snapshot.grants),
         }
     }
 
@@ -233,6 +309,16 @@ impl Entity for Value {
     }
 }
 
+impl Entity for Grant {
+    type PrimaryKey = Uuid;
+    fn primary_key(&self) -> Uuid {
+        self.id
+    }
+    fn kind(&self) -> &'static str {
+        "grant"
+    }
+}
+
 pub struct ModelDeltaExecutor {}
 
 impl Model {
@@ -249,6 +335,7 @@ impl Model {
             AnyDelta::Entry(delta) => hash_map_apply(delta, &mut self.entries),
             AnyDelta::Attribute(delta) => hash_map_apply(delta, &mut self.attributes),
             AnyDelta::Value(delta) => hash_map_apply(delta, &mut self.values),
+            AnyDelta::Grant(delta) => hash_map_apply(delta, &mut self.grants),
         }
     }
 }
@@ -5,6 +5,7 @@ use generation::{Arbitrary, Opts, SimulationContext};
 use gv_client::{client::SqliteClient, query_store::QuerySubscription};
 use gv_core::{
     actions::{Action, CreateAttribute, CreateEntry, CreateValue},
+    analytics::AnalyticsQuery,
     forest::Forest,
     models::entry::{Entry, Position},
     queries::{AllEntries, AnyQuery, AnyQueryResponse, SnapshotAll},
@@ -101,12 +102,43 @@ impl GainzvilleCore {
             })
     }
 
+    /// Undo the current actor's most recent action (Cmd-Z). Returns `false`
+    /// when there is nothing to undo. Throws if the undo was rejected because
+    /// the affected data has changed since; that history entry is dropped.
+    /// History carries over across launches: it's rebuilt from the local
+    /// mutation log when the core opens.
+    pub fn undo(&self) -> Result<bool, FfiError> {
+        RUNTIME
+            .block_on(self.client.undo(self.actor_id))
+            .map(|mx| mx.is_some())
+            .map_err(FfiError::from)
+    }
+
+    /// Redo the current actor's most recently undone action (Shift-Cmd-Z).
+    /// Returns `false` when there is nothing to redo.
+    pub fn redo(&self) -> Result<bool, FfiError> {
+        RUNTIME
+            .block_on(self.client.redo(self.actor_id))
+            .map(|mx| mx.is_some())
+            .map_err(FfiError::from)
+    }
+
+    /// Whether `undo` has anything to revert, for enabling the menu item.
+    pub fn can_undo(&self) -> bool {
+        self.client.can_undo(self.actor_id)
+    }
+
+    /// Whether `redo` has anything to re-apply, for enabling the menu item.
+    pub fn can_redo(&self) -> bool {
+        self.client.can_redo(self.actor_id)
+    }
+
     /// Subscribe to a query. Runs the initial query immediately, populates the
     /// cache, and returns a `FfiQuerySubscription` handle. Dropping the handle
     /// (Swift releasing the reference) auto-removes the query from the cache.
     pub fn subscribe_query(&self, query: AnyQuery) -> Result<Arc<FfiQuerySubscription>, FfiError> {
         let subscription = RUNTIME
-            .block_on(self.client.subscribe_query(query))
+            .block_on(self.client.subscribe_query(self.actor_id, query))
             .map_err(FfiError::from)?;
         Ok(Arc::new(FfiQuerySubscription(subscription)))
     }
@@ -115,7 +147,39 @@ impl GainzvilleCore {
     /// is not subscribed. Swift calls this synchronously from the main thread
     /// after receiving `on_data_changed()`.
     pub fn read_query(&self, query: AnyQuery) -> Option<AnyQueryResponse> {
-        self.client.read_cached_query(query)
+        self.client.read_cached_query(self.actor_id, query)
+    }
+
+    /// Parse analytics query text such as
+    /// `count Boulder where "V Grade" >= V8 group by week`. Names resolve
+    /// against the current actor's library and the standard library; dates
+    /// and period groups use `utc_offset_minutes`. Throws `InvalidQuery` with
+    /// the span to underline when the text doesn't parse.
+    pub fn parse_analytics_query(
+        &self,
+        text: String,
+        utc_offset_minutes: i32,
+    ) -> Result<AnalyticsQuery, FfiError> {
+        RUNTIME
+            .block_on(
+                self.client
+                    .parse_analytics_query(self.actor_id, &text, utc_offset_minutes),
+            )
+            .map_err(FfiError::from)
+    }
+
+    /// Write an analytics query as query text, e.g. to prefill an editor.
+    pub fn format_analytics_query(
+        &self,
+        query: AnalyticsQuery,
+        utc_offset_minutes: i32,
+    ) -> Result<String, FfiError> {
+        RUNTIME
+            .block_on(
+                self.client
+                    .format_analytics_query(self.actor_id, &query, utc_offset_minutes),
+            )
+            .map_err(FfiError::from)
     }
 
     /// Spawn a background task that creates a new activity every 10 seconds.
@@ -144,7 +208,7 @@ impl GainzvilleCore {
         let subscription = RUNTIME
             .block_on(
                 self.client
-                    .subscribe_query(AnyQuery::AllEntries(AllEntries {})),
+                    .subscribe_query(self.actor_id, AnyQuery::AllEntries(AllEntries {})),
             )
             .map_err(FfiError::from)?;
         Ok(Arc::new(FfiQuerySubscription(subscription)))
@@ -254,7 +318,7 @@ impl GainzvilleCore {
     /// repeat calls converge instead of erroring on PK conflicts.
     pub fn dev_create_arbitrary_values(&self, count: u32) -> Result<(), FfiError> {
         let snapshot = RUNTIME
-            .block_on(self.client.run_query(SnapshotAll))
+            .block_on(self.client.run_query(self.actor_id, SnapshotAll))
             .map_err(FfiError::from)?;
         if snapshot.entries.is_empty() || snapshot.attributes.is_empty() {
             return Ok(());
@@ -293,7 +357,7 @@ impl GainzvilleCore {
         // draw from the activities/entries currently present.
         let mut context = SimulationContext::with_opts(Opts::time_now_tight_std());
         let snapshot = RUNTIME
-            .block_on(self.client.run_query(SnapshotAll))
+            .block_on(self.client.run_query(self.actor_id, SnapshotAll))
             .map_err(FfiError::from)?;
         context.load_snapshot(snapshot);
 
@@ -320,7 +384,7 @@ impl GainzvilleCore {
     fn forest_snapshot(&self) -> Option<Forest> {
         match self
             .client
-            .read_cached_query(AnyQuery::AllEntries(AllEntries {}))?
+            .read_cached_query(self.actor_id, AnyQuery::AllEntries(AllEntries {}))?
         {
             AnyQueryResponse::AllEntries(entries) => Some(Forest::from(entries)),
             _ => None,
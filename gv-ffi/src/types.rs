@@ -2,36 +2,57 @@ use chrono::{DateTime, Utc};
 use fractional_index::FractionalIndex;
 use gv_core::{
     actions::{
-        Action, AttachValue, AttributeChange, ConvertToSets, CreateActivity, CreateAttribute,
-        CreateEntry, CreateEntryFromActivity, CreateUser, CreateValue, DeleteAttributeValue,
-        DeleteEntryRecursive, DuplicateEntry, EntryChange, LengthChange, MassChange, MoveEntry,
-        NumericChange, SelectChange, TextChange, UpdateAttribute, UpdateAttributeValue,
-        UpdateEntry, UpdateEntryCompletion, ValueField,
+        AcceptGrant, Action, ActivityChange, ArchiveActivity, AttachValue, AttributeChange,
+        AttributeConversion, AttributeMigration, BooleanChange, ConfigMigration,
+        ConvertAttributeType, ConvertToSets, CreateActivity, CreateAttribute, CreateEntry,
+        CreateEntryFromActivity, CreateUser, CreateValue, DeleteActivity, DeleteAttributeValue,
+        DeleteEntryRecursive, DerivedChange, DuplicateEntry, DurationChange, EntryChange,
+        GradeChange, GrantPermission, LengthChange, MassChange, MoveEntry, MultiselectChange,
+        NumericChange, OptionRemoval, OutOfRange, Redo, ReferencingEntries, RevokeGrant,
+        SelectChange, SpeedChange, TextChange, Undo, UpdateActivity, UpdateAttribute,
+        UpdateAttributeValue, UpdateEntry, UpdateEntryCompletion, ValueField,
     },
+    analytics::{
+        Aggregate, AnalyticsQuery, AnalyticsRow, AttributeField, AttributePredicate, Comparison,
+        EntryFilter, GroupKey, Grouping, Period,
+    },
+    delta::{AnyDelta, Delta},
     models::{
         activity::{Activity, ActivityName},
+        actor::{Actor, ActorKind},
         attribute::{
-            Attribute, AttributeConfig, AttributeValue, LengthConfig, LengthMeasurement,
-            LengthUnit, LengthValue, MassConfig, MassMeasurement, MassUnit, MassValue,
-            MultiselectConfig, NumericConfig, NumericValue, SelectConfig, SelectValue, TextConfig,
-            Value,
+            Attribute, AttributeConfig, AttributeValue, BooleanConfig, DerivedConfig,
+            DurationConfig, DurationDisplay, DurationValue, GradeConfig, LengthConfig,
+            LengthMeasurement, LengthUnit, LengthValue, MassConfig, MassMeasurement, MassUnit,
+            MassValue, Measure, MultiselectConfig, NumericConfig, NumericValue, SelectConfig,
+            SelectValue, SpeedConfig, SpeedMeasurement, SpeedUnit, SpeedValue, TextConfig,
+            TimeUnit, Value,
         },
         attribute_pair::{
-            AttributePair, LengthAttributePair, MassAttributePair, MultiselectAttributePair,
-            NumericAttributePair, SelectAttributePair, TextAttributePair,
+            AttributePair, BooleanAttributePair, DerivedAttributePair, DurationAttributePair,
+            GradeAttributePair, LengthAttributePair, MassAttributePair, MultiselectAttributePair,
+            NumericAttributePair, SelectAttributePair, SpeedAttributePair, TextAttributePair,
         },
+        derived::{DerivedExpr, DerivedNode, DerivedOp},
         entry::{Entry, Position, Temporal},
         entry_join::EntryJoin,
+        grade::GradeScale,
+        grant::{Grant, GrantLevel, GrantScope},
         user::User,
     },
     queries::{
         AllActivities, AllActorIds, AllAttributes, AllEntries, AnyQuery, AnyQueryResponse,
-        DistinctTextValuesForAttribute, EntriesRootedInTimeInterval, FindActivityById,
-        FindActivityTemplateRoot, FindAncestors, FindAttributeById, FindAttributePairsForEntry,
-        FindAttributesByOwner, FindDescendants, FindEntryById, FindEntryJoinById, FindUserById,
-        FindUserByUsername, FindValueByKey, FindValuesForEntries, FindValuesForEntry,
-        IsEmailRegistered,
+        AttributeSeries, DistinctTextValuesForAttribute, EntriesRootedInTimeInterval,
+        FindActivitiesBySource, FindActivityById, FindActivityTemplateRoot, FindAncestors,
+        FindAttributeById, FindAttributePairsForEntry, FindAttributesByOwner, FindDescendants,
+        FindEntriesByActivity, FindEntryById, FindEntryJoinById, FindGrantById,
+        FindGrantsByGrantor, FindGrantsForGrantee, FindUserById, FindUserByUsername,
+        FindValueByKey, FindValuesForAttribute, FindValuesForEntries, FindValuesForEntry,
+        IsEmailRegistered, PersonalRecords, RunAnalytics, TrainingLoad,
     },
+    records::{PersonalRecord, RecordKind, RecordMark},
+    series::{SeriesAggregate, SeriesBucket, SeriesPoint, SeriesRange, SeriesUnit},
+    training_load::{DayLoad, LoadWindow, SessionLoad, TrainingLoadReport, WeekLoad},
     validation::{Email, Username},
 };
 use uuid::Uuid;
@@ -42,11 +63,29 @@ use uuid::Uuid;
 pub enum FfiError {
     #[error("{0}")]
     Generic(String),
+    /// Query text that didn't parse; `start..end` is the byte range of the
+    /// text to underline.
+    #[error("{message}")]
+    InvalidQuery {
+        message: String,
+        start: u32,
+        end: u32,
+    },
 }
 
 impl From<gv_core::error::DomainError> for FfiError {
     fn from(e: gv_core::error::DomainError) -> Self {
-        FfiError::Generic(e.to_string())
+        use gv_core::error::{DomainError, RejectReason, ValidationError};
+        match e {
+            DomainError::Rejected(RejectReason::Validation(ValidationError::InvalidQueryText(
+                e,
+            ))) => FfiError::InvalidQuery {
+                message: e.message,
+                start: e.start,
+                end: e.end,
+            },
+            e => FfiError::Generic(e.to_string()),
+        }
     }
 }
 
@@ -110,6 +149,21 @@ pub struct User {
     pub email: Email,
 }
 
+// --- Actor ---
+
+#[uniffi::remote(Enum)]
+pub enum ActorKind {
+    System,
+    User,
+}
+
+#[uniffi::remote(Record)]
+pub struct Actor {
+    pub actor_id: Uuid,
+    pub actor_kind: ActorKind,
+    pub created_at: DateTime<Utc>,
+}
+
 // --- Activity ---
 
 #[uniffi::remote(Record)]
@@ -119,6 +173,7 @@ pub struct Activity {
     pub source_activity_id: Option<Uuid>,
     pub name: ActivityName,
     pub description: Option<String>,
+    pub archived: bool,
 }
 
 // --- Entry ---
@@ -186,6 +241,34 @@ pub struct SelectConfig {
     pub default: Option<String>,
 }
 
+#[uniffi::remote(Enum)]
+pub enum GradeScale {
+    Yds,
+    French,
+    VScale,
+    Font,
+}
+
+#[uniffi::remote(Record)]
+pub struct GradeConfig {
+    pub scale: GradeScale,
+    pub default: Option<String>,
+}
+
+/// The grades on `scale`, easiest first — the options a grade value picks
+/// from (logic lives in core, `GradeScale::options`).
+#[uniffi::export]
+pub fn grade_scale_options(scale: GradeScale) -> Vec<String> {
+    scale.options()
+}
+
+/// The closest grade on `to`, or `None` across disciplines (routes vs
+/// boulders) or for a grade not on `scale`. Mirrors `GradeScale::convert`.
+#[uniffi::export]
+pub fn grade_converted_to(scale: GradeScale, grade: String, to: GradeScale) -> Option<String> {
+    scale.convert(&grade, &to).map(str::to_string)
+}
+
 #[uniffi::remote(Record)]
 pub struct MultiselectConfig {
     pub options: Vec<String>,
@@ -221,20 +304,119 @@ pub struct LengthConfig {
     pub default_unit: LengthUnit,
 }
 
+#[uniffi::remote(Enum)]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    MinutesPerKilometer,
+    MinutesPerMile,
+}
+
+#[uniffi::remote(Record)]
+pub struct SpeedConfig {
+    pub default_unit: SpeedUnit,
+}
+
+#[uniffi::remote(Enum)]
+pub enum DurationDisplay {
+    Seconds,
+    Minutes,
+    HoursMinutesSeconds,
+}
+
+#[uniffi::remote(Record)]
+pub struct DurationConfig {
+    pub display: DurationDisplay,
+}
+
+#[uniffi::remote(Enum)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+}
+
 #[uniffi::remote(Record)]
 pub struct TextConfig {
     pub default: Option<String>,
     pub autocomplete: bool,
 }
 
+#[uniffi::remote(Record)]
+pub struct BooleanConfig {
+    pub default: Option<bool>,
+}
+
+#[uniffi::remote(Enum)]
+pub enum DerivedOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[uniffi::remote(Enum)]
+pub enum DerivedNode {
+    Constant(f64),
+    Attribute(Uuid),
+    Binary { op: DerivedOp, lhs: u32, rhs: u32 },
+    SumDescendants { term: u32 },
+}
+
+#[uniffi::remote(Record)]
+pub struct DerivedExpr {
+    pub nodes: Vec<DerivedNode>,
+}
+
+#[uniffi::remote(Record)]
+pub struct DerivedConfig {
+    pub expression: DerivedExpr,
+}
+
+/// `lhs op rhs`, with `rhs`'s nodes appended after `lhs`'s. Builds the flat
+/// node list the way core's arithmetic operators on `DerivedExpr` do.
+#[uniffi::export]
+pub fn derived_expr_combine(lhs: DerivedExpr, op: DerivedOp, rhs: DerivedExpr) -> DerivedExpr {
+    match op {
+        DerivedOp::Add => lhs + rhs,
+        DerivedOp::Subtract => lhs - rhs,
+        DerivedOp::Multiply => lhs * rhs,
+        DerivedOp::Divide => lhs / rhs,
+    }
+}
+
+/// `expr` summed over the entry's descendants (`DerivedExpr::sum_descendants`).
+#[uniffi::export]
+pub fn derived_expr_sum_descendants(expr: DerivedExpr) -> DerivedExpr {
+    expr.sum_descendants()
+}
+
+/// Estimated one-rep max, Epley's formula (`DerivedExpr::epley`).
+#[uniffi::export]
+pub fn derived_expr_epley(load: DerivedExpr, reps: DerivedExpr) -> DerivedExpr {
+    DerivedExpr::epley(load, reps)
+}
+
+/// Estimated one-rep max, Brzycki's formula (`DerivedExpr::brzycki`).
+#[uniffi::export]
+pub fn derived_expr_brzycki(load: DerivedExpr, reps: DerivedExpr) -> DerivedExpr {
+    DerivedExpr::brzycki(load, reps)
+}
+
 #[uniffi::remote(Enum)]
 pub enum AttributeConfig {
     Numeric(NumericConfig),
     Select(SelectConfig),
+    Grade(GradeConfig),
     Multiselect(MultiselectConfig),
     Mass(MassConfig),
     Length(LengthConfig),
+    Speed(SpeedConfig),
+    Duration(DurationConfig),
     Text(TextConfig),
+    Boolean(BooleanConfig),
+    Derived(DerivedConfig),
 }
 
 #[uniffi::remote(Record)]
@@ -269,7 +451,16 @@ pub struct MassMeasurement {
 #[uniffi::remote(Enum)]
 pub enum MassValue {
     Exact(MassMeasurement),
-    Range { unit: MassUnit, min: f64, max: f64 },
+    Range {
+        unit: MassUnit,
+        min: f64,
+        max: f64,
+    },
+    Composite {
+        unit: MassUnit,
+        value: f64,
+        units: Vec<MassUnit>,
+    },
 }
 
 /// Unit conversion for mass values; the logic lives in core
@@ -281,6 +472,47 @@ pub fn mass_value_converted_to(value: MassValue, unit: MassUnit) -> MassValue {
     value.converted_to(unit)
 }
 
+/// Collapse a composite mass (e.g. 2 kg + 500 g) into one exact value in
+/// `unit`; mirrors `length_value_from_parts`.
+#[uniffi::export]
+pub fn mass_value_from_parts(parts: Vec<MassMeasurement>, unit: MassUnit) -> MassValue {
+    let parts: Vec<_> = parts.into_iter().map(|m| (m.unit, m.value)).collect();
+    MassValue::from_parts(&parts, unit)
+}
+
+/// A composite mass holding the sum of `parts`, shown over their units
+/// (`MassValue::composite`).
+#[uniffi::export]
+pub fn mass_value_composite(parts: Vec<MassMeasurement>) -> MassValue {
+    let parts: Vec<_> = parts.into_iter().map(|m| (m.unit, m.value)).collect();
+    MassValue::composite(&parts)
+}
+
+/// A mass spread over display units: both bounds of a range, or the same
+/// parts twice for an exact value.
+#[derive(uniffi::Record)]
+pub struct MassParts {
+    pub min: Vec<MassMeasurement>,
+    pub max: Vec<MassMeasurement>,
+}
+
+/// Spread a mass over `units` for composite display; mirrors
+/// `length_value_to_parts`.
+#[uniffi::export]
+pub fn mass_value_to_parts(value: MassValue, units: Vec<MassUnit>) -> MassParts {
+    let (min, max) = value.to_parts(&units);
+    let measurements = |parts: Vec<(MassUnit, f64)>| {
+        parts
+            .into_iter()
+            .map(|(unit, value)| MassMeasurement { unit, value })
+            .collect()
+    };
+    MassParts {
+        min: measurements(min),
+        max: measurements(max),
+    }
+}
+
 #[uniffi::remote(Record)]
 pub struct LengthMeasurement {
     pub unit: LengthUnit,
@@ -295,6 +527,11 @@ pub enum LengthValue {
         min: f64,
         max: f64,
     },
+    Composite {
+        unit: LengthUnit,
+        value: f64,
+        units: Vec<LengthUnit>,
+    },
 }
 
 /// Unit conversion for length values; mirrors `mass_value_converted_to` (logic
@@ -305,14 +542,85 @@ pub fn length_value_converted_to(value: LengthValue, unit: LengthUnit) -> Length
     value.converted_to(unit)
 }
 
+/// Collapse a composite length (e.g. 5 ft + 10 in) into one exact value in
+/// `unit` (logic lives in core, `Measure::from_parts`). Each measurement is one
+/// part; only the exact value is ever stored.
+#[uniffi::export]
+pub fn length_value_from_parts(parts: Vec<LengthMeasurement>, unit: LengthUnit) -> LengthValue {
+    let parts: Vec<_> = parts.into_iter().map(|m| (m.unit, m.value)).collect();
+    LengthValue::from_parts(&parts, unit)
+}
+
+/// A composite length holding the sum of `parts`, shown over their units
+/// (`LengthValue::composite`). Unlike `length_value_from_parts`, the result
+/// keeps its display units.
+#[uniffi::export]
+pub fn length_value_composite(parts: Vec<LengthMeasurement>) -> LengthValue {
+    let parts: Vec<_> = parts.into_iter().map(|m| (m.unit, m.value)).collect();
+    LengthValue::composite(&parts)
+}
+
+/// A length spread over display units; mirrors `MassParts`.
+#[derive(uniffi::Record)]
+pub struct LengthParts {
+    pub min: Vec<LengthMeasurement>,
+    pub max: Vec<LengthMeasurement>,
+}
+
+/// Spread a length over `units` for composite display, largest unit first
+/// (`Measure::to_parts`). A range spreads each bound.
+#[uniffi::export]
+pub fn length_value_to_parts(value: LengthValue, units: Vec<LengthUnit>) -> LengthParts {
+    let (min, max) = value.to_parts(&units);
+    let measurements = |parts: Vec<(LengthUnit, f64)>| {
+        parts
+            .into_iter()
+            .map(|(unit, value)| LengthMeasurement { unit, value })
+            .collect()
+    };
+    LengthParts {
+        min: measurements(min),
+        max: measurements(max),
+    }
+}
+
+#[uniffi::remote(Record)]
+pub struct SpeedMeasurement {
+    pub unit: SpeedUnit,
+    pub value: f64,
+}
+
+#[uniffi::remote(Enum)]
+pub enum SpeedValue {
+    Exact(SpeedMeasurement),
+    Range { unit: SpeedUnit, min: f64, max: f64 },
+}
+
+/// Unit conversion for speed and pace values; mirrors
+/// `mass_value_converted_to` (logic lives in core, `SpeedValue::converted_to`).
+#[uniffi::export]
+pub fn speed_value_converted_to(value: SpeedValue, unit: SpeedUnit) -> SpeedValue {
+    value.converted_to(unit)
+}
+
+#[uniffi::remote(Enum)]
+pub enum DurationValue {
+    Exact(f64),
+    Range { min: f64, max: f64 },
+}
+
 #[uniffi::remote(Enum)]
 pub enum AttributeValue {
     Numeric(NumericValue),
     Select(SelectValue),
+    Grade(SelectValue),
     Multiselect(Vec<String>),
     Mass(MassValue),
     Length(LengthValue),
+    Speed(SpeedValue),
+    Duration(DurationValue),
     Text(String),
+    Boolean(bool),
 }
 
 #[uniffi::remote(Record)]
@@ -351,6 +659,18 @@ pub struct SelectAttributePair {
     pub actual: Option<SelectValue>,
 }
 
+#[uniffi::remote(Record)]
+pub struct GradeAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: GradeConfig,
+    pub index_float: Option<f64>,
+    pub plan: Option<SelectValue>,
+    pub actual: Option<SelectValue>,
+}
+
 #[uniffi::remote(Record)]
 pub struct MultiselectAttributePair {
     pub attr_id: Uuid,
@@ -387,6 +707,30 @@ pub struct LengthAttributePair {
     pub actual: Option<LengthValue>,
 }
 
+#[uniffi::remote(Record)]
+pub struct SpeedAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: SpeedConfig,
+    pub index_float: Option<f64>,
+    pub plan: Option<SpeedValue>,
+    pub actual: Option<SpeedValue>,
+}
+
+#[uniffi::remote(Record)]
+pub struct DurationAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: DurationConfig,
+    pub index_float: Option<f64>,
+    pub plan: Option<DurationValue>,
+    pub actual: Option<DurationValue>,
+}
+
 #[uniffi::remote(Record)]
 pub struct TextAttributePair {
     pub attr_id: Uuid,
@@ -399,14 +743,42 @@ pub struct TextAttributePair {
     pub actual: Option<String>,
 }
 
+#[uniffi::remote(Record)]
+pub struct BooleanAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: BooleanConfig,
+    pub index_float: Option<f64>,
+    pub plan: Option<bool>,
+    pub actual: Option<bool>,
+}
+
+#[uniffi::remote(Record)]
+pub struct DerivedAttributePair {
+    pub attr_id: Uuid,
+    pub entry_id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub config: DerivedConfig,
+    pub plan: Option<f64>,
+    pub actual: Option<f64>,
+}
+
 #[uniffi::remote(Enum)]
 pub enum AttributePair {
     Numeric(NumericAttributePair),
     Select(SelectAttributePair),
+    Grade(GradeAttributePair),
     Multiselect(MultiselectAttributePair),
     Mass(MassAttributePair),
     Length(LengthAttributePair),
+    Speed(SpeedAttributePair),
+    Duration(DurationAttributePair),
     Text(TextAttributePair),
+    Boolean(BooleanAttributePair),
+    Derived(DerivedAttributePair),
 }
 
 // --- EntryJoin ---
@@ -419,6 +791,298 @@ pub struct EntryJoin {
     pub display_name: String,
 }
 
+// --- Grant ---
+
+#[uniffi::remote(Enum)]
+pub enum GrantScope {
+    Entry(Uuid),
+    Activity(Uuid),
+    Attribute(Uuid),
+    Log(Uuid),
+}
+
+#[uniffi::remote(Enum)]
+pub enum GrantLevel {
+    Read,
+    Write,
+    RootWrite,
+}
+
+#[uniffi::remote(Record)]
+pub struct Grant {
+    pub id: Uuid,
+    pub grantor_id: Uuid,
+    pub grantee_id: Uuid,
+    pub scope: GrantScope,
+    pub level: GrantLevel,
+    pub accepted: bool,
+}
+
+// --- Deltas ---
+//
+// uniffi can't export a generic type, so each `Delta<M>` crosses the boundary
+// under a concrete alias.
+
+pub type UserDelta = Delta<User>;
+pub type ActorDelta = Delta<Actor>;
+pub type ActivityDelta = Delta<Activity>;
+pub type EntryDelta = Delta<Entry>;
+pub type AttributeDelta = Delta<Attribute>;
+pub type ValueDelta = Delta<Value>;
+pub type GrantDelta = Delta<Grant>;
+
+#[uniffi::remote(Enum)]
+pub enum UserDelta {
+    Insert { new: User },
+    Update { old: User, new: User },
+    Delete { old: User },
+}
+
+#[uniffi::remote(Enum)]
+pub enum ActorDelta {
+    Insert { new: Actor },
+    Update { old: Actor, new: Actor },
+    Delete { old: Actor },
+}
+
+#[uniffi::remote(Enum)]
+pub enum ActivityDelta {
+    Insert { new: Activity },
+    Update { old: Activity, new: Activity },
+    Delete { old: Activity },
+}
+
+#[uniffi::remote(Enum)]
+pub enum EntryDelta {
+    Insert { new: Entry },
+    Update { old: Entry, new: Entry },
+    Delete { old: Entry },
+}
+
+#[uniffi::remote(Enum)]
+pub enum AttributeDelta {
+    Insert { new: Attribute },
+    Update { old: Attribute, new: Attribute },
+    Delete { old: Attribute },
+}
+
+#[uniffi::remote(Enum)]
+pub enum ValueDelta {
+    Insert { new: Value },
+    Update { old: Value, new: Value },
+    Delete { old: Value },
+}
+
+#[uniffi::remote(Enum)]
+pub enum GrantDelta {
+    Insert { new: Grant },
+    Update { old: Grant, new: Grant },
+    Delete { old: Grant },
+}
+
+#[uniffi::remote(Enum)]
+pub enum AnyDelta {
+    User(UserDelta),
+    Actor(ActorDelta),
+    Activity(ActivityDelta),
+    Entry(EntryDelta),
+    Attribute(AttributeDelta),
+    Value(ValueDelta),
+    Grant(GrantDelta),
+}
+
+// --- Analytics ---
+
+#[uniffi::remote(Record)]
+pub struct AnalyticsQuery {
+    pub filters: Vec<EntryFilter>,
+    pub group_by: Option<Grouping>,
+    pub aggregate: Aggregate,
+}
+
+#[uniffi::remote(Enum)]
+pub enum EntryFilter {
+    Activity {
+        activity_id: Uuid,
+    },
+    WithinActivity {
+        activity_id: Uuid,
+    },
+    TimeWindow {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    Attribute(AttributePredicate),
+    Complete(bool),
+}
+
+#[uniffi::remote(Record)]
+pub struct AttributePredicate {
+    pub target: AttributeField,
+    pub comparison: Comparison,
+    pub operand: AttributeValue,
+}
+
+#[uniffi::remote(Enum)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[uniffi::remote(Record)]
+pub struct AttributeField {
+    pub attribute_id: Uuid,
+    pub field: ValueField,
+}
+
+#[uniffi::remote(Enum)]
+pub enum Grouping {
+    Period {
+        period: Period,
+        utc_offset_minutes: i32,
+    },
+    Attribute(AttributeField),
+}
+
+#[uniffi::remote(Enum)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+#[uniffi::remote(Enum)]
+pub enum Aggregate {
+    Count,
+    Sum(AttributeField),
+    Max(AttributeField),
+    Min(AttributeField),
+    Mean(AttributeField),
+}
+
+#[uniffi::remote(Record)]
+pub struct AnalyticsRow {
+    pub group: GroupKey,
+    pub entries: u32,
+    pub value: Option<f64>,
+}
+
+#[uniffi::remote(Enum)]
+pub enum GroupKey {
+    All,
+    Period { start: DateTime<Utc> },
+    Value(AttributeValue),
+}
+
+#[uniffi::remote(Record)]
+pub struct PersonalRecord {
+    pub activity_id: Uuid,
+    pub attribute_id: Uuid,
+    pub kind: RecordKind,
+    pub best: RecordMark,
+    pub history: Vec<RecordMark>,
+}
+
+#[uniffi::remote(Enum)]
+pub enum RecordKind {
+    Max,
+    MaxAt {
+        load_attribute_id: Uuid,
+        load: AttributeValue,
+    },
+    HardestSend,
+}
+
+#[uniffi::remote(Record)]
+pub struct RecordMark {
+    pub entry_id: Uuid,
+    pub at: DateTime<Utc>,
+    pub value: AttributeValue,
+}
+
+#[uniffi::remote(Record)]
+pub struct LoadWindow {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub utc_offset_minutes: i32,
+}
+
+#[uniffi::remote(Record)]
+pub struct TrainingLoadReport {
+    pub sessions: Vec<SessionLoad>,
+    pub days: Vec<DayLoad>,
+    pub weeks: Vec<WeekLoad>,
+}
+
+#[uniffi::remote(Record)]
+pub struct SessionLoad {
+    pub entry_id: Uuid,
+    pub at: DateTime<Utc>,
+    pub rpe: Option<f64>,
+    pub duration_minutes: Option<f64>,
+    pub load: Option<f64>,
+}
+
+#[uniffi::remote(Record)]
+pub struct DayLoad {
+    pub start: DateTime<Utc>,
+    pub load: f64,
+    pub sessions: u32,
+    pub unknown: u32,
+    pub acute_chronic_ratio: Option<f64>,
+}
+
+#[uniffi::remote(Record)]
+pub struct WeekLoad {
+    pub start: DateTime<Utc>,
+    pub load: f64,
+    pub sessions: u32,
+    pub unknown: u32,
+    pub monotony: Option<f64>,
+    pub strain: Option<f64>,
+}
+
+#[uniffi::remote(Record)]
+pub struct SeriesBucket {
+    pub period: Period,
+    pub utc_offset_minutes: i32,
+}
+
+#[uniffi::remote(Enum)]
+pub enum SeriesAggregate {
+    Sum,
+    Max,
+    Min,
+    Mean,
+    Count,
+}
+
+#[uniffi::remote(Record)]
+pub struct SeriesRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+#[uniffi::remote(Enum)]
+pub enum SeriesUnit {
+    Mass(MassUnit),
+    Length(LengthUnit),
+    Speed(SpeedUnit),
+    Duration(TimeUnit),
+}
+
+#[uniffi::remote(Record)]
+pub struct SeriesPoint {
+    pub time: DateTime<Utc>,
+    pub value: f64,
+    pub entry_id: Option<Uuid>,
+    pub values: u32,
+}
+
 // --- Queries ---
 
 #[uniffi::remote(Record)]
@@ -452,6 +1116,16 @@ pub struct FindActivityTemplateRoot {
 #[uniffi::remote(Record)]
 pub struct AllActivities;
 
+#[uniffi::remote(Record)]
+pub struct FindEntriesByActivity {
+    pub activity_id: Uuid,
+}
+
+#[uniffi::remote(Record)]
+pub struct FindActivitiesBySource {
+    pub source_activity_id: Uuid,
+}
+
 #[uniffi::remote(Record)]
 pub struct AllEntries;
 
@@ -510,6 +1184,11 @@ pub struct FindValuesForEntries {
     pub entry_ids: Vec<Uuid>,
 }
 
+#[uniffi::remote(Record)]
+pub struct FindValuesForAttribute {
+    pub attribute_id: Uuid,
+}
+
 #[uniffi::remote(Record)]
 pub struct FindAttributePairsForEntry {
     pub entry_id: Uuid,
@@ -520,6 +1199,51 @@ pub struct DistinctTextValuesForAttribute {
     pub attribute_id: Uuid,
 }
 
+#[uniffi::remote(Record)]
+pub struct RunAnalytics {
+    pub query: AnalyticsQuery,
+}
+
+#[uniffi::remote(Record)]
+pub struct PersonalRecords {
+    pub owner_id: Uuid,
+    pub activity_id: Option<Uuid>,
+}
+
+#[uniffi::remote(Record)]
+pub struct TrainingLoad {
+    pub owner_id: Uuid,
+    pub rpe_attribute_id: Uuid,
+    pub window: LoadWindow,
+}
+
+#[uniffi::remote(Record)]
+pub struct AttributeSeries {
+    pub activity_id: Uuid,
+    pub attribute_id: Uuid,
+    pub field: ValueField,
+    pub bucket: Option<SeriesBucket>,
+    pub aggregate: SeriesAggregate,
+    pub range: Option<SeriesRange>,
+    pub unit: Option<SeriesUnit>,
+    pub roll_up_sets: bool,
+}
+
+#[uniffi::remote(Record)]
+pub struct FindGrantById {
+    pub grant_id: Uuid,
+}
+
+#[uniffi::remote(Record)]
+pub struct FindGrantsForGrantee {
+    pub grantee_id: Uuid,
+}
+
+#[uniffi::remote(Record)]
+pub struct FindGrantsByGrantor {
+    pub grantor_id: Uuid,
+}
+
 #[uniffi::remote(Enum)]
 pub enum AnyQuery {
     // Auth
@@ -531,6 +1255,8 @@ pub enum AnyQuery {
     FindActivityById(FindActivityById),
     AllActivities(AllActivities),
     FindActivityTemplateRoot(FindActivityTemplateRoot),
+    FindEntriesByActivity(FindEntriesByActivity),
+    FindActivitiesBySource(FindActivitiesBySource),
     // Entry
     AllEntries(AllEntries),
     EntriesRootedInTimeInterval(EntriesRootedInTimeInterval),
@@ -546,8 +1272,18 @@ pub enum AnyQuery {
     FindValueByKey(FindValueByKey),
     FindValuesForEntry(FindValuesForEntry),
     FindValuesForEntries(FindValuesForEntries),
+    FindValuesForAttribute(FindValuesForAttribute),
     FindAttributePairsForEntry(FindAttributePairsForEntry),
     DistinctTextValuesForAttribute(DistinctTextValuesForAttribute),
+    // Analytics
+    RunAnalytics(RunAnalytics),
+    PersonalRecords(PersonalRecords),
+    TrainingLoad(TrainingLoad),
+    AttributeSeries(AttributeSeries),
+    // Grant
+    FindGrantById(FindGrantById),
+    FindGrantsForGrantee(FindGrantsForGrantee),
+    FindGrantsByGrantor(FindGrantsByGrantor),
 }
 
 #[uniffi::remote(Enum)]
@@ -561,6 +1297,8 @@ pub enum AnyQueryResponse {
     FindActivityById(Option<Activity>),
     FindActivityTemplateRoot(Option<Entry>),
     AllActivities(Vec<Activity>),
+    FindEntriesByActivity(Vec<Entry>),
+    FindActivitiesBySource(Vec<Activity>),
     // Entry
     AllEntries(Vec<Entry>),
     EntriesRootedInTimeInterval(Vec<Entry>),
@@ -576,8 +1314,18 @@ pub enum AnyQueryResponse {
     FindValueByKey(Option<Value>),
     FindValuesForEntry(Vec<Value>),
     FindValuesForEntries(Vec<Value>),
+    FindValuesForAttribute(Vec<Value>),
     FindAttributePairsForEntry(Vec<AttributePair>),
     DistinctTextValuesForAttribute(Vec<String>),
+    // Analytics
+    RunAnalytics(Vec<AnalyticsRow>),
+    PersonalRecords(Vec<PersonalRecord>),
+    TrainingLoad(TrainingLoadReport),
+    AttributeSeries(Vec<SeriesPoint>),
+    // Grant
+    FindGrantById(Option<Grant>),
+    FindGrantsForGrantee(Vec<Grant>),
+    FindGrantsByGrantor(Vec<Grant>),
 }
 
 // --- Actions ---
@@ -674,11 +1422,41 @@ pub struct DeleteAttributeValue {
 #[uniffi::remote(Enum)]
 pub enum NumericChange {
     SetDefault(Option<f64>),
+    RaiseMax(Option<f64>),
+    LowerMin(Option<f64>),
+    SetInteger(bool),
 }
 
 #[uniffi::remote(Enum)]
 pub enum SelectChange {
     SetDefault(Option<String>),
+    AddOption {
+        option: String,
+        position: Option<u32>,
+    },
+    RenameOption {
+        from: String,
+        to: String,
+    },
+    SetOrdered(bool),
+}
+
+#[uniffi::remote(Enum)]
+pub enum GradeChange {
+    SetDefault(Option<String>),
+}
+
+#[uniffi::remote(Enum)]
+pub enum MultiselectChange {
+    SetDefault(Option<Vec<String>>),
+    AddOption {
+        option: String,
+        position: Option<u32>,
+    },
+    RenameOption {
+        from: String,
+        to: String,
+    },
 }
 
 #[uniffi::remote(Enum)]
@@ -691,21 +1469,47 @@ pub enum LengthChange {
     SetDefaultUnit(LengthUnit),
 }
 
+#[uniffi::remote(Enum)]
+pub enum SpeedChange {
+    SetDefaultUnit(SpeedUnit),
+}
+
+#[uniffi::remote(Enum)]
+pub enum DurationChange {
+    SetDisplay(DurationDisplay),
+}
+
 #[uniffi::remote(Enum)]
 pub enum TextChange {
     SetDefault(Option<String>),
     SetAutocomplete(bool),
 }
 
+#[uniffi::remote(Enum)]
+pub enum BooleanChange {
+    SetDefault(Option<bool>),
+}
+
+#[uniffi::remote(Enum)]
+pub enum DerivedChange {
+    SetExpression(DerivedExpr),
+}
+
 #[uniffi::remote(Enum)]
 pub enum AttributeChange {
     SetName(String),
     SetDescription(Option<String>),
     Numeric(NumericChange),
     Select(SelectChange),
+    Grade(GradeChange),
+    Multiselect(MultiselectChange),
     Mass(MassChange),
     Length(LengthChange),
+    Speed(SpeedChange),
+    Duration(DurationChange),
     Text(TextChange),
+    Boolean(BooleanChange),
+    Derived(DerivedChange),
 }
 
 #[uniffi::remote(Record)]
@@ -715,6 +1519,52 @@ pub struct UpdateAttribute {
     pub change: AttributeChange,
 }
 
+#[uniffi::remote(Record)]
+pub struct AttributeMigration {
+    pub actor_id: Uuid,
+    pub attribute_id: Uuid,
+    pub migration: ConfigMigration,
+}
+
+#[uniffi::remote(Enum)]
+pub enum ConfigMigration {
+    RemoveOptions(Vec<OptionRemoval>),
+    SetBounds {
+        min: Option<f64>,
+        max: Option<f64>,
+        out_of_range: OutOfRange,
+    },
+}
+
+#[uniffi::remote(Record)]
+pub struct OptionRemoval {
+    pub option: String,
+    pub replacement: Option<String>,
+}
+
+#[uniffi::remote(Enum)]
+pub enum OutOfRange {
+    Clamp,
+    Clear,
+}
+
+#[uniffi::remote(Record)]
+pub struct ConvertAttributeType {
+    pub actor_id: Uuid,
+    pub attribute_id: Uuid,
+    pub conversion: AttributeConversion,
+}
+
+#[uniffi::remote(Enum)]
+pub enum AttributeConversion {
+    ToMass(MassUnit),
+    ToLength(LengthUnit),
+    ToMultiselect,
+    ToText,
+    ToSelect,
+    ToGrade(GradeScale),
+}
+
 #[uniffi::remote(Enum)]
 pub enum EntryChange {
     SetIsSequence(bool),
@@ -741,6 +1591,73 @@ pub struct DuplicateEntry {
     pub entry_id: Uuid,
 }
 
+#[uniffi::remote(Record)]
+pub struct Undo {
+    pub actor_id: Uuid,
+    pub mutation_id: Uuid,
+}
+
+#[uniffi::remote(Record)]
+pub struct Redo {
+    pub actor_id: Uuid,
+    pub mutation_id: Uuid,
+}
+
+#[uniffi::remote(Record)]
+pub struct GrantPermission {
+    pub actor_id: Uuid,
+    pub grant_id: Uuid,
+    pub grantee_id: Uuid,
+    pub scope: GrantScope,
+    pub level: GrantLevel,
+}
+
+#[uniffi::remote(Record)]
+pub struct AcceptGrant {
+    pub actor_id: Uuid,
+    pub grant_id: Uuid,
+}
+
+#[uniffi::remote(Record)]
+pub struct RevokeGrant {
+    pub actor_id: Uuid,
+    pub grant_id: Uuid,
+}
+
+#[uniffi::remote(Record)]
+pub struct DeleteActivity {
+    pub actor_id: Uuid,
+    pub activity_id: Uuid,
+    pub referencing_entries: Option<ReferencingEntries>,
+}
+
+#[uniffi::remote(Enum)]
+pub enum ReferencingEntries {
+    Detach,
+    DeleteRecursive,
+}
+
+#[uniffi::remote(Record)]
+pub struct ArchiveActivity {
+    pub actor_id: Uuid,
+    pub activity_id: Uuid,
+    pub archived: bool,
+}
+
+#[uniffi::remote(Enum)]
+pub enum ActivityChange {
+    SetName(String),
+    SetDescription(Option<String>),
+    SetSourceActivityId(Option<Uuid>),
+}
+
+#[uniffi::remote(Record)]
+pub struct UpdateActivity {
+    pub actor_id: Uuid,
+    pub activity_id: Uuid,
+    pub change: ActivityChange,
+}
+
 #[uniffi::remote(Enum)]
 pub enum Action {
     CreateUser(CreateUser),
@@ -759,4 +1676,14 @@ pub enum Action {
     UpdateEntry(UpdateEntry),
     ConvertToSets(ConvertToSets),
     DuplicateEntry(DuplicateEntry),
+    Undo(Undo),
+    Redo(Redo),
+    GrantPermission(GrantPermission),
+    AcceptGrant(AcceptGrant),
+    RevokeGrant(RevokeGrant),
+    DeleteActivity(DeleteActivity),
+    ArchiveActivity(ArchiveActivity),
+    UpdateActivity(UpdateActivity),
+    AttributeMigration(AttributeMigration),
+    ConvertAttributeType(ConvertAttributeType),
 }
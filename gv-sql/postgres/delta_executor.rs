@@ -12,6 +12,7 @@ use gv_core::{
         actor::Actor,
         attribute::{Attribute, Value},
         entry::Entry,
+        grant::Grant,
         user::User,
     },
 };
@@ -37,6 +38,7 @@ impl AnyDeltaExecutor for PostgresDeltaExecutor<'_> {
             AnyDelta::Entry(delta) => self.apply_delta(delta).await,
             AnyDelta::Attribute(delta) => self.apply_delta(delta).await,
             AnyDelta::Value(delta) => self.apply_delta(delta).await,
+            AnyDelta::Grant(delta) => self.apply_delta(delta).await,
         }
     }
 }
@@ -141,14 +143,15 @@ impl DeltaExecutor<Activity> for PostgresDeltaExecutor<'_> {
                 let row = crate::rows::ActivityRow::from(new);
                 sqlx::query!(
                     r#"
-                    INSERT INTO activities (id, owner_id, source_activity_id, name, description)
-                    VALUES ($1, $2, $3, $4, $5)
+                    INSERT INTO activities (id, owner_id, source_activity_id, name, description, archived)
+                    VALUES ($1, $2, $3, $4, $5, $6)
                     "#,
                     row.id as _,
                     row.owner_id as _,
                     row.source_activity_id as _,
                     row.name as _,
                     row.description,
+                    row.archived,
                 )
                 .execute(&mut *self.conn)
                 .await
@@ -164,13 +167,15 @@ impl DeltaExecutor<Activity> for PostgresDeltaExecutor<'_> {
                         owner_id = $1,
                         source_activity_id = $2,
                         name = $3,
-                        description = $4
-                    WHERE id = $5
+                        description = $4,
+                        archived = $5
+                    WHERE id = $6
                     "#,
                     row.owner_id as _,
                     row.source_activity_id as _,
                     row.name as _,
                     row.description,
+                    row.archived,
                     row.id as _,
                 )
                 .execute(&mut *self.conn)
@@ -398,3 +403,58 @@ impl DeltaExecutor<Value> for PostgresDeltaExecutor<'_> {
         Ok(())
     }
 }
+
+impl DeltaExecutor<Grant> for PostgresDeltaExecutor<'_> {
+    async fn apply_delta(&mut self, delta: Delta<Grant>) -> Result<()> {
+        match delta {
+            Delta::Insert { new } => {
+                let row = crate::rows::GrantRow::from(&new);
+                sqlx::query(
+                    r#"
+                    INSERT INTO grants (id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    "#,
+                )
+                .bind(row.id)
+                .bind(row.grantor_id)
+                .bind(row.grantee_id)
+                .bind(row.scope_kind)
+                .bind(row.scope_id)
+                .bind(row.level)
+                .bind(row.accepted)
+                .execute(&mut *self.conn)
+                .await
+                .sql_err()?;
+            }
+            Delta::Update { old, new } => {
+                assert_eq!(old.id, new.id, "update must not mutate primary key");
+                let row = crate::rows::GrantRow::from(&new);
+                sqlx::query(
+                    r#"
+                    UPDATE grants
+                    SET grantor_id = $1, grantee_id = $2, scope_kind = $3, scope_id = $4, level = $5, accepted = $6
+                    WHERE id = $7
+                    "#,
+                )
+                .bind(row.grantor_id)
+                .bind(row.grantee_id)
+                .bind(row.scope_kind)
+                .bind(row.scope_id)
+                .bind(row.level)
+                .bind(row.accepted)
+                .bind(row.id)
+                .execute(&mut *self.conn)
+                .await
+                .sql_err()?;
+            }
+            Delta::Delete { old } => {
+                sqlx::query("DELETE FROM grants WHERE id = $1")
+                    .bind(crate::columns::UuidColumn(old.id))
+                    .execute(&mut *self.conn)
+                    .await
+                    .sql_err()?;
+            }
+        };
+        Ok(())
+    }
+}
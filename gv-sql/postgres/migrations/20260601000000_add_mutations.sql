@@ -0,0 +1,38 @@
+-- Server mutation log.
+--
+-- Every committed action gets a global sequence number `seq_num`, and each of
+-- its deltas is stored under `(seq_num, op_offset)`. Clients catch up by asking
+-- for everything after the last `seq_num` they saw.
+--
+-- `seq_num` comes from the single-row `mutation_seq` counter rather than a
+-- SEQUENCE. A sequence hands out values at call time. Concurrent transactions
+-- can then commit out of order, and a reader could see seq 11 before seq 10
+-- exists. Incrementing the counter row locks it until the transaction ends, so
+-- seq_nums become visible in order with no gaps. The server bumps it as the
+-- last statement before commit to keep the lock short.
+
+CREATE TABLE IF NOT EXISTS mutation_seq (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    seq_num BIGINT NOT NULL
+);
+
+INSERT INTO mutation_seq (id, seq_num) VALUES (TRUE, 0) ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS mutations (
+    seq_num BIGINT PRIMARY KEY,
+    id UUID UNIQUE NOT NULL,
+    actor_id UUID NOT NULL,
+    action TEXT NOT NULL,  -- JSON as TEXT
+    timestamp TIMESTAMPTZ NOT NULL,
+    committed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS mutation_deltas (
+    seq_num BIGINT NOT NULL REFERENCES mutations(seq_num),
+    op_offset INTEGER NOT NULL,
+    table_name TEXT NOT NULL,
+    delta TEXT NOT NULL,  -- JSON as TEXT
+    PRIMARY KEY (seq_num, op_offset)
+);
+
+CREATE INDEX IF NOT EXISTS mutations_actor_id_idx ON mutations (actor_id, seq_num);
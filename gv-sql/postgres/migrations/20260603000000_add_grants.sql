@@ -0,0 +1,21 @@
+-- Grants: access an owner (`grantor_id`) has extended to another actor
+-- (`grantee_id`) over one entry, activity or attribute. A grant is created
+-- pending and only takes effect once the grantee accepts it. How a grant
+-- propagates beyond its scope is decided in `gv_core::permissions`.
+--
+-- `scope_id` is not a foreign key: it points into one of three tables
+-- depending on `scope_kind`.
+
+CREATE TABLE IF NOT EXISTS grants (
+    id UUID PRIMARY KEY,
+    grantor_id UUID NOT NULL REFERENCES actors(id),
+    grantee_id UUID NOT NULL REFERENCES actors(id),
+    scope_kind TEXT NOT NULL CHECK (scope_kind IN ('entry', 'activity', 'attribute')),
+    scope_id UUID NOT NULL,
+    level TEXT NOT NULL CHECK (level IN ('read', 'write')),
+    accepted BOOLEAN NOT NULL DEFAULT FALSE,
+    CHECK (grantor_id <> grantee_id)
+);
+
+CREATE INDEX IF NOT EXISTS grants_grantee_id_idx ON grants (grantee_id);
+CREATE INDEX IF NOT EXISTS grants_grantor_id_idx ON grants (grantor_id);
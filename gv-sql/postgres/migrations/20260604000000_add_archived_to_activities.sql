@@ -0,0 +1 @@
+ALTER TABLE activities ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS attribute_values_index_float_idx ON attribute_values (attribute_id, index_float);
+CREATE INDEX IF NOT EXISTS attribute_values_index_string_idx ON attribute_values (attribute_id, index_string);
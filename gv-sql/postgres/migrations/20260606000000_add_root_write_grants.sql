@@ -0,0 +1,9 @@
+-- Root-write grants: a grantee may add root entries to the grantor's log
+-- (`scope_kind` 'log', `scope_id` the grantor).
+
+ALTER TABLE grants DROP CONSTRAINT grants_scope_kind_check;
+ALTER TABLE grants ADD CONSTRAINT grants_scope_kind_check
+    CHECK (scope_kind IN ('entry', 'activity', 'attribute', 'log'));
+ALTER TABLE grants DROP CONSTRAINT grants_level_check;
+ALTER TABLE grants ADD CONSTRAINT grants_level_check
+    CHECK (level IN ('read', 'write', 'root_write'));
@@ -1,7 +1,11 @@
 //! Postgres backend. Feature-gated behind `postgres`.
 
 pub mod delta_executor;
+pub mod mutation_log;
 pub mod query_executor;
+pub mod reindex;
 
 pub use delta_executor::PostgresDeltaExecutor;
+pub use mutation_log::PostgresMutationLog;
 pub use query_executor::PostgresQueryExecutor;
+pub use reindex::reindex_values;
@@ -0,0 +1,67 @@
+use gv_core::{error::Result, mutation_log::CommittedMutation, mutators::Mutation};
+use sqlx::PgConnection;
+
+use crate::{
+    error::SqlErr,
+    rows::{CommittedMutationRow, StampedDeltaRow},
+};
+
+/// Writes to the server's `mutations` and `mutation_deltas` tables. Borrow the
+/// transaction the mutation's deltas were applied on, and append last: taking
+/// a sequence number locks the counter row until commit (see the migration).
+pub struct PostgresMutationLog<'c> {
+    conn: &'c mut PgConnection,
+}
+
+impl<'c> PostgresMutationLog<'c> {
+    pub fn new(conn: &'c mut PgConnection) -> Self {
+        PostgresMutationLog { conn }
+    }
+
+    /// Assign the next global sequence number to `mutation` and persist it with
+    /// its deltas stamped `(seq_num, op_offset)`.
+    pub async fn append(&mut self, mutation: Mutation) -> Result<CommittedMutation> {
+        let seq_num: i64 = sqlx::query_scalar(
+            "UPDATE mutation_seq SET seq_num = seq_num + 1 WHERE id RETURNING seq_num",
+        )
+        .fetch_one(&mut *self.conn)
+        .await
+        .sql_err()?;
+        let committed = CommittedMutation { seq_num, mutation };
+
+        let row = CommittedMutationRow::from_committed_mutation(&committed)?;
+        sqlx::query(
+            r#"
+            INSERT INTO mutations (seq_num, id, actor_id, action, timestamp)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(row.seq_num)
+        .bind(row.id)
+        .bind(row.actor_id)
+        .bind(row.action)
+        .bind(row.timestamp)
+        .execute(&mut *self.conn)
+        .await
+        .sql_err()?;
+
+        for stamped in committed.stamped_changes() {
+            let row = StampedDeltaRow::from_stamped_delta(&stamped)?;
+            sqlx::query(
+                r#"
+                INSERT INTO mutation_deltas (seq_num, op_offset, table_name, delta)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(row.seq_num)
+            .bind(row.op_offset)
+            .bind(row.table_name)
+            .bind(row.delta)
+            .execute(&mut *self.conn)
+            .await
+            .sql_err()?;
+        }
+
+        Ok(committed)
+    }
+}
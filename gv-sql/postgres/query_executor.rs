@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+
 use gv_core::{
+    analytics::{self, Comparison, Pushdown},
+    delta::AnyDelta,
     error::{DbErr, DomainError, RejectReason, Result},
-    models::{activity::Activity, user::User},
+    models::{activity::Activity, attribute::Value, entry::Entry, user::User},
+    permissions::Visibility,
     queries::*,
-    query_executor::QueryExecutor,
+    query_executor::{QueryExecutor, ReadScope, evaluate_derived_pairs, find_attributes},
+    records, series, training_load,
 };
 use itertools::Itertools;
 use sqlx::PgConnection;
@@ -10,11 +16,55 @@ use uuid::Uuid;
 
 pub struct PostgresQueryExecutor<'c> {
     conn: &'c mut PgConnection,
+    /// Whose rows reads are restricted to (`ReadScope`); every row when `None`.
+    reader: Option<Uuid>,
 }
 
 impl<'c> PostgresQueryExecutor<'c> {
     pub fn new(conn: &'c mut PgConnection) -> Self {
-        PostgresQueryExecutor { conn }
+        PostgresQueryExecutor { conn, reader: None }
+    }
+
+    /// The CTEs `visible` reads, followed by a comma for the query's own; empty
+    /// when unscoped.
+    fn scoped_ctes(&self) -> String {
+        self.reader
+            .map(|actor_id| format!("{}, ", visible_ctes(actor_id)))
+            .unwrap_or_default()
+    }
+
+    /// `WITH RECURSIVE` and `scoped_ctes`, for a query with no CTEs of its own.
+    fn with_visible(&self) -> String {
+        self.reader
+            .map(|actor_id| format!("WITH RECURSIVE {} ", visible_ctes(actor_id)))
+            .unwrap_or_default()
+    }
+
+    /// A condition that `column` holds the id of a `kind` row the reader can
+    /// see; `TRUE` when unscoped.
+    fn visible(&self, kind: &str, column: &str) -> String {
+        match self.reader {
+            Some(_) => format!("{column} IN (SELECT id FROM visible WHERE kind = '{kind}')"),
+            None => "TRUE".to_string(),
+        }
+    }
+
+    /// A condition that the reader is a party to the grant; `TRUE` when
+    /// unscoped.
+    fn party(&self) -> String {
+        match self.reader {
+            Some(actor_id) => {
+                let actor = uuid_literal(actor_id);
+                format!("(grantor_id = {actor} OR grantee_id = {actor})")
+            }
+            None => "TRUE".to_string(),
+        }
+    }
+}
+
+impl ReadScope for PostgresQueryExecutor<'_> {
+    fn read_as(&mut self, actor_id: Uuid) {
+        self.reader = Some(actor_id);
     }
 }
 
@@ -83,12 +133,16 @@ impl QueryExecutor<FindActivityById> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindActivityById,
     ) -> Result<<FindActivityById as Query>::Response> {
-        let row = sqlx::query_as::<_, crate::rows::ActivityRow>(
-            "SELECT id, owner_id, source_activity_id, name, description FROM activities WHERE id = $1",
-        )
-        .bind(crate::columns::UuidColumn(query.id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?;
+        let sql = format!(
+            "{}SELECT id, owner_id, source_activity_id, name, description, archived FROM activities WHERE id = $1 AND {}",
+            self.with_visible(),
+            self.visible("activity", "id"),
+        );
+        let row = sqlx::query_as::<_, crate::rows::ActivityRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?;
 
         Ok(row.map(Activity::from))
     }
@@ -99,22 +153,49 @@ impl QueryExecutor<AllActivities> for PostgresQueryExecutor<'_> {
         &mut self,
         _query: AllActivities,
     ) -> Result<<AllActivities as Query>::Response> {
-        let rows = sqlx::query_as::<_, crate::rows::ActivityRow>(
-            "SELECT id, owner_id, source_activity_id, name, description FROM activities",
-        )
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?;
+        let sql = format!(
+            "{}SELECT id, owner_id, source_activity_id, name, description, archived FROM activities WHERE NOT archived AND {}",
+            self.with_visible(),
+            self.visible("activity", "id"),
+        );
+        let rows = sqlx::query_as::<_, crate::rows::ActivityRow>(&sql)
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
 
         Ok(rows.into_iter().map(Activity::from).collect())
     }
 }
 
+impl QueryExecutor<FindActivitiesBySource> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindActivitiesBySource,
+    ) -> Result<<FindActivitiesBySource as Query>::Response> {
+        let sql = format!(
+            "{}SELECT id, owner_id, source_activity_id, name, description, archived FROM activities WHERE source_activity_id = $1 AND {}",
+            self.with_visible(),
+            self.visible("activity", "id"),
+        );
+        let rows = sqlx::query_as::<_, crate::rows::ActivityRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.source_activity_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
+        Ok(rows.into_iter().map(Activity::from).collect())
+    }
+}
+
 // --- Entry ---
 
 impl QueryExecutor<AllEntries> for PostgresQueryExecutor<'_> {
     async fn execute(&mut self, _query: AllEntries) -> Result<<AllEntries as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::EntryRow>("SELECT * FROM entries")
+        let sql = format!(
+            "{}SELECT * FROM entries WHERE {}",
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
             .fetch_all(&mut *self.conn)
             .await
             .db_err()?
@@ -129,9 +210,9 @@ impl QueryExecutor<EntriesRootedInTimeInterval> for PostgresQueryExecutor<'_> {
         &mut self,
         query: EntriesRootedInTimeInterval,
     ) -> Result<<EntriesRootedInTimeInterval as Query>::Response> {
-        sqlx::query_as::<sqlx::Postgres, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            WITH RECURSIVE forest AS (
+            WITH RECURSIVE {}forest AS (
                 SELECT * FROM entries e
                 WHERE e.start_time BETWEEN $1 AND $2
                     AND e.parent_id IS NULL
@@ -139,17 +220,20 @@ impl QueryExecutor<EntriesRootedInTimeInterval> for PostgresQueryExecutor<'_> {
                 SELECT c.* FROM entries c
                     INNER JOIN forest ON c.parent_id = forest.id
             )
-            SELECT * FROM forest
+            SELECT * FROM forest WHERE {}
             "#,
-        )
-        .bind(crate::columns::DateTimeColumn(query.from))
-        .bind(crate::columns::DateTimeColumn(query.to))
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?
-        .into_iter()
-        .map(|r| r.to_entry())
-        .collect()
+            self.scoped_ctes(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<sqlx::Postgres, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::DateTimeColumn(query.from))
+            .bind(crate::columns::DateTimeColumn(query.to))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|r| r.to_entry())
+            .collect()
     }
 }
 
@@ -158,9 +242,9 @@ impl QueryExecutor<FindAncestors> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindAncestors,
     ) -> Result<<FindAncestors as Query>::Response> {
-        let results: Vec<(Uuid, Option<Uuid>)> = sqlx::query_as(
+        let sql = format!(
             r#"
-            WITH RECURSIVE ancestors AS (
+            WITH RECURSIVE {}ancestors AS (
                 SELECT id, parent_id, 0 as dist
                     FROM entries
                     WHERE id = $1
@@ -170,13 +254,17 @@ impl QueryExecutor<FindAncestors> for PostgresQueryExecutor<'_> {
                     INNER JOIN ancestors a ON a.parent_id = e.id
             )
             SELECT id, parent_id FROM ancestors
+            WHERE {}
             ORDER BY dist
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?;
+            self.scoped_ctes(),
+            self.visible("entry", "id"),
+        );
+        let results: Vec<(Uuid, Option<Uuid>)> = sqlx::query_as(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
 
         if results.is_empty() {
             return Err(DomainError::Rejected(RejectReason::NotFound(
@@ -201,8 +289,11 @@ impl QueryExecutor<FindAncestors> for PostgresQueryExecutor<'_> {
             );
         }
 
+        // Scoped, the chain stops at the highest ancestor the reader can see:
+        // an entry tree has a single owner, so that's the root of whatever
+        // grant covers the entry.
         assert!(
-            results.last().unwrap().parent_id.is_none(),
+            self.reader.is_some() || results.last().unwrap().parent_id.is_none(),
             "root must have no parent"
         );
 
@@ -215,18 +306,47 @@ impl QueryExecutor<FindActivityTemplateRoot> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindActivityTemplateRoot,
     ) -> Result<<FindActivityTemplateRoot as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
+            {}SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
             FROM entries
-            WHERE activity_id = $1 AND is_template = true AND parent_id IS NULL
+            WHERE activity_id = $1 AND is_template = true AND parent_id IS NULL AND {}
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.activity_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|e| e.to_entry())
-        .transpose()
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.activity_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|e| e.to_entry())
+            .transpose()
+    }
+}
+
+impl QueryExecutor<FindEntriesByActivity> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindEntriesByActivity,
+    ) -> Result<<FindEntriesByActivity as Query>::Response> {
+        let sql = format!(
+            r#"
+            {}SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
+            FROM entries
+            WHERE activity_id = $1 AND {}
+            "#,
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.activity_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|r| r.to_entry())
+            .collect()
     }
 }
 
@@ -235,18 +355,22 @@ impl QueryExecutor<FindEntryById> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindEntryById,
     ) -> Result<<FindEntryById as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
+            {}SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
             FROM entries
-            WHERE id = $1
+            WHERE id = $1 AND {}
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|e| e.to_entry())
-        .transpose()
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|e| e.to_entry())
+            .transpose()
     }
 }
 
@@ -255,24 +379,31 @@ impl QueryExecutor<FindEntryJoinById> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindEntryJoinById,
     ) -> Result<<FindEntryJoinById as Query>::Response> {
-        let row = sqlx::query_as::<_, crate::rows::EntryJoinRow>(
+        // A hidden activity is left out, as if the entry had none, so the
+        // display name can't fall back to it.
+        let sql = format!(
             r#"
-            SELECT
+            {}SELECT
                 e.id, e.activity_id, e.owner_id, e.name, e.parent_id, e.frac_index,
                 e.is_template, e.display_as_sets, e.is_sequence, e.is_complete,
                 e.start_time, e.end_time, e.duration_ms,
                 a.id as act_id, a.owner_id as act_owner_id,
                 a.source_activity_id as act_source_activity_id,
-                a.name as act_name, a.description as act_description
+                a.name as act_name, a.description as act_description,
+                a.archived as act_archived
             FROM entries e
-            LEFT JOIN activities a ON e.activity_id = a.id
-            WHERE e.id = $1
+            LEFT JOIN activities a ON e.activity_id = a.id AND {}
+            WHERE e.id = $1 AND {}
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_optional(&mut *self.conn)
-        .await
-        .db_err()?;
+            self.with_visible(),
+            self.visible("activity", "a.id"),
+            self.visible("entry", "e.id"),
+        );
+        let row = sqlx::query_as::<_, crate::rows::EntryJoinRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?;
 
         match row {
             None => Ok(None),
@@ -293,25 +424,28 @@ impl QueryExecutor<FindDescendants> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindDescendants,
     ) -> Result<<FindDescendants as Query>::Response> {
-        sqlx::query_as::<sqlx::Postgres, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            WITH RECURSIVE tree AS (
+            WITH RECURSIVE {}tree AS (
                 SELECT * FROM entries e
                 WHERE e.id = $1
                 UNION ALL
                 SELECT c.* FROM entries c
                     INNER JOIN tree ON c.parent_id = tree.id
             )
-            SELECT * FROM tree ORDER BY id
+            SELECT * FROM tree WHERE {} ORDER BY id
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?
-        .into_iter()
-        .map(|e| e.to_entry())
-        .collect()
+            self.scoped_ctes(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<sqlx::Postgres, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|e| e.to_entry())
+            .collect()
     }
 }
 
@@ -322,14 +456,18 @@ impl QueryExecutor<FindAttributeById> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindAttributeById,
     ) -> Result<<FindAttributeById as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributeRow>(
-            "SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE id = $1",
-        )
-        .bind(crate::columns::UuidColumn(query.attribute_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|row| row.to_attribute())
-        .transpose()
+        let sql = format!(
+            "{}SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE id = $1 AND {}",
+            self.with_visible(),
+            self.visible("attribute", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::AttributeRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|row| row.to_attribute())
+            .transpose()
     }
 }
 
@@ -338,15 +476,18 @@ impl QueryExecutor<AllAttributes> for PostgresQueryExecutor<'_> {
         &mut self,
         _query: AllAttributes,
     ) -> Result<<AllAttributes as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributeRow>(
-            "SELECT id, owner_id, name, description, data_type, config FROM attributes",
-        )
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?
-        .into_iter()
-        .map(|row| row.to_attribute())
-        .collect()
+        let sql = format!(
+            "{}SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE {}",
+            self.with_visible(),
+            self.visible("attribute", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::AttributeRow>(&sql)
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_attribute())
+            .collect()
     }
 }
 
@@ -355,15 +496,19 @@ impl QueryExecutor<FindAttributesByOwner> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindAttributesByOwner,
     ) -> Result<<FindAttributesByOwner as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributeRow>(
-            "SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE owner_id = $1",
-        )
-        .bind(crate::columns::UuidColumn(query.owner_id))
-        .fetch_all(&mut *self.conn)
-        .await.db_err()?
-        .into_iter()
-        .map(|row| row.to_attribute())
-        .collect()
+        let sql = format!(
+            "{}SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE owner_id = $1 AND {}",
+            self.with_visible(),
+            self.visible("attribute", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::AttributeRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.owner_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_attribute())
+            .collect()
     }
 }
 
@@ -374,15 +519,19 @@ impl QueryExecutor<FindValueByKey> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindValueByKey,
     ) -> Result<<FindValueByKey as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::ValueRow>(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = $1 AND attribute_id = $2",
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .bind(crate::columns::UuidColumn(query.attribute_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|row| row.to_value())
-        .transpose()
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = $1 AND attribute_id = $2 AND {}",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        );
+        sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|row| row.to_value())
+            .transpose()
     }
 }
 
@@ -391,15 +540,19 @@ impl QueryExecutor<FindValuesForEntry> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindValuesForEntry,
     ) -> Result<<FindValuesForEntry as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::ValueRow>(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = $1",
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_all(&mut *self.conn)
-        .await.db_err()?
-        .into_iter()
-        .map(|row| row.to_value())
-        .collect()
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = $1 AND {}",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        );
+        sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect()
     }
 }
 
@@ -408,18 +561,43 @@ impl QueryExecutor<FindValuesForEntries> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindValuesForEntries,
     ) -> Result<<FindValuesForEntries as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::ValueRow>(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = ANY($1)",
-        )
-        // ANY($1) needs Vec<Uuid> (Postgres ARRAY); UuidColumn would need a
-        // PgHasArrayType impl to wrap here. Leave as raw Uuid; the read-side
-        // decoding into ValueRow still goes through UuidColumn.
-        .bind(&query.entry_ids[..])
-        .fetch_all(&mut *self.conn)
-        .await.db_err()?
-        .into_iter()
-        .map(|row| row.to_value())
-        .collect()
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = ANY($1) AND {}",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        );
+        sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            // ANY($1) needs Vec<Uuid> (Postgres ARRAY); UuidColumn would need a
+            // PgHasArrayType impl to wrap here. Leave as raw Uuid; the read-side
+            // decoding into ValueRow still goes through UuidColumn.
+            .bind(&query.entry_ids[..])
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect()
+    }
+}
+
+impl QueryExecutor<FindValuesForAttribute> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindValuesForAttribute,
+    ) -> Result<<FindValuesForAttribute as Query>::Response> {
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE attribute_id = $1 AND {}",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        );
+        sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect()
     }
 }
 
@@ -428,9 +606,9 @@ impl QueryExecutor<FindAttributePairsForEntry> for PostgresQueryExecutor<'_> {
         &mut self,
         query: FindAttributePairsForEntry,
     ) -> Result<<FindAttributePairsForEntry as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributePairRow>(
+        let sql = format!(
             r#"
-            SELECT
+            {}SELECT
                 a.id as attr_id, a.owner_id as attr_owner_id,
                 a.name as attr_name, a.description as attr_description,
                 a.data_type as attr_data_type,
@@ -439,16 +617,21 @@ impl QueryExecutor<FindAttributePairsForEntry> for PostgresQueryExecutor<'_> {
                 v.index_float, v.index_string
             FROM attribute_values v
             INNER JOIN attributes a ON v.attribute_id = a.id
-            WHERE v.entry_id = $1
+            WHERE v.entry_id = $1 AND {} AND {}
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?
-        .into_iter()
-        .map(|row| row.to_attribute_pair())
-        .collect()
+            self.with_visible(),
+            self.visible("entry", "v.entry_id"),
+            self.visible("attribute", "a.id"),
+        );
+        let pairs = sqlx::query_as::<_, crate::rows::AttributePairRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_attribute_pair())
+            .collect::<Result<Vec<_>>>()?;
+        evaluate_derived_pairs(self, query.entry_id, pairs).await
     }
 }
 
@@ -457,17 +640,548 @@ impl QueryExecutor<DistinctTextValuesForAttribute> for PostgresQueryExecutor<'_>
         &mut self,
         query: DistinctTextValuesForAttribute,
     ) -> Result<<DistinctTextValuesForAttribute as Query>::Response> {
-        let values = sqlx::query_as::<_, crate::rows::ValueRow>(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE attribute_id = $1",
+        // A shared attribute (the system's, or one granted to the reader) can
+        // hold values on entries the reader can't see; only theirs count.
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE attribute_id = $1 AND {} AND {}",
+            self.with_visible(),
+            self.visible("attribute", "attribute_id"),
+            self.visible("entry", "entry_id"),
+        );
+        let values = sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(distinct_text_values(&values))
+    }
+}
+
+// --- Analytics ---
+
+impl QueryExecutor<RunAnalytics> for PostgresQueryExecutor<'_> {
+    /// Reads only the entries that may match, with their ancestors: the
+    /// filters `AnalyticsQuery::pushdown` allows run in SQL, and `evaluate`
+    /// checks the whole query over what's read.
+    async fn execute(&mut self, query: RunAnalytics) -> Result<<RunAnalytics as Query>::Response> {
+        let query = query.query;
+        query.validate()?;
+        let attributes = find_attributes(self, query.attribute_ids().collect()).await?;
+        let pushdown = query.pushdown(&attributes);
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let attribute_ids: Vec<Uuid> = attributes.iter().map(|a| a.id).collect();
+        let values = self
+            .pushdown_values(&pushdown, Some(&attribute_ids))
+            .await?;
+        Ok(analytics::evaluate(&query, attributes, entries, values))
+    }
+}
+
+impl PostgresQueryExecutor<'_> {
+    /// The entries `pushdown` loads (see `analytics_entries`).
+    async fn pushdown_entries(&mut self, pushdown: &Pushdown) -> Result<Vec<Entry>> {
+        let mut builder = analytics_entries(pushdown, self.reader);
+        builder.push(" SELECT * FROM entries WHERE id IN (SELECT id FROM loaded)");
+        builder
+            .build_query_as::<crate::rows::EntryRow>()
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|r| r.to_entry())
+            .collect()
+    }
+
+    /// The values on the entries `pushdown` loads: those of `attribute_ids`,
+    /// or of every attribute when `None`.
+    async fn pushdown_values(
+        &mut self,
+        pushdown: &Pushdown,
+        attribute_ids: Option<&[Uuid]>,
+    ) -> Result<Vec<Value>> {
+        if attribute_ids.is_some_and(|ids| ids.is_empty()) {
+            return Ok(vec![]);
+        }
+        let mut builder = analytics_entries(pushdown, self.reader);
+        builder.push(
+            " SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id IN (SELECT id FROM loaded)",
+        );
+        if let Some(attribute_ids) = attribute_ids {
+            builder.push(" AND attribute_id IN (");
+            let mut separated = builder.separated(", ");
+            for attribute_id in attribute_ids {
+                separated.push_bind(crate::columns::UuidColumn(*attribute_id));
+            }
+            builder.push(")");
+        }
+        builder
+            .build_query_as::<crate::rows::ValueRow>()
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect()
+    }
+}
+
+/// `WITH` clauses ending in `loaded`: the non-template entries `pushdown`
+/// admits, their ancestors, and their descendants if it reads them. `timed`
+/// carries each entry's own time columns, or its nearest timed ancestor's.
+/// With a `reader`, only the entries they can see: a tree has one owner, so
+/// the visible ancestors of a visible entry stop at the root of its grant.
+/// Strings compare bytewise (`COLLATE "C"`), as they do in `evaluate`.
+fn analytics_entries(
+    pushdown: &Pushdown,
+    reader: Option<Uuid>,
+) -> sqlx::QueryBuilder<'static, sqlx::Postgres> {
+    let visible = reader
+        .map(|actor_id| format!("{}, ", visible_ctes(actor_id)))
+        .unwrap_or_default();
+    let mut builder = sqlx::QueryBuilder::new(format!(
+        r#"
+        WITH RECURSIVE {visible}base AS (
+            SELECT e.id FROM entries e WHERE e.is_template = "#
+    ));
+    builder.push_bind(false);
+    if reader.is_some() {
+        builder.push(" AND e.id IN (SELECT id FROM visible WHERE kind = 'entry')");
+    }
+    if let Some(owner_id) = pushdown.owner_id {
+        builder.push(" AND e.owner_id = ");
+        builder.push_bind(crate::columns::UuidColumn(owner_id));
+    }
+    if pushdown.top_level {
+        builder.push(" AND e.parent_id IS NULL");
+    }
+    for activity_id in &pushdown.activity_ids {
+        builder.push(" AND e.activity_id = ");
+        builder.push_bind(crate::columns::UuidColumn(*activity_id));
+    }
+    for complete in &pushdown.complete {
+        builder.push(" AND e.is_complete = ");
+        builder.push_bind(*complete);
+    }
+    for predicate in &pushdown.predicates {
+        if predicate.float.is_none() && predicate.string.is_none() {
+            builder.push(" AND 1 = 0");
+            continue;
+        }
+        let op = sql_operator(&predicate.comparison);
+        builder.push(
+            " AND EXISTS (SELECT 1 FROM attribute_values v WHERE v.entry_id = e.id AND v.attribute_id = ",
+        );
+        builder.push_bind(crate::columns::UuidColumn(predicate.attribute_id));
+        builder.push(" AND CASE");
+        if let Some(float) = predicate.float {
+            builder.push(format!(
+                " WHEN v.index_float IS NOT NULL THEN v.index_float {op} "
+            ));
+            builder.push_bind(float);
+        }
+        if let Some(string) = &predicate.string {
+            builder.push(format!(
+                " WHEN v.index_string IS NOT NULL THEN v.index_string COLLATE \"C\" {op} "
+            ));
+            builder.push_bind(string.clone());
+        }
+        builder.push(" END)");
+    }
+    let visible_parent = match reader {
+        Some(_) => " AND a.parent_id IN (SELECT id FROM visible WHERE kind = 'entry')",
+        None => "",
+    };
+    // Each base entry with itself and each of its ancestors, for the
+    // windows: they hold when the entry or any ancestor is timed in them.
+    builder.push(format!(
+        r#"
+        ),
+        lineage AS (
+            SELECT id, id AS ancestor_id FROM base
+            UNION ALL
+            SELECT l.id, a.parent_id FROM lineage l
+                INNER JOIN entries a ON a.id = l.ancestor_id
+            WHERE a.parent_id IS NOT NULL{visible_parent}
+        ),
+        candidates AS (
+            SELECT id FROM base WHERE 1 = 1"#
+    ));
+    for (from, to) in &pushdown.windows {
+        // An entry's instant is its start, or its end less any duration.
+        builder.push(
+            " AND id IN (SELECT l.id FROM lineage l INNER JOIN entries a ON a.id = l.ancestor_id WHERE COALESCE(a.start_time, a.end_time - COALESCE(a.duration_ms, 0) * interval '1 millisecond') >= ",
+        );
+        builder.push_bind(crate::columns::DateTimeColumn(*from));
+        builder.push(
+            " AND COALESCE(a.start_time, a.end_time - COALESCE(a.duration_ms, 0) * interval '1 millisecond') < ",
+        );
+        builder.push_bind(crate::columns::DateTimeColumn(*to));
+        builder.push(")");
+    }
+    builder.push(format!(
+        r#"
+        ),
+        ancestors AS (
+            SELECT id, parent_id FROM entries WHERE id IN (SELECT id FROM candidates)
+            UNION
+            SELECT e.id, e.parent_id FROM entries e
+                INNER JOIN ancestors a ON e.id = a.parent_id{visible_parent}
+        ),
+        descendants AS (
+            SELECT id FROM candidates
+            UNION
+            SELECT e.id FROM entries e
+                INNER JOIN descendants d ON e.parent_id = d.id
+        ),
+        loaded AS (
+            SELECT id FROM ancestors"#
+    ));
+    if pushdown.descendants {
+        builder.push(" UNION SELECT id FROM descendants");
+    }
+    builder.push(")");
+    builder
+}
+
+fn sql_operator(comparison: &Comparison) -> &'static str {
+    match comparison {
+        Comparison::Eq => "=",
+        Comparison::Ne => "<>",
+        Comparison::Lt => "<",
+        Comparison::Le => "<=",
+        Comparison::Gt => ">",
+        Comparison::Ge => ">=",
+    }
+}
+
+impl QueryExecutor<PersonalRecords> for PostgresQueryExecutor<'_> {
+    /// Reads the owner's entries of the activity, with their ancestors, and
+    /// the values on them.
+    async fn execute(
+        &mut self,
+        query: PersonalRecords,
+    ) -> Result<<PersonalRecords as Query>::Response> {
+        let pushdown = query.pushdown();
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let values = self.pushdown_values(&pushdown, None).await?;
+        let attribute_ids = values.iter().map(|v| v.attribute_id).unique().collect();
+        let attributes = find_attributes(self, attribute_ids).await?;
+        Ok(records::personal_records(
+            query.owner_id,
+            query.activity_id,
+            attributes,
+            entries,
+            values,
+        ))
+    }
+}
+
+impl QueryExecutor<TrainingLoad> for PostgresQueryExecutor<'_> {
+    /// Reads the owner's sessions around the window, the entries under them
+    /// and their RPE values.
+    async fn execute(&mut self, query: TrainingLoad) -> Result<<TrainingLoad as Query>::Response> {
+        query.window.validate()?;
+        let pushdown = query.pushdown();
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let values = self
+            .pushdown_values(&pushdown, Some(&[query.rpe_attribute_id]))
+            .await?;
+        Ok(training_load::training_load(
+            query.owner_id,
+            query.rpe_attribute_id,
+            &query.window,
+            entries,
+            values,
+        ))
+    }
+}
+
+impl QueryExecutor<AttributeSeries> for PostgresQueryExecutor<'_> {
+    /// Reads the activity's entries in the range, with their ancestors, and
+    /// the attribute's values on them.
+    async fn execute(
+        &mut self,
+        query: AttributeSeries,
+    ) -> Result<<AttributeSeries as Query>::Response> {
+        query.validate()?;
+        let attributes = find_attributes(self, vec![query.attribute_id]).await?;
+        let pushdown = query.pushdown(&attributes);
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let attribute_ids: Vec<Uuid> = attributes.iter().map(|a| a.id).collect();
+        let values = self
+            .pushdown_values(&pushdown, Some(&attribute_ids))
+            .await?;
+        series::attribute_series(&query, attributes, entries, values)
+    }
+}
+
+// --- Grant ---
+
+impl QueryExecutor<FindGrantById> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindGrantById,
+    ) -> Result<<FindGrantById as Query>::Response> {
+        let sql = format!(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants WHERE id = $1 AND {}",
+            self.party()
+        );
+        sqlx::query_as::<_, crate::rows::GrantRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.grant_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|row| row.to_grant())
+            .transpose()
+    }
+}
+
+impl QueryExecutor<FindGrantsForGrantee> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindGrantsForGrantee,
+    ) -> Result<<FindGrantsForGrantee as Query>::Response> {
+        let sql = format!(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants WHERE grantee_id = $1 AND {}",
+            self.party()
+        );
+        sqlx::query_as::<_, crate::rows::GrantRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.grantee_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_grant())
+            .collect()
+    }
+}
+
+impl QueryExecutor<FindGrantsByGrantor> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindGrantsByGrantor,
+    ) -> Result<<FindGrantsByGrantor as Query>::Response> {
+        let sql = format!(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants WHERE grantor_id = $1 AND {}",
+            self.party()
+        );
+        sqlx::query_as::<_, crate::rows::GrantRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.grantor_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_grant())
+            .collect()
+    }
+}
+
+// --- Read scope ---
+
+impl QueryExecutor<VisibleTo> for PostgresQueryExecutor<'_> {
+    async fn execute(&mut self, query: VisibleTo) -> Result<<VisibleTo as Query>::Response> {
+        let sql = format!(
+            "WITH RECURSIVE {} SELECT kind, id FROM visible",
+            visible_ctes(query.actor_id)
+        );
+        let rows = sqlx::query_as::<_, (String, crate::columns::UuidColumn)>(&sql)
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
+
+        let mut visibility = Visibility::default();
+        for (kind, id) in rows {
+            let ids = match kind.as_str() {
+                "entry" => &mut visibility.entries,
+                "activity" => &mut visibility.activities,
+                "attribute" => &mut visibility.attributes,
+                _ => unreachable!("VisibleTo only selects known kinds"),
+            };
+            ids.insert(id.0);
+        }
+        Ok(visibility)
+    }
+}
+
+/// `WITH RECURSIVE` clauses ending in `visible(kind, id)`: the rows `actor_id`
+/// can read. The same rule as `gv_core::permissions::can_read`, for every row
+/// at once: owned or system-owned rows, plus what accepted grants from the
+/// row's owner cover. The ids are inlined so the clauses splice into queries
+/// that bind their own parameters.
+fn visible_ctes(actor_id: Uuid) -> String {
+    let actor = uuid_literal(actor_id);
+    let system = uuid_literal(gv_core::SYSTEM_ACTOR_ID);
+    format!(
+        r#"
+            accepted AS (
+                SELECT scope_kind, scope_id, grantor_id FROM grants
+                WHERE grantee_id = {actor} AND accepted
+            ),
+            -- Entries reached through a grant, tagged with what the grant was
+            -- on: an entry's subtree, or an activity's template tree.
+            shared(id, via) AS (
+                SELECT e.id, 'entry' FROM entries e
+                    INNER JOIN accepted g ON g.scope_kind = 'entry'
+                        AND g.scope_id = e.id AND g.grantor_id = e.owner_id
+                UNION
+                SELECT e.id, 'activity' FROM entries e
+                    INNER JOIN accepted g ON g.scope_kind = 'activity'
+                        AND g.scope_id = e.activity_id AND g.grantor_id = e.owner_id
+                    WHERE e.is_template AND e.parent_id IS NULL
+                UNION
+                SELECT c.id, s.via FROM entries c
+                    INNER JOIN shared s ON c.parent_id = s.id
+            ),
+            visible(kind, id) AS (
+                SELECT 'entry', id FROM entries WHERE owner_id IN ({actor}, {system})
+                UNION
+                SELECT 'entry', id FROM shared
+                UNION
+                SELECT 'activity', id FROM activities WHERE owner_id IN ({actor}, {system})
+                UNION
+                SELECT 'activity', a.id FROM accepted g
+                    INNER JOIN activities a ON g.scope_kind = 'activity'
+                        AND a.id = g.scope_id AND a.owner_id = g.grantor_id
+                UNION
+                SELECT 'activity', a.id FROM shared s
+                    INNER JOIN entries e ON e.id = s.id
+                    INNER JOIN activities a ON a.id = e.activity_id AND a.owner_id = e.owner_id
+                    WHERE s.via = 'entry'
+                UNION
+                SELECT 'attribute', id FROM attributes WHERE owner_id IN ({actor}, {system})
+                UNION
+                SELECT 'attribute', a.id FROM accepted g
+                    INNER JOIN attributes a ON g.scope_kind = 'attribute'
+                        AND a.id = g.scope_id AND a.owner_id = g.grantor_id
+                UNION
+                SELECT 'attribute', a.id FROM shared s
+                    INNER JOIN entries e ON e.id = s.id
+                    INNER JOIN attribute_values v ON v.entry_id = s.id
+                    INNER JOIN attributes a ON a.id = v.attribute_id AND a.owner_id = e.owner_id
+            )
+            "#
+    )
+}
+
+/// `id` as a SQL literal, for ids spliced into generated SQL.
+fn uuid_literal(id: Uuid) -> String {
+    format!("'{id}'::uuid")
+}
+
+// --- Mutation log ---
+
+impl QueryExecutor<MutationsSince> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: MutationsSince,
+    ) -> Result<<MutationsSince as Query>::Response> {
+        let rows = sqlx::query_as::<_, crate::rows::CommittedMutationRow>(
+            r#"
+            SELECT seq_num, id, actor_id, action, timestamp
+            FROM mutations
+            WHERE seq_num > $1
+            ORDER BY seq_num
+            LIMIT $2
+            "#,
         )
-        .bind(crate::columns::UuidColumn(query.attribute_id))
+        .bind(query.after_seq_num)
+        .bind(i64::from(query.limit))
+        .fetch_all(&mut *self.conn)
+        .await
+        .db_err()?;
+        let Some(last_seq_num) = rows.last().map(|r| r.seq_num) else {
+            return Ok(vec![]);
+        };
+
+        // The fetched mutations are exactly the seq_nums in
+        // (after_seq_num, last_seq_num], so their deltas are one range scan.
+        let mut changes: HashMap<i64, Vec<AnyDelta>> = HashMap::new();
+        let deltas = sqlx::query_as::<_, crate::rows::StampedDeltaRow>(
+            r#"
+            SELECT seq_num, op_offset, table_name, delta
+            FROM mutation_deltas
+            WHERE seq_num > $1 AND seq_num <= $2
+            ORDER BY seq_num, op_offset
+            "#,
+        )
+        .bind(query.after_seq_num)
+        .bind(last_seq_num)
+        .fetch_all(&mut *self.conn)
+        .await
+        .db_err()?;
+        for row in deltas {
+            let stamped = row.to_stamped_delta()?;
+            changes
+                .entry(stamped.offset.seq_num)
+                .or_default()
+                .push(stamped.delta);
+        }
+
+        rows.into_iter()
+            .map(|row| {
+                let changes = changes.remove(&row.seq_num).unwrap_or_default();
+                row.into_committed_mutation(changes)
+            })
+            .collect()
+    }
+}
+
+impl QueryExecutor<LatestSeqNum> for PostgresQueryExecutor<'_> {
+    async fn execute(&mut self, _query: LatestSeqNum) -> Result<<LatestSeqNum as Query>::Response> {
+        sqlx::query_scalar("SELECT seq_num FROM mutation_seq WHERE id")
+            .fetch_one(&mut *self.conn)
+            .await
+            .db_err()
+    }
+}
+
+impl QueryExecutor<FindSeqNumByMutationId> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindSeqNumByMutationId,
+    ) -> Result<<FindSeqNumByMutationId as Query>::Response> {
+        sqlx::query_scalar("SELECT seq_num FROM mutations WHERE id = $1")
+            .bind(crate::columns::UuidColumn(query.mutation_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()
+    }
+}
+
+impl QueryExecutor<FindMutationById> for PostgresQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindMutationById,
+    ) -> Result<<FindMutationById as Query>::Response> {
+        let row = sqlx::query_as::<_, crate::rows::CommittedMutationRow>(
+            "SELECT seq_num, id, actor_id, action, timestamp FROM mutations WHERE id = $1",
+        )
+        .bind(crate::columns::UuidColumn(query.mutation_id))
+        .fetch_optional(&mut *self.conn)
+        .await
+        .db_err()?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let changes = sqlx::query_as::<_, crate::rows::StampedDeltaRow>(
+            r#"
+            SELECT seq_num, op_offset, table_name, delta
+            FROM mutation_deltas
+            WHERE seq_num = $1
+            ORDER BY op_offset
+            "#,
+        )
+        .bind(row.seq_num)
         .fetch_all(&mut *self.conn)
         .await
         .db_err()?
         .into_iter()
-        .map(|row| row.to_value())
+        .map(|row| row.to_stamped_delta().map(|stamped| stamped.delta))
         .collect::<Result<Vec<_>>>()?;
-        Ok(distinct_text_values(&values))
+        Ok(Some(row.into_committed_mutation(changes)?.mutation))
     }
 }
 
@@ -503,7 +1217,7 @@ impl QueryExecutor<SnapshotAll> for PostgresQueryExecutor<'_> {
         .collect();
 
         let activities = sqlx::query_as::<_, crate::rows::ActivityRow>(
-            "SELECT id, owner_id, source_activity_id, name, description FROM activities",
+            "SELECT id, owner_id, source_activity_id, name, description, archived FROM activities",
         )
         .fetch_all(&mut *tx)
         .await
@@ -540,6 +1254,16 @@ impl QueryExecutor<SnapshotAll> for PostgresQueryExecutor<'_> {
         .map(|r| r.to_value())
         .collect::<Result<Vec<_>>>()?;
 
+        let grants = sqlx::query_as::<_, crate::rows::GrantRow>(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .db_err()?
+        .into_iter()
+        .map(|r| r.to_grant())
+        .collect::<Result<Vec<_>>>()?;
+
         tx.commit().await.db_err()?;
 
         Ok(Snapshot {
@@ -549,6 +1273,7 @@ impl QueryExecutor<SnapshotAll> for PostgresQueryExecutor<'_> {
             attributes,
             entries,
             values,
+            grants,
         })
     }
 }
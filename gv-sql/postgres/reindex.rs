@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use gv_core::{
+    delta::Delta,
+    delta_executor::DeltaExecutor,
+    error::{DbErr, Result},
+    queries::FindAttributeById,
+    query_executor::QueryExecutor,
+};
+use itertools::Itertools;
+use sqlx::PgConnection;
+
+use super::{PostgresDeltaExecutor, PostgresQueryExecutor};
+
+/// Derive the index columns of values written before they were indexed.
+/// The migration only adds the columns' indexes, so this runs at startup,
+/// after migrations. A value whose columns are already set, or whose actual
+/// doesn't index (multiselect, cleared), is left as is. Returns the number of
+/// values reindexed.
+pub async fn reindex_values(conn: &mut PgConnection) -> Result<u64> {
+    let stale = sqlx::query_as::<_, crate::rows::ValueRow>(
+        "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE actual IS NOT NULL AND index_float IS NULL AND index_string IS NULL",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .db_err()?
+    .into_iter()
+    .map(|row| row.to_value())
+    .collect::<Result<Vec<_>>>()?;
+
+    let mut attributes = HashMap::new();
+    for attribute_id in stale.iter().map(|v| v.attribute_id).unique() {
+        let attribute = PostgresQueryExecutor::new(conn)
+            .execute(FindAttributeById { attribute_id })
+            .await?;
+        attributes.insert(attribute_id, attribute);
+    }
+    let mut reindexed = 0;
+    for value in stale {
+        let Some(attribute) = &attributes[&value.attribute_id] else {
+            continue;
+        };
+        let indexed = attribute.indexed(value.clone());
+        if indexed == value {
+            continue;
+        }
+        PostgresDeltaExecutor::new(conn)
+            .apply_delta(Delta::Update {
+                old: value,
+                new: indexed,
+            })
+            .await?;
+        reindexed += 1;
+    }
+    Ok(reindexed)
+}
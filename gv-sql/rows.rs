@@ -13,6 +13,8 @@
 use sqlx::FromRow;
 
 use gv_core::{
+    actions::Action,
+    delta::AnyDelta,
     error::{DomainError, RejectReason, Result},
     models::{
         activity::Activity,
@@ -21,8 +23,12 @@ use gv_core::{
         attribute_pair::AttributePair,
         entry::{Entry, Position, Temporal},
         entry_join::EntryJoin,
+        grant::{Grant, GrantLevel, GrantScope},
         user::User,
     },
+    mutation_log::{CommittedMutation, LoggedMutation, MutationState, StampedDelta, SyncOffset},
+    mutators::Mutation,
+    wire,
 };
 
 use crate::columns::{
@@ -106,6 +112,7 @@ pub struct ActivityRow {
     pub source_activity_id: Option<UuidColumn>,
     pub name: ActivityNameColumn,
     pub description: Option<String>,
+    pub archived: bool,
 }
 
 impl From<Activity> for ActivityRow {
@@ -116,6 +123,7 @@ impl From<Activity> for ActivityRow {
             source_activity_id: a.source_activity_id.map(UuidColumn),
             name: ActivityNameColumn(a.name),
             description: a.description,
+            archived: a.archived,
         }
     }
 }
@@ -128,6 +136,7 @@ impl From<ActivityRow> for Activity {
             source_activity_id: row.source_activity_id.map(|c| c.0),
             name: row.name.0,
             description: row.description,
+            archived: row.archived,
         }
     }
 }
@@ -309,6 +318,188 @@ impl ValueRow {
     }
 }
 
+// --- Grant ---
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct GrantRow {
+    pub id: UuidColumn,
+    pub grantor_id: UuidColumn,
+    pub grantee_id: UuidColumn,
+    pub scope_kind: String,
+    pub scope_id: UuidColumn,
+    pub level: String,
+    pub accepted: bool,
+}
+
+impl From<&Grant> for GrantRow {
+    fn from(grant: &Grant) -> Self {
+        GrantRow {
+            id: UuidColumn(grant.id),
+            grantor_id: UuidColumn(grant.grantor_id),
+            grantee_id: UuidColumn(grant.grantee_id),
+            scope_kind: grant.scope.kind().to_string(),
+            scope_id: UuidColumn(grant.scope.id()),
+            level: grant.level.to_string(),
+            accepted: grant.accepted,
+        }
+    }
+}
+
+impl GrantRow {
+    pub fn to_grant(self) -> Result<Grant> {
+        Ok(Grant {
+            id: self.id.0,
+            grantor_id: self.grantor_id.0,
+            grantee_id: self.grantee_id.0,
+            scope: GrantScope::parse(&self.scope_kind, self.scope_id.0)?,
+            level: GrantLevel::parse(&self.level)?,
+            accepted: self.accepted,
+        })
+    }
+}
+
+// --- Mutation (client-local mutation log) ---
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct MutationRow {
+    pub seq: i64,
+    pub id: UuidColumn,
+    pub actor_id: UuidColumn,
+    pub action: String,  // JSON as TEXT
+    pub changes: String, // JSON as TEXT
+    pub timestamp: DateTimeColumn,
+    pub state: String,
+    pub server_seq_num: Option<i64>,
+}
+
+impl MutationRow {
+    pub fn from_logged_mutation(logged: &LoggedMutation) -> Result<Self> {
+        let mutation = &logged.mutation;
+        Ok(MutationRow {
+            seq: logged.seq,
+            id: UuidColumn(mutation.id),
+            actor_id: UuidColumn(mutation.action.actor_id()),
+            action: wire::encode(&mutation.action)
+                .map_err(|e| DomainError::Database(Box::new(e)))?,
+            changes: wire::encode(&mutation.changes)
+                .map_err(|e| DomainError::Database(Box::new(e)))?,
+            timestamp: DateTimeColumn(mutation.timestamp),
+            state: logged.state.to_string(),
+            server_seq_num: logged.server_seq_num,
+        })
+    }
+
+    pub fn to_logged_mutation(self) -> Result<LoggedMutation> {
+        let action: Action =
+            wire::decode(&self.action).map_err(|e| DomainError::Database(Box::new(e)))?;
+        let changes: Vec<AnyDelta> =
+            wire::decode(&self.changes).map_err(|e| DomainError::Database(Box::new(e)))?;
+        Ok(LoggedMutation {
+            seq: self.seq,
+            state: MutationState::parse(&self.state)?,
+            server_seq_num: self.server_seq_num,
+            mutation: Mutation {
+                id: self.id.0,
+                timestamp: self.timestamp.0,
+                action,
+                changes,
+            },
+        })
+    }
+}
+
+// --- CommittedMutation (server mutation log) ---
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct CommittedMutationRow {
+    pub seq_num: i64,
+    pub id: UuidColumn,
+    pub actor_id: UuidColumn,
+    pub action: String, // JSON as TEXT
+    pub timestamp: DateTimeColumn,
+}
+
+impl CommittedMutationRow {
+    /// The `mutations` row for a committed mutation. Its deltas are stored
+    /// separately, one `StampedDeltaRow` each.
+    pub fn from_committed_mutation(committed: &CommittedMutation) -> Result<Self> {
+        let mutation = &committed.mutation;
+        Ok(CommittedMutationRow {
+            seq_num: committed.seq_num,
+            id: UuidColumn(mutation.id),
+            actor_id: UuidColumn(mutation.action.actor_id()),
+            action: wire::encode(&mutation.action)
+                .map_err(|e| DomainError::Database(Box::new(e)))?,
+            timestamp: DateTimeColumn(mutation.timestamp),
+        })
+    }
+
+    /// Reassemble the mutation from this row and its deltas. Caller fetches
+    /// `changes` separately and passes them in `op_offset` order.
+    pub fn into_committed_mutation(self, changes: Vec<AnyDelta>) -> Result<CommittedMutation> {
+        let action: Action =
+            wire::decode(&self.action).map_err(|e| DomainError::Database(Box::new(e)))?;
+        Ok(CommittedMutation {
+            seq_num: self.seq_num,
+            mutation: Mutation {
+                id: self.id.0,
+                timestamp: self.timestamp.0,
+                action,
+                changes,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StampedDeltaRow {
+    pub seq_num: i64,
+    pub op_offset: i32,
+    pub table_name: String,
+    pub delta: String, // JSON as TEXT
+}
+
+impl StampedDeltaRow {
+    pub fn from_stamped_delta(stamped: &StampedDelta) -> Result<Self> {
+        let op_offset = i32::try_from(stamped.offset.op_offset)
+            .map_err(|e| DomainError::Database(Box::new(e)))?;
+        Ok(StampedDeltaRow {
+            seq_num: stamped.offset.seq_num,
+            op_offset,
+            table_name: delta_table_name(&stamped.delta).to_string(),
+            delta: wire::encode(&stamped.delta).map_err(|e| DomainError::Database(Box::new(e)))?,
+        })
+    }
+
+    pub fn to_stamped_delta(self) -> Result<StampedDelta> {
+        let op_offset =
+            u32::try_from(self.op_offset).map_err(|e| DomainError::Database(Box::new(e)))?;
+        let delta: AnyDelta =
+            wire::decode(&self.delta).map_err(|e| DomainError::Database(Box::new(e)))?;
+        Ok(StampedDelta {
+            offset: SyncOffset {
+                seq_num: self.seq_num,
+                op_offset,
+            },
+            delta,
+        })
+    }
+}
+
+/// The table a delta writes to, stored alongside it so the log can be
+/// filtered per table without decoding the JSON.
+fn delta_table_name(delta: &AnyDelta) -> &'static str {
+    match delta {
+        AnyDelta::User(_) => "users",
+        AnyDelta::Actor(_) => "actors",
+        AnyDelta::Activity(_) => "activities",
+        AnyDelta::Entry(_) => "entries",
+        AnyDelta::Attribute(_) => "attributes",
+        AnyDelta::Value(_) => "attribute_values",
+        AnyDelta::Grant(_) => "grants",
+    }
+}
+
 // --- AttributePair (read-only join: attributes JOIN values) ---
 
 #[derive(Debug, Clone, FromRow)]
@@ -388,6 +579,8 @@ pub struct EntryJoinRow {
     pub act_name: Option<ActivityNameColumn>,
     #[sqlx(rename = "act_description")]
     pub act_description: Option<String>,
+    #[sqlx(rename = "act_archived")]
+    pub act_archived: Option<bool>,
 }
 
 impl EntryJoinRow {
@@ -405,6 +598,7 @@ impl EntryJoinRow {
                 source_activity_id: self.act_source_activity_id.map(|c| c.0),
                 name: self.act_name.expect("act_name present when act_id is").0,
                 description: self.act_description,
+                archived: self.act_archived.unwrap_or(false),
             }),
             None => None,
         };
@@ -7,6 +7,7 @@ use gv_core::{
         actor::Actor,
         attribute::{Attribute, Value},
         entry::Entry,
+        grant::Grant,
         user::User,
     },
 };
@@ -32,6 +33,7 @@ impl AnyDeltaExecutor for SqliteDeltaExecutor<'_> {
             AnyDelta::Entry(delta) => self.apply_delta(delta).await,
             AnyDelta::Attribute(delta) => self.apply_delta(delta).await,
             AnyDelta::Value(delta) => self.apply_delta(delta).await,
+            AnyDelta::Grant(delta) => self.apply_delta(delta).await,
         }
     }
 }
@@ -106,23 +108,25 @@ impl DeltaExecutor<Activity> for SqliteDeltaExecutor<'_> {
         match delta {
             Delta::Insert { new } => {
                 let row = crate::rows::ActivityRow::from(new);
-                sqlx::query("INSERT INTO activities (id, owner_id, source_activity_id, name, description) VALUES (?, ?, ?, ?, ?)")
+                sqlx::query("INSERT INTO activities (id, owner_id, source_activity_id, name, description, archived) VALUES (?, ?, ?, ?, ?, ?)")
                     .bind(row.id)
                     .bind(row.owner_id)
                     .bind(row.source_activity_id)
                     .bind(row.name)
                     .bind(row.description)
+                    .bind(row.archived)
                     .execute(&mut *self.conn)
                     .await.sql_err()?;
             }
             Delta::Update { old, new } => {
                 assert_eq!(old.id, new.id, "update must not mutate primary key");
                 let row = crate::rows::ActivityRow::from(new);
-                sqlx::query("UPDATE activities SET owner_id = ?, source_activity_id = ?, name = ?, description = ? WHERE id = ?")
+                sqlx::query("UPDATE activities SET owner_id = ?, source_activity_id = ?, name = ?, description = ?, archived = ? WHERE id = ?")
                     .bind(row.owner_id)
                     .bind(row.source_activity_id)
                     .bind(row.name)
                     .bind(row.description)
+                    .bind(row.archived)
                     .bind(row.id)
                     .execute(&mut *self.conn)
                     .await.sql_err()?;
@@ -327,3 +331,58 @@ impl DeltaExecutor<Value> for SqliteDeltaExecutor<'_> {
         Ok(())
     }
 }
+
+impl DeltaExecutor<Grant> for SqliteDeltaExecutor<'_> {
+    async fn apply_delta(&mut self, delta: Delta<Grant>) -> Result<()> {
+        match delta {
+            Delta::Insert { new } => {
+                let row = crate::rows::GrantRow::from(&new);
+                sqlx::query(
+                    r#"
+                    INSERT INTO grants (id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(row.id)
+                .bind(row.grantor_id)
+                .bind(row.grantee_id)
+                .bind(row.scope_kind)
+                .bind(row.scope_id)
+                .bind(row.level)
+                .bind(row.accepted)
+                .execute(&mut *self.conn)
+                .await
+                .sql_err()?;
+            }
+            Delta::Update { old, new } => {
+                assert_eq!(old.id, new.id, "update must not mutate primary key");
+                let row = crate::rows::GrantRow::from(&new);
+                sqlx::query(
+                    r#"
+                    UPDATE grants
+                    SET grantor_id = ?, grantee_id = ?, scope_kind = ?, scope_id = ?, level = ?, accepted = ?
+                    WHERE id = ?
+                    "#,
+                )
+                .bind(row.grantor_id)
+                .bind(row.grantee_id)
+                .bind(row.scope_kind)
+                .bind(row.scope_id)
+                .bind(row.level)
+                .bind(row.accepted)
+                .bind(row.id)
+                .execute(&mut *self.conn)
+                .await
+                .sql_err()?;
+            }
+            Delta::Delete { old } => {
+                sqlx::query("DELETE FROM grants WHERE id = ?")
+                    .bind(crate::columns::UuidColumn(old.id))
+                    .execute(&mut *self.conn)
+                    .await
+                    .sql_err()?;
+            }
+        };
+        Ok(())
+    }
+}
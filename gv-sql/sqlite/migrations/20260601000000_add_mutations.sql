@@ -0,0 +1,15 @@
+-- Local mutation log. One row per applied action, written in the same
+-- transaction as its deltas. `seq` orders mutations in the order they were
+-- applied on this client.
+
+CREATE TABLE IF NOT EXISTS mutations (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id BLOB UNIQUE NOT NULL,
+    actor_id BLOB NOT NULL,
+    action TEXT NOT NULL,   -- JSON as TEXT
+    changes TEXT NOT NULL,  -- JSON as TEXT
+    timestamp TEXT NOT NULL,
+    state TEXT NOT NULL CHECK (state IN ('pending', 'sent', 'acked', 'rejected'))
+);
+
+CREATE INDEX IF NOT EXISTS mutations_state_idx ON mutations (state, seq);
@@ -0,0 +1,12 @@
+-- Sync bookkeeping for the local mutation log.
+
+-- The global seq_num the server committed a mutation under, once acked.
+ALTER TABLE mutations ADD COLUMN server_seq_num INTEGER;
+
+-- Single row: the latest server seq_num this client has applied.
+CREATE TABLE IF NOT EXISTS sync_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_seen_server_seq INTEGER NOT NULL
+);
+
+INSERT OR IGNORE INTO sync_state (id, last_seen_server_seq) VALUES (1, 0);
@@ -0,0 +1 @@
+ALTER TABLE activities ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
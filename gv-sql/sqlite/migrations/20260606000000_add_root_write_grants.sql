@@ -0,0 +1,21 @@
+-- Root-write grants: a grantee may add root entries to the grantor's log
+-- (`scope_kind` 'log', `scope_id` the grantor). SQLite can't alter a CHECK
+-- constraint, so the table is rebuilt.
+
+CREATE TABLE grants_new (
+    id BLOB PRIMARY KEY,
+    grantor_id BLOB NOT NULL REFERENCES actors(id),
+    grantee_id BLOB NOT NULL REFERENCES actors(id),
+    scope_kind TEXT NOT NULL CHECK (scope_kind IN ('entry', 'activity', 'attribute', 'log')),
+    scope_id BLOB NOT NULL,
+    level TEXT NOT NULL CHECK (level IN ('read', 'write', 'root_write')),
+    accepted INTEGER NOT NULL DEFAULT 0,
+    CHECK (grantor_id <> grantee_id)
+);
+
+INSERT INTO grants_new SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants;
+DROP TABLE grants;
+ALTER TABLE grants_new RENAME TO grants;
+
+CREATE INDEX IF NOT EXISTS grants_grantee_id_idx ON grants (grantee_id);
+CREATE INDEX IF NOT EXISTS grants_grantor_id_idx ON grants (grantor_id);
@@ -1,7 +1,11 @@
 //! SQLite backend. Feature-gated behind `sqlite`.
 
 pub mod delta_executor;
+pub mod mutation_log;
 pub mod query_executor;
+pub mod reindex;
 
 pub use delta_executor::SqliteDeltaExecutor;
+pub use mutation_log::SqliteMutationLog;
 pub use query_executor::SqliteQueryExecutor;
+pub use reindex::reindex_values;
@@ -0,0 +1,100 @@
+use gv_core::{
+    delta::AnyDelta,
+    error::{DomainError, Result},
+    mutation_log::{LoggedMutation, MutationState},
+    mutators::Mutation,
+    wire,
+};
+use sqlx::SqliteConnection;
+use uuid::Uuid;
+
+use crate::{columns::UuidColumn, error::SqlErr, rows::MutationRow};
+
+/// Writes to the client-local `mutations` table. Borrow the same connection
+/// (transaction) the mutation's deltas are applied on so the log row commits
+/// or rolls back with them.
+pub struct SqliteMutationLog<'c> {
+    conn: &'c mut SqliteConnection,
+}
+
+impl<'c> SqliteMutationLog<'c> {
+    pub fn new(conn: &'c mut SqliteConnection) -> Self {
+        SqliteMutationLog { conn }
+    }
+
+    /// Append a freshly applied mutation as `Pending`, returning its local
+    /// sequence number.
+    pub async fn append(&mut self, mutation: &Mutation) -> Result<i64> {
+        let row = MutationRow::from_logged_mutation(&LoggedMutation {
+            seq: 0, // Assigned by the database.
+            state: MutationState::Pending,
+            server_seq_num: None,
+            mutation: mutation.clone(),
+        })?;
+        sqlx::query_scalar(
+            "INSERT INTO mutations (id, actor_id, action, changes, timestamp, state)
+             VALUES (?, ?, ?, ?, ?, ?)
+             RETURNING seq",
+        )
+        .bind(row.id)
+        .bind(row.actor_id)
+        .bind(row.action)
+        .bind(row.changes)
+        .bind(row.timestamp)
+        .bind(row.state)
+        .fetch_one(&mut *self.conn)
+        .await
+        .sql_err()
+    }
+
+    /// Mark a pending mutation as pushed to the server. Mutations already
+    /// past `Pending` are left alone.
+    pub async fn mark_sent(&mut self, mutation_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE mutations SET state = 'sent' WHERE id = ? AND state = 'pending'")
+            .bind(UuidColumn(mutation_id))
+            .execute(&mut *self.conn)
+            .await
+            .sql_err()?;
+        Ok(())
+    }
+
+    /// Record the server's verdict on a mutation. `server_seq_num` is the
+    /// global sequence number of an acked mutation.
+    pub async fn resolve(
+        &mut self,
+        mutation_id: Uuid,
+        state: MutationState,
+        server_seq_num: Option<i64>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE mutations SET state = ?, server_seq_num = ? WHERE id = ?")
+            .bind(state.to_string())
+            .bind(server_seq_num)
+            .bind(UuidColumn(mutation_id))
+            .execute(&mut *self.conn)
+            .await
+            .sql_err()?;
+        Ok(())
+    }
+
+    /// Replace the deltas logged for a mutation, after a rebase re-ran its
+    /// action against newer state.
+    pub async fn replace_changes(&mut self, mutation_id: Uuid, changes: &[AnyDelta]) -> Result<()> {
+        let changes = wire::encode(changes).map_err(|e| DomainError::Database(Box::new(e)))?;
+        sqlx::query("UPDATE mutations SET changes = ? WHERE id = ?")
+            .bind(changes)
+            .bind(UuidColumn(mutation_id))
+            .execute(&mut *self.conn)
+            .await
+            .sql_err()?;
+        Ok(())
+    }
+
+    pub async fn set_last_seen_server_seq(&mut self, seq_num: i64) -> Result<()> {
+        sqlx::query("UPDATE sync_state SET last_seen_server_seq = ? WHERE id = 1")
+            .bind(seq_num)
+            .execute(&mut *self.conn)
+            .await
+            .sql_err()?;
+        Ok(())
+    }
+}
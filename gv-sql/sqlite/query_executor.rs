@@ -1,8 +1,11 @@
 use gv_core::{
+    analytics::{self, Comparison, Pushdown},
     error::{DbErr, DomainError, RejectReason, Result},
-    models::{activity::Activity, user::User},
+    models::{activity::Activity, attribute::Value, entry::Entry, user::User},
+    permissions::Visibility,
     queries::*,
-    query_executor::QueryExecutor,
+    query_executor::{QueryExecutor, ReadScope, evaluate_derived_pairs, find_attributes},
+    records, series, training_load,
 };
 use itertools::Itertools;
 use sqlx::{FromRow, SqliteConnection};
@@ -10,11 +13,55 @@ use uuid::Uuid;
 
 pub struct SqliteQueryExecutor<'c> {
     conn: &'c mut SqliteConnection,
+    /// Whose rows reads are restricted to (`ReadScope`); every row when `None`.
+    reader: Option<Uuid>,
 }
 
 impl<'c> SqliteQueryExecutor<'c> {
     pub fn new(conn: &'c mut SqliteConnection) -> Self {
-        SqliteQueryExecutor { conn }
+        SqliteQueryExecutor { conn, reader: None }
+    }
+
+    /// The CTEs `visible` reads, followed by a comma for the query's own; empty
+    /// when unscoped.
+    fn scoped_ctes(&self) -> String {
+        self.reader
+            .map(|actor_id| format!("{}, ", visible_ctes(actor_id)))
+            .unwrap_or_default()
+    }
+
+    /// `WITH RECURSIVE` and `scoped_ctes`, for a query with no CTEs of its own.
+    fn with_visible(&self) -> String {
+        self.reader
+            .map(|actor_id| format!("WITH RECURSIVE {} ", visible_ctes(actor_id)))
+            .unwrap_or_default()
+    }
+
+    /// A condition that `column` holds the id of a `kind` row the reader can
+    /// see; `TRUE` when unscoped.
+    fn visible(&self, kind: &str, column: &str) -> String {
+        match self.reader {
+            Some(_) => format!("{column} IN (SELECT id FROM visible WHERE kind = '{kind}')"),
+            None => "TRUE".to_string(),
+        }
+    }
+
+    /// A condition that the reader is a party to the grant; `TRUE` when
+    /// unscoped.
+    fn party(&self) -> String {
+        match self.reader {
+            Some(actor_id) => {
+                let actor = uuid_literal(actor_id);
+                format!("(grantor_id = {actor} OR grantee_id = {actor})")
+            }
+            None => "TRUE".to_string(),
+        }
+    }
+}
+
+impl ReadScope for SqliteQueryExecutor<'_> {
+    fn read_as(&mut self, actor_id: Uuid) {
+        self.reader = Some(actor_id);
     }
 }
 
@@ -83,12 +130,16 @@ impl QueryExecutor<FindActivityById> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindActivityById,
     ) -> Result<<FindActivityById as Query>::Response> {
-        let row = sqlx::query_as::<_, crate::rows::ActivityRow>(
-            "SELECT id, owner_id, source_activity_id, name, description FROM activities WHERE id = ?",
-        )
-        .bind(crate::columns::UuidColumn(query.id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?;
+        let sql = format!(
+            "{}SELECT id, owner_id, source_activity_id, name, description, archived FROM activities WHERE id = ? AND {}",
+            self.with_visible(),
+            self.visible("activity", "id"),
+        );
+        let row = sqlx::query_as::<_, crate::rows::ActivityRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?;
         Ok(row.map(Activity::from))
     }
 }
@@ -98,12 +149,34 @@ impl QueryExecutor<AllActivities> for SqliteQueryExecutor<'_> {
         &mut self,
         _query: AllActivities,
     ) -> Result<<AllActivities as Query>::Response> {
-        let rows = sqlx::query_as::<_, crate::rows::ActivityRow>(
-            "SELECT id, owner_id, source_activity_id, name, description FROM activities",
-        )
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?;
+        let sql = format!(
+            "{}SELECT id, owner_id, source_activity_id, name, description, archived FROM activities WHERE NOT archived AND {}",
+            self.with_visible(),
+            self.visible("activity", "id"),
+        );
+        let rows = sqlx::query_as::<_, crate::rows::ActivityRow>(&sql)
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
+        Ok(rows.into_iter().map(Activity::from).collect())
+    }
+}
+
+impl QueryExecutor<FindActivitiesBySource> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindActivitiesBySource,
+    ) -> Result<<FindActivitiesBySource as Query>::Response> {
+        let sql = format!(
+            "{}SELECT id, owner_id, source_activity_id, name, description, archived FROM activities WHERE source_activity_id = ? AND {}",
+            self.with_visible(),
+            self.visible("activity", "id"),
+        );
+        let rows = sqlx::query_as::<_, crate::rows::ActivityRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.source_activity_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
         Ok(rows.into_iter().map(Activity::from).collect())
     }
 }
@@ -112,7 +185,12 @@ impl QueryExecutor<AllActivities> for SqliteQueryExecutor<'_> {
 
 impl QueryExecutor<AllEntries> for SqliteQueryExecutor<'_> {
     async fn execute(&mut self, _query: AllEntries) -> Result<<AllEntries as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::EntryRow>("SELECT * FROM entries")
+        let sql = format!(
+            "{}SELECT * FROM entries WHERE {}",
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
             .fetch_all(&mut *self.conn)
             .await
             .db_err()?
@@ -127,9 +205,9 @@ impl QueryExecutor<EntriesRootedInTimeInterval> for SqliteQueryExecutor<'_> {
         &mut self,
         query: EntriesRootedInTimeInterval,
     ) -> Result<<EntriesRootedInTimeInterval as Query>::Response> {
-        sqlx::query_as::<sqlx::Sqlite, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            WITH RECURSIVE forest AS (
+            WITH RECURSIVE {}forest AS (
                 SELECT * FROM entries e
                 WHERE e.start_time BETWEEN ? AND ?
                     AND e.parent_id IS NULL
@@ -137,17 +215,20 @@ impl QueryExecutor<EntriesRootedInTimeInterval> for SqliteQueryExecutor<'_> {
                 SELECT c.* FROM entries c
                     INNER JOIN forest ON c.parent_id = forest.id
             )
-            SELECT * FROM forest
+            SELECT * FROM forest WHERE {}
             "#,
-        )
-        .bind(crate::columns::DateTimeColumn(query.from))
-        .bind(crate::columns::DateTimeColumn(query.to))
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?
-        .into_iter()
-        .map(|r| r.to_entry())
-        .collect()
+            self.scoped_ctes(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<sqlx::Sqlite, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::DateTimeColumn(query.from))
+            .bind(crate::columns::DateTimeColumn(query.to))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|r| r.to_entry())
+            .collect()
     }
 }
 
@@ -156,9 +237,9 @@ impl QueryExecutor<FindAncestors> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindAncestors,
     ) -> Result<<FindAncestors as Query>::Response> {
-        let results: Vec<AncestorRow> = sqlx::query_as(
+        let sql = format!(
             r#"
-            WITH RECURSIVE ancestors AS (
+            WITH RECURSIVE {}ancestors AS (
                 SELECT id, parent_id, 0 as dist
                     FROM entries
                     WHERE id = ?
@@ -168,13 +249,17 @@ impl QueryExecutor<FindAncestors> for SqliteQueryExecutor<'_> {
                     INNER JOIN ancestors a ON a.parent_id = e.id
             )
             SELECT id, parent_id FROM ancestors
+            WHERE {}
             ORDER BY dist
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?;
+            self.scoped_ctes(),
+            self.visible("entry", "id"),
+        );
+        let results: Vec<AncestorRow> = sqlx::query_as(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
 
         if results.is_empty() {
             return Err(DomainError::Rejected(RejectReason::NotFound(
@@ -195,8 +280,11 @@ impl QueryExecutor<FindAncestors> for SqliteQueryExecutor<'_> {
             );
         }
 
+        // Scoped, the chain stops at the highest ancestor the reader can see:
+        // an entry tree has a single owner, so that's the root of whatever
+        // grant covers the entry.
         assert!(
-            results.last().unwrap().parent_id.is_none(),
+            self.reader.is_some() || results.last().unwrap().parent_id.is_none(),
             "root must have no parent"
         );
 
@@ -209,18 +297,47 @@ impl QueryExecutor<FindActivityTemplateRoot> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindActivityTemplateRoot,
     ) -> Result<<FindActivityTemplateRoot as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
+            {}SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
             FROM entries
-            WHERE activity_id = ? AND is_template = 1 AND parent_id IS NULL
+            WHERE activity_id = ? AND is_template = 1 AND parent_id IS NULL AND {}
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.activity_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|e| e.to_entry())
-        .transpose()
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.activity_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|e| e.to_entry())
+            .transpose()
+    }
+}
+
+impl QueryExecutor<FindEntriesByActivity> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindEntriesByActivity,
+    ) -> Result<<FindEntriesByActivity as Query>::Response> {
+        let sql = format!(
+            r#"
+            {}SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
+            FROM entries
+            WHERE activity_id = ? AND {}
+            "#,
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.activity_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|r| r.to_entry())
+            .collect()
     }
 }
 
@@ -229,18 +346,22 @@ impl QueryExecutor<FindEntryById> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindEntryById,
     ) -> Result<<FindEntryById as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
+            {}SELECT id, owner_id, activity_id, name, parent_id, frac_index, is_template, display_as_sets, is_sequence, is_complete, start_time, end_time, duration_ms
             FROM entries
-            WHERE id = ?
+            WHERE id = ? AND {}
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|e| e.to_entry())
-        .transpose()
+            self.with_visible(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|e| e.to_entry())
+            .transpose()
     }
 }
 
@@ -249,24 +370,31 @@ impl QueryExecutor<FindEntryJoinById> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindEntryJoinById,
     ) -> Result<<FindEntryJoinById as Query>::Response> {
-        let row = sqlx::query_as::<_, crate::rows::EntryJoinRow>(
+        // A hidden activity is left out, as if the entry had none, so the
+        // display name can't fall back to it.
+        let sql = format!(
             r#"
-            SELECT
+            {}SELECT
                 e.id, e.activity_id, e.owner_id, e.name, e.parent_id, e.frac_index,
                 e.is_template, e.display_as_sets, e.is_sequence, e.is_complete,
                 e.start_time, e.end_time, e.duration_ms,
                 a.id as act_id, a.owner_id as act_owner_id,
                 a.source_activity_id as act_source_activity_id,
-                a.name as act_name, a.description as act_description
+                a.name as act_name, a.description as act_description,
+                a.archived as act_archived
             FROM entries e
-            LEFT JOIN activities a ON e.activity_id = a.id
-            WHERE e.id = ?
+            LEFT JOIN activities a ON e.activity_id = a.id AND {}
+            WHERE e.id = ? AND {}
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_optional(&mut *self.conn)
-        .await
-        .db_err()?;
+            self.with_visible(),
+            self.visible("activity", "a.id"),
+            self.visible("entry", "e.id"),
+        );
+        let row = sqlx::query_as::<_, crate::rows::EntryJoinRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?;
 
         match row {
             None => Ok(None),
@@ -287,25 +415,28 @@ impl QueryExecutor<FindDescendants> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindDescendants,
     ) -> Result<<FindDescendants as Query>::Response> {
-        sqlx::query_as::<sqlx::Sqlite, crate::rows::EntryRow>(
+        let sql = format!(
             r#"
-            WITH RECURSIVE tree AS (
+            WITH RECURSIVE {}tree AS (
                 SELECT * FROM entries e
                 WHERE e.id = ?
                 UNION ALL
                 SELECT c.* FROM entries c
                     INNER JOIN tree ON c.parent_id = tree.id
             )
-            SELECT * FROM tree ORDER BY id
+            SELECT * FROM tree WHERE {} ORDER BY id
             "#,
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?
-        .into_iter()
-        .map(|e| e.to_entry())
-        .collect()
+            self.scoped_ctes(),
+            self.visible("entry", "id"),
+        );
+        sqlx::query_as::<sqlx::Sqlite, crate::rows::EntryRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|e| e.to_entry())
+            .collect()
     }
 }
 
@@ -316,14 +447,18 @@ impl QueryExecutor<FindAttributeById> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindAttributeById,
     ) -> Result<<FindAttributeById as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributeRow>(
-            "SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE id = ?",
-        )
-        .bind(crate::columns::UuidColumn(query.attribute_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|row| row.to_attribute())
-        .transpose()
+        let sql = format!(
+            "{}SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE id = ? AND {}",
+            self.with_visible(),
+            self.visible("attribute", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::AttributeRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|row| row.to_attribute())
+            .transpose()
     }
 }
 
@@ -332,15 +467,18 @@ impl QueryExecutor<AllAttributes> for SqliteQueryExecutor<'_> {
         &mut self,
         _query: AllAttributes,
     ) -> Result<<AllAttributes as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributeRow>(
-            "SELECT id, owner_id, name, description, data_type, config FROM attributes",
-        )
-        .fetch_all(&mut *self.conn)
-        .await
-        .db_err()?
-        .into_iter()
-        .map(|row| row.to_attribute())
-        .collect()
+        let sql = format!(
+            "{}SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE {}",
+            self.with_visible(),
+            self.visible("attribute", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::AttributeRow>(&sql)
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_attribute())
+            .collect()
     }
 }
 
@@ -349,15 +487,19 @@ impl QueryExecutor<FindAttributesByOwner> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindAttributesByOwner,
     ) -> Result<<FindAttributesByOwner as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributeRow>(
-            "SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE owner_id = ?",
-        )
-        .bind(crate::columns::UuidColumn(query.owner_id))
-        .fetch_all(&mut *self.conn)
-        .await.db_err()?
-        .into_iter()
-        .map(|row| row.to_attribute())
-        .collect()
+        let sql = format!(
+            "{}SELECT id, owner_id, name, description, data_type, config FROM attributes WHERE owner_id = ? AND {}",
+            self.with_visible(),
+            self.visible("attribute", "id"),
+        );
+        sqlx::query_as::<_, crate::rows::AttributeRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.owner_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_attribute())
+            .collect()
     }
 }
 
@@ -368,15 +510,19 @@ impl QueryExecutor<FindValueByKey> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindValueByKey,
     ) -> Result<<FindValueByKey as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::ValueRow>(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = ? AND attribute_id = ?",
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .bind(crate::columns::UuidColumn(query.attribute_id))
-        .fetch_optional(&mut *self.conn)
-        .await.db_err()?
-        .map(|row| row.to_value())
-        .transpose()
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = ? AND attribute_id = ? AND {}",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        );
+        sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|row| row.to_value())
+            .transpose()
     }
 }
 
@@ -385,15 +531,19 @@ impl QueryExecutor<FindValuesForEntry> for SqliteQueryExecutor<'_> {
         &mut self,
         query: FindValuesForEntry,
     ) -> Result<<FindValuesForEntry as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::ValueRow>(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = ?",
-        )
-        .bind(crate::columns::UuidColumn(query.entry_id))
-        .fetch_all(&mut *self.conn)
-        .await.db_err()?
-        .into_iter()
-        .map(|row| row.to_value())
-        .collect()
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id = ? AND {}",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        );
+        sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect()
     }
 }
 
@@ -405,9 +555,11 @@ impl QueryExecutor<FindValuesForEntries> for SqliteQueryExecutor<'_> {
         if query.entry_ids.is_empty() {
             return Ok(vec![]);
         }
-        let mut builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id IN (",
-        );
+        let mut builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE {} AND entry_id IN (",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        ));
         let mut separated = builder.separated(", ");
         for id in &query.entry_ids {
             separated.push_bind(crate::columns::UuidColumn(*id));
@@ -424,14 +576,35 @@ impl QueryExecutor<FindValuesForEntries> for SqliteQueryExecutor<'_> {
     }
 }
 
+impl QueryExecutor<FindValuesForAttribute> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindValuesForAttribute,
+    ) -> Result<<FindValuesForAttribute as Query>::Response> {
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE attribute_id = ? AND {}",
+            self.with_visible(),
+            self.visible("entry", "entry_id"),
+        );
+        sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect()
+    }
+}
+
 impl QueryExecutor<FindAttributePairsForEntry> for SqliteQueryExecutor<'_> {
     async fn execute(
         &mut self,
         query: FindAttributePairsForEntry,
     ) -> Result<<FindAttributePairsForEntry as Query>::Response> {
-        sqlx::query_as::<_, crate::rows::AttributePairRow>(
+        let sql = format!(
             r#"
-            SELECT
+            {}SELECT
                 a.id as attr_id, a.owner_id as attr_owner_id,
                 a.name as attr_name, a.description as attr_description,
                 a.data_type as attr_data_type,
@@ -440,35 +613,546 @@ impl QueryExecutor<FindAttributePairsForEntry> for SqliteQueryExecutor<'_> {
                 v.index_float, v.index_string
             FROM attribute_values v
             INNER JOIN attributes a ON v.attribute_id = a.id
-            WHERE v.entry_id = ?
+            WHERE v.entry_id = ? AND {} AND {}
             "#,
+            self.with_visible(),
+            self.visible("entry", "v.entry_id"),
+            self.visible("attribute", "a.id"),
+        );
+        let pairs = sqlx::query_as::<_, crate::rows::AttributePairRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.entry_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_attribute_pair())
+            .collect::<Result<Vec<_>>>()?;
+        evaluate_derived_pairs(self, query.entry_id, pairs).await
+    }
+}
+
+impl QueryExecutor<DistinctTextValuesForAttribute> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: DistinctTextValuesForAttribute,
+    ) -> Result<<DistinctTextValuesForAttribute as Query>::Response> {
+        // A shared attribute (the system's, or one granted to the reader) can
+        // hold values on entries the reader can't see; only theirs count.
+        let sql = format!(
+            "{}SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE attribute_id = ? AND {} AND {}",
+            self.with_visible(),
+            self.visible("attribute", "attribute_id"),
+            self.visible("entry", "entry_id"),
+        );
+        let values = sqlx::query_as::<_, crate::rows::ValueRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.attribute_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(distinct_text_values(&values))
+    }
+}
+
+// --- Analytics ---
+
+impl QueryExecutor<RunAnalytics> for SqliteQueryExecutor<'_> {
+    /// Reads only the entries that may match, with their ancestors: the
+    /// filters `AnalyticsQuery::pushdown` allows run in SQL, and `evaluate`
+    /// checks the whole query over what's read.
+    async fn execute(&mut self, query: RunAnalytics) -> Result<<RunAnalytics as Query>::Response> {
+        let query = query.query;
+        query.validate()?;
+        let attributes = find_attributes(self, query.attribute_ids().collect()).await?;
+        let pushdown = query.pushdown(&attributes);
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let attribute_ids: Vec<Uuid> = attributes.iter().map(|a| a.id).collect();
+        let values = self
+            .pushdown_values(&pushdown, Some(&attribute_ids))
+            .await?;
+        Ok(analytics::evaluate(&query, attributes, entries, values))
+    }
+}
+
+impl SqliteQueryExecutor<'_> {
+    /// The entries `pushdown` loads (see `analytics_entries`).
+    async fn pushdown_entries(&mut self, pushdown: &Pushdown) -> Result<Vec<Entry>> {
+        let mut builder = analytics_entries(pushdown, self.reader);
+        builder.push(" SELECT * FROM entries WHERE id IN (SELECT id FROM loaded)");
+        builder
+            .build_query_as::<crate::rows::EntryRow>()
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|r| r.to_entry())
+            .collect()
+    }
+
+    /// The values on the entries `pushdown` loads: those of `attribute_ids`,
+    /// or of every attribute when `None`.
+    async fn pushdown_values(
+        &mut self,
+        pushdown: &Pushdown,
+        attribute_ids: Option<&[Uuid]>,
+    ) -> Result<Vec<Value>> {
+        if attribute_ids.is_some_and(|ids| ids.is_empty()) {
+            return Ok(vec![]);
+        }
+        let mut builder = analytics_entries(pushdown, self.reader);
+        builder.push(
+            " SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE entry_id IN (SELECT id FROM loaded)",
+        );
+        if let Some(attribute_ids) = attribute_ids {
+            builder.push(" AND attribute_id IN (");
+            let mut separated = builder.separated(", ");
+            for attribute_id in attribute_ids {
+                separated.push_bind(crate::columns::UuidColumn(*attribute_id));
+            }
+            builder.push(")");
+        }
+        builder
+            .build_query_as::<crate::rows::ValueRow>()
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_value())
+            .collect()
+    }
+}
+
+/// `WITH` clauses ending in `loaded`: the non-template entries `pushdown`
+/// admits, their ancestors, and their descendants if it reads them. `timed`
+/// carries each entry's own time columns, or its nearest timed ancestor's.
+/// With a `reader`, only the entries they can see: a tree has one owner, so
+/// the visible ancestors of a visible entry stop at the root of its grant.
+/// Stored times are RFC 3339 text in UTC, so they compare as strings.
+fn analytics_entries(
+    pushdown: &Pushdown,
+    reader: Option<Uuid>,
+) -> sqlx::QueryBuilder<'static, sqlx::Sqlite> {
+    let visible = reader
+        .map(|actor_id| format!("{}, ", visible_ctes(actor_id)))
+        .unwrap_or_default();
+    let mut builder = sqlx::QueryBuilder::new(format!(
+        r#"
+        WITH RECURSIVE {visible}base AS (
+            SELECT e.id FROM entries e WHERE e.is_template = "#
+    ));
+    builder.push_bind(false);
+    if reader.is_some() {
+        builder.push(" AND e.id IN (SELECT id FROM visible WHERE kind = 'entry')");
+    }
+    if let Some(owner_id) = pushdown.owner_id {
+        builder.push(" AND e.owner_id = ");
+        builder.push_bind(crate::columns::UuidColumn(owner_id));
+    }
+    if pushdown.top_level {
+        builder.push(" AND e.parent_id IS NULL");
+    }
+    for activity_id in &pushdown.activity_ids {
+        builder.push(" AND e.activity_id = ");
+        builder.push_bind(crate::columns::UuidColumn(*activity_id));
+    }
+    for complete in &pushdown.complete {
+        builder.push(" AND e.is_complete = ");
+        builder.push_bind(*complete);
+    }
+    for predicate in &pushdown.predicates {
+        if predicate.float.is_none() && predicate.string.is_none() {
+            builder.push(" AND 1 = 0");
+            continue;
+        }
+        let op = sql_operator(&predicate.comparison);
+        builder.push(
+            " AND EXISTS (SELECT 1 FROM attribute_values v WHERE v.entry_id = e.id AND v.attribute_id = ",
+        );
+        builder.push_bind(crate::columns::UuidColumn(predicate.attribute_id));
+        builder.push(" AND CASE");
+        if let Some(float) = predicate.float {
+            builder.push(format!(
+                " WHEN v.index_float IS NOT NULL THEN v.index_float {op} "
+            ));
+            builder.push_bind(float);
+        }
+        if let Some(string) = &predicate.string {
+            builder.push(format!(
+                " WHEN v.index_string IS NOT NULL THEN v.index_string {op} "
+            ));
+            builder.push_bind(string.clone());
+        }
+        builder.push(" END)");
+    }
+    let visible_parent = match reader {
+        Some(_) => " AND a.parent_id IN (SELECT id FROM visible WHERE kind = 'entry')",
+        None => "",
+    };
+    // Each base entry with itself and each of its ancestors, for the
+    // windows: they hold when the entry or any ancestor is timed in them.
+    builder.push(format!(
+        r#"
+        ),
+        lineage AS (
+            SELECT id, id AS ancestor_id FROM base
+            UNION ALL
+            SELECT l.id, a.parent_id FROM lineage l
+                INNER JOIN entries a ON a.id = l.ancestor_id
+            WHERE a.parent_id IS NOT NULL{visible_parent}
+        ),
+        candidates AS (
+            SELECT id FROM base WHERE 1 = 1"#
+    ));
+    for (from, to) in &pushdown.windows {
+        // An entry timed only by its end and a duration starts before its
+        // end; bounding the end from below never drops it, and the
+        // evaluators apply the upper bound.
+        builder.push(" AND id IN (SELECT l.id FROM lineage l INNER JOIN entries a ON a.id = l.ancestor_id WHERE (a.start_time >= ");
+        builder.push_bind(crate::columns::DateTimeColumn(*from));
+        builder.push(" AND a.start_time < ");
+        builder.push_bind(crate::columns::DateTimeColumn(*to));
+        builder.push(") OR (a.start_time IS NULL AND a.end_time >= ");
+        builder.push_bind(crate::columns::DateTimeColumn(*from));
+        builder.push(" AND (a.duration_ms IS NOT NULL OR a.end_time < ");
+        builder.push_bind(crate::columns::DateTimeColumn(*to));
+        builder.push(")))");
+    }
+    builder.push(format!(
+        r#"
+        ),
+        ancestors AS (
+            SELECT id, parent_id FROM entries WHERE id IN (SELECT id FROM candidates)
+            UNION
+            SELECT e.id, e.parent_id FROM entries e
+                INNER JOIN ancestors a ON e.id = a.parent_id{visible_parent}
+        ),
+        descendants AS (
+            SELECT id FROM candidates
+            UNION
+            SELECT e.id FROM entries e
+                INNER JOIN descendants d ON e.parent_id = d.id
+        ),
+        loaded AS (
+            SELECT id FROM ancestors"#
+    ));
+    if pushdown.descendants {
+        builder.push(" UNION SELECT id FROM descendants");
+    }
+    builder.push(")");
+    builder
+}
+
+fn sql_operator(comparison: &Comparison) -> &'static str {
+    match comparison {
+        Comparison::Eq => "=",
+        Comparison::Ne => "<>",
+        Comparison::Lt => "<",
+        Comparison::Le => "<=",
+        Comparison::Gt => ">",
+        Comparison::Ge => ">=",
+    }
+}
+
+impl QueryExecutor<PersonalRecords> for SqliteQueryExecutor<'_> {
+    /// Reads the owner's entries of the activity, with their ancestors, and
+    /// the values on them.
+    async fn execute(
+        &mut self,
+        query: PersonalRecords,
+    ) -> Result<<PersonalRecords as Query>::Response> {
+        let pushdown = query.pushdown();
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let values = self.pushdown_values(&pushdown, None).await?;
+        let attribute_ids = values.iter().map(|v| v.attribute_id).unique().collect();
+        let attributes = find_attributes(self, attribute_ids).await?;
+        Ok(records::personal_records(
+            query.owner_id,
+            query.activity_id,
+            attributes,
+            entries,
+            values,
+        ))
+    }
+}
+
+impl QueryExecutor<TrainingLoad> for SqliteQueryExecutor<'_> {
+    /// Reads the owner's sessions around the window, the entries under them
+    /// and their RPE values.
+    async fn execute(&mut self, query: TrainingLoad) -> Result<<TrainingLoad as Query>::Response> {
+        query.window.validate()?;
+        let pushdown = query.pushdown();
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let values = self
+            .pushdown_values(&pushdown, Some(&[query.rpe_attribute_id]))
+            .await?;
+        Ok(training_load::training_load(
+            query.owner_id,
+            query.rpe_attribute_id,
+            &query.window,
+            entries,
+            values,
+        ))
+    }
+}
+
+impl QueryExecutor<AttributeSeries> for SqliteQueryExecutor<'_> {
+    /// Reads the activity's entries in the range, with their ancestors, and
+    /// the attribute's values on them.
+    async fn execute(
+        &mut self,
+        query: AttributeSeries,
+    ) -> Result<<AttributeSeries as Query>::Response> {
+        query.validate()?;
+        let attributes = find_attributes(self, vec![query.attribute_id]).await?;
+        let pushdown = query.pushdown(&attributes);
+        let entries = self.pushdown_entries(&pushdown).await?;
+        let attribute_ids: Vec<Uuid> = attributes.iter().map(|a| a.id).collect();
+        let values = self
+            .pushdown_values(&pushdown, Some(&attribute_ids))
+            .await?;
+        series::attribute_series(&query, attributes, entries, values)
+    }
+}
+
+// --- Grant ---
+
+impl QueryExecutor<FindGrantById> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindGrantById,
+    ) -> Result<<FindGrantById as Query>::Response> {
+        let sql = format!(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants WHERE id = ? AND {}",
+            self.party()
+        );
+        sqlx::query_as::<_, crate::rows::GrantRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.grant_id))
+            .fetch_optional(&mut *self.conn)
+            .await
+            .db_err()?
+            .map(|row| row.to_grant())
+            .transpose()
+    }
+}
+
+impl QueryExecutor<FindGrantsForGrantee> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindGrantsForGrantee,
+    ) -> Result<<FindGrantsForGrantee as Query>::Response> {
+        let sql = format!(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants WHERE grantee_id = ? AND {}",
+            self.party()
+        );
+        sqlx::query_as::<_, crate::rows::GrantRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.grantee_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_grant())
+            .collect()
+    }
+}
+
+impl QueryExecutor<FindGrantsByGrantor> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindGrantsByGrantor,
+    ) -> Result<<FindGrantsByGrantor as Query>::Response> {
+        let sql = format!(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants WHERE grantor_id = ? AND {}",
+            self.party()
+        );
+        sqlx::query_as::<_, crate::rows::GrantRow>(&sql)
+            .bind(crate::columns::UuidColumn(query.grantor_id))
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?
+            .into_iter()
+            .map(|row| row.to_grant())
+            .collect()
+    }
+}
+
+// --- Read scope ---
+
+impl QueryExecutor<VisibleTo> for SqliteQueryExecutor<'_> {
+    async fn execute(&mut self, query: VisibleTo) -> Result<<VisibleTo as Query>::Response> {
+        let sql = format!(
+            "WITH RECURSIVE {} SELECT kind, id FROM visible",
+            visible_ctes(query.actor_id)
+        );
+        let rows = sqlx::query_as::<_, (String, crate::columns::UuidColumn)>(&sql)
+            .fetch_all(&mut *self.conn)
+            .await
+            .db_err()?;
+
+        let mut visibility = Visibility::default();
+        for (kind, id) in rows {
+            let ids = match kind.as_str() {
+                "entry" => &mut visibility.entries,
+                "activity" => &mut visibility.activities,
+                "attribute" => &mut visibility.attributes,
+                _ => unreachable!("VisibleTo only selects known kinds"),
+            };
+            ids.insert(id.0);
+        }
+        Ok(visibility)
+    }
+}
+
+/// `WITH RECURSIVE` clauses ending in `visible(kind, id)`: the rows `actor_id`
+/// can read. The same rule as `gv_core::permissions::can_read`, for every row
+/// at once: owned or system-owned rows, plus what accepted grants from the
+/// row's owner cover. The ids are inlined so the clauses splice into queries
+/// that bind their own parameters.
+fn visible_ctes(actor_id: Uuid) -> String {
+    let actor = uuid_literal(actor_id);
+    let system = uuid_literal(gv_core::SYSTEM_ACTOR_ID);
+    format!(
+        r#"
+            accepted AS (
+                SELECT scope_kind, scope_id, grantor_id FROM grants
+                WHERE grantee_id = {actor} AND accepted
+            ),
+            -- Entries reached through a grant, tagged with what the grant was
+            -- on: an entry's subtree, or an activity's template tree.
+            shared(id, via) AS (
+                SELECT e.id, 'entry' FROM entries e
+                    INNER JOIN accepted g ON g.scope_kind = 'entry'
+                        AND g.scope_id = e.id AND g.grantor_id = e.owner_id
+                UNION
+                SELECT e.id, 'activity' FROM entries e
+                    INNER JOIN accepted g ON g.scope_kind = 'activity'
+                        AND g.scope_id = e.activity_id AND g.grantor_id = e.owner_id
+                    WHERE e.is_template AND e.parent_id IS NULL
+                UNION
+                SELECT c.id, s.via FROM entries c
+                    INNER JOIN shared s ON c.parent_id = s.id
+            ),
+            visible(kind, id) AS (
+                SELECT 'entry', id FROM entries WHERE owner_id IN ({actor}, {system})
+                UNION
+                SELECT 'entry', id FROM shared
+                UNION
+                SELECT 'activity', id FROM activities WHERE owner_id IN ({actor}, {system})
+                UNION
+                SELECT 'activity', a.id FROM accepted g
+                    INNER JOIN activities a ON g.scope_kind = 'activity'
+                        AND a.id = g.scope_id AND a.owner_id = g.grantor_id
+                UNION
+                SELECT 'activity', a.id FROM shared s
+                    INNER JOIN entries e ON e.id = s.id
+                    INNER JOIN activities a ON a.id = e.activity_id AND a.owner_id = e.owner_id
+                    WHERE s.via = 'entry'
+                UNION
+                SELECT 'attribute', id FROM attributes WHERE owner_id IN ({actor}, {system})
+                UNION
+                SELECT 'attribute', a.id FROM accepted g
+                    INNER JOIN attributes a ON g.scope_kind = 'attribute'
+                        AND a.id = g.scope_id AND a.owner_id = g.grantor_id
+                UNION
+                SELECT 'attribute', a.id FROM shared s
+                    INNER JOIN entries e ON e.id = s.id
+                    INNER JOIN attribute_values v ON v.entry_id = s.id
+                    INNER JOIN attributes a ON a.id = v.attribute_id AND a.owner_id = e.owner_id
+            )
+            "#
+    )
+}
+
+/// `id` as a SQL literal, for ids spliced into generated SQL.
+fn uuid_literal(id: Uuid) -> String {
+    format!("X'{}'", id.simple())
+}
+
+// --- Mutation log ---
+
+impl QueryExecutor<UnackedMutations> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        _query: UnackedMutations,
+    ) -> Result<<UnackedMutations as Query>::Response> {
+        sqlx::query_as::<_, crate::rows::MutationRow>(
+            "SELECT seq, id, actor_id, action, changes, timestamp, state, server_seq_num
+             FROM mutations WHERE state IN ('pending', 'sent') ORDER BY seq ASC",
         )
-        .bind(crate::columns::UuidColumn(query.entry_id))
         .fetch_all(&mut *self.conn)
         .await
         .db_err()?
         .into_iter()
-        .map(|row| row.to_attribute_pair())
+        .map(|r| r.to_logged_mutation())
         .collect()
     }
 }
 
-impl QueryExecutor<DistinctTextValuesForAttribute> for SqliteQueryExecutor<'_> {
+impl QueryExecutor<LastSeenServerSeq> for SqliteQueryExecutor<'_> {
     async fn execute(
         &mut self,
-        query: DistinctTextValuesForAttribute,
-    ) -> Result<<DistinctTextValuesForAttribute as Query>::Response> {
-        let values = sqlx::query_as::<_, crate::rows::ValueRow>(
-            "SELECT entry_id, attribute_id, plan, actual, index_float, index_string FROM attribute_values WHERE attribute_id = ?",
+        _query: LastSeenServerSeq,
+    ) -> Result<<LastSeenServerSeq as Query>::Response> {
+        sqlx::query_scalar("SELECT last_seen_server_seq FROM sync_state WHERE id = 1")
+            .fetch_one(&mut *self.conn)
+            .await
+            .db_err()
+    }
+}
+
+impl QueryExecutor<PendingMutations> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        _query: PendingMutations,
+    ) -> Result<<PendingMutations as Query>::Response> {
+        sqlx::query_as::<_, crate::rows::MutationRow>(
+            "SELECT seq, id, actor_id, action, changes, timestamp, state, server_seq_num
+             FROM mutations WHERE state = 'pending' ORDER BY seq ASC",
         )
-        .bind(crate::columns::UuidColumn(query.attribute_id))
         .fetch_all(&mut *self.conn)
         .await
         .db_err()?
         .into_iter()
-        .map(|row| row.to_value())
-        .collect::<Result<Vec<_>>>()?;
-        Ok(distinct_text_values(&values))
+        .map(|r| r.to_logged_mutation())
+        .collect()
+    }
+}
+
+impl QueryExecutor<MutationHistory> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: MutationHistory,
+    ) -> Result<<MutationHistory as Query>::Response> {
+        sqlx::query_as::<_, crate::rows::MutationRow>(
+            "SELECT seq, id, actor_id, action, changes, timestamp, state, server_seq_num
+             FROM mutations ORDER BY seq DESC LIMIT ?",
+        )
+        .bind(query.limit)
+        .fetch_all(&mut *self.conn)
+        .await
+        .db_err()?
+        .into_iter()
+        .map(|r| r.to_logged_mutation())
+        .collect()
+    }
+}
+
+impl QueryExecutor<FindMutationById> for SqliteQueryExecutor<'_> {
+    async fn execute(
+        &mut self,
+        query: FindMutationById,
+    ) -> Result<<FindMutationById as Query>::Response> {
+        sqlx::query_as::<_, crate::rows::MutationRow>(
+            "SELECT seq, id, actor_id, action, changes, timestamp, state, server_seq_num
+             FROM mutations WHERE id = ? AND state != 'rejected'",
+        )
+        .bind(crate::columns::UuidColumn(query.mutation_id))
+        .fetch_optional(&mut *self.conn)
+        .await
+        .db_err()?
+        .map(|r| r.to_logged_mutation().map(|logged| logged.mutation))
+        .transpose()
     }
 }
 
@@ -505,7 +1189,7 @@ impl QueryExecutor<SnapshotAll> for SqliteQueryExecutor<'_> {
         .collect();
 
         let activities = sqlx::query_as::<_, crate::rows::ActivityRow>(
-            "SELECT id, owner_id, source_activity_id, name, description FROM activities",
+            "SELECT id, owner_id, source_activity_id, name, description, archived FROM activities",
         )
         .fetch_all(&mut *tx)
         .await
@@ -542,6 +1226,16 @@ impl QueryExecutor<SnapshotAll> for SqliteQueryExecutor<'_> {
         .map(|r| r.to_value())
         .collect::<Result<Vec<_>>>()?;
 
+        let grants = sqlx::query_as::<_, crate::rows::GrantRow>(
+            "SELECT id, grantor_id, grantee_id, scope_kind, scope_id, level, accepted FROM grants",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .db_err()?
+        .into_iter()
+        .map(|r| r.to_grant())
+        .collect::<Result<Vec<_>>>()?;
+
         tx.commit().await.db_err()?;
 
         Ok(Snapshot {
@@ -551,6 +1245,7 @@ impl QueryExecutor<SnapshotAll> for SqliteQueryExecutor<'_> {
             attributes,
             entries,
             values,
+            grants,
         })
     }
 }
@@ -9,18 +9,29 @@ use chrono::{TimeZone, Utc};
 use fractional_index::FractionalIndex;
 use gv_core::{
     SYSTEM_ACTOR_ID,
+    actions::CreateEntry,
+    delta::Delta,
     models::{
         activity::{Activity, ActivityName},
         attribute::{
-            Attribute, AttributeConfig, AttributeValue, LengthConfig, LengthMeasurement,
-            LengthUnit, LengthValue, MassConfig, MassMeasurement, MassUnit, MassValue,
-            MultiselectConfig, NumericConfig, NumericValue, TextConfig, Value,
+            Attribute, AttributeConfig, AttributeValue, BooleanConfig, DerivedConfig,
+            DurationConfig, DurationDisplay, DurationValue, GradeConfig, LengthConfig,
+            LengthMeasurement, LengthUnit, LengthValue, MassConfig, MassMeasurement, MassUnit,
+            MassValue, MultiselectConfig, NumericConfig, NumericValue, SelectValue, SpeedConfig,
+            SpeedMeasurement, SpeedUnit, SpeedValue, TextConfig, Value,
         },
+        derived::DerivedExpr,
         entry::{Entry, Position, Temporal},
+        grade::GradeScale,
     },
+    mutation_log::{CommittedMutation, LoggedMutation, MutationState},
+    mutators::Mutation,
     validation::{Email, Username},
 };
-use gv_sql::rows::{ActivityRow, AttributeRow, EntryRow, UserRow, ValueRow};
+use gv_sql::rows::{
+    ActivityRow, AttributeRow, CommittedMutationRow, EntryRow, MutationRow, StampedDeltaRow,
+    UserRow, ValueRow,
+};
 use uuid::Uuid;
 
 fn sample_user() -> User {
@@ -38,6 +49,7 @@ fn sample_activity() -> Activity {
         source_activity_id: Some(Uuid::new_v4()),
         name: ActivityName::parse("Bench Press".to_string()).unwrap(),
         description: Some("Standard barbell bench".to_string()),
+        archived: false,
     }
 }
 
@@ -103,6 +115,7 @@ fn activity_round_trips_minimal() {
         source_activity_id: None,
         name: ActivityName::parse("Squat".to_string()).unwrap(),
         description: None,
+        archived: false,
     };
     let row: ActivityRow = activity.clone().into();
     let got: Activity = row.into();
@@ -218,6 +231,40 @@ fn attribute_round_trips_length() {
     assert_eq!(got, attr);
 }
 
+#[test]
+fn attribute_round_trips_speed() {
+    let attr = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: SYSTEM_ACTOR_ID,
+        name: "Pace".to_string(),
+        description: None,
+        config: AttributeConfig::Speed(SpeedConfig {
+            default_unit: SpeedUnit::MinutesPerKilometer,
+        }),
+    };
+    let row = AttributeRow::from_attribute(&attr).unwrap();
+    assert_eq!(row.data_type, "Speed");
+    let got = row.to_attribute().unwrap();
+    assert_eq!(got, attr);
+}
+
+#[test]
+fn attribute_round_trips_duration() {
+    let attr = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: SYSTEM_ACTOR_ID,
+        name: "Hold Time".to_string(),
+        description: None,
+        config: AttributeConfig::Duration(DurationConfig {
+            display: DurationDisplay::HoursMinutesSeconds,
+        }),
+    };
+    let row = AttributeRow::from_attribute(&attr).unwrap();
+    assert_eq!(row.data_type, "Duration");
+    let got = row.to_attribute().unwrap();
+    assert_eq!(got, attr);
+}
+
 #[test]
 fn attribute_round_trips_text() {
     let attr = Attribute {
@@ -332,6 +379,65 @@ fn value_round_trips_length() {
     assert_eq!(got, value);
 }
 
+#[test]
+fn value_round_trips_composite_length() {
+    let value = Value {
+        entry_id: Uuid::new_v4(),
+        attribute_id: Uuid::new_v4(),
+        index_float: None,
+        index_string: None,
+        plan: None,
+        actual: Some(AttributeValue::Length(LengthValue::Composite {
+            unit: LengthUnit::Inch,
+            value: 70.0,
+            units: vec![LengthUnit::Foot, LengthUnit::Inch],
+        })),
+    };
+    let row = ValueRow::from_value(&value).unwrap();
+    let got = row.to_value().unwrap();
+    assert_eq!(got, value);
+}
+
+#[test]
+fn value_round_trips_speed() {
+    let value = Value {
+        entry_id: Uuid::new_v4(),
+        attribute_id: Uuid::new_v4(),
+        index_float: None,
+        index_string: None,
+        plan: Some(AttributeValue::Speed(SpeedValue::Range {
+            unit: SpeedUnit::MinutesPerMile,
+            min: 7.5,
+            max: 8.0,
+        })),
+        actual: Some(AttributeValue::Speed(SpeedValue::Exact(SpeedMeasurement {
+            unit: SpeedUnit::KilometersPerHour,
+            value: 12.4,
+        }))),
+    };
+    let row = ValueRow::from_value(&value).unwrap();
+    let got = row.to_value().unwrap();
+    assert_eq!(got, value);
+}
+
+#[test]
+fn value_round_trips_duration() {
+    let value = Value {
+        entry_id: Uuid::new_v4(),
+        attribute_id: Uuid::new_v4(),
+        index_float: None,
+        index_string: None,
+        plan: Some(AttributeValue::Duration(DurationValue::Range {
+            min: 7.0,
+            max: 10.0,
+        })),
+        actual: Some(AttributeValue::Duration(DurationValue::Exact(8.25))),
+    };
+    let row = ValueRow::from_value(&value).unwrap();
+    let got = row.to_value().unwrap();
+    assert_eq!(got, value);
+}
+
 #[test]
 fn value_round_trips_text() {
     let value = Value {
@@ -347,6 +453,96 @@ fn value_round_trips_text() {
     assert_eq!(got, value);
 }
 
+#[test]
+fn attribute_round_trips_boolean() {
+    let attr = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: SYSTEM_ACTOR_ID,
+        name: "Used Chalk".to_string(),
+        description: None,
+        config: AttributeConfig::Boolean(BooleanConfig {
+            default: Some(false),
+        }),
+    };
+    let row = AttributeRow::from_attribute(&attr).unwrap();
+    assert_eq!(row.data_type, "Boolean");
+    let got = row.to_attribute().unwrap();
+    assert_eq!(got, attr);
+}
+
+#[test]
+fn value_round_trips_boolean() {
+    let value = Value {
+        entry_id: Uuid::new_v4(),
+        attribute_id: Uuid::new_v4(),
+        index_float: None,
+        index_string: None,
+        // `false` is a value, distinct from no value (None).
+        plan: Some(AttributeValue::Boolean(false)),
+        actual: Some(AttributeValue::Boolean(true)),
+    };
+    let row = ValueRow::from_value(&value).unwrap();
+    let got = row.to_value().unwrap();
+    assert_eq!(got, value);
+}
+
+#[test]
+fn attribute_round_trips_grade() {
+    let attr = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: SYSTEM_ACTOR_ID,
+        name: "Font Grade".to_string(),
+        description: None,
+        config: AttributeConfig::Grade(GradeConfig {
+            scale: GradeScale::Font,
+            default: Some("6A+".to_string()),
+        }),
+    };
+    let row = AttributeRow::from_attribute(&attr).unwrap();
+    assert_eq!(row.data_type, "Grade");
+    let got = row.to_attribute().unwrap();
+    assert_eq!(got, attr);
+}
+
+#[test]
+fn attribute_round_trips_derived() {
+    let (reps, load) = (Uuid::new_v4(), Uuid::new_v4());
+    let attr = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: SYSTEM_ACTOR_ID,
+        name: "Tonnage".to_string(),
+        description: None,
+        config: AttributeConfig::Derived(DerivedConfig {
+            expression: (DerivedExpr::attribute(reps) * DerivedExpr::attribute(load))
+                .sum_descendants(),
+        }),
+    };
+    let row = AttributeRow::from_attribute(&attr).unwrap();
+    assert_eq!(row.data_type, "Derived");
+    let got = row.to_attribute().unwrap();
+    assert_eq!(got, attr);
+}
+
+#[test]
+fn value_round_trips_grade() {
+    let value = Value {
+        entry_id: Uuid::new_v4(),
+        attribute_id: Uuid::new_v4(),
+        index_float: None,
+        index_string: None,
+        plan: Some(AttributeValue::Grade(SelectValue::Range {
+            min: "5.11a".to_string(),
+            max: "5.11c".to_string(),
+        })),
+        actual: Some(AttributeValue::Grade(SelectValue::Exact(
+            "5.11b".to_string(),
+        ))),
+    };
+    let row = ValueRow::from_value(&value).unwrap();
+    let got = row.to_value().unwrap();
+    assert_eq!(got, value);
+}
+
 #[test]
 fn value_round_trips_empty() {
     let value = Value {
@@ -361,3 +557,53 @@ fn value_round_trips_empty() {
     let got = row.to_value().unwrap();
     assert_eq!(got, value);
 }
+
+#[test]
+fn mutation_round_trips() {
+    let entry = sample_entry_with_position();
+    let logged = LoggedMutation {
+        seq: 7,
+        state: MutationState::Acked,
+        server_seq_num: Some(12),
+        mutation: Mutation {
+            id: Uuid::new_v4(),
+            timestamp: Utc.with_ymd_and_hms(2026, 6, 1, 8, 0, 0).unwrap(),
+            action: CreateEntry::from(entry.clone()).into(),
+            changes: vec![Delta::Insert { new: entry }.into()],
+        },
+    };
+    let row = MutationRow::from_logged_mutation(&logged).unwrap();
+    assert_eq!(row.actor_id.0, SYSTEM_ACTOR_ID);
+    assert_eq!(row.state, "acked");
+    let got = row.to_logged_mutation().unwrap();
+    assert_eq!(got, logged);
+}
+
+#[test]
+fn committed_mutation_round_trips() {
+    let entry = sample_entry_root();
+    let committed = CommittedMutation {
+        seq_num: 42,
+        mutation: Mutation {
+            id: Uuid::new_v4(),
+            timestamp: Utc.with_ymd_and_hms(2026, 6, 1, 8, 0, 0).unwrap(),
+            action: CreateEntry::from(entry.clone()).into(),
+            changes: vec![Delta::Insert { new: entry }.into()],
+        },
+    };
+    let stamped = committed
+        .stamped_changes()
+        .into_iter()
+        .map(|s| {
+            let row = StampedDeltaRow::from_stamped_delta(&s).unwrap();
+            assert_eq!(row.table_name, "entries");
+            row.to_stamped_delta().unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(stamped, committed.stamped_changes());
+
+    let row = CommittedMutationRow::from_committed_mutation(&committed).unwrap();
+    let changes = stamped.into_iter().map(|s| s.delta).collect();
+    let got = row.into_committed_mutation(changes).unwrap();
+    assert_eq!(got, committed);
+}
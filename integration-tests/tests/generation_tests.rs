@@ -29,12 +29,16 @@ const M_RUNS: usize = 10;
 async fn run_sim(pool: &PgPool, seed: u64, n: usize) -> Vec<String> {
     // Start each run from an empty database so the only inputs are the seed.
     sqlx::query(
-        "TRUNCATE actors, users, activities, entries, attributes, attribute_values \
-         RESTART IDENTITY CASCADE",
+        "TRUNCATE actors, users, activities, entries, attributes, attribute_values, \
+         mutations, mutation_deltas RESTART IDENTITY CASCADE",
     )
     .execute(pool)
     .await
     .expect("truncate should succeed");
+    sqlx::query("UPDATE mutation_seq SET seq_num = 0")
+        .execute(pool)
+        .await
+        .expect("seq reset should succeed");
 
     let server = PostgresServer::with_io(pool.clone(), Arc::new(SimIo::new(seed)));
     let mut rng = ChaCha8Rng::seed_from_u64(seed);
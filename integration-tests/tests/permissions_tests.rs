@@ -0,0 +1,879 @@
+//! Grants and the permission checks every mutator makes (`gv_core::permissions`,
+//! `docs/permissions.md`), driven through the Postgres server as two users: an
+//! athlete who owns the data and a coach they share it with.
+
+use fractional_index::FractionalIndex;
+use gv_core::{
+    actions::{
+        AcceptGrant, Action, ActivityChange, ArchiveActivity, AttachValue, ConvertToSets,
+        CreateActivity, CreateAttribute, CreateEntry, CreateUser, CreateValue, DeleteActivity,
+        GrantPermission, MoveEntry, ReferencingEntries, RevokeGrant, UpdateActivity,
+        UpdateAttributeValue, UpdateEntryCompletion, ValueField,
+    },
+    error::{DomainError, RejectReason},
+    models::{
+        activity::{Activity, ActivityName},
+        attribute::{
+            Attribute, AttributeConfig, AttributeValue, NumericConfig, NumericValue, Value,
+        },
+        entry::{Entry, Position, Temporal},
+        grant::{GrantLevel, GrantScope},
+        user::User,
+    },
+    permissions::{Resource, can_read, can_write},
+    queries::{
+        AllActivities, AllAttributes, AllEntries, DistinctTextValuesForAttribute, FindEntryById,
+        FindGrantById, FindGrantsByGrantor, FindGrantsForGrantee, FindValuesForEntry,
+    },
+    query_executor::{QueryExecutor, ScopedQueryExecutor},
+    validation::{Email, Username},
+};
+use gv_server::server::PostgresServer;
+use gv_sql::postgres::PostgresQueryExecutor;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn user() -> User {
+    let id = Uuid::new_v4();
+    User {
+        actor_id: id,
+        username: Username::parse(format!("u{}", id.simple())).unwrap(),
+        email: Email::parse(format!("{}@test.com", id.simple())).unwrap(),
+    }
+}
+
+fn entry(owner_id: Uuid, activity_id: Option<Uuid>, parent_id: Option<Uuid>) -> Entry {
+    Entry {
+        id: Uuid::new_v4(),
+        activity_id,
+        owner_id,
+        name: None,
+        position: parent_id.map(|parent_id| Position {
+            parent_id,
+            frac_index: FractionalIndex::default(),
+        }),
+        is_template: false,
+        display_as_sets: false,
+        is_sequence: true,
+        is_complete: false,
+        temporal: match parent_id {
+            None => Temporal::Start {
+                start: "2026-06-01T09:00:00Z".parse().unwrap(),
+            },
+            Some(_) => Temporal::None,
+        },
+    }
+}
+
+fn numeric_attribute(owner_id: Uuid, name: &str) -> Attribute {
+    Attribute {
+        id: Uuid::new_v4(),
+        owner_id,
+        name: name.to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: false,
+            default: None,
+        }),
+    }
+}
+
+fn exact(v: f64) -> Option<AttributeValue> {
+    Some(AttributeValue::Numeric(NumericValue::Exact(v)))
+}
+
+fn is_unauthorized<T: std::fmt::Debug>(result: gv_core::error::Result<T>) -> bool {
+    matches!(
+        result,
+        Err(DomainError::Rejected(RejectReason::Unauthorized(_)))
+    )
+}
+
+/// An athlete's workout (a root sequence) holding a squat logged against the
+/// athlete's "Squat" activity, with a weight value on the squat.
+struct Fixture {
+    server: PostgresServer,
+    athlete: Uuid,
+    coach: Uuid,
+    activity: Activity,
+    template_root: Entry,
+    workout: Entry,
+    squat: Entry,
+    weight: Attribute,
+    reps: Attribute,
+}
+
+impl Fixture {
+    async fn new(pool: PgPool) -> Fixture {
+        let server = PostgresServer::new(pool);
+        let athlete = user();
+        let coach = user();
+        for u in [&athlete, &coach] {
+            server
+                .run_action(CreateUser::from(u.clone()).into())
+                .await
+                .unwrap();
+        }
+        let (athlete, coach) = (athlete.actor_id, coach.actor_id);
+
+        let activity = Activity {
+            id: Uuid::new_v4(),
+            owner_id: athlete,
+            source_activity_id: None,
+            name: ActivityName::parse("Squat".to_string()).unwrap(),
+            description: None,
+            archived: false,
+        };
+        let create_activity: CreateActivity = activity.into_create_activity(Uuid::new_v4());
+        let template_root = create_activity.template[0].clone();
+        server.run_action(create_activity.into()).await.unwrap();
+
+        let weight = numeric_attribute(athlete, "Weight");
+        let reps = numeric_attribute(athlete, "Reps");
+        for attribute in [&weight, &reps] {
+            server
+                .run_action(CreateAttribute::from(attribute.clone()).into())
+                .await
+                .unwrap();
+        }
+
+        let workout = entry(athlete, None, None);
+        let mut squat = entry(athlete, Some(activity.id), Some(workout.id));
+        squat.is_sequence = false;
+        for e in [&workout, &squat] {
+            server
+                .run_action(CreateEntry::from(e.clone()).into())
+                .await
+                .unwrap();
+        }
+        server
+            .run_action(
+                CreateValue {
+                    actor_id: athlete,
+                    value: Value {
+                        entry_id: squat.id,
+                        attribute_id: weight.id,
+                        index_float: None,
+                        index_string: None,
+                        plan: exact(100.0),
+                        actual: None,
+                    },
+                }
+                .into(),
+            )
+            .await
+            .unwrap();
+
+        Fixture {
+            server,
+            athlete,
+            coach,
+            activity,
+            template_root,
+            workout,
+            squat,
+            weight,
+            reps,
+        }
+    }
+
+    /// Offer the coach `level` access to `scope`, accepting it when `accept`.
+    async fn grant(&self, scope: GrantScope, level: GrantLevel, accept: bool) -> Uuid {
+        let grant_id = Uuid::new_v4();
+        self.server
+            .run_action(
+                GrantPermission {
+                    actor_id: self.athlete,
+                    grant_id,
+                    grantee_id: self.coach,
+                    scope,
+                    level,
+                }
+                .into(),
+            )
+            .await
+            .unwrap();
+        if accept {
+            self.server
+                .run_action(
+                    AcceptGrant {
+                        actor_id: self.coach,
+                        grant_id,
+                    }
+                    .into(),
+                )
+                .await
+                .unwrap();
+        }
+        grant_id
+    }
+
+    async fn coach_can(&self, write: bool, resource: Resource<'_>) -> bool {
+        let mut conn = self.server.pool.acquire().await.unwrap();
+        let mut executor = PostgresQueryExecutor::new(&mut conn);
+        if write {
+            can_write(&mut executor, self.coach, resource)
+                .await
+                .unwrap()
+        } else {
+            can_read(&mut executor, self.coach, resource).await.unwrap()
+        }
+    }
+
+    fn complete_squat(&self) -> Action {
+        UpdateEntryCompletion {
+            actor_id: self.coach,
+            entry_id: self.squat.id,
+            is_complete: true,
+        }
+        .into()
+    }
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_grant_applies_only_once_accepted(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    assert!(is_unauthorized(
+        f.server.run_action(f.complete_squat()).await
+    ));
+
+    let grant_id = f
+        .grant(GrantScope::Entry(f.workout.id), GrantLevel::Write, false)
+        .await;
+    assert!(!f.coach_can(false, Resource::Entry(&f.workout)).await);
+    assert!(is_unauthorized(
+        f.server.run_action(f.complete_squat()).await
+    ));
+
+    f.server
+        .run_action(
+            AcceptGrant {
+                actor_id: f.coach,
+                grant_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    f.server.run_action(f.complete_squat()).await.unwrap();
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_entry_grant_propagates(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    f.grant(GrantScope::Entry(f.workout.id), GrantLevel::Write, true)
+        .await;
+
+    // Children, including ones created after the grant, which still belong
+    // to the athlete.
+    assert!(f.coach_can(true, Resource::Entry(&f.squat)).await);
+    let mut bench = entry(f.athlete, None, Some(f.workout.id));
+    bench.is_sequence = false;
+    f.server
+        .run_action(
+            CreateEntry {
+                actor_id: f.coach,
+                entry: bench.clone(),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert!(f.coach_can(true, Resource::Entry(&bench)).await);
+
+    // The activity the squat is logged against, and the attribute its value
+    // uses; values follow their entry.
+    assert!(f.coach_can(false, Resource::Activity(&f.activity)).await);
+    assert!(f.coach_can(false, Resource::Attribute(&f.weight)).await);
+    f.server
+        .run_action(
+            UpdateAttributeValue {
+                actor_id: f.coach,
+                entry_id: f.squat.id,
+                attribute_id: f.weight.id,
+                field: ValueField::Actual,
+                value: exact(105.0),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // Nothing the workout doesn't use: not the unused attribute, not the
+    // athlete's other entries.
+    assert!(!f.coach_can(false, Resource::Attribute(&f.reps)).await);
+    let attach_reps = AttachValue {
+        actor_id: f.coach,
+        entry_id: f.squat.id,
+        attribute_id: f.reps.id,
+    };
+    assert!(is_unauthorized(
+        f.server.run_action(attach_reps.into()).await
+    ));
+    let other_workout = entry(f.athlete, None, None);
+    f.server
+        .run_action(CreateEntry::from(other_workout.clone()).into())
+        .await
+        .unwrap();
+    assert!(!f.coach_can(false, Resource::Entry(&other_workout)).await);
+
+    // Nor can the coach take the squat out of the shared workout.
+    let move_out = MoveEntry {
+        actor_id: f.coach,
+        entry_id: f.squat.id,
+        position: Some(Position {
+            parent_id: other_workout.id,
+            frac_index: FractionalIndex::default(),
+        }),
+        temporal: Temporal::None,
+    };
+    assert!(is_unauthorized(f.server.run_action(move_out.into()).await));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_read_grant_does_not_allow_writes(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    f.grant(GrantScope::Entry(f.workout.id), GrantLevel::Read, true)
+        .await;
+
+    assert!(f.coach_can(false, Resource::Entry(&f.squat)).await);
+    assert!(!f.coach_can(true, Resource::Entry(&f.squat)).await);
+    assert!(is_unauthorized(
+        f.server.run_action(f.complete_squat()).await
+    ));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_root_write_grant_reads_your_writes(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    f.grant(GrantScope::Log(f.athlete), GrantLevel::RootWrite, true)
+        .await;
+
+    // The coach adds a session to the athlete's log, and is granted it.
+    let session = entry(f.athlete, None, None);
+    let mutation = f
+        .server
+        .run_action(
+            CreateEntry {
+                actor_id: f.coach,
+                entry: session.clone(),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert!(!mutation.is_undoable());
+    assert!(f.coach_can(true, Resource::Entry(&session)).await);
+
+    // Including what the athlete adds to it.
+    let mut extra = entry(f.athlete, None, Some(session.id));
+    extra.is_sequence = false;
+    f.server
+        .run_action(CreateEntry::from(extra.clone()).into())
+        .await
+        .unwrap();
+    assert!(f.coach_can(true, Resource::Entry(&extra)).await);
+
+    // But nothing else in the log, nor its activities and attributes.
+    assert!(!f.coach_can(false, Resource::Entry(&f.workout)).await);
+    assert!(!f.coach_can(false, Resource::Activity(&f.activity)).await);
+    assert!(!f.coach_can(false, Resource::Attribute(&f.weight)).await);
+    assert!(is_unauthorized(
+        f.server.run_action(f.complete_squat()).await
+    ));
+    let mut under_workout = entry(f.athlete, None, Some(f.workout.id));
+    under_workout.is_sequence = false;
+    let create = CreateEntry {
+        actor_id: f.coach,
+        entry: under_workout,
+    };
+    assert!(is_unauthorized(f.server.run_action(create.into()).await));
+
+    let mut conn = f.server.pool.acquire().await.unwrap();
+    let mut coach = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), f.coach);
+    let mut ids: Vec<Uuid> = coach
+        .execute(AllEntries {})
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    ids.sort();
+    let mut written = vec![session.id, extra.id];
+    written.sort();
+    assert_eq!(ids, written);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_root_write_grant_is_over_a_log(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    let share = |scope, level| {
+        Action::from(GrantPermission {
+            actor_id: f.athlete,
+            grant_id: Uuid::new_v4(),
+            grantee_id: f.coach,
+            scope,
+            level,
+        })
+    };
+    for action in [
+        share(GrantScope::Entry(f.workout.id), GrantLevel::RootWrite),
+        share(GrantScope::Log(f.athlete), GrantLevel::Write),
+    ] {
+        assert!(matches!(
+            f.server.run_action(action).await,
+            Err(DomainError::Rejected(RejectReason::Precondition(_)))
+        ));
+    }
+    assert!(is_unauthorized(
+        f.server
+            .run_action(share(GrantScope::Log(f.coach), GrantLevel::RootWrite))
+            .await
+    ));
+
+    // Pending, it lets the coach add nothing.
+    f.grant(GrantScope::Log(f.athlete), GrantLevel::RootWrite, false)
+        .await;
+    let create = CreateEntry {
+        actor_id: f.coach,
+        entry: entry(f.athlete, None, None),
+    };
+    assert!(is_unauthorized(f.server.run_action(create.into()).await));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_activity_grant_covers_its_template(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    f.grant(GrantScope::Activity(f.activity.id), GrantLevel::Write, true)
+        .await;
+
+    assert!(f.coach_can(true, Resource::Activity(&f.activity)).await);
+    assert!(f.coach_can(true, Resource::Entry(&f.template_root)).await);
+    let mut step = entry(f.athlete, None, Some(f.template_root.id));
+    step.is_template = true;
+    f.server
+        .run_action(
+            CreateEntry {
+                actor_id: f.coach,
+                entry: step,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // Logs of the activity are not part of its template.
+    assert!(!f.coach_can(false, Resource::Entry(&f.squat)).await);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_delete_activity_needs_write_on_referencing_entries(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    let activity_grant = f
+        .grant(GrantScope::Activity(f.activity.id), GrantLevel::Write, true)
+        .await;
+    let squat_grant = f
+        .grant(GrantScope::Entry(f.squat.id), GrantLevel::Read, true)
+        .await;
+    let workout_grant = f
+        .grant(GrantScope::Entry(f.workout.id), GrantLevel::Read, false)
+        .await;
+    let delete = |actor_id| DeleteActivity {
+        actor_id,
+        activity_id: f.activity.id,
+        referencing_entries: Some(ReferencingEntries::DeleteRecursive),
+    };
+
+    // The coach may write the activity but not the athlete's logged squat.
+    assert!(f.coach_can(true, Resource::Activity(&f.activity)).await);
+    assert!(is_unauthorized(
+        f.server.run_action(delete(f.coach).into()).await
+    ));
+    // Archiving touches only the activity itself.
+    f.server
+        .run_action(
+            ArchiveActivity {
+                actor_id: f.coach,
+                activity_id: f.activity.id,
+                archived: true,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    f.server.run_action(delete(f.athlete).into()).await.unwrap();
+    let mut conn = f.server.pool.acquire().await.unwrap();
+    let mut athlete = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), f.athlete);
+    for id in [f.template_root.id, f.squat.id] {
+        assert_eq!(
+            athlete
+                .execute(FindEntryById { entry_id: id })
+                .await
+                .unwrap(),
+            None
+        );
+    }
+    assert!(
+        athlete
+            .execute(FindEntryById {
+                entry_id: f.workout.id
+            })
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(
+        athlete
+            .execute(FindValuesForEntry {
+                entry_id: f.squat.id
+            })
+            .await
+            .unwrap(),
+        vec![]
+    );
+
+    // Grants over the deleted activity and entries go with them.
+    let remaining: Vec<Uuid> = athlete
+        .execute(FindGrantsByGrantor {
+            grantor_id: f.athlete,
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|g| g.id)
+        .collect();
+    assert!(!remaining.contains(&activity_grant));
+    assert!(!remaining.contains(&squat_grant));
+    assert_eq!(remaining, vec![workout_grant]);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_delete_activity_needs_write_on_every_deleted_entry(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    let sets_id = Uuid::new_v4();
+    f.server
+        .run_action(
+            ConvertToSets {
+                actor_id: f.athlete,
+                entry_id: f.squat.id,
+                sequence_id: sets_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    f.grant(GrantScope::Activity(f.activity.id), GrantLevel::Write, true)
+        .await;
+    f.grant(GrantScope::Entry(f.squat.id), GrantLevel::Write, true)
+        .await;
+
+    // The squat is writable, but deleting it takes its whole sets sequence,
+    // which the coach was never granted.
+    assert!(f.coach_can(true, Resource::Entry(&f.squat)).await);
+    assert!(is_unauthorized(
+        f.server
+            .run_action(
+                DeleteActivity {
+                    actor_id: f.coach,
+                    activity_id: f.activity.id,
+                    referencing_entries: Some(ReferencingEntries::DeleteRecursive),
+                }
+                .into(),
+            )
+            .await
+    ));
+    let mut conn = f.server.pool.acquire().await.unwrap();
+    assert!(
+        PostgresQueryExecutor::new(&mut conn)
+            .execute(FindEntryById { entry_id: sets_id })
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_update_activity_needs_write(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    let rename = |actor_id, name: &str| -> Action {
+        UpdateActivity {
+            actor_id,
+            activity_id: f.activity.id,
+            change: ActivityChange::SetName(name.to_string()),
+        }
+        .into()
+    };
+    f.grant(GrantScope::Activity(f.activity.id), GrantLevel::Read, true)
+        .await;
+    assert!(is_unauthorized(
+        f.server.run_action(rename(f.coach, "Back squat")).await
+    ));
+
+    // The coach may link their own activity to the one they can read.
+    let copy = Activity {
+        id: Uuid::new_v4(),
+        owner_id: f.coach,
+        name: ActivityName::parse("Back squat".to_string()).unwrap(),
+        ..f.activity.clone()
+    };
+    f.server
+        .run_action(copy.into_create_activity(Uuid::new_v4()).into())
+        .await
+        .unwrap();
+    f.server
+        .run_action(
+            UpdateActivity {
+                actor_id: f.coach,
+                activity_id: copy.id,
+                change: ActivityChange::SetSourceActivityId(Some(f.activity.id)),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    f.server
+        .run_action(rename(f.athlete, "Back squat"))
+        .await
+        .unwrap();
+    let mut conn = f.server.pool.acquire().await.unwrap();
+    let mut coach = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), f.coach);
+    let mut names: Vec<(String, Option<Uuid>)> = coach
+        .execute(AllActivities {})
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|a| (a.name.to_string(), a.source_activity_id))
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            ("Back squat".to_string(), None),
+            ("Back squat".to_string(), Some(f.activity.id)),
+        ]
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_revoked_grant_no_longer_applies(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+
+    // Revoked by the grantor.
+    let grant_id = f
+        .grant(GrantScope::Entry(f.workout.id), GrantLevel::Write, true)
+        .await;
+    f.server.run_action(f.complete_squat()).await.unwrap();
+    f.server
+        .run_action(
+            RevokeGrant {
+                actor_id: f.athlete,
+                grant_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert!(is_unauthorized(
+        f.server.run_action(f.complete_squat()).await
+    ));
+
+    // Given up by the grantee.
+    let grant_id = f
+        .grant(GrantScope::Entry(f.workout.id), GrantLevel::Write, true)
+        .await;
+    assert!(f.coach_can(true, Resource::Entry(&f.squat)).await);
+    f.server
+        .run_action(
+            RevokeGrant {
+                actor_id: f.coach,
+                grant_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert!(!f.coach_can(false, Resource::Entry(&f.squat)).await);
+
+    let mut conn = f.server.pool.acquire().await.unwrap();
+    let found = PostgresQueryExecutor::new(&mut conn)
+        .execute(FindGrantById { grant_id })
+        .await
+        .unwrap();
+    assert_eq!(found, None);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_grant_parties(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    let stranger = user();
+    f.server
+        .run_action(CreateUser::from(stranger.clone()).into())
+        .await
+        .unwrap();
+
+    // Only the owner can share, and not with themselves.
+    let share = |actor_id, grantee_id| {
+        Action::from(GrantPermission {
+            actor_id,
+            grant_id: Uuid::new_v4(),
+            grantee_id,
+            scope: GrantScope::Entry(f.workout.id),
+            level: GrantLevel::Read,
+        })
+    };
+    assert!(is_unauthorized(
+        f.server.run_action(share(f.coach, stranger.actor_id)).await
+    ));
+    assert!(matches!(
+        f.server.run_action(share(f.athlete, f.athlete)).await,
+        Err(DomainError::Rejected(RejectReason::Precondition(_)))
+    ));
+
+    // Only the grantee can accept, and only the parties can revoke.
+    let grant_id = f
+        .grant(GrantScope::Entry(f.workout.id), GrantLevel::Read, false)
+        .await;
+    for actor_id in [f.athlete, stranger.actor_id] {
+        let accept = AcceptGrant { actor_id, grant_id };
+        assert!(is_unauthorized(f.server.run_action(accept.into()).await));
+    }
+    let revoke = RevokeGrant {
+        actor_id: stranger.actor_id,
+        grant_id,
+    };
+    assert!(is_unauthorized(f.server.run_action(revoke.into()).await));
+
+    let mut conn = f.server.pool.acquire().await.unwrap();
+    let mut executor = PostgresQueryExecutor::new(&mut conn);
+    let offered = executor
+        .execute(FindGrantsForGrantee {
+            grantee_id: f.coach,
+        })
+        .await
+        .unwrap();
+    assert_eq!(offered.len(), 1);
+    assert!(!offered[0].accepted);
+    let given = executor
+        .execute(FindGrantsByGrantor {
+            grantor_id: f.athlete,
+        })
+        .await
+        .unwrap();
+    assert_eq!(given, offered);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_grant_changes_are_not_undoable(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    let mutation = f
+        .server
+        .run_action(
+            GrantPermission {
+                actor_id: f.athlete,
+                grant_id: Uuid::new_v4(),
+                grantee_id: f.coach,
+                scope: GrantScope::Attribute(f.weight.id),
+                level: GrantLevel::Read,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert!(!mutation.is_undoable());
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_queries_only_return_what_the_actor_can_read(pool: PgPool) {
+    let f = Fixture::new(pool).await;
+    let other_workout = entry(f.athlete, None, None);
+    f.server
+        .run_action(CreateEntry::from(other_workout.clone()).into())
+        .await
+        .unwrap();
+
+    let mut conn = f.server.pool.acquire().await.unwrap();
+    let ids = |entries: Vec<Entry>| {
+        let mut ids: Vec<Uuid> = entries.into_iter().map(|e| e.id).collect();
+        ids.sort();
+        ids
+    };
+
+    // Nothing shared yet.
+    let mut coach = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), f.coach);
+    assert_eq!(coach.execute(AllEntries {}).await.unwrap(), vec![]);
+    assert_eq!(coach.execute(AllActivities {}).await.unwrap(), vec![]);
+    assert_eq!(coach.execute(AllAttributes {}).await.unwrap(), vec![]);
+    let find_squat = FindEntryById {
+        entry_id: f.squat.id,
+    };
+    assert_eq!(coach.execute(find_squat.clone()).await.unwrap(), None);
+    drop(coach);
+
+    // A pending grant still shares nothing; the accepted one shares the
+    // workout, its activity and the attribute it uses.
+    let grant_id = f
+        .grant(GrantScope::Entry(f.workout.id), GrantLevel::Read, false)
+        .await;
+    let mut coach = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), f.coach);
+    assert_eq!(coach.execute(AllEntries {}).await.unwrap(), vec![]);
+    drop(coach);
+    f.server
+        .run_action(
+            AcceptGrant {
+                actor_id: f.coach,
+                grant_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    let mut coach = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), f.coach);
+    let mut shared = vec![f.workout.id, f.squat.id];
+    shared.sort();
+    assert_eq!(ids(coach.execute(AllEntries {}).await.unwrap()), shared);
+    assert_eq!(
+        coach.execute(find_squat).await.unwrap(),
+        Some(f.squat.clone())
+    );
+    assert_eq!(
+        coach.execute(AllActivities {}).await.unwrap(),
+        vec![f.activity.clone()]
+    );
+    assert_eq!(
+        coach.execute(AllAttributes {}).await.unwrap(),
+        vec![f.weight.clone()]
+    );
+    let values = coach
+        .execute(FindValuesForEntry {
+            entry_id: f.squat.id,
+        })
+        .await
+        .unwrap();
+    assert_eq!(values.len(), 1);
+    assert_eq!(
+        coach
+            .execute(DistinctTextValuesForAttribute {
+                attribute_id: f.reps.id,
+            })
+            .await
+            .unwrap(),
+        Vec::<String>::new()
+    );
+    drop(coach);
+
+    // The athlete still sees everything of theirs, including the template.
+    let mut athlete = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), f.athlete);
+    let mut own = vec![
+        f.template_root.id,
+        f.workout.id,
+        f.squat.id,
+        other_workout.id,
+    ];
+    own.sort();
+    assert_eq!(ids(athlete.execute(AllEntries {}).await.unwrap()), own);
+}
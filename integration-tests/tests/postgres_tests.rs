@@ -3,10 +3,26 @@ use std::sync::Arc;
 use fractional_index::FractionalIndex;
 use generation::{Arbitrary, GenerationContext, SimulationContext, io::SimIo, model::Model};
 use gv_core::{
-    actions::{Action, CreateUser, MoveEntry},
+    actions::{
+        Action, CreateAttribute, CreateEntry, CreateUser, CreateValue, MoveEntry, Redo, Undo,
+        ValueField,
+    },
+    analytics::{
+        Aggregate, AnalyticsQuery, AttributeField, AttributePredicate, Comparison, EntryFilter,
+    },
     error::{DomainError, RejectReason},
-    models::entry::{Entry, Position, Temporal},
-    queries::SnapshotAll,
+    models::{
+        attribute::{
+            Attribute, AttributeConfig, AttributeValue, DerivedConfig, NumericConfig, NumericValue,
+            SelectConfig, SelectValue, Value,
+        },
+        derived::DerivedExpr,
+        entry::{Entry, Position, Temporal},
+    },
+    mutation_log::{CommittedMutation, SyncOffset},
+    queries::{
+        FindEntryById, FindValueByKey, LatestSeqNum, MutationsSince, RunAnalytics, SnapshotAll,
+    },
     query_executor::QueryExecutor,
 };
 use gv_server::server::PostgresServer;
@@ -74,6 +90,341 @@ async fn test_move_entry_disallows_cycles(pool: PgPool) {
     ));
 }
 
+/// The server replays an undo or redo from its own log, and only for the
+/// mutation's author.
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_undo_replays_the_logged_mutation(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let mut rng = rand::rng();
+    let context = SimulationContext::default();
+    let create_user = CreateUser::arbitrary(&mut rng, &context);
+    let actor_id = create_user.user.actor_id;
+    server.commit_action(create_user.into()).await.unwrap();
+    let create_other = CreateUser::arbitrary(&mut rng, &context);
+    let other_id = create_other.user.actor_id;
+    server.commit_action(create_other.into()).await.unwrap();
+
+    let mut entry = Entry::arbitrary(&mut rng, &context);
+    entry.owner_id = actor_id;
+    entry.is_template = true;
+    entry.activity_id = None;
+    entry.position = None;
+    entry.temporal = Temporal::None;
+    let create = server
+        .commit_action(Action::CreateEntry(entry.clone().into()))
+        .await
+        .unwrap();
+    let mutation_id = create.mutation.id;
+    let find = |server: &PostgresServer| {
+        let pool = server.pool.clone();
+        async move {
+            let mut conn = pool.acquire().await.unwrap();
+            PostgresQueryExecutor::new(&mut conn)
+                .execute(FindEntryById { entry_id: entry.id })
+                .await
+                .unwrap()
+        }
+    };
+
+    let result = server
+        .commit_action(
+            Undo {
+                actor_id: other_id,
+                mutation_id,
+            }
+            .into(),
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(DomainError::Rejected(RejectReason::Unauthorized(_)))
+    ));
+
+    server
+        .commit_action(
+            Undo {
+                actor_id,
+                mutation_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(find(&server).await, None);
+    server
+        .commit_action(
+            Redo {
+                actor_id,
+                mutation_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert!(find(&server).await.is_some());
+
+    let result = server
+        .commit_action(
+            Undo {
+                actor_id,
+                mutation_id: uuid::Uuid::new_v4(),
+            }
+            .into(),
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(DomainError::Rejected(RejectReason::NotFound(_)))
+    ));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_reindex_values_backfills_unindexed_rows(pool: PgPool) {
+    let server = PostgresServer::new(pool.clone());
+    let mut rng = rand::rng();
+    let context = SimulationContext::default();
+    let create_user = CreateUser::arbitrary(&mut rng, &context);
+    let actor_id = create_user.user.actor_id;
+    server.commit_action(create_user.into()).await.unwrap();
+
+    let mut entry = Entry::arbitrary(&mut rng, &context);
+    entry.owner_id = actor_id;
+    entry.is_template = false;
+    entry.activity_id = None;
+    entry.position = None;
+    entry.temporal = Temporal::None;
+    server
+        .commit_action(Action::CreateEntry(entry.clone().into()))
+        .await
+        .unwrap();
+    let grade = Attribute {
+        id: uuid::Uuid::new_v4(),
+        owner_id: actor_id,
+        name: "Grade".to_string(),
+        description: None,
+        config: AttributeConfig::Select(SelectConfig {
+            options: vec!["V1".to_string(), "V2".to_string()],
+            ordered: true,
+            default: None,
+        }),
+    };
+    server
+        .commit_action(CreateAttribute::from(grade.clone()).into())
+        .await
+        .unwrap();
+    let value = Value {
+        entry_id: entry.id,
+        attribute_id: grade.id,
+        index_float: None,
+        index_string: None,
+        plan: None,
+        actual: Some(AttributeValue::Select(SelectValue::Exact("V2".to_string()))),
+    };
+    server
+        .commit_action(CreateValue { actor_id, value }.into())
+        .await
+        .unwrap();
+
+    // Stand in for rows written before values were indexed.
+    sqlx::query("UPDATE attribute_values SET index_float = NULL, index_string = NULL")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let index = async || {
+        let mut conn = pool.acquire().await.unwrap();
+        let value = PostgresQueryExecutor::new(&mut conn)
+            .execute(FindValueByKey {
+                entry_id: entry.id,
+                attribute_id: grade.id,
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        (value.index_float, value.index_string)
+    };
+    assert_eq!(index().await, (None, None));
+
+    assert_eq!(server.reindex_values().await.unwrap(), 1);
+    assert_eq!(index().await, (Some(1.0), Some("V2".to_string())));
+    assert_eq!(server.reindex_values().await.unwrap(), 0);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_analytics_filters_in_sql_match_the_model(pool: PgPool) {
+    let server = PostgresServer::new(pool.clone());
+    let create_user = CreateUser::arbitrary(&mut rand::rng(), &SimulationContext::default());
+    let actor_id = create_user.user.actor_id;
+    server.commit_action(create_user.into()).await.unwrap();
+    let attribute = |name: &str, config: AttributeConfig| Attribute {
+        id: uuid::Uuid::new_v4(),
+        owner_id: actor_id,
+        name: name.to_string(),
+        description: None,
+        config,
+    };
+    let reps = attribute(
+        "Reps",
+        AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }),
+    );
+    let total = attribute(
+        "Total Reps",
+        AttributeConfig::Derived(DerivedConfig {
+            expression: DerivedExpr::attribute(reps.id).sum_descendants(),
+        }),
+    );
+    let mut actions: Vec<Action> = vec![
+        CreateAttribute::from(reps.clone()).into(),
+        CreateAttribute::from(total.clone()).into(),
+    ];
+    // The second session is timed by its end and duration, so it starts on
+    // March 8 but ends on March 9. Sets take their session's time.
+    for (temporal, set_reps) in [
+        (
+            Temporal::Start {
+                start: "2026-03-02T10:00:00Z".parse().unwrap(),
+            },
+            vec![5.0, 8.0],
+        ),
+        (
+            Temporal::DurationAndEnd {
+                duration_ms: 2 * 60 * 60 * 1000,
+                end: "2026-03-09T01:00:00Z".parse().unwrap(),
+            },
+            vec![3.0, 11.0],
+        ),
+        (
+            Temporal::Start {
+                start: "2026-03-20T10:00:00Z".parse().unwrap(),
+            },
+            vec![12.0],
+        ),
+    ] {
+        let session = Entry {
+            id: uuid::Uuid::new_v4(),
+            activity_id: None,
+            name: None,
+            owner_id: actor_id,
+            position: None,
+            display_as_sets: false,
+            is_sequence: true,
+            is_complete: true,
+            is_template: false,
+            temporal,
+        };
+        actions.push(CreateEntry::from(session.clone()).into());
+        let mut frac_index = FractionalIndex::default();
+        for n in set_reps {
+            let set = Entry {
+                id: uuid::Uuid::new_v4(),
+                position: Some(Position {
+                    parent_id: session.id,
+                    frac_index: frac_index.clone(),
+                }),
+                is_sequence: false,
+                is_complete: false,
+                temporal: Temporal::None,
+                ..session.clone()
+            };
+            frac_index = FractionalIndex::new_after(&frac_index);
+            actions.push(CreateEntry::from(set.clone()).into());
+            actions.push(
+                CreateValue {
+                    actor_id,
+                    value: Value {
+                        entry_id: set.id,
+                        attribute_id: reps.id,
+                        index_float: None,
+                        index_string: None,
+                        plan: None,
+                        actual: Some(AttributeValue::Numeric(NumericValue::Exact(n))),
+                    },
+                }
+                .into(),
+            );
+        }
+    }
+    for action in actions {
+        server.commit_action(action).await.unwrap();
+    }
+    let mut conn = pool.acquire().await.unwrap();
+    let mut executor = PostgresQueryExecutor::new(&mut conn);
+
+    let window = |from: &str, to: &str| EntryFilter::TimeWindow {
+        from: from.parse().unwrap(),
+        to: to.parse().unwrap(),
+    };
+    let at_least_five = EntryFilter::Attribute(AttributePredicate {
+        target: AttributeField {
+            attribute_id: reps.id,
+            field: ValueField::Actual,
+        },
+        comparison: Comparison::Ge,
+        operand: AttributeValue::Numeric(NumericValue::Exact(5.0)),
+    });
+    let field = |attribute: &Attribute| AttributeField {
+        attribute_id: attribute.id,
+        field: ValueField::Actual,
+    };
+    let model = Model::from_snapshot(executor.execute(SnapshotAll).await.unwrap());
+    for (query, expected) in [
+        // Sets of 5 and 8 on March 2 and of 11 on March 8.
+        (
+            AnalyticsQuery {
+                filters: vec![
+                    window("2026-03-01T00:00:00Z", "2026-03-09T00:00:00Z"),
+                    at_least_five.clone(),
+                ],
+                group_by: None,
+                aggregate: Aggregate::Count,
+            },
+            Some(3.0),
+        ),
+        // The second session ends in this window but starts before it.
+        (
+            AnalyticsQuery {
+                filters: vec![
+                    window("2026-03-09T00:00:00Z", "2026-03-21T00:00:00Z"),
+                    at_least_five,
+                ],
+                group_by: None,
+                aggregate: Aggregate::Sum(field(&reps)),
+            },
+            Some(12.0),
+        ),
+        // Session totals sum their sets, which the filters don't match.
+        (
+            AnalyticsQuery {
+                filters: vec![
+                    EntryFilter::Complete(true),
+                    window("2026-03-01T00:00:00Z", "2026-03-09T00:00:00Z"),
+                    EntryFilter::Attribute(AttributePredicate {
+                        target: field(&total),
+                        comparison: Comparison::Gt,
+                        operand: AttributeValue::Numeric(NumericValue::Exact(0.0)),
+                    }),
+                ],
+                group_by: None,
+                aggregate: Aggregate::Max(field(&total)),
+            },
+            Some(14.0),
+        ),
+    ] {
+        let rows = executor
+            .execute(RunAnalytics {
+                query: query.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(rows[0].value, expected, "{query:?}");
+        assert_eq!(model.run_analytics(&query).unwrap(), rows);
+    }
+}
+
 #[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
 async fn test_arbitrary_create_user(pool: PgPool) {
     let server = PostgresServer::new(pool);
@@ -156,6 +507,120 @@ async fn test_arbitrary_actions(pool: PgPool) {
     }
 }
 
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_mutation_log_assigns_global_seq_nums(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let mut rng = rand::rng();
+    let context = SimulationContext::default();
+
+    let create_user = CreateUser::arbitrary(&mut rng, &context);
+    let actor_id = create_user.user.actor_id;
+    let first = server.commit_action(create_user.into()).await.unwrap();
+
+    // A rejected action rolls back without consuming a seq_num.
+    let mut orphan = Entry::arbitrary(&mut rng, &context);
+    orphan.owner_id = actor_id;
+    orphan.position = Some(Position {
+        parent_id: uuid::Uuid::new_v4(),
+        frac_index: FractionalIndex::default(),
+    });
+    assert!(matches!(
+        server
+            .commit_action(Action::CreateEntry(orphan.into()))
+            .await,
+        Err(DomainError::Rejected(RejectReason::NotFound(_)))
+    ));
+
+    let mut entry = Entry::arbitrary(&mut rng, &context);
+    entry.owner_id = actor_id;
+    entry.activity_id = None;
+    entry.position = None;
+    entry.is_template = false;
+    entry.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
+    };
+    let second = server
+        .commit_action(Action::CreateEntry(entry.into()))
+        .await
+        .unwrap();
+    assert_eq!(second.seq_num, first.seq_num + 1);
+
+    let mut conn = server.pool.acquire().await.unwrap();
+    let mut executor = PostgresQueryExecutor::new(&mut conn);
+    assert_eq!(
+        executor.execute(LatestSeqNum).await.unwrap(),
+        second.seq_num
+    );
+    let log = executor
+        .execute(MutationsSince {
+            after_seq_num: 0,
+            limit: 10,
+        })
+        .await
+        .unwrap();
+    // Postgres stores the timestamp at microsecond precision, so compare the
+    // rest of the mutation.
+    let key = |c: &CommittedMutation| {
+        (
+            c.seq_num,
+            c.mutation.id,
+            c.mutation.action.clone(),
+            c.mutation.changes.clone(),
+        )
+    };
+    assert_eq!(
+        log.iter().map(key).collect::<Vec<_>>(),
+        vec![key(&first), key(&second)]
+    );
+    let since_first = executor
+        .execute(MutationsSince {
+            after_seq_num: first.seq_num,
+            limit: 10,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        since_first.iter().map(key).collect::<Vec<_>>(),
+        vec![key(&second)]
+    );
+
+    let stamped = second.stamped_changes();
+    assert_eq!(stamped.len(), second.mutation.changes.len());
+    for (i, s) in stamped.iter().enumerate() {
+        assert_eq!(
+            s.offset,
+            SyncOffset {
+                seq_num: second.seq_num,
+                op_offset: i as u32,
+            }
+        );
+    }
+}
+
+/// Concurrent commits each get a distinct seq_num and together fill 1..=N
+/// with no gaps.
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_concurrent_commits_get_contiguous_seq_nums(pool: PgPool) {
+    let server = Arc::new(PostgresServer::new(pool));
+    let mut rng = rand::rng();
+    let context = SimulationContext::default();
+
+    let mut handles = Vec::new();
+    for _ in 0..16 {
+        let server = server.clone();
+        let action: Action = CreateUser::arbitrary(&mut rng, &context).into();
+        handles.push(tokio::spawn(
+            async move { server.commit_action(action).await },
+        ));
+    }
+    let mut seq_nums = Vec::new();
+    for handle in handles {
+        seq_nums.push(handle.await.unwrap().unwrap().seq_num);
+    }
+    seq_nums.sort();
+    assert_eq!(seq_nums, (1..=16).collect::<Vec<i64>>());
+}
+
 /// Short variant name for an `Action`, for scannable per-action logging.
 fn action_kind(action: &Action) -> &'static str {
     match action {
@@ -175,5 +640,15 @@ fn action_kind(action: &Action) -> &'static str {
         Action::UpdateEntry(_) => "UpdateEntry",
         Action::ConvertToSets(_) => "ConvertToSets",
         Action::DuplicateEntry(_) => "DuplicateEntry",
+        Action::Undo(_) => "Undo",
+        Action::Redo(_) => "Redo",
+        Action::GrantPermission(_) => "GrantPermission",
+        Action::AcceptGrant(_) => "AcceptGrant",
+        Action::RevokeGrant(_) => "RevokeGrant",
+        Action::DeleteActivity(_) => "DeleteActivity",
+        Action::ArchiveActivity(_) => "ArchiveActivity",
+        Action::UpdateActivity(_) => "UpdateActivity",
+        Action::AttributeMigration(_) => "AttributeMigration",
+        Action::ConvertAttributeType(_) => "ConvertAttributeType",
     }
 }
@@ -1,5 +1,8 @@
 use generation::{Arbitrary, SimulationContext};
-use gv_core::models::attribute::{MassConfig, MassMeasurement, MassUnit, MassValue};
+use gv_core::models::attribute::{
+    MassConfig, MassMeasurement, MassUnit, MassValue, SpeedConfig, SpeedMeasurement, SpeedUnit,
+    SpeedValue,
+};
 use gv_core::models::entry::Entry;
 use gv_core::models::user::User;
 use gv_core::validation::Username;
@@ -126,6 +129,62 @@ fn mass_value_converted_to_stays_valid(tc: TestCase) {
     cfg.validate_value(&value.converted_to(to)).unwrap();
 }
 
+fn speed_unit(tc: &TestCase) -> SpeedUnit {
+    tc.draw(gs::sampled_from(vec![
+        SpeedUnit::MetersPerSecond,
+        SpeedUnit::KilometersPerHour,
+        SpeedUnit::MilesPerHour,
+        SpeedUnit::MinutesPerKilometer,
+        SpeedUnit::MinutesPerMile,
+    ]))
+}
+
+#[hegel::test]
+fn speed_unit_conversion_round_trip(tc: TestCase) {
+    let from = speed_unit(&tc);
+    let to = speed_unit(&tc);
+    // Pace ↔ speed legs invert the magnitude, so the bound applies at both
+    // ends: tiny magnitudes would invert past any physical speed (and toward
+    // infinity), so they're remapped to the zero seed, which maps to itself.
+    let v = tc.draw(gs::floats::<f64>().min_value(0.0).max_value(1e6));
+    let v = if v < 1e-6 { 0.0 } else { v };
+    let back = to.convert(from.convert(v, &to), &from);
+    assert!(
+        (back - v).abs() <= v * 1e-12,
+        "{v} {from:?} -> {to:?} -> {back}"
+    );
+}
+
+#[hegel::test]
+fn speed_value_converted_to_stays_valid(tc: TestCase) {
+    let cfg = SpeedConfig {
+        default_unit: SpeedUnit::KilometersPerHour,
+    };
+    let from = speed_unit(&tc);
+    let to = speed_unit(&tc);
+    let magnitude = |tc: &TestCase| {
+        tc.draw(gs::integers::<i64>().min_value(0).max_value(100_000)) as f64 / 100.0
+    };
+    let a = magnitude(&tc);
+    let b = magnitude(&tc);
+    let value = if tc.draw(gs::booleans()) {
+        SpeedValue::Exact(SpeedMeasurement {
+            unit: from,
+            value: a,
+        })
+    } else {
+        SpeedValue::Range {
+            unit: from,
+            min: a.min(b),
+            max: a.max(b),
+        }
+    };
+    cfg.validate_value(&value).unwrap();
+    // Conversion keeps a valid value valid even across the pace ↔ speed
+    // inversion: finite, non-negative, 2-decimal, range endpoints ordered.
+    cfg.validate_value(&value.converted_to(to)).unwrap();
+}
+
 #[hegel::test]
 fn entry_round_trip(tc: TestCase) {
     let _ = tracing_subscriber::registry()
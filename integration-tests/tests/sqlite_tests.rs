@@ -1,27 +1,44 @@
 use fractional_index::FractionalIndex;
-use generation::{Arbitrary, SimulationContext};
+use generation::{Arbitrary, SimulationContext, model::Model};
 use gv_client::client::SqliteClient;
 use gv_core::{
     actions::{
-        Action, AttachValue, AttributeChange, ConvertToSets, CreateActivity, CreateAttribute,
-        CreateEntry, CreateEntryFromActivity, CreateUser, CreateValue, DeleteAttributeValue,
-        DeleteEntryRecursive, DuplicateEntry, EntryChange, MassChange, MoveEntry, NumericChange,
-        SelectChange, UpdateAttribute, UpdateAttributeValue, UpdateEntry, ValueField,
+        Action, ActivityChange, ArchiveActivity, AttachValue, AttributeChange, AttributeConversion,
+        AttributeMigration, ConfigMigration, ConvertAttributeType, ConvertToSets, CreateActivity,
+        CreateAttribute, CreateEntry, CreateEntryFromActivity, CreateUser, CreateValue,
+        DeleteActivity, DeleteAttributeValue, DeleteEntryRecursive, DerivedChange, DuplicateEntry,
+        EntryChange, MassChange, MoveEntry, MultiselectChange, NumericChange, OptionRemoval,
+        OutOfRange, Redo, ReferencingEntries, SelectChange, Undo, UpdateActivity, UpdateAttribute,
+        UpdateAttributeValue, UpdateEntry, UpdateEntryCompletion, ValueField,
     },
+    analytics::{
+        Aggregate, AnalyticsQuery, AttributeField, AttributePredicate, Comparison, EntryFilter,
+        GroupKey, Grouping, Period,
+    },
+    error::{DomainError, RejectReason, ValidationError},
     models::{
         activity::{Activity, ActivityName},
         attribute::{
-            Attribute, AttributeConfig, AttributeValue, MassConfig, MassMeasurement, MassUnit,
-            MassValue, NumericConfig, NumericValue, SelectConfig, SelectValue, Value,
+            Attribute, AttributeConfig, AttributeValue, DerivedConfig, GradeConfig, LengthUnit,
+            MassConfig, MassMeasurement, MassUnit, MassValue, MultiselectConfig, NumericConfig,
+            NumericValue, SelectConfig, SelectValue, TextConfig, Value,
         },
+        attribute_pair::AttributePair,
+        derived::DerivedExpr,
         entry::{Entry, Position, Temporal},
+        grade::GradeScale,
         user::User,
     },
+    mutation_log::MutationState,
     queries::{
-        AllEntries, FindAttributeById, FindDescendants, FindEntryById, FindValueByKey,
-        FindValuesForEntries,
+        AllActivities, AllAttributes, AllEntries, AttributeSeries, FindActivityById,
+        FindAttributeById, FindDescendants, FindEntryById, FindEntryJoinById, FindValueByKey,
+        FindValuesForEntries, MutationHistory, PendingMutations, PersonalRecords, RunAnalytics,
+        SnapshotAll, TrainingLoad,
     },
-    query_executor::QueryExecutor,
+    query_executor::{QueryExecutor, ScopedQueryExecutor},
+    series::{SeriesAggregate, SeriesBucket, SeriesRange, SeriesUnit},
+    training_load::LoadWindow,
     validation::{Email, Username},
 };
 use gv_sql::sqlite::SqliteQueryExecutor;
@@ -845,506 +862,2506 @@ async fn test_update_attribute_defaults(pool: SqlitePool) {
     assert_eq!(read_attribute(&sqlite_client, mass.id).await.name, "Weight");
 }
 
-#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_update_attribute_noop(pool: SqlitePool) {
-    let sqlite_client =
-        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
-    let user = create_user(&sqlite_client).await;
+/// Create `attribute` and give `entry` a value of it with `plan` and `actual`.
+async fn seed_attribute_value(
+    client: &SqliteClient,
+    owner_id: Uuid,
+    entry_id: Uuid,
+    attribute: &Attribute,
+    plan: Option<AttributeValue>,
+    actual: Option<AttributeValue>,
+) {
+    run_actions(
+        client,
+        [
+            CreateAttribute::from(attribute.clone()).into(),
+            CreateValue {
+                actor_id: owner_id,
+                value: Value {
+                    entry_id,
+                    attribute_id: attribute.id,
+                    index_float: None,
+                    index_string: None,
+                    plan,
+                    actual,
+                },
+            }
+            .into(),
+        ],
+    )
+    .await;
+}
 
-    // --- No-op: re-applying the same value emits no deltas.
-    let numeric = Attribute {
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_update_attribute_numeric_bounds(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let (user, entry) = seed_entry(&client).await;
+    let weight = Attribute {
         id: Uuid::new_v4(),
         owner_id: user.actor_id,
-        name: "Reps".to_string(),
+        name: "Bodyweight".to_string(),
         description: None,
         config: AttributeConfig::Numeric(NumericConfig {
-            min: None,
-            max: None,
+            min: Some(40.0),
+            max: Some(100.0),
             integer: false,
-            default: Some(5.0),
+            default: None,
         }),
     };
-    sqlite_client
-        .run_action(CreateAttribute::from(numeric.clone()).into())
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        entry.id,
+        &weight,
+        Some(AttributeValue::Numeric(NumericValue::Exact(80.0))),
+        Some(AttributeValue::Numeric(NumericValue::Exact(80.5))),
+    )
+    .await;
+    let update = |change| -> Action {
+        UpdateAttribute {
+            actor_id: user.actor_id,
+            attribute_id: weight.id,
+            change: AttributeChange::Numeric(change),
+        }
+        .into()
+    };
+    let precondition = |r: gv_core::error::Result<_>| {
+        matches!(r, Err(DomainError::Rejected(RejectReason::Precondition(_))))
+    };
+
+    // Bounds only ever widen.
+    assert!(precondition(
+        client
+            .run_action(update(NumericChange::RaiseMax(Some(90.0))))
+            .await
+    ));
+    assert!(precondition(
+        client
+            .run_action(update(NumericChange::LowerMin(Some(50.0))))
+            .await
+    ));
+    client
+        .run_action(update(NumericChange::RaiseMax(Some(150.0))))
+        .await
+        .unwrap();
+    client
+        .run_action(update(NumericChange::LowerMin(None)))
         .await
         .unwrap();
+    let config = read_attribute(&client, weight.id)
+        .await
+        .as_numeric()
+        .cloned()
+        .unwrap();
+    assert_eq!((config.min, config.max), (None, Some(150.0)));
+    assert!(precondition(
+        client
+            .run_action(update(NumericChange::RaiseMax(Some(120.0))))
+            .await
+    ));
 
-    // Call the mutator directly so we can inspect the produced deltas.
-    let mutation = {
-        let mut conn = sqlite_client.pool.acquire().await.unwrap();
-        let mut executor = SqliteQueryExecutor::new(&mut *conn);
-        gv_core::mutators::update_attribute(
-            &mut executor,
-            &gv_core::io::SystemIo::default(),
-            UpdateAttribute {
+    // The stored 80.5 blocks requiring whole numbers until it is rounded.
+    assert!(precondition(
+        client
+            .run_action(update(NumericChange::SetInteger(true)))
+            .await
+    ));
+    client
+        .run_action(
+            UpdateAttributeValue {
                 actor_id: user.actor_id,
-                attribute_id: numeric.id,
-                change: AttributeChange::Numeric(NumericChange::SetDefault(Some(5.0))),
-            },
+                entry_id: entry.id,
+                attribute_id: weight.id,
+                field: ValueField::Actual,
+                value: Some(AttributeValue::Numeric(NumericValue::Exact(81.0))),
+            }
+            .into(),
         )
         .await
-        .unwrap()
-    };
+        .unwrap();
+    let mutation = client
+        .run_action(update(NumericChange::SetInteger(true)))
+        .await
+        .unwrap();
+    assert_eq!(mutation.changes.len(), 1, "no values rewritten");
     assert!(
-        mutation.changes.is_empty(),
-        "setting the default to its current value must be a no-op"
+        read_attribute(&client, weight.id)
+            .await
+            .as_numeric()
+            .unwrap()
+            .integer
     );
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_template_temporal_rules(pool: SqlitePool) {
-    let sqlite_client =
-        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
-    let user = create_user(&sqlite_client).await;
-
-    let activity = Activity {
+async fn test_update_attribute_options(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let (user, entry) = seed_entry(&client).await;
+    let strings = |options: &[&str]| options.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+    let grade = Attribute {
         id: Uuid::new_v4(),
         owner_id: user.actor_id,
-        name: ActivityName::parse("Squat".to_string()).unwrap(),
+        name: "Grade".to_string(),
         description: None,
-        source_activity_id: None,
+        config: AttributeConfig::Select(SelectConfig {
+            options: strings(&["V1", "V2", "V4"]),
+            ordered: true,
+            default: Some("V2".to_string()),
+        }),
     };
-    sqlite_client
-        .run_action(activity.clone().into_create_activity(Uuid::new_v4()).into())
-        .await
-        .unwrap();
-
-    let template_entry = |temporal: Temporal| Entry {
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        entry.id,
+        &grade,
+        Some(AttributeValue::Select(SelectValue::Range {
+            min: "V1".to_string(),
+            max: "V2".to_string(),
+        })),
+        Some(AttributeValue::Select(SelectValue::Exact("V2".to_string()))),
+    )
+    .await;
+    let holds = Attribute {
         id: Uuid::new_v4(),
-        activity_id: Some(activity.id),
         owner_id: user.actor_id,
-        name: None,
-        position: None,
-        is_template: true,
-        display_as_sets: false,
-        is_sequence: false,
-        is_complete: false,
-        temporal,
+        name: "Holds".to_string(),
+        description: None,
+        config: AttributeConfig::Multiselect(MultiselectConfig {
+            options: strings(&["Crimp", "Slopr"]),
+            default: None,
+        }),
+    };
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        entry.id,
+        &holds,
+        None,
+        Some(AttributeValue::Multiselect(strings(&["Slopr", "Crimp"]))),
+    )
+    .await;
+    let update = |attribute_id, change| -> Action {
+        UpdateAttribute {
+            actor_id: user.actor_id,
+            attribute_id,
+            change,
+        }
+        .into()
+    };
+    let value = async |attribute_id| {
+        client
+            .run_query(
+                user.actor_id,
+                FindValueByKey {
+                    entry_id: entry.id,
+                    attribute_id,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap()
     };
 
-    // create_entry: a template entry with a start time is rejected...
+    // Insert a grade mid-scale; the stored range keeps its order.
+    client
+        .run_action(update(
+            grade.id,
+            AttributeChange::Select(SelectChange::AddOption {
+                option: "V3".to_string(),
+                position: Some(2),
+            }),
+        ))
+        .await
+        .unwrap();
     assert!(
-        sqlite_client
-            .run_action(
-                CreateEntry::from(template_entry(Temporal::Start {
-                    start: sqlx::types::chrono::Utc::now()
-                }))
-                .into(),
-            )
+        client
+            .run_action(update(
+                grade.id,
+                AttributeChange::Select(SelectChange::AddOption {
+                    option: "V1".to_string(),
+                    position: None,
+                }),
+            ))
             .await
             .is_err(),
-        "template entry with a start time must be rejected"
+        "duplicate option must be rejected"
     );
-    // ...but duration-only and None are allowed.
-    sqlite_client
-        .run_action(
-            CreateEntry::from(template_entry(Temporal::Duration { duration: 60_000 })).into(),
-        )
+
+    // Renaming rewrites the config default and both stored fields.
+    let rename = client
+        .run_action(update(
+            grade.id,
+            AttributeChange::Select(SelectChange::RenameOption {
+                from: "V2".to_string(),
+                to: "V2+".to_string(),
+            }),
+        ))
         .await
         .unwrap();
-    let none_template = template_entry(Temporal::None);
-    let none_template_id = none_template.id;
-    sqlite_client
-        .run_action(CreateEntry::from(none_template.clone()).into())
+    assert_eq!(rename.changes.len(), 2, "attribute + one value");
+    let config = read_attribute(&client, grade.id)
         .await
+        .expect_select()
+        .cloned()
         .unwrap();
-
-    // move_entry: giving a template a start time is rejected.
-    assert!(
-        sqlite_client
-            .run_action(
-                MoveEntry {
-                    actor_id: user.actor_id,
-                    entry_id: none_template_id,
-                    position: None,
-                    temporal: Temporal::Start {
-                        start: sqlx::types::chrono::Utc::now()
-                    },
-                }
-                .into(),
-            )
-            .await
-            .is_err(),
-        "moving a template to a start-timed temporal must be rejected"
+    assert_eq!(config.options, strings(&["V1", "V2+", "V3", "V4"]));
+    assert_eq!(config.default.as_deref(), Some("V2+"));
+    let stored = value(grade.id).await;
+    assert_eq!(
+        stored.plan,
+        Some(AttributeValue::Select(SelectValue::Range {
+            min: "V1".to_string(),
+            max: "V2+".to_string(),
+        }))
+    );
+    assert_eq!(
+        stored.actual,
+        Some(AttributeValue::Select(SelectValue::Exact(
+            "V2+".to_string()
+        )))
     );
 
-    // Sanity: a log entry at root with no start/end is still rejected.
-    let log_root = Entry {
-        id: Uuid::new_v4(),
-        activity_id: None,
-        owner_id: user.actor_id,
-        name: None,
-        position: None,
-        is_template: false,
-        display_as_sets: false,
-        is_sequence: false,
-        is_complete: false,
-        temporal: Temporal::Start {
-            start: sqlx::types::chrono::Utc::now(),
-        },
-    };
-    sqlite_client
-        .run_action(CreateEntry::from(log_root.clone()).into())
+    // The stored range blocks unordering.
+    assert!(matches!(
+        client
+            .run_action(update(
+                grade.id,
+                AttributeChange::Select(SelectChange::SetOrdered(false)),
+            ))
+            .await,
+        Err(DomainError::Rejected(RejectReason::Precondition(_)))
+    ));
+
+    // Multiselect: fix a typo'd option and pick up the new default.
+    client
+        .run_action(update(
+            holds.id,
+            AttributeChange::Multiselect(MultiselectChange::RenameOption {
+                from: "Slopr".to_string(),
+                to: "Sloper".to_string(),
+            }),
+        ))
         .await
         .unwrap();
-    assert!(
-        sqlite_client
-            .run_action(
-                MoveEntry {
-                    actor_id: user.actor_id,
-                    entry_id: log_root.id,
-                    position: None,
-                    temporal: Temporal::None,
-                }
-                .into(),
-            )
-            .await
-            .is_err(),
-        "log root with no start/end must still be rejected"
+    client
+        .run_action(update(
+            holds.id,
+            AttributeChange::Multiselect(MultiselectChange::AddOption {
+                option: "Pinch".to_string(),
+                position: None,
+            }),
+        ))
+        .await
+        .unwrap();
+    client
+        .run_action(update(
+            holds.id,
+            AttributeChange::Multiselect(MultiselectChange::SetDefault(Some(strings(&["Pinch"])))),
+        ))
+        .await
+        .unwrap();
+    let config = read_attribute(&client, holds.id)
+        .await
+        .expect_multiselect()
+        .cloned()
+        .unwrap();
+    assert_eq!(config.options, strings(&["Crimp", "Sloper", "Pinch"]));
+    assert_eq!(config.default, Some(strings(&["Pinch"])));
+    assert_eq!(
+        value(holds.id).await.actual,
+        Some(AttributeValue::Multiselect(strings(&["Sloper", "Crimp"])))
     );
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_update_entry_set_is_sequence(pool: SqlitePool) {
-    let sqlite_client =
-        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
-    let user = create_user(&sqlite_client).await;
-
-    // A sequence root with one child.
-    let parent = Entry {
+async fn test_value_index_columns_follow_actual(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let (user, entry) = seed_entry(&client).await;
+    let load = Attribute {
         id: Uuid::new_v4(),
-        activity_id: None,
-        name: None,
         owner_id: user.actor_id,
-        position: None,
-        display_as_sets: false,
-        is_sequence: true,
-        is_complete: false,
-        is_template: false,
-        temporal: Temporal::Start {
-            start: sqlx::types::chrono::Utc::now(),
-        },
+        name: "Load".to_string(),
+        description: None,
+        config: AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram,
+        }),
     };
-    let child = Entry {
+    let grade = Attribute {
         id: Uuid::new_v4(),
-        activity_id: None,
-        name: None,
         owner_id: user.actor_id,
-        position: Some(Position {
-            parent_id: parent.id,
-            frac_index: FractionalIndex::default(),
+        name: "Grade".to_string(),
+        description: None,
+        config: AttributeConfig::Select(SelectConfig {
+            options: vec!["V1".to_string(), "V2".to_string()],
+            ordered: true,
+            default: None,
         }),
-        display_as_sets: false,
-        is_sequence: false,
-        is_complete: false,
-        is_template: false,
-        temporal: Temporal::None,
     };
-    sqlite_client
-        .run_action(CreateEntry::from(parent.clone()).into())
-        .await
-        .unwrap();
-    sqlite_client
-        .run_action(CreateEntry::from(child.clone()).into())
-        .await
-        .unwrap();
-
-    async fn find(client: &SqliteClient, id: Uuid) -> Option<Entry> {
-        let mut conn = client.pool.acquire().await.unwrap();
-        SqliteQueryExecutor::new(&mut *conn)
-            .execute(FindEntryById { entry_id: id })
-            .await
-            .unwrap()
-    }
-
-    // Converting the sequence to a scalar deletes its children.
-    sqlite_client
-        .run_action(
-            UpdateEntry {
+    // Whatever index the action carries is replaced by one derived from actual.
+    run_actions(
+        &client,
+        [
+            CreateAttribute::from(load.clone()).into(),
+            CreateValue {
                 actor_id: user.actor_id,
-                entry_id: parent.id,
-                change: EntryChange::SetIsSequence(false),
+                value: Value {
+                    entry_id: entry.id,
+                    attribute_id: load.id,
+                    index_float: Some(99.0),
+                    index_string: Some("stale".to_string()),
+                    plan: None,
+                    actual: Some(AttributeValue::Mass(MassValue::Exact(MassMeasurement {
+                        unit: MassUnit::Gram,
+                        value: 2500.0,
+                    }))),
+                },
             }
             .into(),
-        )
+        ],
+    )
+    .await;
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        entry.id,
+        &grade,
+        None,
+        Some(AttributeValue::Select(SelectValue::Exact("V2".to_string()))),
+    )
+    .await;
+    let index = async |attribute_id| {
+        let value = client
+            .run_query(
+                user.actor_id,
+                FindValueByKey {
+                    entry_id: entry.id,
+                    attribute_id,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        (value.index_float, value.index_string)
+    };
+    assert_eq!(index(load.id).await, (Some(2.5), None));
+    assert_eq!(index(grade.id).await, (Some(1.0), Some("V2".to_string())));
+
+    // Updating actual re-derives the index; clearing it clears the index.
+    let set_actual = |attribute_id, value| -> Action {
+        UpdateAttributeValue {
+            actor_id: user.actor_id,
+            entry_id: entry.id,
+            attribute_id,
+            field: ValueField::Actual,
+            value,
+        }
+        .into()
+    };
+    client
+        .run_action(set_actual(
+            load.id,
+            Some(AttributeValue::Mass(MassValue::Exact(MassMeasurement {
+                unit: MassUnit::Kilogram,
+                value: 40.0,
+            }))),
+        ))
         .await
         .unwrap();
-    assert_eq!(
-        find(&sqlite_client, parent.id).await.map(|e| e.is_sequence),
-        Some(false)
-    );
-    assert!(
-        find(&sqlite_client, child.id).await.is_none(),
-        "child must be deleted on scalar conversion"
-    );
+    assert_eq!(index(load.id).await, (Some(40.0), None));
+    client.run_action(set_actual(load.id, None)).await.unwrap();
+    assert_eq!(index(load.id).await, (None, None));
 
-    // Converting back to a sequence is allowed.
-    sqlite_client
+    // Inserting an easier grade shifts the stored ordinal.
+    client
         .run_action(
-            UpdateEntry {
+            UpdateAttribute {
                 actor_id: user.actor_id,
-                entry_id: parent.id,
-                change: EntryChange::SetIsSequence(true),
+                attribute_id: grade.id,
+                change: AttributeChange::Select(SelectChange::AddOption {
+                    option: "V0".to_string(),
+                    position: Some(0),
+                }),
             }
             .into(),
         )
         .await
         .unwrap();
-    assert_eq!(
-        find(&sqlite_client, parent.id).await.map(|e| e.is_sequence),
-        Some(true)
-    );
+    assert_eq!(index(grade.id).await, (Some(2.0), Some("V2".to_string())));
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_create_entry_from_activity_instantiates_template(pool: SqlitePool) {
-    let sqlite_client =
-        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
-    let user = create_user(&sqlite_client).await;
-
-    // Attribute with a default to seed onto the template.
-    let reps = Attribute {
+async fn test_reindex_values_backfills_unindexed_rows(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool.clone(), Arc::new(gv_core::io::SystemIo::default()));
+    let (user, entry) = seed_entry(&client).await;
+    let attribute = |name: &str, config| Attribute {
         id: Uuid::new_v4(),
         owner_id: user.actor_id,
-        name: "Reps".to_string(),
+        name: name.to_string(),
         description: None,
-        config: AttributeConfig::Numeric(NumericConfig {
-            min: None,
-            max: None,
-            integer: true,
-            default: Some(5.0),
+        config,
+    };
+    let load = attribute(
+        "Load",
+        AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram,
+        }),
+    );
+    let grade = attribute(
+        "Grade",
+        AttributeConfig::Select(SelectConfig {
+            options: vec!["V1".to_string(), "V2".to_string()],
+            ordered: true,
+            default: None,
+        }),
+    );
+    let holds = attribute(
+        "Holds",
+        AttributeConfig::Multiselect(MultiselectConfig {
+            options: vec!["Crimp".to_string()],
+            default: None,
         }),
+    );
+    let mass = |kg| {
+        AttributeValue::Mass(MassValue::Exact(MassMeasurement {
+            unit: MassUnit::Kilogram,
+            value: kg,
+        }))
     };
-    sqlite_client
-        .run_action(CreateAttribute::from(reps.clone()).into())
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        entry.id,
+        &load,
+        None,
+        Some(mass(40.0)),
+    )
+    .await;
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        entry.id,
+        &grade,
+        None,
+        Some(AttributeValue::Select(SelectValue::Exact("V2".to_string()))),
+    )
+    .await;
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        entry.id,
+        &holds,
+        None,
+        Some(AttributeValue::Multiselect(vec!["Crimp".to_string()])),
+    )
+    .await;
+
+    // Stand in for rows written before values were indexed.
+    sqlx::query("UPDATE attribute_values SET index_float = NULL, index_string = NULL")
+        .execute(&pool)
         .await
         .unwrap();
-
-    // Activity with a known sequence template root.
-    let activity = Activity {
-        id: Uuid::new_v4(),
-        owner_id: user.actor_id,
-        name: ActivityName::parse("Bench".to_string()).unwrap(),
-        description: None,
-        source_activity_id: None,
+    let index = async |attribute_id| {
+        let value = client
+            .run_query(
+                user.actor_id,
+                FindValueByKey {
+                    entry_id: entry.id,
+                    attribute_id,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        (value.index_float, value.index_string)
     };
-    let template_root = Entry {
+    assert_eq!(index(load.id).await, (None, None));
+
+    let mut conn = pool.acquire().await.unwrap();
+    assert_eq!(gv_sql::sqlite::reindex_values(&mut conn).await.unwrap(), 2);
+    assert_eq!(index(load.id).await, (Some(40.0), None));
+    assert_eq!(index(grade.id).await, (Some(1.0), Some("V2".to_string())));
+    assert_eq!(index(holds.id).await, (None, None));
+    // Indexed rows are left alone, so a second pass is a no-op.
+    assert_eq!(gv_sql::sqlite::reindex_values(&mut conn).await.unwrap(), 0);
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_derived_attributes_compute_on_read(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let sets = Entry {
         id: Uuid::new_v4(),
-        activity_id: Some(activity.id),
-        owner_id: user.actor_id,
+        activity_id: None,
         name: None,
+        owner_id: user.actor_id,
         position: None,
-        is_template: true,
         display_as_sets: false,
         is_sequence: true,
         is_complete: false,
-        temporal: Temporal::None,
+        is_template: false,
+        temporal: Temporal::Start {
+            start: sqlx::types::chrono::Utc::now(),
+        },
     };
-    sqlite_client
-        .run_action(
-            CreateActivity {
-                actor_id: user.actor_id,
-                activity: activity.clone(),
-                template: vec![template_root.clone()],
-            }
-            .into(),
-        )
-        .await
-        .unwrap();
-
-    // A template child (scalar) with a seeded "Reps" value.
-    let template_child = Entry {
+    let set = Entry {
         id: Uuid::new_v4(),
-        activity_id: None,
-        owner_id: user.actor_id,
-        name: Some("Set".to_string()),
         position: Some(Position {
-            parent_id: template_root.id,
+            parent_id: sets.id,
             frac_index: FractionalIndex::default(),
         }),
-        is_template: true,
-        display_as_sets: false,
         is_sequence: false,
-        is_complete: false,
         temporal: Temporal::None,
+        ..sets.clone()
     };
-    sqlite_client
-        .run_action(CreateEntry::from(template_child.clone()).into())
-        .await
-        .unwrap();
-    sqlite_client
-        .run_action(
-            AttachValue {
-                actor_id: user.actor_id,
-                entry_id: template_child.id,
-                attribute_id: reps.id,
-            }
-            .into(),
-        )
-        .await
-        .unwrap();
-
-    // Instantiate the activity at a day root.
-    let start = sqlx::types::chrono::Utc::now();
-    sqlite_client
-        .run_action(
-            CreateEntryFromActivity {
-                actor_id: user.actor_id,
-                activity_id: activity.id,
-                position: None,
-                temporal: Temporal::Start { start },
-                is_template: false,
-            }
-            .into(),
-        )
-        .await
-        .unwrap();
+    let attribute = |name: &str, config: AttributeConfig| Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: name.to_string(),
+        description: None,
+        config,
+    };
+    let reps = attribute(
+        "Reps",
+        AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }),
+    );
+    let load = attribute(
+        "Load",
+        AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram,
+        }),
+    );
+    let volume = attribute(
+        "Volume",
+        AttributeConfig::Derived(DerivedConfig {
+            expression: DerivedExpr::attribute(reps.id) * DerivedExpr::attribute(load.id),
+        }),
+    );
+    let tonnage = attribute(
+        "Tonnage",
+        AttributeConfig::Derived(DerivedConfig {
+            expression: DerivedExpr::attribute(volume.id).sum_descendants(),
+        }),
+    );
+    let attach = |entry: &Entry, attribute: &Attribute, actual| -> Action {
+        CreateValue {
+            actor_id: user.actor_id,
+            value: Value {
+                entry_id: entry.id,
+                attribute_id: attribute.id,
+                index_float: None,
+                index_string: None,
+                plan: None,
+                actual,
+            },
+        }
+        .into()
+    };
+    run_actions(
+        &client,
+        [
+            CreateEntry::from(sets.clone()).into(),
+            CreateEntry::from(set.clone()).into(),
+            CreateAttribute::from(reps.clone()).into(),
+            CreateAttribute::from(load.clone()).into(),
+            CreateAttribute::from(volume.clone()).into(),
+            CreateAttribute::from(tonnage.clone()).into(),
+            attach(
+                &set,
+                &reps,
+                Some(AttributeValue::Numeric(NumericValue::Exact(5.0))),
+            ),
+            attach(
+                &set,
+                &load,
+                Some(AttributeValue::Mass(MassValue::Exact(MassMeasurement {
+                    unit: MassUnit::Kilogram,
+                    value: 100.0,
+                }))),
+            ),
+            attach(&set, &volume, None),
+            attach(&sets, &tonnage, None),
+        ],
+    )
+    .await;
 
-    // Read all entries; the instances are the non-template ones.
-    let all = {
-        let mut conn = sqlite_client.pool.acquire().await.unwrap();
-        SqliteQueryExecutor::new(&mut *conn)
-            .execute(AllEntries {})
+    let derived_actual = async |entry: &Entry, attribute: &Attribute| {
+        let join = client
+            .run_query(user.actor_id, FindEntryJoinById { entry_id: entry.id })
             .await
             .unwrap()
+            .unwrap();
+        join.attributes
+            .into_iter()
+            .find_map(|p| match p {
+                AttributePair::Derived(d) if d.attr_id == attribute.id => Some(d.actual),
+                _ => None,
+            })
+            .expect("derived pair should be attached")
     };
-    let instances: Vec<&Entry> = all.iter().filter(|e| !e.is_template).collect();
-    assert_eq!(instances.len(), 2, "root + child instantiated");
+    assert_eq!(derived_actual(&set, &volume).await, Some(500.0));
+    assert_eq!(derived_actual(&sets, &tonnage).await, Some(500.0));
 
-    let inst_root = instances
-        .iter()
-        .find(|e| e.position.is_none())
-        .expect("instance root");
-    assert_eq!(inst_root.activity_id, Some(activity.id));
-    assert!(inst_root.is_sequence, "structure copied from template");
-    assert_ne!(inst_root.id, template_root.id, "fresh id");
-    assert!(matches!(inst_root.temporal, Temporal::Start { .. }));
+    // Derived values are computed, never entered.
+    assert!(matches!(
+        client
+            .run_action(attach(
+                &sets,
+                &volume,
+                Some(AttributeValue::Numeric(NumericValue::Exact(1.0))),
+            ))
+            .await,
+        Err(DomainError::Rejected(RejectReason::AttributeMismatch))
+    ));
+    // Volume reading tonnage would close a cycle through tonnage's sum.
+    assert!(matches!(
+        client
+            .run_action(
+                UpdateAttribute {
+                    actor_id: user.actor_id,
+                    attribute_id: volume.id,
+                    change: AttributeChange::Derived(DerivedChange::SetExpression(
+                        DerivedExpr::attribute(tonnage.id),
+                    )),
+                }
+                .into(),
+            )
+            .await,
+        Err(DomainError::Rejected(RejectReason::Precondition(_)))
+    ));
+}
 
-    let inst_child = instances
-        .iter()
-        .find(|e| e.position.as_ref().map(|p| p.parent_id) == Some(inst_root.id))
-        .expect("instance child under instance root");
-    assert_ne!(inst_child.id, template_child.id);
-    assert!(!inst_child.is_template);
-
-    // The template's Reps value was copied onto the instantiated child.
-    let values = {
-        let mut conn = sqlite_client.pool.acquire().await.unwrap();
-        SqliteQueryExecutor::new(&mut *conn)
-            .execute(FindValuesForEntries {
-                entry_ids: vec![inst_child.id],
-            })
-            .await
-            .unwrap()
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_analytics_counts_hard_problems_each_week(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let v_grade = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "V Grade".to_string(),
+        description: None,
+        config: AttributeConfig::Grade(GradeConfig {
+            scale: GradeScale::VScale,
+            default: None,
+        }),
     };
-    assert_eq!(values.len(), 1);
-    assert_eq!(values[0].attribute_id, reps.id);
-    match values[0].actual.clone().expect("seeded actual") {
-        AttributeValue::Numeric(NumericValue::Exact(v)) => assert_eq!(v, 5.0),
-        other => panic!("expected Numeric Exact 5.0, got {:?}", other),
+    let mut actions: Vec<Action> = vec![CreateAttribute::from(v_grade.clone()).into()];
+    for (start, grades) in [
+        ("2026-03-01T20:00:00Z", ["V8", "V5"]),
+        ("2026-03-08T20:00:00Z", ["V9", "V8"]),
+    ] {
+        let session = Entry {
+            id: Uuid::new_v4(),
+            activity_id: None,
+            name: None,
+            owner_id: user.actor_id,
+            position: None,
+            display_as_sets: false,
+            is_sequence: true,
+            is_complete: false,
+            is_template: false,
+            temporal: Temporal::Start {
+                start: start.parse().unwrap(),
+            },
+        };
+        actions.push(CreateEntry::from(session.clone()).into());
+        let mut frac_index = FractionalIndex::default();
+        for grade in grades {
+            let problem = Entry {
+                id: Uuid::new_v4(),
+                position: Some(Position {
+                    parent_id: session.id,
+                    frac_index: frac_index.clone(),
+                }),
+                is_sequence: false,
+                temporal: Temporal::None,
+                ..session.clone()
+            };
+            frac_index = FractionalIndex::new_after(&frac_index);
+            actions.push(CreateEntry::from(problem.clone()).into());
+            actions.push(
+                CreateValue {
+                    actor_id: user.actor_id,
+                    value: Value {
+                        entry_id: problem.id,
+                        attribute_id: v_grade.id,
+                        index_float: None,
+                        index_string: None,
+                        plan: None,
+                        actual: Some(AttributeValue::Grade(SelectValue::Exact(grade.to_string()))),
+                    },
+                }
+                .into(),
+            );
+        }
     }
+    run_actions(&client, actions).await;
+
+    let query = AnalyticsQuery {
+        filters: vec![EntryFilter::Attribute(AttributePredicate {
+            target: AttributeField {
+                attribute_id: v_grade.id,
+                field: ValueField::Actual,
+            },
+            comparison: Comparison::Ge,
+            operand: AttributeValue::Grade(SelectValue::Exact("V8".to_string())),
+        })],
+        group_by: Some(Grouping::Period {
+            period: Period::Week,
+            utc_offset_minutes: 0,
+        }),
+        aggregate: Aggregate::Count,
+    };
+    let rows = client
+        .run_query(
+            user.actor_id,
+            RunAnalytics {
+                query: query.clone(),
+            },
+        )
+        .await
+        .unwrap();
+    let weekly: Vec<_> = rows.iter().map(|r| (&r.group, r.value)).collect();
+    assert_eq!(
+        weekly,
+        vec![
+            (
+                &GroupKey::Period {
+                    start: "2026-02-23T00:00:00Z".parse().unwrap()
+                },
+                Some(1.0)
+            ),
+            (
+                &GroupKey::Period {
+                    start: "2026-03-02T00:00:00Z".parse().unwrap()
+                },
+                Some(2.0)
+            ),
+        ]
+    );
+
+    // The in-memory model answers the same way.
+    let model = Model::from_snapshot(client.run_query(user.actor_id, SnapshotAll).await.unwrap());
+    assert_eq!(model.run_analytics(&query).unwrap(), rows);
+
+    // The same query as text; "V Grade" is the user's own attribute, not the
+    // standard library's.
+    let text = r#"count where "V Grade" >= V8 group by week"#;
+    let parsed = client
+        .parse_analytics_query(user.actor_id, text, 0)
+        .await
+        .unwrap();
+    assert_eq!(parsed, query);
+    let formatted = client
+        .format_analytics_query(user.actor_id, &query, 0)
+        .await
+        .unwrap();
+    assert_eq!(formatted, text);
+    let err = client
+        .parse_analytics_query(user.actor_id, "count where \"V Grade\" >= V99", 0)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        DomainError::Rejected(RejectReason::Validation(ValidationError::InvalidQueryText(e)))
+            if (e.start, e.end) == (25, 28)
+    ));
 }
 
-/// Property-style round-trip: arbitrary entries written through the real client
-/// write path (`run_action` → mutator → `SqliteDeltaExecutor`) and read back via
-/// `AllEntries`.
-///
-/// This catches regressions where `EntryRow`'s `FromRow`-derived decode fails for
-/// non-`Temporal::None` entries — e.g. when a `*Column` type's `compatible()` is
-/// stricter than the underlying sqlx type's, rejecting valid SQL column types.
-///
-/// Ported from a gv-sql delta-level test; going through `run_action` means we
-/// exercise the same path the live app uses (and no longer depend on a seeded
-/// SYSTEM actor — users are created via arbitrary `CreateUser` actions).
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn arbitrary_entries_round_trip_through_all_entries(pool: SqlitePool) {
-    const N_USERS: usize = 4;
-    const N_ENTRIES: usize = 30;
-
+async fn test_analytics_filters_in_sql_match_the_model(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
-    let mut rng = ChaCha8Rng::seed_from_u64(0xb0c0_dabad7e5);
-    let mut context = SimulationContext::default();
-
-    // Seed a few users so generated entries can be owned by real actors. The
-    // `From<Entry>` for `CreateEntry` makes the actor the entry's owner, so this
-    // also satisfies the create-entry permission check. We apply each returned
-    // mutation back to the context model so subsequent generation sees the actors.
-    for _ in 0..N_USERS {
-        let create = CreateUser::arbitrary(&mut rng, &context);
-        let mx = client.run_action(create.into()).await.unwrap();
-        context.apply_mutation(mx).await.unwrap();
+    let user = create_user(&client).await;
+    let attribute = |name: &str, config: AttributeConfig| Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: name.to_string(),
+        description: None,
+        config,
+    };
+    let reps = attribute(
+        "Reps",
+        AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }),
+    );
+    let total = attribute(
+        "Total Reps",
+        AttributeConfig::Derived(DerivedConfig {
+            expression: DerivedExpr::attribute(reps.id).sum_descendants(),
+        }),
+    );
+    let mut actions: Vec<Action> = vec![
+        CreateAttribute::from(reps.clone()).into(),
+        CreateAttribute::from(total.clone()).into(),
+    ];
+    // The second session is timed by its end and duration, so it starts on
+    // March 8 but ends on March 9. Sets take their session's time.
+    for (temporal, set_reps) in [
+        (
+            Temporal::Start {
+                start: "2026-03-02T10:00:00Z".parse().unwrap(),
+            },
+            vec![5.0, 8.0],
+        ),
+        (
+            Temporal::DurationAndEnd {
+                duration_ms: 2 * 60 * 60 * 1000,
+                end: "2026-03-09T01:00:00Z".parse().unwrap(),
+            },
+            vec![3.0, 11.0],
+        ),
+        (
+            Temporal::Start {
+                start: "2026-03-20T10:00:00Z".parse().unwrap(),
+            },
+            vec![12.0],
+        ),
+    ] {
+        let session = Entry {
+            id: Uuid::new_v4(),
+            activity_id: None,
+            name: None,
+            owner_id: user.actor_id,
+            position: None,
+            display_as_sets: false,
+            is_sequence: true,
+            is_complete: true,
+            is_template: false,
+            temporal,
+        };
+        actions.push(CreateEntry::from(session.clone()).into());
+        let mut frac_index = FractionalIndex::default();
+        for n in set_reps {
+            let set = Entry {
+                id: Uuid::new_v4(),
+                position: Some(Position {
+                    parent_id: session.id,
+                    frac_index: frac_index.clone(),
+                }),
+                is_sequence: false,
+                is_complete: false,
+                temporal: Temporal::None,
+                ..session.clone()
+            };
+            frac_index = FractionalIndex::new_after(&frac_index);
+            actions.push(CreateEntry::from(set.clone()).into());
+            actions.push(
+                CreateValue {
+                    actor_id: user.actor_id,
+                    value: Value {
+                        entry_id: set.id,
+                        attribute_id: reps.id,
+                        index_float: None,
+                        index_string: None,
+                        plan: None,
+                        actual: Some(AttributeValue::Numeric(NumericValue::Exact(n))),
+                    },
+                }
+                .into(),
+            );
+        }
     }
+    run_actions(&client, actions).await;
 
-    // Generate arbitrary log entries, keeping the model in sync so generated
-    // positions reference real sequence parents (and owners real actors).
-    let mut inserted: HashMap<Uuid, Entry> = HashMap::with_capacity(N_ENTRIES);
-    for _ in 0..N_ENTRIES {
-        let create = CreateEntry::arbitrary(&mut rng, &context);
-        inserted.insert(create.entry.id, create.entry.clone());
-        let mx = client.run_action(create.into()).await.unwrap();
-        context.apply_mutation(mx).await.unwrap();
+    let window = |from: &str, to: &str| EntryFilter::TimeWindow {
+        from: from.parse().unwrap(),
+        to: to.parse().unwrap(),
+    };
+    let at_least_five = EntryFilter::Attribute(AttributePredicate {
+        target: AttributeField {
+            attribute_id: reps.id,
+            field: ValueField::Actual,
+        },
+        comparison: Comparison::Ge,
+        operand: AttributeValue::Numeric(NumericValue::Exact(5.0)),
+    });
+    let field = |attribute: &Attribute| AttributeField {
+        attribute_id: attribute.id,
+        field: ValueField::Actual,
+    };
+    let model = Model::from_snapshot(client.run_query(user.actor_id, SnapshotAll).await.unwrap());
+    for (query, expected) in [
+        // Sets of 5 and 8 on March 2 and of 11 on March 8.
+        (
+            AnalyticsQuery {
+                filters: vec![
+                    window("2026-03-01T00:00:00Z", "2026-03-09T00:00:00Z"),
+                    at_least_five.clone(),
+                ],
+                group_by: None,
+                aggregate: Aggregate::Count,
+            },
+            Some(3.0),
+        ),
+        // The second session ends in this window but starts before it.
+        (
+            AnalyticsQuery {
+                filters: vec![
+                    window("2026-03-09T00:00:00Z", "2026-03-21T00:00:00Z"),
+                    at_least_five,
+                ],
+                group_by: None,
+                aggregate: Aggregate::Sum(field(&reps)),
+            },
+            Some(12.0),
+        ),
+        // Session totals sum their sets, which the filters don't match.
+        (
+            AnalyticsQuery {
+                filters: vec![
+                    EntryFilter::Complete(true),
+                    window("2026-03-01T00:00:00Z", "2026-03-09T00:00:00Z"),
+                    EntryFilter::Attribute(AttributePredicate {
+                        target: field(&total),
+                        comparison: Comparison::Gt,
+                        operand: AttributeValue::Numeric(NumericValue::Exact(0.0)),
+                    }),
+                ],
+                group_by: None,
+                aggregate: Aggregate::Max(field(&total)),
+            },
+            Some(14.0),
+        ),
+    ] {
+        let rows = client
+            .run_query(
+                user.actor_id,
+                RunAnalytics {
+                    query: query.clone(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows[0].value, expected, "{query:?}");
+        assert_eq!(model.run_analytics(&query).unwrap(), rows);
     }
+}
 
-    // Read every entry back via AllEntries — this is what feeds the forest cache
-    // in the live app.
-    let entries = {
-        let mut conn = client.pool.acquire().await.unwrap();
-        SqliteQueryExecutor::new(&mut *conn)
-            .execute(AllEntries {})
-            .await
-            .expect("AllEntries query must not fail")
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_personal_records_compare_loads_across_units(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let bench = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Bench".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
     };
-
+    let load = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Load".to_string(),
+        description: None,
+        config: AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram,
+        }),
+    };
+    let mut actions: Vec<Action> = vec![
+        CreateActivity {
+            actor_id: user.actor_id,
+            activity: bench.clone(),
+            template: vec![Entry {
+                is_template: true,
+                ..log_entry(user.actor_id, Some(bench.id), None)
+            }],
+        }
+        .into(),
+        CreateAttribute::from(load.clone()).into(),
+    ];
+    let mass =
+        |unit, value| AttributeValue::Mass(MassValue::Exact(MassMeasurement { unit, value }));
+    let mut sets = Vec::new();
+    for (start, weight) in [
+        ("2026-05-01T17:00:00Z", mass(MassUnit::Kilogram, 100.0)),
+        // 220 lb is 99.8 kg: the bigger number, but no record.
+        ("2026-05-03T17:00:00Z", mass(MassUnit::Pound, 220.0)),
+        ("2026-05-05T17:00:00Z", mass(MassUnit::Pound, 225.0)),
+    ] {
+        let set = Entry {
+            temporal: Temporal::Start {
+                start: start.parse().unwrap(),
+            },
+            ..log_entry(user.actor_id, Some(bench.id), None)
+        };
+        actions.push(CreateEntry::from(set.clone()).into());
+        actions.push(
+            CreateValue {
+                actor_id: user.actor_id,
+                value: Value {
+                    entry_id: set.id,
+                    attribute_id: load.id,
+                    index_float: None,
+                    index_string: None,
+                    plan: None,
+                    actual: Some(weight),
+                },
+            }
+            .into(),
+        );
+        sets.push(set);
+    }
+    // An untimed set in a workout takes the workout's time.
+    let workout = Entry {
+        temporal: Temporal::Start {
+            start: "2026-05-07T17:00:00Z".parse().unwrap(),
+        },
+        ..log_entry(user.actor_id, None, None)
+    };
+    let nested = log_entry(
+        user.actor_id,
+        Some(bench.id),
+        child_position(workout.id, FractionalIndex::default()),
+    );
+    actions.push(CreateEntry::from(workout.clone()).into());
+    actions.push(CreateEntry::from(nested.clone()).into());
+    actions.push(
+        CreateValue {
+            actor_id: user.actor_id,
+            value: Value {
+                entry_id: nested.id,
+                attribute_id: load.id,
+                index_float: None,
+                index_string: None,
+                plan: None,
+                actual: Some(mass(MassUnit::Kilogram, 105.0)),
+            },
+        }
+        .into(),
+    );
+    run_actions(&client, actions).await;
+
+    let records = client
+        .run_query(
+            user.actor_id,
+            PersonalRecords {
+                owner_id: user.actor_id,
+                activity_id: Some(bench.id),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    let heaviest = &records[0];
+    assert_eq!(heaviest.attribute_id, load.id);
+    assert_eq!(heaviest.best.entry_id, nested.id);
+    assert_eq!(Some(heaviest.best.at), workout.temporal.canonical_instant());
+    let raised_by: Vec<Uuid> = heaviest.history.iter().map(|m| m.entry_id).collect();
+    assert_eq!(raised_by, vec![sets[0].id, sets[2].id, nested.id]);
+
+    // The in-memory model finds the same records.
+    let model = Model::from_snapshot(client.run_query(user.actor_id, SnapshotAll).await.unwrap());
     assert_eq!(
-        entries.len(),
-        inserted.len(),
-        "all inserted entries should come back through AllEntries"
+        model.personal_records(user.actor_id, Some(bench.id)),
+        records
     );
-    for got in entries {
-        let original = inserted.get(&got.id).expect("unknown id returned");
-        assert_eq!(&got, original, "entry round-trip mismatch");
-    }
 }
 
-// --- Sets (display_as_sets) ---
-
-fn log_entry(owner_id: Uuid, activity_id: Option<Uuid>, position: Option<Position>) -> Entry {
-    Entry {
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_training_load_keeps_unknown_sessions_apart(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let rpe = Attribute {
         id: Uuid::new_v4(),
-        activity_id,
-        owner_id,
-        name: None,
-        position,
-        is_template: false,
-        display_as_sets: false,
-        is_sequence: false,
-        is_complete: false,
-        temporal: Temporal::None,
+        owner_id: user.actor_id,
+        name: "RPE".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: Some(1.0),
+            max: Some(10.0),
+            integer: false,
+            default: None,
+        }),
+    };
+    let mut actions: Vec<Action> = vec![CreateAttribute::from(rpe.clone()).into()];
+    let mut sessions = Vec::new();
+    for (start, minutes, effort) in [
+        ("2026-06-01T07:00:00Z", 60, Some(4.0)),
+        ("2026-06-02T07:00:00Z", 90, Some(8.0)),
+        // Logged without an RPE: its load is unknown, not zero.
+        ("2026-06-02T18:00:00Z", 30, None),
+        // Before the window, but in the chronic period of its days.
+        ("2026-05-10T07:00:00Z", 60, Some(5.0)),
+    ] {
+        let session = Entry {
+            temporal: Temporal::StartAndDuration {
+                start: start.parse().unwrap(),
+                duration_ms: minutes * 60_000,
+            },
+            ..log_entry(user.actor_id, None, None)
+        };
+        actions.push(CreateEntry::from(session.clone()).into());
+        if let Some(effort) = effort {
+            actions.push(
+                CreateValue {
+                    actor_id: user.actor_id,
+                    value: Value {
+                        entry_id: session.id,
+                        attribute_id: rpe.id,
+                        index_float: None,
+                        index_string: None,
+                        plan: None,
+                        actual: Some(AttributeValue::Numeric(NumericValue::Exact(effort))),
+                    },
+                }
+                .into(),
+            );
+        }
+        sessions.push(session);
     }
-}
-
-fn child_position(parent_id: Uuid, frac_index: FractionalIndex) -> Option<Position> {
-    Some(Position {
-        parent_id,
-        frac_index,
-    })
-}
+    // After the window, in its week, rated on the exercise under it.
+    let late = Entry {
+        temporal: Temporal::StartAndDuration {
+            start: "2026-06-05T07:00:00Z".parse().unwrap(),
+            duration_ms: 30 * 60_000,
+        },
+        ..log_entry(user.actor_id, None, None)
+    };
+    let exercise = log_entry(
+        user.actor_id,
+        None,
+        child_position(late.id, FractionalIndex::default()),
+    );
+    actions.push(CreateEntry::from(late.clone()).into());
+    actions.push(CreateEntry::from(exercise.clone()).into());
+    actions.push(
+        CreateValue {
+            actor_id: user.actor_id,
+            value: Value {
+                entry_id: exercise.id,
+                attribute_id: rpe.id,
+                index_float: None,
+                index_string: None,
+                plan: None,
+                actual: Some(AttributeValue::Numeric(NumericValue::Exact(6.0))),
+            },
+        }
+        .into(),
+    );
+    run_actions(&client, actions).await;
 
-async fn find_entry(client: &SqliteClient, id: Uuid) -> Option<Entry> {
-    let mut conn = client.pool.acquire().await.unwrap();
-    SqliteQueryExecutor::new(&mut *conn)
-        .execute(FindEntryById { entry_id: id })
+    let query = TrainingLoad {
+        owner_id: user.actor_id,
+        rpe_attribute_id: rpe.id,
+        window: LoadWindow {
+            from: "2026-06-01T00:00:00Z".parse().unwrap(),
+            to: "2026-06-03T00:00:00Z".parse().unwrap(),
+            utc_offset_minutes: 0,
+        },
+    };
+    let report = client
+        .run_query(user.actor_id, query.clone())
         .await
-        .unwrap()
+        .unwrap();
+    let loads: Vec<_> = report.sessions.iter().map(|s| s.load).collect();
+    assert_eq!(loads, vec![Some(240.0), Some(720.0), None]);
+    let days: Vec<_> = report
+        .days
+        .iter()
+        .map(|d| (d.load, d.sessions, d.unknown))
+        .collect();
+    assert_eq!(days, vec![(240.0, 1, 0), (720.0, 2, 1)]);
+    // 240 over the week against (300 + 240) / 4 over the four.
+    assert_eq!(report.days[0].acute_chronic_ratio, Some(240.0 / 135.0));
+    assert_eq!(report.weeks.len(), 1);
+    let week = &report.weeks[0];
+    assert_eq!((week.load, week.sessions, week.unknown), (1140.0, 4, 1));
+
+    // The in-memory model reports the same.
+    let model = Model::from_snapshot(client.run_query(user.actor_id, SnapshotAll).await.unwrap());
+    assert_eq!(
+        model
+            .training_load(user.actor_id, rpe.id, &query.window)
+            .unwrap(),
+        report
+    );
+
+    // A backwards window is rejected.
+    let backwards = TrainingLoad {
+        window: LoadWindow {
+            from: query.window.to,
+            to: query.window.from,
+            utc_offset_minutes: 0,
+        },
+        ..query
+    };
+    assert!(client.run_query(user.actor_id, backwards).await.is_err());
 }
 
-/// A flagged sets sequence (root, on the timeline) with `member_count`
-/// members of the given activity, built through the public actions.
-async fn seed_sets_sequence(
-    client: &SqliteClient,
-    owner_id: Uuid,
-    activity_id: Option<Uuid>,
-    member_count: usize,
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_attribute_series_rolls_up_sets_in_requested_unit(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let bench = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Bench".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
+    };
+    let load = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Load".to_string(),
+        description: None,
+        config: AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram,
+        }),
+    };
+    let mut actions: Vec<Action> = vec![
+        CreateActivity {
+            actor_id: user.actor_id,
+            activity: bench.clone(),
+            template: vec![Entry {
+                is_template: true,
+                ..log_entry(user.actor_id, Some(bench.id), None)
+            }],
+        }
+        .into(),
+        CreateAttribute::from(load.clone()).into(),
+    ];
+    // Each workout is timed; its sets sequence and sets are not.
+    let mut workouts = Vec::new();
+    for (start, unit, loads) in [
+        ("2026-07-06T17:00:00Z", MassUnit::Kilogram, [80.0, 90.0]),
+        ("2026-07-15T17:00:00Z", MassUnit::Pound, [200.0, 210.0]),
+    ] {
+        let workout = Entry {
+            temporal: Temporal::Start {
+                start: start.parse().unwrap(),
+            },
+            ..log_entry(user.actor_id, None, None)
+        };
+        actions.push(CreateEntry::from(workout.clone()).into());
+        // The first set is logged under the workout and converted to sets;
+        // the rest follow it in the new sequence.
+        let sequence_id = Uuid::new_v4();
+        let mut frac_index = FractionalIndex::default();
+        for (i, weight) in loads.into_iter().enumerate() {
+            let parent_id = if i == 0 { workout.id } else { sequence_id };
+            let set = log_entry(
+                user.actor_id,
+                Some(bench.id),
+                child_position(parent_id, frac_index.clone()),
+            );
+            frac_index = FractionalIndex::new_after(&frac_index);
+            actions.push(CreateEntry::from(set.clone()).into());
+            if i == 0 {
+                actions.push(
+                    ConvertToSets {
+                        actor_id: user.actor_id,
+                        entry_id: set.id,
+                        sequence_id,
+                    }
+                    .into(),
+                );
+            }
+            actions.push(
+                CreateValue {
+                    actor_id: user.actor_id,
+                    value: Value {
+                        entry_id: set.id,
+                        attribute_id: load.id,
+                        index_float: None,
+                        index_string: None,
+                        plan: None,
+                        actual: Some(AttributeValue::Mass(MassValue::Exact(MassMeasurement {
+                            unit: unit.clone(),
+                            value: weight,
+                        }))),
+                    },
+                }
+                .into(),
+            );
+        }
+        workouts.push((workout, sequence_id));
+    }
+    run_actions(&client, actions).await;
+
+    // The heaviest set of each sequence, in pounds, at its workout's time.
+    let query = AttributeSeries {
+        activity_id: bench.id,
+        attribute_id: load.id,
+        field: ValueField::Actual,
+        bucket: None,
+        aggregate: SeriesAggregate::Max,
+        range: None,
+        unit: Some(SeriesUnit::Mass(MassUnit::Pound)),
+        roll_up_sets: true,
+    };
+    let points = client
+        .run_query(user.actor_id, query.clone())
+        .await
+        .unwrap();
+    let plotted: Vec<_> = points
+        .iter()
+        .map(|p| (p.entry_id, (p.value * 10.0).round() / 10.0, p.values))
+        .collect();
+    assert_eq!(
+        plotted,
+        vec![
+            (Some(workouts[0].1), 198.4, 2),
+            (Some(workouts[1].1), 210.0, 2),
+        ]
+    );
+    assert_eq!(
+        points[0].time,
+        workouts[0].0.temporal.canonical_instant().unwrap()
+    );
+
+    // Weekly volume in the attribute's kilograms.
+    let weekly = AttributeSeries {
+        bucket: Some(SeriesBucket {
+            period: Period::Week,
+            utc_offset_minutes: 0,
+        }),
+        aggregate: SeriesAggregate::Sum,
+        unit: None,
+        roll_up_sets: false,
+        ..query.clone()
+    };
+    let weeks = client
+        .run_query(user.actor_id, weekly.clone())
+        .await
+        .unwrap();
+    let volume: Vec<_> = weeks
+        .iter()
+        .map(|p| (p.entry_id, (p.value * 10.0).round() / 10.0, p.values))
+        .collect();
+    assert_eq!(volume, vec![(None, 170.0, 2), (None, 186.0, 2)]);
+
+    // A range takes the untimed sets by their workout's time.
+    let ranged = AttributeSeries {
+        range: Some(SeriesRange {
+            from: "2026-07-13T00:00:00Z".parse().unwrap(),
+            to: "2026-07-20T00:00:00Z".parse().unwrap(),
+        }),
+        ..query.clone()
+    };
+    let second = client
+        .run_query(user.actor_id, ranged.clone())
+        .await
+        .unwrap();
+    assert_eq!(second, points[1..]);
+
+    // The in-memory model plots the same.
+    let model = Model::from_snapshot(client.run_query(user.actor_id, SnapshotAll).await.unwrap());
+    assert_eq!(model.attribute_series(&query).unwrap(), points);
+    assert_eq!(model.attribute_series(&weekly).unwrap(), weeks);
+    assert_eq!(model.attribute_series(&ranged).unwrap(), second);
+
+    // A length unit doesn't fit a mass attribute.
+    let metres = AttributeSeries {
+        unit: Some(SeriesUnit::Length(LengthUnit::Meter)),
+        ..query
+    };
+    assert!(client.run_query(user.actor_id, metres).await.is_err());
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_attribute_migration_remaps_values(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let (user, first) = seed_entry(&client).await;
+    let second = log_entry(user.actor_id, None, None);
+    client
+        .run_action(CreateEntry::from(second.clone()).into())
+        .await
+        .unwrap();
+    let strings = |options: &[&str]| options.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+    let exact = |o: &str| Some(AttributeValue::Select(SelectValue::Exact(o.to_string())));
+    let grade = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Grade".to_string(),
+        description: None,
+        config: AttributeConfig::Select(SelectConfig {
+            options: strings(&["5a", "5b", "5c", "6a"]),
+            ordered: true,
+            default: Some("5b".to_string()),
+        }),
+    };
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        first.id,
+        &grade,
+        exact("5b"),
+        exact("5c"),
+    )
+    .await;
+    client
+        .run_action(
+            CreateValue {
+                actor_id: user.actor_id,
+                value: Value {
+                    entry_id: second.id,
+                    attribute_id: grade.id,
+                    index_float: None,
+                    index_string: None,
+                    plan: exact("6a"),
+                    actual: None,
+                },
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    let migrate = |attribute_id, migration| -> Action {
+        AttributeMigration {
+            actor_id: user.actor_id,
+            attribute_id,
+            migration,
+        }
+        .into()
+    };
+    let removal = |option: &str, replacement: Option<&str>| OptionRemoval {
+        option: option.to_string(),
+        replacement: replacement.map(str::to_string),
+    };
+
+    // A replacement must be one of the options that remain.
+    assert!(
+        client
+            .run_action(migrate(
+                grade.id,
+                ConfigMigration::RemoveOptions(vec![
+                    removal("5b", Some("5c")),
+                    removal("5c", None),
+                ]),
+            ))
+            .await
+            .is_err()
+    );
+
+    // Fold 5b into 5a and retire 5c; 6a is untouched.
+    let mutation = client
+        .run_action(migrate(
+            grade.id,
+            ConfigMigration::RemoveOptions(vec![removal("5b", Some("5a")), removal("5c", None)]),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(mutation.values_changed(), 1);
+    let config = read_attribute(&client, grade.id)
+        .await
+        .expect_select()
+        .cloned()
+        .unwrap();
+    assert_eq!(config.options, strings(&["5a", "6a"]));
+    assert_eq!(config.default.as_deref(), Some("5a"));
+    let stored = |entry_id| {
+        let client = &client;
+        async move {
+            client
+                .run_query(
+                    user.actor_id,
+                    FindValueByKey {
+                        entry_id,
+                        attribute_id: grade.id,
+                    },
+                )
+                .await
+                .unwrap()
+                .unwrap()
+        }
+    };
+    let value = stored(first.id).await;
+    assert_eq!((value.plan, value.actual), (exact("5a"), None));
+    assert_eq!(stored(second.id).await.plan, exact("6a"));
+
+    // Numeric bounds: tighten, clamping (or clearing) what falls outside.
+    let reps = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Reps".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: Some(30.0),
+        }),
+    };
+    let numeric = |v| Some(AttributeValue::Numeric(NumericValue::Exact(v)));
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        first.id,
+        &reps,
+        numeric(5.0),
+        numeric(25.0),
+    )
+    .await;
+    let set_bounds = |out_of_range| {
+        migrate(
+            reps.id,
+            ConfigMigration::SetBounds {
+                min: Some(1.0),
+                max: Some(20.0),
+                out_of_range,
+            },
+        )
+    };
+    let mutation = client
+        .run_action(set_bounds(OutOfRange::Clamp))
+        .await
+        .unwrap();
+    assert_eq!(mutation.values_changed(), 1);
+    let config = read_attribute(&client, reps.id)
+        .await
+        .as_numeric()
+        .cloned()
+        .unwrap();
+    assert_eq!(
+        (config.min, config.max, config.default),
+        (Some(1.0), Some(20.0), Some(20.0))
+    );
+    let value = client
+        .run_query(
+            user.actor_id,
+            FindValueByKey {
+                entry_id: first.id,
+                attribute_id: reps.id,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((value.plan, value.actual), (numeric(5.0), numeric(20.0)));
+
+    // Already within bounds: nothing left to migrate.
+    let mutation = client
+        .run_action(set_bounds(OutOfRange::Clear))
+        .await
+        .unwrap();
+    assert!(mutation.changes.is_empty());
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_convert_attribute_type(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let (user, first) = seed_entry(&client).await;
+    let second = log_entry(user.actor_id, None, None);
+    client
+        .run_action(CreateEntry::from(second.clone()).into())
+        .await
+        .unwrap();
+    let convert = |attribute_id, conversion| -> Action {
+        ConvertAttributeType {
+            actor_id: user.actor_id,
+            attribute_id,
+            conversion,
+        }
+        .into()
+    };
+
+    // Numeric -> Mass: magnitudes are kept and measured in the chosen unit.
+    let weight = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Weight".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: false,
+            default: Some(60.0),
+        }),
+    };
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        first.id,
+        &weight,
+        Some(AttributeValue::Numeric(NumericValue::Exact(60.0))),
+        Some(AttributeValue::Numeric(NumericValue::Range {
+            min: 60.0,
+            max: 62.5,
+        })),
+    )
+    .await;
+    let to_mass = || convert(weight.id, AttributeConversion::ToMass(MassUnit::Kilogram));
+    // The numeric default has no mass form.
+    assert!(client.run_action(to_mass()).await.is_err());
+    client
+        .run_action(
+            UpdateAttribute {
+                actor_id: user.actor_id,
+                attribute_id: weight.id,
+                change: AttributeChange::Numeric(NumericChange::SetDefault(None)),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    let mutation = client.run_action(to_mass()).await.unwrap();
+    assert_eq!(mutation.values_changed(), 1);
+    assert_eq!(
+        read_attribute(&client, weight.id).await.config,
+        AttributeConfig::Mass(MassConfig {
+            default_unit: MassUnit::Kilogram
+        })
+    );
+    let value = client
+        .run_query(
+            user.actor_id,
+            FindValueByKey {
+                entry_id: first.id,
+                attribute_id: weight.id,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        value.actual,
+        Some(AttributeValue::Mass(MassValue::Range {
+            unit: MassUnit::Kilogram,
+            min: 60.0,
+            max: 62.5
+        }))
+    );
+
+    // Text -> Select: the stored texts become the options.
+    let text = |s: &str| Some(AttributeValue::Text(s.to_string()));
+    let hold = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Hold".to_string(),
+        description: None,
+        config: AttributeConfig::Text(TextConfig {
+            default: Some("Jug".to_string()),
+            autocomplete: false,
+        }),
+    };
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        first.id,
+        &hold,
+        text("Sloper"),
+        text("Crimp"),
+    )
+    .await;
+    client
+        .run_action(
+            CreateValue {
+                actor_id: user.actor_id,
+                value: Value {
+                    entry_id: second.id,
+                    attribute_id: hold.id,
+                    index_float: None,
+                    index_string: None,
+                    plan: text("Crimp"),
+                    actual: None,
+                },
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    let mutation = client
+        .run_action(convert(hold.id, AttributeConversion::ToSelect))
+        .await
+        .unwrap();
+    assert_eq!(mutation.values_changed(), 2);
+    let config = read_attribute(&client, hold.id)
+        .await
+        .expect_select()
+        .cloned()
+        .unwrap();
+    assert_eq!(config.options, ["Crimp", "Sloper", "Jug"]);
+    assert_eq!(config.default.as_deref(), Some("Jug"));
+
+    // Select -> Multiselect: each selection becomes a one-option set.
+    client
+        .run_action(convert(hold.id, AttributeConversion::ToMultiselect))
+        .await
+        .unwrap();
+    let value = client
+        .run_query(
+            user.actor_id,
+            FindValueByKey {
+                entry_id: first.id,
+                attribute_id: hold.id,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        value.plan,
+        Some(AttributeValue::Multiselect(vec!["Sloper".to_string()]))
+    );
+
+    // A select range has no text form, so the conversion is refused whole.
+    let grade = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Grade".to_string(),
+        description: None,
+        config: AttributeConfig::Select(SelectConfig {
+            options: vec!["5a".to_string(), "5b".to_string()],
+            ordered: true,
+            default: None,
+        }),
+    };
+    seed_attribute_value(
+        &client,
+        user.actor_id,
+        first.id,
+        &grade,
+        Some(AttributeValue::Select(SelectValue::Range {
+            min: "5a".to_string(),
+            max: "5b".to_string(),
+        })),
+        None,
+    )
+    .await;
+    let result = client
+        .run_action(convert(grade.id, AttributeConversion::ToText))
+        .await;
+    assert!(matches!(
+        result,
+        Err(DomainError::Rejected(RejectReason::Precondition(_)))
+    ));
+    assert!(
+        read_attribute(&client, grade.id)
+            .await
+            .expect_select()
+            .is_ok()
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_update_attribute_noop(pool: SqlitePool) {
+    let sqlite_client =
+        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&sqlite_client).await;
+
+    // --- No-op: re-applying the same value emits no deltas.
+    let numeric = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Reps".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: false,
+            default: Some(5.0),
+        }),
+    };
+    sqlite_client
+        .run_action(CreateAttribute::from(numeric.clone()).into())
+        .await
+        .unwrap();
+
+    // Call the mutator directly so we can inspect the produced deltas.
+    let mutation = {
+        let mut conn = sqlite_client.pool.acquire().await.unwrap();
+        let mut executor = SqliteQueryExecutor::new(&mut *conn);
+        gv_core::mutators::update_attribute(
+            &mut executor,
+            &gv_core::io::SystemIo::default(),
+            UpdateAttribute {
+                actor_id: user.actor_id,
+                attribute_id: numeric.id,
+                change: AttributeChange::Numeric(NumericChange::SetDefault(Some(5.0))),
+            },
+        )
+        .await
+        .unwrap()
+    };
+    assert!(
+        mutation.changes.is_empty(),
+        "setting the default to its current value must be a no-op"
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_template_temporal_rules(pool: SqlitePool) {
+    let sqlite_client =
+        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&sqlite_client).await;
+
+    let activity = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Squat".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
+    };
+    sqlite_client
+        .run_action(activity.clone().into_create_activity(Uuid::new_v4()).into())
+        .await
+        .unwrap();
+
+    let template_entry = |temporal: Temporal| Entry {
+        id: Uuid::new_v4(),
+        activity_id: Some(activity.id),
+        owner_id: user.actor_id,
+        name: None,
+        position: None,
+        is_template: true,
+        display_as_sets: false,
+        is_sequence: false,
+        is_complete: false,
+        temporal,
+    };
+
+    // create_entry: a template entry with a start time is rejected...
+    assert!(
+        sqlite_client
+            .run_action(
+                CreateEntry::from(template_entry(Temporal::Start {
+                    start: sqlx::types::chrono::Utc::now()
+                }))
+                .into(),
+            )
+            .await
+            .is_err(),
+        "template entry with a start time must be rejected"
+    );
+    // ...but duration-only and None are allowed.
+    sqlite_client
+        .run_action(
+            CreateEntry::from(template_entry(Temporal::Duration { duration: 60_000 })).into(),
+        )
+        .await
+        .unwrap();
+    let none_template = template_entry(Temporal::None);
+    let none_template_id = none_template.id;
+    sqlite_client
+        .run_action(CreateEntry::from(none_template.clone()).into())
+        .await
+        .unwrap();
+
+    // move_entry: giving a template a start time is rejected.
+    assert!(
+        sqlite_client
+            .run_action(
+                MoveEntry {
+                    actor_id: user.actor_id,
+                    entry_id: none_template_id,
+                    position: None,
+                    temporal: Temporal::Start {
+                        start: sqlx::types::chrono::Utc::now()
+                    },
+                }
+                .into(),
+            )
+            .await
+            .is_err(),
+        "moving a template to a start-timed temporal must be rejected"
+    );
+
+    // Sanity: a log entry at root with no start/end is still rejected.
+    let log_root = Entry {
+        id: Uuid::new_v4(),
+        activity_id: None,
+        owner_id: user.actor_id,
+        name: None,
+        position: None,
+        is_template: false,
+        display_as_sets: false,
+        is_sequence: false,
+        is_complete: false,
+        temporal: Temporal::Start {
+            start: sqlx::types::chrono::Utc::now(),
+        },
+    };
+    sqlite_client
+        .run_action(CreateEntry::from(log_root.clone()).into())
+        .await
+        .unwrap();
+    assert!(
+        sqlite_client
+            .run_action(
+                MoveEntry {
+                    actor_id: user.actor_id,
+                    entry_id: log_root.id,
+                    position: None,
+                    temporal: Temporal::None,
+                }
+                .into(),
+            )
+            .await
+            .is_err(),
+        "log root with no start/end must still be rejected"
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_update_entry_set_is_sequence(pool: SqlitePool) {
+    let sqlite_client =
+        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&sqlite_client).await;
+
+    // A sequence root with one child.
+    let parent = Entry {
+        id: Uuid::new_v4(),
+        activity_id: None,
+        name: None,
+        owner_id: user.actor_id,
+        position: None,
+        display_as_sets: false,
+        is_sequence: true,
+        is_complete: false,
+        is_template: false,
+        temporal: Temporal::Start {
+            start: sqlx::types::chrono::Utc::now(),
+        },
+    };
+    let child = Entry {
+        id: Uuid::new_v4(),
+        activity_id: None,
+        name: None,
+        owner_id: user.actor_id,
+        position: Some(Position {
+            parent_id: parent.id,
+            frac_index: FractionalIndex::default(),
+        }),
+        display_as_sets: false,
+        is_sequence: false,
+        is_complete: false,
+        is_template: false,
+        temporal: Temporal::None,
+    };
+    sqlite_client
+        .run_action(CreateEntry::from(parent.clone()).into())
+        .await
+        .unwrap();
+    sqlite_client
+        .run_action(CreateEntry::from(child.clone()).into())
+        .await
+        .unwrap();
+
+    async fn find(client: &SqliteClient, id: Uuid) -> Option<Entry> {
+        let mut conn = client.pool.acquire().await.unwrap();
+        SqliteQueryExecutor::new(&mut *conn)
+            .execute(FindEntryById { entry_id: id })
+            .await
+            .unwrap()
+    }
+
+    // Converting the sequence to a scalar deletes its children.
+    sqlite_client
+        .run_action(
+            UpdateEntry {
+                actor_id: user.actor_id,
+                entry_id: parent.id,
+                change: EntryChange::SetIsSequence(false),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        find(&sqlite_client, parent.id).await.map(|e| e.is_sequence),
+        Some(false)
+    );
+    assert!(
+        find(&sqlite_client, child.id).await.is_none(),
+        "child must be deleted on scalar conversion"
+    );
+
+    // Converting back to a sequence is allowed.
+    sqlite_client
+        .run_action(
+            UpdateEntry {
+                actor_id: user.actor_id,
+                entry_id: parent.id,
+                change: EntryChange::SetIsSequence(true),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        find(&sqlite_client, parent.id).await.map(|e| e.is_sequence),
+        Some(true)
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_create_entry_from_activity_instantiates_template(pool: SqlitePool) {
+    let sqlite_client =
+        SqliteClient::from_pool(pool, std::sync::Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&sqlite_client).await;
+
+    // Attribute with a default to seed onto the template.
+    let reps = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Reps".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: Some(5.0),
+        }),
+    };
+    sqlite_client
+        .run_action(CreateAttribute::from(reps.clone()).into())
+        .await
+        .unwrap();
+
+    // Activity with a known sequence template root.
+    let activity = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Bench".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
+    };
+    let template_root = Entry {
+        id: Uuid::new_v4(),
+        activity_id: Some(activity.id),
+        owner_id: user.actor_id,
+        name: None,
+        position: None,
+        is_template: true,
+        display_as_sets: false,
+        is_sequence: true,
+        is_complete: false,
+        temporal: Temporal::None,
+    };
+    sqlite_client
+        .run_action(
+            CreateActivity {
+                actor_id: user.actor_id,
+                activity: activity.clone(),
+                template: vec![template_root.clone()],
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // A template child (scalar) with a seeded "Reps" value.
+    let template_child = Entry {
+        id: Uuid::new_v4(),
+        activity_id: None,
+        owner_id: user.actor_id,
+        name: Some("Set".to_string()),
+        position: Some(Position {
+            parent_id: template_root.id,
+            frac_index: FractionalIndex::default(),
+        }),
+        is_template: true,
+        display_as_sets: false,
+        is_sequence: false,
+        is_complete: false,
+        temporal: Temporal::None,
+    };
+    sqlite_client
+        .run_action(CreateEntry::from(template_child.clone()).into())
+        .await
+        .unwrap();
+    sqlite_client
+        .run_action(
+            AttachValue {
+                actor_id: user.actor_id,
+                entry_id: template_child.id,
+                attribute_id: reps.id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // Instantiate the activity at a day root.
+    let start = sqlx::types::chrono::Utc::now();
+    sqlite_client
+        .run_action(
+            CreateEntryFromActivity {
+                actor_id: user.actor_id,
+                activity_id: activity.id,
+                position: None,
+                temporal: Temporal::Start { start },
+                is_template: false,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // Read all entries; the instances are the non-template ones.
+    let all = {
+        let mut conn = sqlite_client.pool.acquire().await.unwrap();
+        SqliteQueryExecutor::new(&mut *conn)
+            .execute(AllEntries {})
+            .await
+            .unwrap()
+    };
+    let instances: Vec<&Entry> = all.iter().filter(|e| !e.is_template).collect();
+    assert_eq!(instances.len(), 2, "root + child instantiated");
+
+    let inst_root = instances
+        .iter()
+        .find(|e| e.position.is_none())
+        .expect("instance root");
+    assert_eq!(inst_root.activity_id, Some(activity.id));
+    assert!(inst_root.is_sequence, "structure copied from template");
+    assert_ne!(inst_root.id, template_root.id, "fresh id");
+    assert!(matches!(inst_root.temporal, Temporal::Start { .. }));
+
+    let inst_child = instances
+        .iter()
+        .find(|e| e.position.as_ref().map(|p| p.parent_id) == Some(inst_root.id))
+        .expect("instance child under instance root");
+    assert_ne!(inst_child.id, template_child.id);
+    assert!(!inst_child.is_template);
+
+    // The template's Reps value was copied onto the instantiated child.
+    let values = {
+        let mut conn = sqlite_client.pool.acquire().await.unwrap();
+        SqliteQueryExecutor::new(&mut *conn)
+            .execute(FindValuesForEntries {
+                entry_ids: vec![inst_child.id],
+            })
+            .await
+            .unwrap()
+    };
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[0].attribute_id, reps.id);
+    match values[0].actual.clone().expect("seeded actual") {
+        AttributeValue::Numeric(NumericValue::Exact(v)) => assert_eq!(v, 5.0),
+        other => panic!("expected Numeric Exact 5.0, got {:?}", other),
+    }
+}
+
+/// Property-style round-trip: arbitrary entries written through the real client
+/// write path (`run_action` → mutator → `SqliteDeltaExecutor`) and read back via
+/// `AllEntries`.
+///
+/// This catches regressions where `EntryRow`'s `FromRow`-derived decode fails for
+/// non-`Temporal::None` entries — e.g. when a `*Column` type's `compatible()` is
+/// stricter than the underlying sqlx type's, rejecting valid SQL column types.
+///
+/// Ported from a gv-sql delta-level test; going through `run_action` means we
+/// exercise the same path the live app uses (and no longer depend on a seeded
+/// SYSTEM actor — users are created via arbitrary `CreateUser` actions).
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn arbitrary_entries_round_trip_through_all_entries(pool: SqlitePool) {
+    const N_USERS: usize = 4;
+    const N_ENTRIES: usize = 30;
+
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let mut rng = ChaCha8Rng::seed_from_u64(0xb0c0_dabad7e5);
+    let mut context = SimulationContext::default();
+
+    // Seed a few users so generated entries can be owned by real actors. The
+    // `From<Entry>` for `CreateEntry` makes the actor the entry's owner, so this
+    // also satisfies the create-entry permission check. We apply each returned
+    // mutation back to the context model so subsequent generation sees the actors.
+    for _ in 0..N_USERS {
+        let create = CreateUser::arbitrary(&mut rng, &context);
+        let mx = client.run_action(create.into()).await.unwrap();
+        context.apply_mutation(mx).await.unwrap();
+    }
+
+    // Generate arbitrary log entries, keeping the model in sync so generated
+    // positions reference real sequence parents (and owners real actors).
+    let mut inserted: HashMap<Uuid, Entry> = HashMap::with_capacity(N_ENTRIES);
+    for _ in 0..N_ENTRIES {
+        let create = CreateEntry::arbitrary(&mut rng, &context);
+        inserted.insert(create.entry.id, create.entry.clone());
+        let mx = client.run_action(create.into()).await.unwrap();
+        context.apply_mutation(mx).await.unwrap();
+    }
+
+    // Read every entry back via AllEntries — this is what feeds the forest cache
+    // in the live app.
+    let entries = {
+        let mut conn = client.pool.acquire().await.unwrap();
+        SqliteQueryExecutor::new(&mut *conn)
+            .execute(AllEntries {})
+            .await
+            .expect("AllEntries query must not fail")
+    };
+
+    assert_eq!(
+        entries.len(),
+        inserted.len(),
+        "all inserted entries should come back through AllEntries"
+    );
+    for got in entries {
+        let original = inserted.get(&got.id).expect("unknown id returned");
+        assert_eq!(&got, original, "entry round-trip mismatch");
+    }
+}
+
+/// One user's entries never show up in another user's `AllEntries`: with no
+/// grants between them, each actor sees exactly the entries they own.
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn arbitrary_entries_never_leak_between_users(pool: SqlitePool) {
+    const N_USERS: usize = 4;
+    const N_ENTRIES: usize = 30;
+
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let mut rng = ChaCha8Rng::seed_from_u64(0x5c0_9ed);
+    let mut context = SimulationContext::default();
+
+    let mut users = Vec::with_capacity(N_USERS);
+    for _ in 0..N_USERS {
+        let create = CreateUser::arbitrary(&mut rng, &context);
+        users.push(create.user.actor_id);
+        let mx = client.run_action(create.into()).await.unwrap();
+        context.apply_mutation(mx).await.unwrap();
+    }
+    let mut inserted = Vec::with_capacity(N_ENTRIES);
+    for _ in 0..N_ENTRIES {
+        let create = CreateEntry::arbitrary(&mut rng, &context);
+        inserted.push(create.entry.clone());
+        let mx = client.run_action(create.into()).await.unwrap();
+        context.apply_mutation(mx).await.unwrap();
+    }
+
+    let mut conn = client.pool.acquire().await.unwrap();
+    for actor_id in users {
+        let mut visible = ScopedQueryExecutor::new(SqliteQueryExecutor::new(&mut conn), actor_id)
+            .execute(AllEntries {})
+            .await
+            .unwrap();
+        visible.sort_by_key(|e| e.id);
+        let mut owned: Vec<Entry> = inserted
+            .iter()
+            .filter(|e| e.owner_id == actor_id)
+            .cloned()
+            .collect();
+        owned.sort_by_key(|e| e.id);
+        assert_eq!(
+            visible, owned,
+            "actor {actor_id} sees exactly their own entries"
+        );
+    }
+}
+
+/// The client reads on an actor's behalf: another user's unshared activity,
+/// attribute, entry and values stay out of their queries, their analytics and
+/// the names their query text resolves against.
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_client_reads_only_what_the_actor_can_see(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let alice = create_user(&client).await;
+    let bob = create_user(&client).await;
+    let sled = Activity {
+        id: Uuid::new_v4(),
+        owner_id: alice.actor_id,
+        name: ActivityName::parse("Sled Push".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
+    };
+    let laps = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: alice.actor_id,
+        name: "Laps".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }),
+    };
+    let session = log_entry(alice.actor_id, Some(sled.id), None);
+    run_actions(
+        &client,
+        [
+            CreateActivity {
+                actor_id: alice.actor_id,
+                activity: sled.clone(),
+                template: vec![Entry {
+                    is_template: true,
+                    ..log_entry(alice.actor_id, Some(sled.id), None)
+                }],
+            }
+            .into(),
+            CreateAttribute::from(laps.clone()).into(),
+            CreateEntry::from(session.clone()).into(),
+            CreateValue {
+                actor_id: alice.actor_id,
+                value: Value {
+                    entry_id: session.id,
+                    attribute_id: laps.id,
+                    index_float: None,
+                    index_string: None,
+                    plan: None,
+                    actual: Some(AttributeValue::Numeric(NumericValue::Exact(6.0))),
+                },
+            }
+            .into(),
+        ],
+    )
+    .await;
+
+    let activity_ids =
+        |activities: Vec<Activity>| -> Vec<Uuid> { activities.iter().map(|a| a.id).collect() };
+    let attribute_ids =
+        |attributes: Vec<Attribute>| -> Vec<Uuid> { attributes.iter().map(|a| a.id).collect() };
+    assert_eq!(
+        activity_ids(
+            client
+                .run_query(alice.actor_id, AllActivities {})
+                .await
+                .unwrap()
+        ),
+        vec![sled.id]
+    );
+    assert_eq!(
+        attribute_ids(
+            client
+                .run_query(alice.actor_id, AllAttributes {})
+                .await
+                .unwrap()
+        ),
+        vec![laps.id]
+    );
+    assert!(
+        client
+            .run_query(bob.actor_id, AllActivities {})
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        client
+            .run_query(bob.actor_id, AllAttributes {})
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        client
+            .run_query(bob.actor_id, AllEntries {})
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        client
+            .run_query(
+                bob.actor_id,
+                FindEntryById {
+                    entry_id: session.id
+                }
+            )
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(
+        client
+            .run_query(
+                bob.actor_id,
+                FindValuesForEntries {
+                    entry_ids: vec![session.id]
+                }
+            )
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Analytics only count what the reader can see.
+    let query = AnalyticsQuery {
+        filters: vec![EntryFilter::Attribute(AttributePredicate {
+            target: AttributeField {
+                attribute_id: laps.id,
+                field: ValueField::Actual,
+            },
+            comparison: Comparison::Ge,
+            operand: AttributeValue::Numeric(NumericValue::Exact(5.0)),
+        })],
+        group_by: None,
+        aggregate: Aggregate::Count,
+    };
+    let count = |rows: Vec<gv_core::analytics::AnalyticsRow>| {
+        rows.iter().map(|r| r.value).sum::<Option<f64>>()
+    };
+    let run = |actor_id| {
+        client.run_query(
+            actor_id,
+            RunAnalytics {
+                query: query.clone(),
+            },
+        )
+    };
+    assert_eq!(count(run(alice.actor_id).await.unwrap()), Some(1.0));
+    assert_eq!(count(run(bob.actor_id).await.unwrap()), Some(0.0));
+
+    // Query text resolves names against the reader's own library.
+    let text = r#"count where "Laps" >= 5"#;
+    assert_eq!(
+        client
+            .parse_analytics_query(alice.actor_id, text, 0)
+            .await
+            .unwrap(),
+        query
+    );
+    assert!(matches!(
+        client.parse_analytics_query(bob.actor_id, text, 0).await,
+        Err(DomainError::Rejected(RejectReason::Validation(
+            ValidationError::InvalidQueryText(_)
+        )))
+    ));
+}
+
+// --- Sets (display_as_sets) ---
+
+fn log_entry(owner_id: Uuid, activity_id: Option<Uuid>, position: Option<Position>) -> Entry {
+    Entry {
+        id: Uuid::new_v4(),
+        activity_id,
+        owner_id,
+        name: None,
+        position,
+        is_template: false,
+        display_as_sets: false,
+        is_sequence: false,
+        is_complete: false,
+        temporal: Temporal::None,
+    }
+}
+
+fn child_position(parent_id: Uuid, frac_index: FractionalIndex) -> Option<Position> {
+    Some(Position {
+        parent_id,
+        frac_index,
+    })
+}
+
+async fn find_entry(client: &SqliteClient, id: Uuid) -> Option<Entry> {
+    let mut conn = client.pool.acquire().await.unwrap();
+    SqliteQueryExecutor::new(&mut *conn)
+        .execute(FindEntryById { entry_id: id })
+        .await
+        .unwrap()
+}
+
+/// A flagged sets sequence (root, on the timeline) with `member_count`
+/// members of the given activity, built through the public actions.
+async fn seed_sets_sequence(
+    client: &SqliteClient,
+    owner_id: Uuid,
+    activity_id: Option<Uuid>,
+    member_count: usize,
 ) -> (Entry, Vec<Entry>) {
     let mut sequence = log_entry(owner_id, None, None);
     sequence.is_sequence = true;
@@ -1352,429 +3369,724 @@ async fn seed_sets_sequence(
         start: sqlx::types::chrono::Utc::now(),
     };
     client
-        .run_action(CreateEntry::from(sequence.clone()).into())
+        .run_action(CreateEntry::from(sequence.clone()).into())
+        .await
+        .unwrap();
+
+    let mut members = Vec::new();
+    let mut frac_index = FractionalIndex::default();
+    for _ in 0..member_count {
+        let member = log_entry(
+            owner_id,
+            activity_id,
+            child_position(sequence.id, frac_index.clone()),
+        );
+        client
+            .run_action(CreateEntry::from(member.clone()).into())
+            .await
+            .unwrap();
+        members.push(member);
+        frac_index = FractionalIndex::new_after(&frac_index);
+    }
+
+    client
+        .run_action(
+            UpdateEntry {
+                actor_id: owner_id,
+                entry_id: sequence.id,
+                change: EntryChange::SetDisplayAsSets(true),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    (sequence, members)
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_convert_to_sets_happy_path(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+
+    let activity = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Bench Press".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
+    };
+    client
+        .run_action(activity.clone().into_create_activity(Uuid::new_v4()).into())
+        .await
+        .unwrap();
+
+    let start = sqlx::types::chrono::Utc::now();
+    let mut entry = log_entry(user.actor_id, Some(activity.id), None);
+    entry.temporal = Temporal::StartAndDuration {
+        start,
+        duration_ms: 300_000,
+    };
+    entry.is_complete = true;
+    client
+        .run_action(CreateEntry::from(entry.clone()).into())
+        .await
+        .unwrap();
+
+    let sequence_id = Uuid::new_v4();
+    client
+        .run_action(
+            ConvertToSets {
+                actor_id: user.actor_id,
+                entry_id: entry.id,
+                sequence_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // The sequence takes the entry's root slot and start; anonymous, flagged.
+    let sequence = find_entry(&client, sequence_id).await.unwrap();
+    assert!(sequence.position.is_none(), "sequence takes the root slot");
+    assert!(sequence.is_sequence);
+    assert!(sequence.display_as_sets);
+    assert_eq!(sequence.activity_id, None);
+    assert_eq!(sequence.name, None);
+    assert_eq!(sequence.temporal, Temporal::Start { start });
+    assert!(!sequence.is_complete);
+
+    // The entry becomes the sole member, keeping only its duration.
+    let member = find_entry(&client, entry.id).await.unwrap();
+    assert_eq!(member.parent_id(), Some(sequence_id));
+    assert_eq!(member.temporal, Temporal::Duration { duration: 300_000 });
+    assert_eq!(member.activity_id, Some(activity.id));
+    assert!(member.is_complete, "member completion is untouched");
+
+    // The flag round-trips through AllEntries (Entry::arbitrary no longer
+    // generates it, so this is the persistence coverage for the column).
+    let mut conn = client.pool.acquire().await.unwrap();
+    let all = SqliteQueryExecutor::new(&mut *conn)
+        .execute(AllEntries)
+        .await
+        .unwrap();
+    assert!(
+        all.iter().any(|e| e.id == sequence_id && e.display_as_sets),
+        "display_as_sets must round-trip"
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_convert_to_sets_rejections(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+
+    let activity = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Squat".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
+    };
+    let template_root_id = Uuid::new_v4();
+    client
+        .run_action(
+            activity
+                .clone()
+                .into_create_activity(template_root_id)
+                .into(),
+        )
+        .await
+        .unwrap();
+
+    // An activity template root cannot be converted.
+    assert!(
+        client
+            .run_action(
+                ConvertToSets {
+                    actor_id: user.actor_id,
+                    entry_id: template_root_id,
+                    sequence_id: Uuid::new_v4(),
+                }
+                .into(),
+            )
+            .await
+            .is_err(),
+        "template root conversion must be rejected"
+    );
+
+    // The client-supplied sequence_id must be unused.
+    let mut on_timeline = log_entry(user.actor_id, None, None);
+    on_timeline.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
+    };
+    client
+        .run_action(CreateEntry::from(on_timeline.clone()).into())
+        .await
+        .unwrap();
+    assert!(
+        client
+            .run_action(
+                ConvertToSets {
+                    actor_id: user.actor_id,
+                    entry_id: on_timeline.id,
+                    sequence_id: on_timeline.id,
+                }
+                .into(),
+            )
+            .await
+            .is_err(),
+        "colliding sequence_id must be rejected"
+    );
+
+    // A log root with no start/end cannot be converted: the sequence would
+    // land off the timeline.
+    let off_timeline = log_entry(user.actor_id, None, None);
+    client
+        .run_action(CreateEntry::from(off_timeline.clone()).into())
+        .await
+        .unwrap();
+    assert!(
+        client
+            .run_action(
+                ConvertToSets {
+                    actor_id: user.actor_id,
+                    entry_id: off_timeline.id,
+                    sequence_id: Uuid::new_v4(),
+                }
+                .into(),
+            )
+            .await
+            .is_err(),
+        "off-timeline root conversion must be rejected"
+    );
+
+    // A member of an activity-bearing sets sequence cannot be converted: the
+    // anonymous sequence would break the members' shared activity.
+    let (_, members) = seed_sets_sequence(&client, user.actor_id, Some(activity.id), 2).await;
+    assert!(
+        client
+            .run_action(
+                ConvertToSets {
+                    actor_id: user.actor_id,
+                    entry_id: members[0].id,
+                    sequence_id: Uuid::new_v4(),
+                }
+                .into(),
+            )
+            .await
+            .is_err(),
+        "converting a member among activity-bearing siblings must be rejected"
+    );
+
+    // Among all-anonymous members it is fine (anonymity matches).
+    let (_, anon_members) = seed_sets_sequence(&client, user.actor_id, None, 2).await;
+    client
+        .run_action(
+            ConvertToSets {
+                actor_id: user.actor_id,
+                entry_id: anon_members[0].id,
+                sequence_id: Uuid::new_v4(),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_duplicate_entry_exact_copy_after_source(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+
+    let mut parent = log_entry(user.actor_id, None, None);
+    parent.is_sequence = true;
+    parent.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
+    };
+    let fi1 = FractionalIndex::default();
+    let fi2 = FractionalIndex::new_after(&fi1);
+    let mut c1 = log_entry(user.actor_id, None, child_position(parent.id, fi1.clone()));
+    c1.is_sequence = true;
+    c1.is_complete = false;
+    c1.temporal = Temporal::Duration { duration: 90_000 };
+    let c2 = log_entry(user.actor_id, None, child_position(parent.id, fi2));
+    let mut c1a = log_entry(
+        user.actor_id,
+        None,
+        child_position(c1.id, FractionalIndex::default()),
+    );
+    c1a.is_complete = true;
+
+    let attribute = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Reps".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }),
+    };
+    let value = Value {
+        entry_id: c1a.id,
+        attribute_id: attribute.id,
+        index_float: Some(13.0),
+        index_string: None,
+        plan: None,
+        actual: Some(AttributeValue::Numeric(NumericValue::Exact(13.0))),
+    };
+
+    run_actions(
+        &client,
+        [
+            CreateEntry::from(parent.clone()).into(),
+            CreateEntry::from(c1.clone()).into(),
+            CreateEntry::from(c2.clone()).into(),
+            CreateEntry::from(c1a.clone()).into(),
+            CreateAttribute {
+                actor_id: user.actor_id,
+                attribute: attribute.clone(),
+            }
+            .into(),
+            CreateValue {
+                actor_id: user.actor_id,
+                value: value.clone(),
+            }
+            .into(),
+        ],
+    )
+    .await;
+
+    client
+        .run_action(
+            DuplicateEntry {
+                actor_id: user.actor_id,
+                entry_id: c1.id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // The copy lands between c1 and c2 in sibling order.
+    let mut conn = client.pool.acquire().await.unwrap();
+    let subtree = SqliteQueryExecutor::new(&mut *conn)
+        .execute(FindDescendants {
+            entry_id: parent.id,
+        })
+        .await
+        .unwrap();
+    let forest = gv_core::forest::Forest::from(subtree);
+    let children: Vec<Uuid> = forest.children(parent.id).iter().map(|e| e.id).collect();
+    assert_eq!(children.len(), 3);
+    assert_eq!(children[0], c1.id);
+    assert_eq!(children[2], c2.id);
+    let dup_id = children[1];
+    assert_ne!(dup_id, c1.id);
+
+    // Exact copy: temporal/completion verbatim, subtree deep-copied with
+    // fresh ids, value re-keyed onto the copied grandchild.
+    let dup = forest.entry(dup_id).unwrap();
+    assert_eq!(dup.temporal, Temporal::Duration { duration: 90_000 });
+    assert!(dup.is_sequence);
+    let dup_children = forest.children(dup_id);
+    assert_eq!(dup_children.len(), 1);
+    let dup_grandchild = dup_children[0];
+    assert_ne!(dup_grandchild.id, c1a.id);
+    assert!(dup_grandchild.is_complete, "completion copies verbatim");
+
+    let dup_value = SqliteQueryExecutor::new(&mut *conn)
+        .execute(FindValueByKey {
+            entry_id: dup_grandchild.id,
+            attribute_id: attribute.id,
+        })
+        .await
+        .unwrap()
+        .expect("value must be re-keyed onto the copy");
+    assert_eq!(
+        dup_value.actual,
+        Some(AttributeValue::Numeric(NumericValue::Exact(13.0)))
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_duplicate_entry_roots(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+
+    // A log root duplicates in place: same temporal, also a root.
+    let start = sqlx::types::chrono::Utc::now();
+    let mut root = log_entry(user.actor_id, None, None);
+    root.temporal = Temporal::Start { start };
+    client
+        .run_action(CreateEntry::from(root.clone()).into())
         .await
         .unwrap();
-
-    let mut members = Vec::new();
-    let mut frac_index = FractionalIndex::default();
-    for _ in 0..member_count {
-        let member = log_entry(
-            owner_id,
-            activity_id,
-            child_position(sequence.id, frac_index.clone()),
-        );
-        client
-            .run_action(CreateEntry::from(member.clone()).into())
-            .await
-            .unwrap();
-        members.push(member);
-        frac_index = FractionalIndex::new_after(&frac_index);
-    }
-
     client
         .run_action(
-            UpdateEntry {
-                actor_id: owner_id,
-                entry_id: sequence.id,
-                change: EntryChange::SetDisplayAsSets(true),
+            DuplicateEntry {
+                actor_id: user.actor_id,
+                entry_id: root.id,
             }
             .into(),
         )
         .await
         .unwrap();
+    let mut conn = client.pool.acquire().await.unwrap();
+    let all = SqliteQueryExecutor::new(&mut *conn)
+        .execute(AllEntries)
+        .await
+        .unwrap();
+    let copies: Vec<&Entry> = all
+        .iter()
+        .filter(|e| e.position.is_none() && e.temporal == Temporal::Start { start })
+        .collect();
+    assert_eq!(copies.len(), 2, "copy is a root with the same temporal");
 
-    (sequence, members)
+    // An activity template root cannot be duplicated (it would mint a second
+    // template root for the activity).
+    let activity = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Deadlift".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
+    };
+    let template_root_id = Uuid::new_v4();
+    client
+        .run_action(activity.into_create_activity(template_root_id).into())
+        .await
+        .unwrap();
+    assert!(
+        client
+            .run_action(
+                DuplicateEntry {
+                    actor_id: user.actor_id,
+                    entry_id: template_root_id,
+                }
+                .into(),
+            )
+            .await
+            .is_err(),
+        "template root duplication must be rejected"
+    );
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_convert_to_sets_happy_path(pool: SqlitePool) {
+async fn test_set_display_as_sets_guards(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
     let user = create_user(&client).await;
 
     let activity = Activity {
         id: Uuid::new_v4(),
         owner_id: user.actor_id,
-        name: ActivityName::parse("Bench Press".to_string()).unwrap(),
+        name: ActivityName::parse("Pull Up".to_string()).unwrap(),
         description: None,
         source_activity_id: None,
+        archived: false,
     };
     client
         .run_action(activity.clone().into_create_activity(Uuid::new_v4()).into())
         .await
         .unwrap();
 
-    let start = sqlx::types::chrono::Utc::now();
-    let mut entry = log_entry(user.actor_id, Some(activity.id), None);
-    entry.temporal = Temporal::StartAndDuration {
-        start,
-        duration_ms: 300_000,
+    let set_flag = |entry_id, value| {
+        UpdateEntry {
+            actor_id: user.actor_id,
+            entry_id,
+            change: EntryChange::SetDisplayAsSets(value),
+        }
+        .into()
+    };
+
+    // A scalar cannot be flagged.
+    let mut scalar = log_entry(user.actor_id, None, None);
+    scalar.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
     };
-    entry.is_complete = true;
     client
-        .run_action(CreateEntry::from(entry.clone()).into())
+        .run_action(CreateEntry::from(scalar.clone()).into())
         .await
         .unwrap();
+    assert!(client.run_action(set_flag(scalar.id, true)).await.is_err());
 
-    let sequence_id = Uuid::new_v4();
+    // An empty sequence cannot be flagged.
+    let mut empty = log_entry(user.actor_id, None, None);
+    empty.is_sequence = true;
+    empty.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
+    };
+    client
+        .run_action(CreateEntry::from(empty.clone()).into())
+        .await
+        .unwrap();
+    assert!(client.run_action(set_flag(empty.id, true)).await.is_err());
+
+    // Heterogeneous members (activity + anonymous) cannot be flagged.
+    let fi1 = FractionalIndex::default();
+    let fi2 = FractionalIndex::new_after(&fi1);
+    let m1 = log_entry(
+        user.actor_id,
+        Some(activity.id),
+        child_position(empty.id, fi1),
+    );
+    let m2 = log_entry(user.actor_id, None, child_position(empty.id, fi2.clone()));
+    run_actions(
+        &client,
+        [
+            CreateEntry::from(m1.clone()).into(),
+            CreateEntry::from(m2.clone()).into(),
+        ],
+    )
+    .await;
+    assert!(client.run_action(set_flag(empty.id, true)).await.is_err());
+
+    // Homogeneous members flag fine; setting again is a no-op; a flagged
+    // sequence cannot become a scalar until broken out.
     client
         .run_action(
-            ConvertToSets {
+            DeleteEntryRecursive {
                 actor_id: user.actor_id,
-                entry_id: entry.id,
-                sequence_id,
+                entry_id: m2.id,
             }
             .into(),
         )
         .await
         .unwrap();
+    client.run_action(set_flag(empty.id, true)).await.unwrap();
+    assert!(find_entry(&client, empty.id).await.unwrap().display_as_sets);
+    client.run_action(set_flag(empty.id, true)).await.unwrap();
 
-    // The sequence takes the entry's root slot and start; anonymous, flagged.
-    let sequence = find_entry(&client, sequence_id).await.unwrap();
-    assert!(sequence.position.is_none(), "sequence takes the root slot");
-    assert!(sequence.is_sequence);
-    assert!(sequence.display_as_sets);
-    assert_eq!(sequence.activity_id, None);
-    assert_eq!(sequence.name, None);
-    assert_eq!(sequence.temporal, Temporal::Start { start });
-    assert!(!sequence.is_complete);
-
-    // The entry becomes the sole member, keeping only its duration.
-    let member = find_entry(&client, entry.id).await.unwrap();
-    assert_eq!(member.parent_id(), Some(sequence_id));
-    assert_eq!(member.temporal, Temporal::Duration { duration: 300_000 });
-    assert_eq!(member.activity_id, Some(activity.id));
-    assert!(member.is_complete, "member completion is untouched");
-
-    // The flag round-trips through AllEntries (Entry::arbitrary no longer
-    // generates it, so this is the persistence coverage for the column).
-    let mut conn = client.pool.acquire().await.unwrap();
-    let all = SqliteQueryExecutor::new(&mut *conn)
-        .execute(AllEntries)
-        .await
-        .unwrap();
+    let to_scalar = UpdateEntry {
+        actor_id: user.actor_id,
+        entry_id: empty.id,
+        change: EntryChange::SetIsSequence(false),
+    };
     assert!(
-        all.iter().any(|e| e.id == sequence_id && e.display_as_sets),
-        "display_as_sets must round-trip"
+        client.run_action(to_scalar.clone().into()).await.is_err(),
+        "flagged sequence cannot become a scalar"
     );
+
+    // Break out, then scalar conversion works again.
+    client.run_action(set_flag(empty.id, false)).await.unwrap();
+    client.run_action(to_scalar.into()).await.unwrap();
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_convert_to_sets_rejections(pool: SqlitePool) {
+async fn test_sets_member_activity_guards(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
     let user = create_user(&client).await;
 
-    let activity = Activity {
+    let make_activity = |name: &str| Activity {
         id: Uuid::new_v4(),
         owner_id: user.actor_id,
-        name: ActivityName::parse("Squat".to_string()).unwrap(),
+        name: ActivityName::parse(name.to_string()).unwrap(),
         description: None,
         source_activity_id: None,
+        archived: false,
     };
-    let template_root_id = Uuid::new_v4();
-    client
-        .run_action(
-            activity
-                .clone()
-                .into_create_activity(template_root_id)
-                .into(),
+    let activity_a = make_activity("Bench Press");
+    let activity_b = make_activity("Overhead Press");
+    run_actions(
+        &client,
+        [
+            activity_a.clone().into_create_activity(Uuid::new_v4()).into(),
+            activity_b.clone().into_create_activity(Uuid::new_v4()).into(),
+        ],
+    )
+    .await;
+
+    let (sequence, members) =
+        seed_sets_sequence(&client, user.actor_id, Some(activity_a.id), 2).await;
+    let append_position = || {
+        child_position(
+            sequence.id,
+            FractionalIndex::new_after(members[1].frac_index().unwrap()),
         )
+    };
+
+    // CreateEntry into the flagged sequence: wrong activity and anonymous are
+    // rejected; the shared activity is accepted.
+    let wrong = log_entry(user.actor_id, Some(activity_b.id), append_position());
+    assert!(
+        client
+            .run_action(CreateEntry::from(wrong).into())
+            .await
+            .is_err()
+    );
+    let anonymous = log_entry(user.actor_id, None, append_position());
+    assert!(
+        client
+            .run_action(CreateEntry::from(anonymous).into())
+            .await
+            .is_err()
+    );
+    let matching = log_entry(user.actor_id, Some(activity_a.id), append_position());
+    client
+        .run_action(CreateEntry::from(matching.clone()).into())
         .await
         .unwrap();
 
-    // An activity template root cannot be converted.
+    // CreateEntryFromActivity into the flagged sequence: B rejected, A fine.
+    let instantiate = |activity_id| CreateEntryFromActivity {
+        actor_id: user.actor_id,
+        activity_id,
+        position: child_position(
+            sequence.id,
+            FractionalIndex::new_after(matching.frac_index().unwrap()),
+        ),
+        temporal: Temporal::None,
+        is_template: false,
+    };
     assert!(
         client
-            .run_action(
-                ConvertToSets {
-                    actor_id: user.actor_id,
-                    entry_id: template_root_id,
-                    sequence_id: Uuid::new_v4(),
-                }
-                .into(),
-            )
+            .run_action(instantiate(activity_b.id).into())
             .await
-            .is_err(),
-        "template root conversion must be rejected"
+            .is_err()
     );
-
-    // The client-supplied sequence_id must be unused.
-    let mut on_timeline = log_entry(user.actor_id, None, None);
-    on_timeline.temporal = Temporal::Start {
+    client
+        .run_action(instantiate(activity_a.id).into())
+        .await
+        .unwrap();
+
+    // MoveEntry into the flagged sequence: wrong activity rejected, matching
+    // accepted; reordering existing members is fine.
+    let mut outsider = log_entry(user.actor_id, Some(activity_b.id), None);
+    outsider.temporal = Temporal::Start {
         start: sqlx::types::chrono::Utc::now(),
     };
     client
-        .run_action(CreateEntry::from(on_timeline.clone()).into())
+        .run_action(CreateEntry::from(outsider.clone()).into())
         .await
         .unwrap();
-    assert!(
-        client
-            .run_action(
-                ConvertToSets {
-                    actor_id: user.actor_id,
-                    entry_id: on_timeline.id,
-                    sequence_id: on_timeline.id,
-                }
-                .into(),
-            )
-            .await
-            .is_err(),
-        "colliding sequence_id must be rejected"
-    );
+    let move_into = |entry_id| MoveEntry {
+        actor_id: user.actor_id,
+        entry_id,
+        position: child_position(
+            sequence.id,
+            FractionalIndex::new_before(members[0].frac_index().unwrap()),
+        ),
+        temporal: Temporal::None,
+    };
+    assert!(client.run_action(move_into(outsider.id).into()).await.is_err());
+    client
+        .run_action(move_into(members[1].id).into())
+        .await
+        .unwrap();
+}
 
-    // A log root with no start/end cannot be converted: the sequence would
-    // land off the timeline.
-    let off_timeline = log_entry(user.actor_id, None, None);
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_sets_min_member_guards(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+
+    let (sequence, members) = seed_sets_sequence(&client, user.actor_id, None, 2).await;
+
+    // Deleting down to one member is fine; deleting the last is rejected.
     client
-        .run_action(CreateEntry::from(off_timeline.clone()).into())
+        .run_action(
+            DeleteEntryRecursive {
+                actor_id: user.actor_id,
+                entry_id: members[0].id,
+            }
+            .into(),
+        )
         .await
         .unwrap();
     assert!(
         client
             .run_action(
-                ConvertToSets {
+                DeleteEntryRecursive {
                     actor_id: user.actor_id,
-                    entry_id: off_timeline.id,
-                    sequence_id: Uuid::new_v4(),
+                    entry_id: members[1].id,
                 }
                 .into(),
             )
             .await
             .is_err(),
-        "off-timeline root conversion must be rejected"
+        "deleting the last member must be rejected"
     );
 
-    // A member of an activity-bearing sets sequence cannot be converted: the
-    // anonymous sequence would break the members' shared activity.
-    let (_, members) = seed_sets_sequence(&client, user.actor_id, Some(activity.id), 2).await;
+    // Moving the last member out is rejected.
     assert!(
         client
             .run_action(
-                ConvertToSets {
+                MoveEntry {
                     actor_id: user.actor_id,
-                    entry_id: members[0].id,
-                    sequence_id: Uuid::new_v4(),
+                    entry_id: members[1].id,
+                    position: None,
+                    temporal: Temporal::Start {
+                        start: sqlx::types::chrono::Utc::now()
+                    },
                 }
                 .into(),
             )
             .await
             .is_err(),
-        "converting a member among activity-bearing siblings must be rejected"
+        "moving the last member out must be rejected"
     );
 
-    // Among all-anonymous members it is fine (anonymity matches).
-    let (_, anon_members) = seed_sets_sequence(&client, user.actor_id, None, 2).await;
+    // The "+" flow: duplicating the last member appends a sibling copy.
     client
         .run_action(
-            ConvertToSets {
+            DuplicateEntry {
                 actor_id: user.actor_id,
-                entry_id: anon_members[0].id,
-                sequence_id: Uuid::new_v4(),
+                entry_id: members[1].id,
             }
             .into(),
         )
         .await
         .unwrap();
-}
-
-#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_duplicate_entry_exact_copy_after_source(pool: SqlitePool) {
-    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
-    let user = create_user(&client).await;
-
-    let mut parent = log_entry(user.actor_id, None, None);
-    parent.is_sequence = true;
-    parent.temporal = Temporal::Start {
-        start: sqlx::types::chrono::Utc::now(),
-    };
-    let fi1 = FractionalIndex::default();
-    let fi2 = FractionalIndex::new_after(&fi1);
-    let mut c1 = log_entry(user.actor_id, None, child_position(parent.id, fi1.clone()));
-    c1.is_sequence = true;
-    c1.is_complete = false;
-    c1.temporal = Temporal::Duration { duration: 90_000 };
-    let c2 = log_entry(user.actor_id, None, child_position(parent.id, fi2));
-    let mut c1a = log_entry(
-        user.actor_id,
-        None,
-        child_position(c1.id, FractionalIndex::default()),
-    );
-    c1a.is_complete = true;
-
-    let attribute = Attribute {
-        id: Uuid::new_v4(),
-        owner_id: user.actor_id,
-        name: "Reps".to_string(),
-        description: None,
-        config: AttributeConfig::Numeric(NumericConfig {
-            min: None,
-            max: None,
-            integer: true,
-            default: None,
-        }),
-    };
-    let value = Value {
-        entry_id: c1a.id,
-        attribute_id: attribute.id,
-        index_float: Some(13.0),
-        index_string: None,
-        plan: None,
-        actual: Some(AttributeValue::Numeric(NumericValue::Exact(13.0))),
-    };
-
-    run_actions(
-        &client,
-        [
-            CreateEntry::from(parent.clone()).into(),
-            CreateEntry::from(c1.clone()).into(),
-            CreateEntry::from(c2.clone()).into(),
-            CreateEntry::from(c1a.clone()).into(),
-            CreateAttribute {
-                actor_id: user.actor_id,
-                attribute: attribute.clone(),
-            }
-            .into(),
-            CreateValue {
-                actor_id: user.actor_id,
-                value: value.clone(),
-            }
-            .into(),
-        ],
-    )
-    .await;
 
+    // Breaking out lifts the floor; deleting the whole sequence is always
+    // fine and removes the members with it.
     client
         .run_action(
-            DuplicateEntry {
+            UpdateEntry {
                 actor_id: user.actor_id,
-                entry_id: c1.id,
+                entry_id: sequence.id,
+                change: EntryChange::SetDisplayAsSets(false),
             }
             .into(),
         )
         .await
         .unwrap();
-
-    // The copy lands between c1 and c2 in sibling order.
-    let mut conn = client.pool.acquire().await.unwrap();
-    let subtree = SqliteQueryExecutor::new(&mut *conn)
-        .execute(FindDescendants {
-            entry_id: parent.id,
-        })
-        .await
-        .unwrap();
-    let forest = gv_core::forest::Forest::from(subtree);
-    let children: Vec<Uuid> = forest.children(parent.id).iter().map(|e| e.id).collect();
-    assert_eq!(children.len(), 3);
-    assert_eq!(children[0], c1.id);
-    assert_eq!(children[2], c2.id);
-    let dup_id = children[1];
-    assert_ne!(dup_id, c1.id);
-
-    // Exact copy: temporal/completion verbatim, subtree deep-copied with
-    // fresh ids, value re-keyed onto the copied grandchild.
-    let dup = forest.entry(dup_id).unwrap();
-    assert_eq!(dup.temporal, Temporal::Duration { duration: 90_000 });
-    assert!(dup.is_sequence);
-    let dup_children = forest.children(dup_id);
-    assert_eq!(dup_children.len(), 1);
-    let dup_grandchild = dup_children[0];
-    assert_ne!(dup_grandchild.id, c1a.id);
-    assert!(dup_grandchild.is_complete, "completion copies verbatim");
-
-    let dup_value = SqliteQueryExecutor::new(&mut *conn)
-        .execute(FindValueByKey {
-            entry_id: dup_grandchild.id,
-            attribute_id: attribute.id,
-        })
-        .await
-        .unwrap()
-        .expect("value must be re-keyed onto the copy");
-    assert_eq!(
-        dup_value.actual,
-        Some(AttributeValue::Numeric(NumericValue::Exact(13.0)))
-    );
-}
-
-#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_duplicate_entry_roots(pool: SqlitePool) {
-    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
-    let user = create_user(&client).await;
-
-    // A log root duplicates in place: same temporal, also a root.
-    let start = sqlx::types::chrono::Utc::now();
-    let mut root = log_entry(user.actor_id, None, None);
-    root.temporal = Temporal::Start { start };
-    client
-        .run_action(CreateEntry::from(root.clone()).into())
-        .await
-        .unwrap();
     client
         .run_action(
-            DuplicateEntry {
+            DeleteEntryRecursive {
                 actor_id: user.actor_id,
-                entry_id: root.id,
+                entry_id: members[1].id,
             }
             .into(),
         )
         .await
         .unwrap();
-    let mut conn = client.pool.acquire().await.unwrap();
-    let all = SqliteQueryExecutor::new(&mut *conn)
-        .execute(AllEntries)
-        .await
-        .unwrap();
-    let copies: Vec<&Entry> = all
-        .iter()
-        .filter(|e| e.position.is_none() && e.temporal == Temporal::Start { start })
-        .collect();
-    assert_eq!(copies.len(), 2, "copy is a root with the same temporal");
 
-    // An activity template root cannot be duplicated (it would mint a second
-    // template root for the activity).
-    let activity = Activity {
-        id: Uuid::new_v4(),
-        owner_id: user.actor_id,
-        name: ActivityName::parse("Deadlift".to_string()).unwrap(),
-        description: None,
-        source_activity_id: None,
-    };
-    let template_root_id = Uuid::new_v4();
-    client
-        .run_action(activity.into_create_activity(template_root_id).into())
-        .await
-        .unwrap();
-    assert!(
-        client
-            .run_action(
-                DuplicateEntry {
-                    actor_id: user.actor_id,
-                    entry_id: template_root_id,
-                }
-                .into(),
-            )
-            .await
-            .is_err(),
-        "template root duplication must be rejected"
-    );
+    let (sequence2, _) = seed_sets_sequence(&client, user.actor_id, None, 1).await;
+    client
+        .run_action(
+            DeleteEntryRecursive {
+                actor_id: user.actor_id,
+                entry_id: sequence2.id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    assert!(find_entry(&client, sequence2.id).await.is_none());
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_set_display_as_sets_guards(pool: SqlitePool) {
+async fn test_break_out_names_sequence(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
     let user = create_user(&client).await;
 
-    let activity = Activity {
-        id: Uuid::new_v4(),
-        owner_id: user.actor_id,
-        name: ActivityName::parse("Pull Up".to_string()).unwrap(),
-        description: None,
-        source_activity_id: None,
-    };
-    client
-        .run_action(activity.clone().into_create_activity(Uuid::new_v4()).into())
-        .await
-        .unwrap();
-
     let set_flag = |entry_id, value| {
         UpdateEntry {
             actor_id: user.actor_id,
@@ -1783,489 +4095,993 @@ async fn test_set_display_as_sets_guards(pool: SqlitePool) {
         }
         .into()
     };
+    let name_of = |entry: Option<Entry>| entry.and_then(|e| e.name);
 
-    // A scalar cannot be flagged.
-    let mut scalar = log_entry(user.actor_id, None, None);
-    scalar.temporal = Temporal::Start {
-        start: sqlx::types::chrono::Utc::now(),
+    // Activity-backed members: name derives from the activity.
+    let activity = Activity {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: ActivityName::parse("Bench Press".to_string()).unwrap(),
+        description: None,
+        source_activity_id: None,
+        archived: false,
     };
     client
-        .run_action(CreateEntry::from(scalar.clone()).into())
+        .run_action(activity.clone().into_create_activity(Uuid::new_v4()).into())
         .await
         .unwrap();
-    assert!(client.run_action(set_flag(scalar.id, true)).await.is_err());
+    let (sequence, _) = seed_sets_sequence(&client, user.actor_id, Some(activity.id), 2).await;
+    client
+        .run_action(set_flag(sequence.id, false))
+        .await
+        .unwrap();
+    assert_eq!(
+        name_of(find_entry(&client, sequence.id).await),
+        Some("Bench Press Sets".to_string())
+    );
 
-    // An empty sequence cannot be flagged.
-    let mut empty = log_entry(user.actor_id, None, None);
-    empty.is_sequence = true;
-    empty.temporal = Temporal::Start {
-        start: sqlx::types::chrono::Utc::now(),
-    };
+    // Fully anonymous members leave the sequence unnamed (no "Unnamed Sets").
+    let (sequence2, _) = seed_sets_sequence(&client, user.actor_id, None, 1).await;
     client
-        .run_action(CreateEntry::from(empty.clone()).into())
+        .run_action(set_flag(sequence2.id, false))
         .await
         .unwrap();
-    assert!(client.run_action(set_flag(empty.id, true)).await.is_err());
+    assert_eq!(
+        name_of(find_entry(&client, sequence2.id).await),
+        None,
+        "anonymous unnamed member derives no name"
+    );
 
-    // Heterogeneous members (activity + anonymous) cannot be flagged.
-    let fi1 = FractionalIndex::default();
-    let fi2 = FractionalIndex::new_after(&fi1);
-    let m1 = log_entry(
+    // A sequence that already has a name keeps it.
+    let mut named = log_entry(user.actor_id, None, None);
+    named.name = Some("My Workout".to_string());
+    named.is_sequence = true;
+    named.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
+    };
+    let member = log_entry(
         user.actor_id,
         Some(activity.id),
-        child_position(empty.id, fi1),
+        child_position(named.id, FractionalIndex::default()),
     );
-    let m2 = log_entry(user.actor_id, None, child_position(empty.id, fi2.clone()));
     run_actions(
         &client,
         [
-            CreateEntry::from(m1.clone()).into(),
-            CreateEntry::from(m2.clone()).into(),
+            CreateEntry::from(named.clone()).into(),
+            CreateEntry::from(member.clone()).into(),
         ],
     )
     .await;
-    assert!(client.run_action(set_flag(empty.id, true)).await.is_err());
+    client.run_action(set_flag(named.id, true)).await.unwrap();
+    client.run_action(set_flag(named.id, false)).await.unwrap();
+    assert_eq!(
+        name_of(find_entry(&client, named.id).await),
+        Some("My Workout".to_string()),
+        "an existing name is never clobbered"
+    );
+}
 
-    // Homogeneous members flag fine; setting again is a no-op; a flagged
-    // sequence cannot become a scalar until broken out.
-    client
-        .run_action(
-            DeleteEntryRecursive {
-                actor_id: user.actor_id,
-                entry_id: m2.id,
-            }
-            .into(),
-        )
-        .await
-        .unwrap();
-    client.run_action(set_flag(empty.id, true)).await.unwrap();
-    assert!(find_entry(&client, empty.id).await.unwrap().display_as_sets);
-    client.run_action(set_flag(empty.id, true)).await.unwrap();
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_create_entry_born_flagged_rejected(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
 
-    let to_scalar = UpdateEntry {
-        actor_id: user.actor_id,
-        entry_id: empty.id,
-        change: EntryChange::SetIsSequence(false),
+    let mut entry = log_entry(user.actor_id, None, None);
+    entry.is_sequence = true;
+    entry.display_as_sets = true;
+    entry.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
     };
     assert!(
-        client.run_action(to_scalar.clone().into()).await.is_err(),
-        "flagged sequence cannot become a scalar"
+        client
+            .run_action(CreateEntry::from(entry).into())
+            .await
+            .is_err(),
+        "a new entry cannot be born with display_as_sets"
     );
-
-    // Break out, then scalar conversion works again.
-    client.run_action(set_flag(empty.id, false)).await.unwrap();
-    client.run_action(to_scalar.into()).await.unwrap();
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_sets_member_activity_guards(pool: SqlitePool) {
+async fn test_sets_in_activity_templates(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
     let user = create_user(&client).await;
 
-    let make_activity = |name: &str| Activity {
+    let activity = Activity {
         id: Uuid::new_v4(),
         owner_id: user.actor_id,
-        name: ActivityName::parse(name.to_string()).unwrap(),
+        name: ActivityName::parse("Core Series".to_string()).unwrap(),
         description: None,
         source_activity_id: None,
+        archived: false,
     };
-    let activity_a = make_activity("Bench Press");
-    let activity_b = make_activity("Overhead Press");
-    run_actions(
-        &client,
-        [
-            activity_a.clone().into_create_activity(Uuid::new_v4()).into(),
-            activity_b.clone().into_create_activity(Uuid::new_v4()).into(),
-        ],
-    )
-    .await;
-
-    let (sequence, members) =
-        seed_sets_sequence(&client, user.actor_id, Some(activity_a.id), 2).await;
-    let append_position = || {
-        child_position(
-            sequence.id,
-            FractionalIndex::new_after(members[1].frac_index().unwrap()),
-        )
+    let template_entry = |activity_id, position, is_sequence, display_as_sets| Entry {
+        id: Uuid::new_v4(),
+        activity_id,
+        owner_id: user.actor_id,
+        name: Some("set".to_string()),
+        position,
+        is_template: true,
+        display_as_sets,
+        is_sequence,
+        is_complete: false,
+        temporal: Temporal::None,
     };
 
-    // CreateEntry into the flagged sequence: wrong activity and anonymous are
-    // rejected; the shared activity is accepted.
-    let wrong = log_entry(user.actor_id, Some(activity_b.id), append_position());
-    assert!(
-        client
-            .run_action(CreateEntry::from(wrong).into())
-            .await
-            .is_err()
+    // A template containing a flagged sequence with no members is rejected.
+    let bad_root = template_entry(Some(activity.id), None, true, false);
+    let bad_sets = template_entry(
+        None,
+        child_position(bad_root.id, FractionalIndex::default()),
+        true,
+        true,
     );
-    let anonymous = log_entry(user.actor_id, None, append_position());
     assert!(
         client
-            .run_action(CreateEntry::from(anonymous).into())
+            .run_action(
+                CreateActivity {
+                    actor_id: user.actor_id,
+                    activity: activity.clone(),
+                    template: vec![bad_root, bad_sets],
+                }
+                .into(),
+            )
             .await
-            .is_err()
+            .is_err(),
+        "template with an empty sets sequence must be rejected"
+    );
+
+    // A valid sets template: root sequence -> flagged sequence -> 2 anonymous
+    // members.
+    let root = template_entry(Some(activity.id), None, true, false);
+    let sets = template_entry(
+        None,
+        child_position(root.id, FractionalIndex::default()),
+        true,
+        true,
+    );
+    let fi1 = FractionalIndex::default();
+    let fi2 = FractionalIndex::new_after(&fi1);
+    let member1 = template_entry(None, child_position(sets.id, fi1), false, false);
+    let member2 = template_entry(None, child_position(sets.id, fi2), false, false);
+    client
+        .run_action(
+            CreateActivity {
+                actor_id: user.actor_id,
+                activity: activity.clone(),
+                template: vec![root, sets.clone(), member1, member2],
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    // Instantiation preserves display_as_sets into the log.
+    client
+        .run_action(
+            CreateEntryFromActivity {
+                actor_id: user.actor_id,
+                activity_id: activity.id,
+                position: None,
+                temporal: Temporal::Start {
+                    start: sqlx::types::chrono::Utc::now(),
+                },
+                is_template: false,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    let mut conn = client.pool.acquire().await.unwrap();
+    let all = SqliteQueryExecutor::new(&mut *conn)
+        .execute(AllEntries)
+        .await
+        .unwrap();
+    let instantiated_sets: Vec<&Entry> = all
+        .iter()
+        .filter(|e| !e.is_template && e.display_as_sets)
+        .collect();
+    assert_eq!(
+        instantiated_sets.len(),
+        1,
+        "instantiated subtree must preserve display_as_sets"
+    );
+    assert_ne!(instantiated_sets[0].id, sets.id, "instance has a fresh id");
+    let forest = gv_core::forest::Forest::from(all.clone());
+    assert_eq!(
+        forest.children(instantiated_sets[0].id).len(),
+        2,
+        "instantiated sets sequence keeps its members"
     );
-    let matching = log_entry(user.actor_id, Some(activity_a.id), append_position());
-    client
-        .run_action(CreateEntry::from(matching.clone()).into())
-        .await
-        .unwrap();
+}
 
-    // CreateEntryFromActivity into the flagged sequence: B rejected, A fine.
-    let instantiate = |activity_id| CreateEntryFromActivity {
-        actor_id: user.actor_id,
-        activity_id,
-        position: child_position(
-            sequence.id,
-            FractionalIndex::new_after(matching.frac_index().unwrap()),
-        ),
-        temporal: Temporal::None,
-        is_template: false,
+/// A root entry on the timeline, built through the public actions.
+async fn seed_root_entry(client: &SqliteClient, owner_id: Uuid, is_sequence: bool) -> Entry {
+    let mut entry = log_entry(owner_id, None, None);
+    entry.is_sequence = is_sequence;
+    entry.temporal = Temporal::Start {
+        start: sqlx::types::chrono::Utc::now(),
     };
-    assert!(
-        client
-            .run_action(instantiate(activity_b.id).into())
-            .await
-            .is_err()
-    );
     client
-        .run_action(instantiate(activity_a.id).into())
+        .run_action(CreateEntry::from(entry.clone()).into())
         .await
         .unwrap();
+    entry
+}
 
-    // MoveEntry into the flagged sequence: wrong activity rejected, matching
-    // accepted; reordering existing members is fine.
-    let mut outsider = log_entry(user.actor_id, Some(activity_b.id), None);
-    outsider.temporal = Temporal::Start {
-        start: sqlx::types::chrono::Utc::now(),
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_undo_redo_round_trip(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+
+    let sequence = seed_root_entry(&client, user.actor_id, true).await;
+    let fi1 = FractionalIndex::default();
+    let fi2 = FractionalIndex::new_after(&fi1);
+    let child1 = log_entry(user.actor_id, None, child_position(sequence.id, fi1));
+    let child2 = log_entry(user.actor_id, None, child_position(sequence.id, fi2));
+    run_actions(
+        &client,
+        [
+            CreateEntry::from(child1.clone()).into(),
+            CreateEntry::from(child2.clone()).into(),
+        ],
+    )
+    .await;
+    let snapshot = |client: SqliteClient| async move {
+        let mut conn = client.pool.acquire().await.unwrap();
+        let mut entries = SqliteQueryExecutor::new(&mut *conn)
+            .execute(AllEntries)
+            .await
+            .unwrap();
+        entries.sort_by_key(|e| e.id);
+        entries
     };
+    let before_delete = snapshot(client.clone()).await;
+
     client
-        .run_action(CreateEntry::from(outsider.clone()).into())
+        .run_action(
+            DeleteEntryRecursive {
+                actor_id: user.actor_id,
+                entry_id: sequence.id,
+            }
+            .into(),
+        )
         .await
         .unwrap();
-    let move_into = |entry_id| MoveEntry {
-        actor_id: user.actor_id,
-        entry_id,
-        position: child_position(
-            sequence.id,
-            FractionalIndex::new_before(members[0].frac_index().unwrap()),
-        ),
-        temporal: Temporal::None,
-    };
-    assert!(client.run_action(move_into(outsider.id).into()).await.is_err());
+    assert!(find_entry(&client, sequence.id).await.is_none());
+
+    // Undo restores the whole subtree exactly.
+    assert!(client.undo(user.actor_id).await.unwrap().is_some());
+    assert_eq!(snapshot(client.clone()).await, before_delete);
+    assert!(client.can_redo(user.actor_id));
+
+    // Redo deletes it again; a second undo brings it back.
+    assert!(client.redo(user.actor_id).await.unwrap().is_some());
+    assert!(find_entry(&client, sequence.id).await.is_none());
+    assert!(find_entry(&client, child1.id).await.is_none());
+    client.undo(user.actor_id).await.unwrap();
+    assert_eq!(snapshot(client.clone()).await, before_delete);
+
+    // Undo walks back through earlier actions in order.
+    client.undo(user.actor_id).await.unwrap();
+    assert!(find_entry(&client, child2.id).await.is_none());
+    assert!(find_entry(&client, child1.id).await.is_some());
+
+    // A new action clears the redo stack.
+    assert!(client.can_redo(user.actor_id));
     client
-        .run_action(move_into(members[1].id).into())
+        .run_action(
+            MoveEntry {
+                actor_id: user.actor_id,
+                entry_id: child1.id,
+                position: None,
+                temporal: Temporal::Start {
+                    start: sqlx::types::chrono::Utc::now(),
+                },
+            }
+            .into(),
+        )
         .await
         .unwrap();
+    assert!(!client.can_redo(user.actor_id));
+    assert!(client.redo(user.actor_id).await.unwrap().is_none());
+
+    // Undoing the move restores the original position.
+    client.undo(user.actor_id).await.unwrap();
+    assert_eq!(find_entry(&client, child1.id).await.unwrap(), child1);
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_sets_min_member_guards(pool: SqlitePool) {
+async fn test_undo_stacks_are_per_actor(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
-    let user = create_user(&client).await;
+    let alice = create_user(&client).await;
+    let bob = create_user(&client).await;
 
-    let (sequence, members) = seed_sets_sequence(&client, user.actor_id, None, 2).await;
+    let alice_entry = seed_root_entry(&client, alice.actor_id, false).await;
+    let bob_entry = seed_root_entry(&client, bob.actor_id, false).await;
 
-    // Deleting down to one member is fine; deleting the last is rejected.
+    // Alice's undo reverts her own latest action, not Bob's more recent one.
+    client.undo(alice.actor_id).await.unwrap();
+    assert!(find_entry(&client, alice_entry.id).await.is_none());
+    assert!(find_entry(&client, bob_entry.id).await.is_some());
+    assert!(client.can_undo(bob.actor_id));
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_undo_rejected_when_state_has_moved_on(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+
+    // A row edited since the mutation blocks the undo.
+    let entry = seed_root_entry(&client, user.actor_id, false).await;
+    let complete = client
+        .run_action(
+            UpdateEntryCompletion {
+                actor_id: user.actor_id,
+                entry_id: entry.id,
+                is_complete: true,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
     client
         .run_action(
-            DeleteEntryRecursive {
+            UpdateEntryCompletion {
                 actor_id: user.actor_id,
-                entry_id: members[0].id,
+                entry_id: entry.id,
+                is_complete: false,
             }
             .into(),
         )
         .await
         .unwrap();
+    let result = client
+        .run_action(
+            Undo {
+                actor_id: user.actor_id,
+                mutation_id: complete.id,
+            }
+            .into(),
+        )
+        .await;
     assert!(
-        client
-            .run_action(
-                DeleteEntryRecursive {
-                    actor_id: user.actor_id,
-                    entry_id: members[1].id,
-                }
-                .into(),
-            )
-            .await
-            .is_err(),
-        "deleting the last member must be rejected"
+        matches!(
+            result,
+            Err(DomainError::Rejected(RejectReason::Precondition(_)))
+        ),
+        "stale undo must be rejected, got {:?}",
+        result
     );
 
-    // Moving the last member out is rejected.
+    // Undoing an insert is rejected once the entry has gained children.
+    let parent = seed_root_entry(&client, user.actor_id, true).await;
+    let create_parent = client.undo(user.actor_id).await.unwrap().unwrap();
+    client.redo(user.actor_id).await.unwrap();
+    let child = log_entry(
+        user.actor_id,
+        None,
+        child_position(parent.id, FractionalIndex::default()),
+    );
+    client
+        .run_action(CreateEntry::from(child).into())
+        .await
+        .unwrap();
+    let Action::Undo(undo_create) = create_parent.action else {
+        panic!("expected the undo mutation to carry an Undo action");
+    };
     assert!(
         client
             .run_action(
-                MoveEntry {
+                Undo {
                     actor_id: user.actor_id,
-                    entry_id: members[1].id,
-                    position: None,
-                    temporal: Temporal::Start {
-                        start: sqlx::types::chrono::Utc::now()
-                    },
+                    mutation_id: undo_create.mutation_id,
                 }
                 .into(),
             )
             .await
             .is_err(),
-        "moving the last member out must be rejected"
+        "deleting an entry that gained children must be rejected"
     );
 
-    // The "+" flow: duplicating the last member appends a sibling copy.
+    // Restoring a position that now creates a cycle is rejected: `x` leaves
+    // `p`, then `p` moves under `x`.
+    let p = seed_root_entry(&client, user.actor_id, true).await;
+    let mut x = log_entry(
+        user.actor_id,
+        None,
+        child_position(p.id, FractionalIndex::default()),
+    );
+    x.is_sequence = true;
     client
+        .run_action(CreateEntry::from(x.clone()).into())
+        .await
+        .unwrap();
+    let move_x = client
         .run_action(
-            DuplicateEntry {
+            MoveEntry {
                 actor_id: user.actor_id,
-                entry_id: members[1].id,
+                entry_id: x.id,
+                position: None,
+                temporal: Temporal::Start {
+                    start: sqlx::types::chrono::Utc::now(),
+                },
             }
             .into(),
         )
         .await
         .unwrap();
-
-    // Breaking out lifts the floor; deleting the whole sequence is always
-    // fine and removes the members with it.
     client
         .run_action(
-            UpdateEntry {
+            MoveEntry {
                 actor_id: user.actor_id,
-                entry_id: sequence.id,
-                change: EntryChange::SetDisplayAsSets(false),
+                entry_id: p.id,
+                position: child_position(x.id, FractionalIndex::default()),
+                temporal: Temporal::None,
             }
             .into(),
         )
         .await
         .unwrap();
-    client
+    let result = client
         .run_action(
-            DeleteEntryRecursive {
+            Undo {
                 actor_id: user.actor_id,
-                entry_id: members[1].id,
+                mutation_id: move_x.id,
             }
             .into(),
         )
+        .await;
+    assert!(
+        matches!(
+            result,
+            Err(DomainError::Rejected(RejectReason::Precondition(_)))
+        ),
+        "undo that would create a cycle must be rejected, got {:?}",
+        result
+    );
+}
+
+/// Undo and redo re-check restored attributes the way creating and updating
+/// them does: an input can't be removed from under a derived attribute, and a
+/// derived attribute can't come back once its input is gone.
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_undo_rechecks_derived_attribute_inputs(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let reps = Attribute {
+        id: Uuid::new_v4(),
+        owner_id: user.actor_id,
+        name: "Reps".to_string(),
+        description: None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }),
+    };
+    let total = Attribute {
+        id: Uuid::new_v4(),
+        name: "Total Reps".to_string(),
+        config: AttributeConfig::Derived(DerivedConfig {
+            expression: DerivedExpr::attribute(reps.id).sum_descendants(),
+        }),
+        ..reps.clone()
+    };
+    let create_reps = client
+        .run_action(CreateAttribute::from(reps.clone()).into())
+        .await
+        .unwrap();
+    let create_total = client
+        .run_action(CreateAttribute::from(total.clone()).into())
         .await
         .unwrap();
 
-    let (sequence2, _) = seed_sets_sequence(&client, user.actor_id, None, 1).await;
-    client
+    // The input can't be undone while the derived attribute reads it.
+    let result = client
         .run_action(
-            DeleteEntryRecursive {
+            Undo {
                 actor_id: user.actor_id,
-                entry_id: sequence2.id,
+                mutation_id: create_reps.id,
             }
             .into(),
         )
+        .await;
+    assert!(
+        matches!(
+            result,
+            Err(DomainError::Rejected(RejectReason::Precondition(_)))
+        ),
+        "removing a derived attribute's input must be rejected, got {:?}",
+        result
+    );
+
+    // Once both are undone, the derived attribute can't come back alone.
+    client.undo(user.actor_id).await.unwrap();
+    client.undo(user.actor_id).await.unwrap();
+    let redo_total = |client: SqliteClient| async move {
+        client
+            .run_action(
+                Redo {
+                    actor_id: user.actor_id,
+                    mutation_id: create_total.id,
+                }
+                .into(),
+            )
+            .await
+    };
+    let result = redo_total(client.clone()).await;
+    assert!(
+        matches!(
+            result,
+            Err(DomainError::Rejected(RejectReason::NotFound(_)))
+        ),
+        "restoring a derived attribute without its input must be rejected, got {:?}",
+        result
+    );
+
+    // With the input back, it can.
+    client.redo(user.actor_id).await.unwrap();
+    redo_total(client.clone()).await.unwrap();
+    let restored = client
+        .run_query(
+            user.actor_id,
+            FindAttributeById {
+                attribute_id: total.id,
+            },
+        )
         .await
         .unwrap();
-    assert!(find_entry(&client, sequence2.id).await.is_none());
+    assert_eq!(restored, Some(total));
 }
 
+/// Two clients over one database (e.g. two windows): an undo made stale by
+/// the other session is rejected and dropped from the history.
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_break_out_names_sequence(pool: SqlitePool) {
-    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+async fn test_rejected_undo_is_discarded(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool.clone(), Arc::new(gv_core::io::SystemIo::default()));
+    let other = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
     let user = create_user(&client).await;
 
-    let set_flag = |entry_id, value| {
-        UpdateEntry {
-            actor_id: user.actor_id,
-            entry_id,
-            change: EntryChange::SetDisplayAsSets(value),
-        }
-        .into()
-    };
-    let name_of = |entry: Option<Entry>| entry.and_then(|e| e.name);
+    let entry = seed_root_entry(&client, user.actor_id, false).await;
+    other
+        .run_action(
+            UpdateEntryCompletion {
+                actor_id: user.actor_id,
+                entry_id: entry.id,
+                is_complete: true,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
 
-    // Activity-backed members: name derives from the activity.
-    let activity = Activity {
-        id: Uuid::new_v4(),
-        owner_id: user.actor_id,
-        name: ActivityName::parse("Bench Press".to_string()).unwrap(),
-        description: None,
-        source_activity_id: None,
-    };
-    client
-        .run_action(activity.clone().into_create_activity(Uuid::new_v4()).into())
+    assert!(client.can_undo(user.actor_id));
+    assert!(client.undo(user.actor_id).await.is_err());
+    assert!(find_entry(&client, entry.id).await.is_some());
+    assert!(!client.can_undo(user.actor_id));
+    assert!(!client.can_redo(user.actor_id));
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_mutation_log_records_applied_actions(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let entry = seed_root_entry(&client, user.actor_id, false).await;
+    let complete = client
+        .run_action(
+            UpdateEntryCompletion {
+                actor_id: user.actor_id,
+                entry_id: entry.id,
+                is_complete: true,
+            }
+            .into(),
+        )
         .await
         .unwrap();
-    let (sequence, _) = seed_sets_sequence(&client, user.actor_id, Some(activity.id), 2).await;
-    client
-        .run_action(set_flag(sequence.id, false))
+
+    // A rejected action rolls back with its transaction and leaves no row.
+    let rejected = client
+        .run_action(
+            UpdateEntryCompletion {
+                actor_id: user.actor_id,
+                entry_id: Uuid::new_v4(),
+                is_complete: true,
+            }
+            .into(),
+        )
+        .await;
+    assert!(rejected.is_err());
+
+    let pending = client
+        .run_query(user.actor_id, PendingMutations)
         .await
         .unwrap();
-    assert_eq!(
-        name_of(find_entry(&client, sequence.id).await),
-        Some("Bench Press Sets".to_string())
-    );
-
-    // Fully anonymous members leave the sequence unnamed (no "Unnamed Sets").
-    let (sequence2, _) = seed_sets_sequence(&client, user.actor_id, None, 1).await;
-    client
-        .run_action(set_flag(sequence2.id, false))
+    assert_eq!(pending.len(), 3);
+    assert!(pending.windows(2).all(|w| w[0].seq < w[1].seq));
+    assert!(pending.iter().all(|m| m.state == MutationState::Pending));
+    assert!(matches!(pending[0].mutation.action, Action::CreateUser(_)));
+    assert!(matches!(pending[1].mutation.action, Action::CreateEntry(_)));
+    assert_eq!(pending[2].mutation, complete);
+
+    let history = client
+        .run_query(user.actor_id, MutationHistory { limit: 2 })
         .await
         .unwrap();
-    assert_eq!(
-        name_of(find_entry(&client, sequence2.id).await),
-        None,
-        "anonymous unnamed member derives no name"
-    );
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].mutation.id, complete.id);
+    assert_eq!(history[1].seq, pending[1].seq);
+}
 
-    // A sequence that already has a name keeps it.
-    let mut named = log_entry(user.actor_id, None, None);
-    named.name = Some("My Workout".to_string());
-    named.is_sequence = true;
-    named.temporal = Temporal::Start {
-        start: sqlx::types::chrono::Utc::now(),
+/// An activity with a one-entry template, plus a copy of it, both owned by
+/// `owner_id`. Returns `(activity, template_root, copy)`.
+async fn seed_activity_with_copy(
+    client: &SqliteClient,
+    owner_id: Uuid,
+    name: &str,
+) -> (Activity, Entry, Activity) {
+    let activity = Activity {
+        id: Uuid::new_v4(),
+        owner_id,
+        source_activity_id: None,
+        name: ActivityName::parse(name.to_string()).unwrap(),
+        description: None,
+        archived: false,
     };
-    let member = log_entry(
-        user.actor_id,
-        Some(activity.id),
-        child_position(named.id, FractionalIndex::default()),
-    );
+    let mut template_root = log_entry(owner_id, Some(activity.id), None);
+    template_root.is_template = true;
+    let copy = Activity {
+        id: Uuid::new_v4(),
+        source_activity_id: Some(activity.id),
+        ..activity.clone()
+    };
+    let mut copy_root = log_entry(owner_id, Some(copy.id), None);
+    copy_root.is_template = true;
     run_actions(
-        &client,
+        client,
         [
-            CreateEntry::from(named.clone()).into(),
-            CreateEntry::from(member.clone()).into(),
+            CreateActivity {
+                actor_id: owner_id,
+                activity: activity.clone(),
+                template: vec![template_root.clone()],
+            }
+            .into(),
+            CreateActivity {
+                actor_id: owner_id,
+                activity: copy.clone(),
+                template: vec![copy_root],
+            }
+            .into(),
         ],
     )
     .await;
-    client.run_action(set_flag(named.id, true)).await.unwrap();
-    client.run_action(set_flag(named.id, false)).await.unwrap();
-    assert_eq!(
-        name_of(find_entry(&client, named.id).await),
-        Some("My Workout".to_string()),
-        "an existing name is never clobbered"
-    );
+    (activity, template_root, copy)
+}
+
+async fn find_activity(client: &SqliteClient, actor_id: Uuid, id: Uuid) -> Option<Activity> {
+    client
+        .run_query(actor_id, FindActivityById { id })
+        .await
+        .unwrap()
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_create_entry_born_flagged_rejected(pool: SqlitePool) {
+async fn test_delete_activity_detaches_referencing_entries(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
     let user = create_user(&client).await;
+    let (activity, template_root, copy) =
+        seed_activity_with_copy(&client, user.actor_id, "Bench").await;
+    let anonymous = log_entry(user.actor_id, Some(activity.id), None);
+    let mut named = log_entry(user.actor_id, Some(activity.id), None);
+    named.name = Some("Heavy bench".to_string());
+    run_actions(
+        &client,
+        [
+            CreateEntry::from(anonymous.clone()).into(),
+            CreateEntry::from(named.clone()).into(),
+        ],
+    )
+    .await;
 
-    let mut entry = log_entry(user.actor_id, None, None);
-    entry.is_sequence = true;
-    entry.display_as_sets = true;
-    entry.temporal = Temporal::Start {
-        start: sqlx::types::chrono::Utc::now(),
+    // Referencing entries block the delete until the caller picks a strategy.
+    let delete = |referencing_entries| DeleteActivity {
+        actor_id: user.actor_id,
+        activity_id: activity.id,
+        referencing_entries,
     };
+    let err = client.run_action(delete(None).into()).await.unwrap_err();
+    assert!(matches!(
+        err,
+        DomainError::Rejected(RejectReason::Precondition(_))
+    ));
     assert!(
-        client
-            .run_action(CreateEntry::from(entry).into())
+        find_activity(&client, user.actor_id, activity.id)
             .await
-            .is_err(),
-        "a new entry cannot be born with display_as_sets"
+            .is_some()
     );
+
+    client
+        .run_action(delete(Some(ReferencingEntries::Detach)).into())
+        .await
+        .unwrap();
+
+    assert!(
+        find_activity(&client, user.actor_id, activity.id)
+            .await
+            .is_none()
+    );
+    assert!(find_entry(&client, template_root.id).await.is_none());
+    let anonymous = find_entry(&client, anonymous.id).await.unwrap();
+    assert_eq!(anonymous.activity_id, None);
+    assert_eq!(anonymous.name.as_deref(), Some("Bench"));
+    let named = find_entry(&client, named.id).await.unwrap();
+    assert_eq!(named.activity_id, None);
+    assert_eq!(named.name.as_deref(), Some("Heavy bench"));
+    let copy = find_activity(&client, user.actor_id, copy.id)
+        .await
+        .unwrap();
+    assert_eq!(copy.source_activity_id, None);
 }
 
 #[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
-async fn test_sets_in_activity_templates(pool: SqlitePool) {
+async fn test_delete_activity_recursive(pool: SqlitePool) {
     let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
     let user = create_user(&client).await;
-
-    let activity = Activity {
+    let (activity, template_root, _) =
+        seed_activity_with_copy(&client, user.actor_id, "Squat").await;
+    let reps = Attribute {
         id: Uuid::new_v4(),
         owner_id: user.actor_id,
-        name: ActivityName::parse("Core Series".to_string()).unwrap(),
+        name: "Reps".to_string(),
         description: None,
-        source_activity_id: None,
-    };
-    let template_entry = |activity_id, position, is_sequence, display_as_sets| Entry {
-        id: Uuid::new_v4(),
-        activity_id,
-        owner_id: user.actor_id,
-        name: Some("set".to_string()),
-        position,
-        is_template: true,
-        display_as_sets,
-        is_sequence,
-        is_complete: false,
-        temporal: Temporal::None,
+        config: AttributeConfig::Numeric(NumericConfig {
+            min: None,
+            max: None,
+            integer: true,
+            default: None,
+        }),
     };
-
-    // A template containing a flagged sequence with no members is rejected.
-    let bad_root = template_entry(Some(activity.id), None, true, false);
-    let bad_sets = template_entry(
-        None,
-        child_position(bad_root.id, FractionalIndex::default()),
-        true,
-        true,
-    );
-    assert!(
+    let standalone = log_entry(user.actor_id, Some(activity.id), None);
+    let unrelated = seed_root_entry(&client, user.actor_id, false).await;
+    run_actions(
+        &client,
+        [
+            CreateAttribute::from(reps.clone()).into(),
+            CreateEntry::from(standalone.clone()).into(),
+        ],
+    )
+    .await;
+    for entry_id in [template_root.id, standalone.id] {
         client
             .run_action(
-                CreateActivity {
+                AttachValue {
                     actor_id: user.actor_id,
-                    activity: activity.clone(),
-                    template: vec![bad_root, bad_sets],
+                    entry_id,
+                    attribute_id: reps.id,
                 }
                 .into(),
             )
             .await
-            .is_err(),
-        "template with an empty sets sequence must be rejected"
-    );
+            .unwrap();
+    }
+    let (sequence, members) =
+        seed_sets_sequence(&client, user.actor_id, Some(activity.id), 2).await;
 
-    // A valid sets template: root sequence -> flagged sequence -> 2 anonymous
-    // members.
-    let root = template_entry(Some(activity.id), None, true, false);
-    let sets = template_entry(
-        None,
-        child_position(root.id, FractionalIndex::default()),
-        true,
-        true,
-    );
-    let fi1 = FractionalIndex::default();
-    let fi2 = FractionalIndex::new_after(&fi1);
-    let member1 = template_entry(None, child_position(sets.id, fi1), false, false);
-    let member2 = template_entry(None, child_position(sets.id, fi2), false, false);
     client
         .run_action(
-            CreateActivity {
+            DeleteActivity {
                 actor_id: user.actor_id,
-                activity: activity.clone(),
-                template: vec![root, sets.clone(), member1, member2],
+                activity_id: activity.id,
+                referencing_entries: Some(ReferencingEntries::DeleteRecursive),
             }
             .into(),
         )
         .await
         .unwrap();
 
-    // Instantiation preserves display_as_sets into the log.
-    client
-        .run_action(
-            CreateEntryFromActivity {
-                actor_id: user.actor_id,
-                activity_id: activity.id,
-                position: None,
-                temporal: Temporal::Start {
-                    start: sqlx::types::chrono::Utc::now(),
-                },
-                is_template: false,
-            }
-            .into(),
+    assert!(
+        find_activity(&client, user.actor_id, activity.id)
+            .await
+            .is_none()
+    );
+    // The sets sequence goes with its members rather than being left empty.
+    for id in [template_root.id, standalone.id, sequence.id]
+        .into_iter()
+        .chain(members.iter().map(|m| m.id))
+    {
+        assert!(find_entry(&client, id).await.is_none());
+    }
+    assert!(find_entry(&client, unrelated.id).await.is_some());
+    let values = client
+        .run_query(
+            user.actor_id,
+            FindValuesForEntries {
+                entry_ids: vec![template_root.id, standalone.id],
+            },
         )
         .await
         .unwrap();
-    let mut conn = client.pool.acquire().await.unwrap();
-    let all = SqliteQueryExecutor::new(&mut *conn)
-        .execute(AllEntries)
+    assert!(values.is_empty());
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_archive_activity(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let (activity, _, _) = seed_activity_with_copy(&client, user.actor_id, "Deadlift").await;
+    let entry = log_entry(user.actor_id, Some(activity.id), None);
+    client
+        .run_action(CreateEntry::from(entry.clone()).into())
+        .await
+        .unwrap();
+    let archive = |archived| ArchiveActivity {
+        actor_id: user.actor_id,
+        activity_id: activity.id,
+        archived,
+    };
+    let in_library = |activities: Vec<Activity>| activities.iter().any(|a| a.id == activity.id);
+
+    client.run_action(archive(true).into()).await.unwrap();
+    assert!(!in_library(
+        client
+            .run_query(user.actor_id, AllActivities {})
+            .await
+            .unwrap()
+    ));
+    assert!(
+        find_activity(&client, user.actor_id, activity.id)
+            .await
+            .unwrap()
+            .archived
+    );
+    let entry = find_entry(&client, entry.id).await.unwrap();
+    assert_eq!(entry.activity_id, Some(activity.id));
+
+    // Archiving twice is a no-op.
+    let again = client.run_action(archive(true).into()).await.unwrap();
+    assert!(again.changes.is_empty());
+
+    client.run_action(archive(false).into()).await.unwrap();
+    assert!(in_library(
+        client
+            .run_query(user.actor_id, AllActivities {})
+            .await
+            .unwrap()
+    ));
+    assert!(
+        !find_activity(&client, user.actor_id, activity.id)
+            .await
+            .unwrap()
+            .archived
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/sqlite/migrations")]
+async fn test_update_activity(pool: SqlitePool) {
+    let client = SqliteClient::from_pool(pool, Arc::new(gv_core::io::SystemIo::default()));
+    let user = create_user(&client).await;
+    let (activity, _, copy) = seed_activity_with_copy(&client, user.actor_id, "Bnech").await;
+    let update = |activity_id, change| -> Action {
+        UpdateActivity {
+            actor_id: user.actor_id,
+            activity_id,
+            change,
+        }
+        .into()
+    };
+
+    client
+        .run_action(update(
+            activity.id,
+            ActivityChange::SetName("  Bench  ".to_string()),
+        ))
+        .await
+        .unwrap();
+    client
+        .run_action(update(
+            activity.id,
+            ActivityChange::SetDescription(Some("Barbell bench press".to_string())),
+        ))
+        .await
+        .unwrap();
+    let updated = find_activity(&client, user.actor_id, activity.id)
+        .await
+        .unwrap();
+    assert_eq!(updated.name.to_string(), "Bench");
+    assert_eq!(updated.description.as_deref(), Some("Barbell bench press"));
+
+    let err = client
+        .run_action(update(
+            activity.id,
+            ActivityChange::SetName(" ".to_string()),
+        ))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        DomainError::Rejected(RejectReason::Validation(
+            ValidationError::InvalidActivityName(_)
+        ))
+    ));
+
+    // Renaming to the current name changes nothing.
+    let noop = client
+        .run_action(update(
+            activity.id,
+            ActivityChange::SetName("Bench".to_string()),
+        ))
+        .await
+        .unwrap();
+    assert!(noop.changes.is_empty());
+
+    // The copy already points at the activity, so the reverse link (or a
+    // self-link) would make the source chain loop.
+    for source in [copy.id, activity.id] {
+        let err = client
+            .run_action(update(
+                activity.id,
+                ActivityChange::SetSourceActivityId(Some(source)),
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::Rejected(RejectReason::Precondition(_))
+        ));
+    }
+    let err = client
+        .run_action(update(
+            activity.id,
+            ActivityChange::SetSourceActivityId(Some(Uuid::new_v4())),
+        ))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        DomainError::Rejected(RejectReason::NotFound(_))
+    ));
+
+    client
+        .run_action(update(copy.id, ActivityChange::SetSourceActivityId(None)))
+        .await
+        .unwrap();
+    client
+        .run_action(update(
+            activity.id,
+            ActivityChange::SetSourceActivityId(Some(copy.id)),
+        ))
         .await
         .unwrap();
-    let instantiated_sets: Vec<&Entry> = all
-        .iter()
-        .filter(|e| !e.is_template && e.display_as_sets)
-        .collect();
     assert_eq!(
-        instantiated_sets.len(),
-        1,
-        "instantiated subtree must preserve display_as_sets"
+        find_activity(&client, user.actor_id, activity.id)
+            .await
+            .unwrap()
+            .source_activity_id,
+        Some(copy.id)
     );
-    assert_ne!(instantiated_sets[0].id, sets.id, "instance has a fresh id");
-    let forest = gv_core::forest::Forest::from(all.clone());
     assert_eq!(
-        forest.children(instantiated_sets[0].id).len(),
-        2,
-        "instantiated sets sequence keeps its members"
+        find_activity(&client, user.actor_id, copy.id)
+            .await
+            .unwrap()
+            .source_activity_id,
+        None
     );
+
+    // A source chain that already loops (e.g. left behind by a replay) is
+    // reported, not followed forever.
+    sqlx::query("UPDATE activities SET source_activity_id = ? WHERE id = ?")
+        .bind(activity.id)
+        .bind(copy.id)
+        .execute(&client.pool)
+        .await
+        .unwrap();
+    let (other, _, _) = seed_activity_with_copy(&client, user.actor_id, "Squat").await;
+    let err = client
+        .run_action(update(
+            other.id,
+            ActivityChange::SetSourceActivityId(Some(activity.id)),
+        ))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DomainError::InvariantViolation { .. }));
 }
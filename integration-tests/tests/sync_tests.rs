@@ -0,0 +1,492 @@
+//! Write-path sync between SQLite clients and the Postgres server, in-process.
+//!
+//! Each test gets a fresh Postgres database from `sqlx::test` and opens its
+//! clients on throwaway SQLite files, so a "device" is just a `SqliteClient`
+//! and "the network" is a direct call into `PostgresServer`.
+
+use fractional_index::FractionalIndex;
+use gv_client::client::SqliteClient;
+use gv_core::{
+    actions::{
+        AcceptGrant, Action, CreateEntry, CreateUser, DeleteEntryRecursive, GrantPermission,
+        UpdateEntryCompletion,
+    },
+    delta::AnyDelta,
+    error::Result,
+    models::{
+        entry::{Entry, Position, Temporal},
+        grant::{GrantLevel, GrantScope},
+        user::User,
+    },
+    mutation_log::MutationState,
+    queries::{
+        FindEntryById, FindUserById, MutationHistory, PendingMutations, SnapshotAll,
+        UnackedMutations,
+    },
+    query_executor::QueryExecutor,
+    sync::{PullRequest, PullResponse, PushRequest, PushResponse, PushResult, SyncServer},
+    validation::{Email, Username},
+};
+use gv_server::{server::PostgresServer, sync::SyncSession};
+use gv_sql::postgres::PostgresQueryExecutor;
+use sqlx::PgPool;
+use sqlx::types::chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+async fn open_client() -> SqliteClient {
+    let path = std::env::temp_dir().join(format!("gv-sync-{}.db", Uuid::new_v4()));
+    SqliteClient::init(&format!("sqlite://{}?mode=rwc", path.display()))
+        .await
+        .unwrap()
+}
+
+async fn create_user(client: &SqliteClient) -> User {
+    let id = Uuid::new_v4();
+    let user = User {
+        actor_id: id,
+        username: Username::parse(format!("u{}", id.simple())).unwrap(),
+        email: Email::parse(format!("{}@test.com", id.simple())).unwrap(),
+    };
+    client
+        .run_action(CreateUser::from(user.clone()).into())
+        .await
+        .unwrap();
+    user
+}
+
+/// Whole-second start times: Postgres stores microseconds, so sub-second
+/// precision would make client and server copies of an entry differ.
+fn entry(owner_id: Uuid, position: Option<Position>, is_sequence: bool) -> Entry {
+    Entry {
+        id: Uuid::new_v4(),
+        activity_id: None,
+        owner_id,
+        name: None,
+        position: position.clone(),
+        is_template: false,
+        display_as_sets: false,
+        is_sequence,
+        is_complete: false,
+        temporal: match position {
+            Some(_) => Temporal::None,
+            None => Temporal::Start {
+                start: Utc.with_ymd_and_hms(2026, 6, 1, 9, 0, 0).unwrap(),
+            },
+        },
+    }
+}
+
+fn child_of(parent_id: Uuid, frac_index: FractionalIndex) -> Option<Position> {
+    Some(Position {
+        parent_id,
+        frac_index,
+    })
+}
+
+async fn create_entry(client: &SqliteClient, entry: &Entry) -> Uuid {
+    client
+        .run_action(CreateEntry::from(entry.clone()).into())
+        .await
+        .unwrap()
+        .id
+}
+
+async fn find_entry(client: &SqliteClient, actor_id: Uuid, entry_id: Uuid) -> Option<Entry> {
+    client
+        .run_query(actor_id, FindEntryById { entry_id })
+        .await
+        .unwrap()
+}
+
+/// Entries owned by `owner_id`, sorted by id, on a client.
+async fn client_entries(client: &SqliteClient, owner_id: Uuid) -> Vec<Entry> {
+    let mut entries: Vec<_> = client
+        .run_query(owner_id, SnapshotAll)
+        .await
+        .unwrap()
+        .entries
+        .into_iter()
+        .filter(|e| e.owner_id == owner_id)
+        .collect();
+    entries.sort_by_key(|e| e.id);
+    entries
+}
+
+/// Entries owned by `owner_id`, sorted by id, on the server.
+async fn server_entries(server: &PostgresServer, owner_id: Uuid) -> Vec<Entry> {
+    let mut conn = server.pool.acquire().await.unwrap();
+    let mut entries: Vec<_> = PostgresQueryExecutor::new(&mut conn)
+        .execute(SnapshotAll)
+        .await
+        .unwrap()
+        .entries
+        .into_iter()
+        .filter(|e| e.owner_id == owner_id)
+        .collect();
+    entries.sort_by_key(|e| e.id);
+    entries
+}
+
+/// A server whose pushes never get an answer (e.g. the connection drops after
+/// sending), while pulls go through.
+struct PushGoesUnanswered<'a>(SyncSession<'a>);
+
+impl SyncServer for PushGoesUnanswered<'_> {
+    async fn push(&self, _request: PushRequest) -> Result<PushResponse> {
+        Ok(PushResponse { results: vec![] })
+    }
+
+    async fn pull(&self, request: PullRequest) -> Result<PullResponse> {
+        self.0.pull(request).await
+    }
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_sync_pushes_local_mutations_and_pulls_them_elsewhere(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let phone = open_client().await;
+    let laptop = open_client().await;
+
+    let user = create_user(&phone).await;
+    let session = server.sync_session(user.actor_id);
+    let root = entry(user.actor_id, None, true);
+    let create_root = create_entry(&phone, &root).await;
+
+    let report = phone.sync(&session).await.unwrap();
+    assert_eq!(report.acked.len(), 2);
+    assert!(report.acked.contains(&create_root));
+    assert!(report.rejected.is_empty());
+    assert_eq!(report.last_seen_server_seq, 2);
+    assert!(
+        phone
+            .run_query(user.actor_id, PendingMutations)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let history = phone
+        .run_query(user.actor_id, MutationHistory { limit: 10 })
+        .await
+        .unwrap();
+    assert!(history.iter().all(|m| m.state == MutationState::Acked));
+    assert_eq!(history[0].server_seq_num, Some(2));
+
+    assert_eq!(
+        server_entries(&server, user.actor_id).await,
+        vec![root.clone()]
+    );
+
+    let report = laptop.sync(&session).await.unwrap();
+    assert_eq!(report.pulled, 2);
+    assert_eq!(
+        find_entry(&laptop, user.actor_id, root.id).await,
+        Some(root)
+    );
+
+    // Nothing new either way: a second round is a no-op.
+    let report = phone.sync(&session).await.unwrap();
+    assert_eq!(report.pulled, 0);
+    assert!(report.acked.is_empty());
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_sync_rebases_offline_edits_over_server_changes(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let phone = open_client().await;
+    let laptop = open_client().await;
+
+    let user = create_user(&phone).await;
+    let session = server.sync_session(user.actor_id);
+    let root = entry(user.actor_id, None, true);
+    create_entry(&phone, &root).await;
+    phone.sync(&session).await.unwrap();
+    laptop.sync(&session).await.unwrap();
+
+    // Both devices add a child while offline; the laptop syncs first.
+    let fi = FractionalIndex::default();
+    let from_phone = entry(user.actor_id, child_of(root.id, fi.clone()), false);
+    let from_laptop = entry(
+        user.actor_id,
+        child_of(root.id, FractionalIndex::new_after(&fi)),
+        false,
+    );
+    create_entry(&phone, &from_phone).await;
+    create_entry(&laptop, &from_laptop).await;
+    laptop.sync(&session).await.unwrap();
+
+    let report = phone.sync(&session).await.unwrap();
+    assert_eq!(report.acked.len(), 1);
+    assert_eq!(report.pulled, 2);
+    laptop.sync(&session).await.unwrap();
+
+    let expected = server_entries(&server, user.actor_id).await;
+    assert_eq!(expected.len(), 3);
+    assert_eq!(client_entries(&phone, user.actor_id).await, expected);
+    assert_eq!(client_entries(&laptop, user.actor_id).await, expected);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_sync_reverts_mutations_the_server_rejects(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let phone = open_client().await;
+    let laptop = open_client().await;
+
+    let user = create_user(&phone).await;
+    let session = server.sync_session(user.actor_id);
+    let root = entry(user.actor_id, None, false);
+    create_entry(&phone, &root).await;
+    phone.sync(&session).await.unwrap();
+    laptop.sync(&session).await.unwrap();
+
+    // The laptop deletes the entry; the phone, not having seen that, completes it.
+    laptop
+        .run_action(
+            DeleteEntryRecursive {
+                actor_id: user.actor_id,
+                entry_id: root.id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    laptop.sync(&session).await.unwrap();
+    let complete = phone
+        .run_action(Action::UpdateEntryCompletion(UpdateEntryCompletion {
+            actor_id: user.actor_id,
+            entry_id: root.id,
+            is_complete: true,
+        }))
+        .await
+        .unwrap();
+    assert!(
+        find_entry(&phone, user.actor_id, root.id)
+            .await
+            .unwrap()
+            .is_complete
+    );
+
+    let report = phone.sync(&session).await.unwrap();
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.rejected[0].0, complete.id);
+    assert!(report.acked.is_empty());
+    assert_eq!(find_entry(&phone, user.actor_id, root.id).await, None);
+    let history = phone
+        .run_query(user.actor_id, MutationHistory { limit: 1 })
+        .await
+        .unwrap();
+    assert_eq!(history[0].mutation.id, complete.id);
+    assert_eq!(history[0].state, MutationState::Rejected);
+}
+
+/// Mutations the server hasn't answered are reverted, the server's changes
+/// applied underneath, and the actions re-run on top. One that no longer
+/// applies is dropped; one that still does survives and is acked later.
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_sync_reapplies_unacked_mutations_through_mutators(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let phone = open_client().await;
+    let laptop = open_client().await;
+
+    let user = create_user(&phone).await;
+    let session = server.sync_session(user.actor_id);
+    let sequence = entry(user.actor_id, None, true);
+    create_entry(&phone, &sequence).await;
+    phone.sync(&session).await.unwrap();
+    laptop.sync(&session).await.unwrap();
+
+    let orphaned = entry(
+        user.actor_id,
+        child_of(sequence.id, FractionalIndex::default()),
+        false,
+    );
+    let create_orphaned = create_entry(&phone, &orphaned).await;
+    let standalone = entry(user.actor_id, None, false);
+    let create_standalone = create_entry(&phone, &standalone).await;
+
+    laptop
+        .run_action(
+            DeleteEntryRecursive {
+                actor_id: user.actor_id,
+                entry_id: sequence.id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    laptop.sync(&session).await.unwrap();
+
+    let report = phone.sync(&PushGoesUnanswered(session)).await.unwrap();
+    assert_eq!(report.pulled, 1);
+    assert_eq!(report.dropped, vec![create_orphaned]);
+    assert_eq!(find_entry(&phone, user.actor_id, sequence.id).await, None);
+    assert_eq!(find_entry(&phone, user.actor_id, orphaned.id).await, None);
+    assert_eq!(
+        find_entry(&phone, user.actor_id, standalone.id).await,
+        Some(standalone.clone())
+    );
+    let unacked = phone
+        .run_query(user.actor_id, UnackedMutations)
+        .await
+        .unwrap();
+    assert_eq!(unacked.len(), 1);
+    assert_eq!(unacked[0].mutation.id, create_standalone);
+    assert_eq!(unacked[0].state, MutationState::Sent);
+
+    let report = phone.sync(&session).await.unwrap();
+    assert_eq!(report.acked, vec![create_standalone]);
+    assert_eq!(
+        client_entries(&phone, user.actor_id).await,
+        server_entries(&server, user.actor_id).await
+    );
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_push_is_idempotent(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let phone = open_client().await;
+    let user = create_user(&phone).await;
+    let session = server.sync_session(user.actor_id);
+    let pending = phone
+        .run_query(user.actor_id, PendingMutations)
+        .await
+        .unwrap();
+    let request = PushRequest {
+        last_seen_server_seq: 0,
+        mutations: pending.into_iter().map(|l| l.mutation).collect(),
+    };
+
+    let first = session.push(request.clone()).await.unwrap();
+    let second = session.push(request).await.unwrap();
+    assert!(matches!(
+        first.results[..],
+        [PushResult::Acked { seq_num: 1, .. }]
+    ));
+    assert_eq!(first, second);
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_push_rejects_mutations_acting_as_another_actor(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let phone = open_client().await;
+    let victim = create_user(&phone).await;
+    let intruder = create_user(&phone).await;
+    let pending = phone
+        .run_query(victim.actor_id, PendingMutations)
+        .await
+        .unwrap();
+    let create_victim = pending[0].mutation.clone();
+    let request = PushRequest {
+        last_seen_server_seq: 0,
+        mutations: vec![create_victim.clone()],
+    };
+
+    let response = server
+        .sync_session(intruder.actor_id)
+        .push(request.clone())
+        .await
+        .unwrap();
+    assert!(matches!(
+        &response.results[..],
+        [PushResult::Rejected { mutation_id, .. }] if *mutation_id == create_victim.id
+    ));
+    let mut conn = server.pool.acquire().await.unwrap();
+    let found = PostgresQueryExecutor::new(&mut conn)
+        .execute(FindUserById {
+            actor_id: victim.actor_id,
+        })
+        .await
+        .unwrap();
+    assert_eq!(found, None);
+    drop(conn);
+
+    // Once committed by its own actor, replaying it elsewhere still doesn't ack.
+    server
+        .sync_session(victim.actor_id)
+        .push(request.clone())
+        .await
+        .unwrap();
+    let response = server
+        .sync_session(intruder.actor_id)
+        .push(request)
+        .await
+        .unwrap();
+    assert!(matches!(
+        response.results[..],
+        [PushResult::Rejected { .. }]
+    ));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_pull_only_returns_what_the_actor_can_read(pool: PgPool) {
+    let server = PostgresServer::new(pool);
+    let phone = open_client().await;
+    let laptop = open_client().await;
+    let bob = create_user(&laptop).await;
+    let bob_session = server.sync_session(bob.actor_id);
+    laptop.sync(&bob_session).await.unwrap();
+
+    let alice = create_user(&phone).await;
+    let alice_session = server.sync_session(alice.actor_id);
+    phone.sync(&alice_session).await.unwrap();
+    let private = entry(alice.actor_id, None, false);
+    create_entry(&phone, &private).await;
+    let shared = entry(alice.actor_id, None, false);
+    create_entry(&phone, &shared).await;
+    let grant_id = Uuid::new_v4();
+    phone
+        .run_action(
+            GrantPermission {
+                actor_id: alice.actor_id,
+                grant_id,
+                grantee_id: bob.actor_id,
+                scope: GrantScope::Entry(shared.id),
+                level: GrantLevel::Read,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    phone.sync(&alice_session).await.unwrap();
+    // Accepted from another of Bob's devices.
+    server
+        .commit_action(
+            AcceptGrant {
+                actor_id: bob.actor_id,
+                grant_id,
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+
+    let pulled = bob_session
+        .pull(PullRequest {
+            after_seq_num: 0,
+            limit: 100,
+        })
+        .await
+        .unwrap();
+    let entry_ids: Vec<Uuid> = pulled
+        .mutations
+        .iter()
+        .flat_map(|committed| &committed.mutation.changes)
+        .filter_map(|delta| match delta {
+            AnyDelta::Entry(d) => d.after().or(d.before()).map(|e| e.id),
+            _ => None,
+        })
+        .collect();
+    assert!(!entry_ids.contains(&private.id));
+    assert!(entry_ids.contains(&shared.id));
+
+    let report = laptop.sync(&bob_session).await.unwrap();
+    assert_eq!(report.last_seen_server_seq, pulled.through_seq_num);
+    // Unscoped, so a pulled private entry couldn't hide behind Bob's reads.
+    assert_eq!(
+        client_entries(&laptop, alice.actor_id).await,
+        vec![shared.clone()]
+    );
+    assert_eq!(
+        find_entry(&laptop, bob.actor_id, shared.id).await,
+        Some(shared)
+    );
+}
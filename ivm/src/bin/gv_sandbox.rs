@@ -42,6 +42,7 @@ fn world(actors: Vec<Actor>, activities: Vec<Activity>) -> Snapshot {
         attributes: vec![],
         entries: vec![],
         values: vec![],
+        grants: vec![],
     }
 }
 
@@ -0,0 +1,58 @@
+use gv_server::{auth::AuthKey, config::ServerConfig, http, server::PostgresServer};
+use sqlx::postgres::PgPoolOptions;
+use tokio::net::TcpListener;
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt::init();
+
+    let config = ServerConfig::from_env()?;
+    let pool = PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .connect(&config.database_url)
+        .await?;
+    let listener = TcpListener::bind(config.bind_addr).await?;
+
+    let server = PostgresServer::new(pool.clone());
+    let reindexed = server.reindex_values().await?;
+    info!(reindexed, "derived index columns for unindexed values");
+
+    http::serve(
+        listener,
+        server,
+        AuthKey::new(config.auth_secret),
+        shutdown_signal(),
+    )
+    .await?;
+    pool.close().await;
+    info!("shut down");
+    Ok(())
+}
+
+/// Resolves on Ctrl-C, or on SIGTERM (what `docker stop` and most process
+/// managers send).
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to listen for Ctrl-C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("shutdown signal received, draining connections");
+}
@@ -0,0 +1,61 @@
+use std::net::SocketAddr;
+
+/// Server settings, read from the environment (and `.env`, if present).
+///
+/// | Variable                | Default        |
+/// |-------------------------|----------------|
+/// | `DATABASE_URL`          | required       |
+/// | `GV_AUTH_SECRET`        | required       |
+/// | `GV_BIND_ADDR`          | `0.0.0.0:8080` |
+/// | `GV_DB_MAX_CONNECTIONS` | `10`           |
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub database_url: String,
+    /// Signs and verifies bearer tokens (see `crate::auth`).
+    pub auth_secret: String,
+    pub bind_addr: SocketAddr,
+    pub max_connections: u32,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ConfigError {
+    #[error("{0} must be set")]
+    Missing(&'static str),
+    #[error("invalid {var}: {value:?}")]
+    Invalid { var: &'static str, value: String },
+}
+
+impl ServerConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|var| std::env::var(var).ok())
+    }
+
+    /// Build a config from `lookup`, which maps a variable name to its value.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let database_url = lookup("DATABASE_URL").ok_or(ConfigError::Missing("DATABASE_URL"))?;
+        let auth_secret = lookup("GV_AUTH_SECRET").ok_or(ConfigError::Missing("GV_AUTH_SECRET"))?;
+        Ok(ServerConfig {
+            database_url,
+            auth_secret,
+            bind_addr: parse_or(
+                &lookup,
+                "GV_BIND_ADDR",
+                SocketAddr::from(([0, 0, 0, 0], 8080)),
+            )?,
+            max_connections: parse_or(&lookup, "GV_DB_MAX_CONNECTIONS", 10)?,
+        })
+    }
+}
+
+fn parse_or<T: std::str::FromStr>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    var: &'static str,
+    default: T,
+) -> Result<T, ConfigError> {
+    match lookup(var) {
+        None => Ok(default),
+        Some(value) => value
+            .parse()
+            .map_err(|_| ConfigError::Invalid { var, value }),
+    }
+}
@@ -0,0 +1,245 @@
+//! JSON-over-HTTP API for the server.
+//!
+//! | Route             | Request body  | Response body       |
+//! |-------------------|---------------|---------------------|
+//! | `GET /health`     | —             | `{"status": ...}`   |
+//! | `POST /actions`   | `Action`      | `CommittedMutation` |
+//! | `POST /queries`   | `QueryRequest`| `AnyQueryResponse`  |
+//! | `POST /sync/push` | `PushRequest` | `PushResponse`      |
+//! | `POST /sync/pull` | `PullRequest` | `PullResponse`      |
+//!
+//! Errors are returned as an [`ErrorBody`], with the status chosen by who is at
+//! fault (see `gv_core::error`): a rejected action is the caller's problem
+//! (4xx), an invariant violation or database failure is ours (5xx).
+//!
+//! Every route but `/health` requires a bearer token (see [`crate::auth`]) and
+//! runs as the actor it authenticates; a request without a valid one gets a
+//! 401. An action, or a pushed mutation, acting as anyone else is rejected.
+//!
+//! Queries run on behalf of the requesting actor and only return rows that
+//! actor can read (`gv_core::query_executor::ScopedQueryExecutor`); pulls
+//! likewise only return what the actor can read (`gv_core::sync::scope_pulled`).
+
+use std::{future::Future, sync::Arc};
+
+use axum::{
+    Json, Router,
+    extract::{FromRef, State, rejection::JsonRejection},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use gv_core::{
+    actions::Action,
+    error::{DbErr, DomainError, RejectReason},
+    mutation_log::CommittedMutation,
+    queries::{AnyQuery, AnyQueryResponse},
+    query_executor::{ScopedQueryExecutor, execute_any_query},
+    sync::{PullRequest, PullResponse, PushRequest, PushResponse, SyncServer},
+};
+use gv_sql::postgres::PostgresQueryExecutor;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tracing::{error, info, instrument};
+
+use crate::{
+    auth::{AuthKey, Authenticated},
+    server::PostgresServer,
+};
+
+#[derive(Clone)]
+struct AppState {
+    server: Arc<PostgresServer>,
+    auth: Arc<AuthKey>,
+}
+
+impl FromRef<AppState> for Arc<PostgresServer> {
+    fn from_ref(state: &AppState) -> Self {
+        state.server.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<AuthKey> {
+    fn from_ref(state: &AppState) -> Self {
+        state.auth.clone()
+    }
+}
+
+pub fn router(server: Arc<PostgresServer>, auth: Arc<AuthKey>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/actions", post(submit_action))
+        .route("/queries", post(run_query))
+        .route("/sync/push", post(push))
+        .route("/sync/pull", post(pull))
+        .with_state(AppState { server, auth })
+}
+
+/// Serve the API on `listener` until `shutdown` resolves, then stop accepting
+/// connections and wait for in-flight requests to finish.
+pub async fn serve(
+    listener: TcpListener,
+    server: PostgresServer,
+    auth: AuthKey,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    info!(addr = ?listener.local_addr()?, "listening");
+    axum::serve(listener, router(Arc::new(server), Arc::new(auth)))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthBody {
+    pub status: String,
+}
+
+/// Healthy when the database answers.
+async fn health(State(server): State<Arc<PostgresServer>>) -> (StatusCode, Json<HealthBody>) {
+    let ping = async {
+        let mut conn = server.pool.acquire().await.db_err()?;
+        sqlx::query("SELECT 1").execute(&mut *conn).await.db_err()
+    };
+    match ping.await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(HealthBody {
+                status: "ok".to_string(),
+            }),
+        ),
+        Err(e) => {
+            error!(error = %e, "health check failed");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(HealthBody {
+                    status: "unavailable".to_string(),
+                }),
+            )
+        }
+    }
+}
+
+#[instrument(skip_all, fields(%actor_id))]
+async fn submit_action(
+    State(server): State<Arc<PostgresServer>>,
+    Authenticated(actor_id): Authenticated,
+    body: Result<Json<Action>, JsonRejection>,
+) -> Result<Json<CommittedMutation>, ApiError> {
+    let Json(action) = body?;
+    if action.actor_id() != actor_id {
+        return Err(DomainError::Rejected(RejectReason::Unauthorized(format!(
+            "action acts as {}, not the authenticated actor",
+            action.actor_id()
+        )))
+        .into());
+    }
+    Ok(Json(server.commit_action(action).await?))
+}
+
+/// A query, asked on behalf of the authenticated actor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryRequest {
+    pub query: AnyQuery,
+}
+
+#[instrument(skip_all, fields(%actor_id))]
+async fn run_query(
+    State(server): State<Arc<PostgresServer>>,
+    Authenticated(actor_id): Authenticated,
+    body: Result<Json<QueryRequest>, JsonRejection>,
+) -> Result<Json<AnyQueryResponse>, ApiError> {
+    let Json(QueryRequest { query }) = body?;
+    let mut conn = server.pool.acquire().await.db_err()?;
+    let mut executor = ScopedQueryExecutor::new(PostgresQueryExecutor::new(&mut conn), actor_id);
+    Ok(Json(execute_any_query(&mut executor, query).await?))
+}
+
+async fn push(
+    State(server): State<Arc<PostgresServer>>,
+    Authenticated(actor_id): Authenticated,
+    body: Result<Json<PushRequest>, JsonRejection>,
+) -> Result<Json<PushResponse>, ApiError> {
+    let Json(request) = body?;
+    Ok(Json(server.sync_session(actor_id).push(request).await?))
+}
+
+async fn pull(
+    State(server): State<Arc<PostgresServer>>,
+    Authenticated(actor_id): Authenticated,
+    body: Result<Json<PullRequest>, JsonRejection>,
+) -> Result<Json<PullResponse>, ApiError> {
+    let Json(request) = body?;
+    Ok(Json(server.sync_session(actor_id).pull(request).await?))
+}
+
+/// The body of every error response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    /// `"bad_request"`, `"unauthenticated"`, `"rejected"`, or `"internal"`.
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request body was not valid JSON for the route.
+    BadRequest(JsonRejection),
+    /// The route needs a bearer token and the request had no valid one.
+    Unauthenticated,
+    Domain(DomainError),
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(e: JsonRejection) -> Self {
+        ApiError::BadRequest(e)
+    }
+}
+
+impl From<DomainError> for ApiError {
+    fn from(e: DomainError) -> Self {
+        ApiError::Domain(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, kind, message) = match self {
+            ApiError::BadRequest(rejection) => {
+                (rejection.status(), "bad_request", rejection.body_text())
+            }
+            ApiError::Unauthenticated => (
+                StatusCode::UNAUTHORIZED,
+                "unauthenticated",
+                "missing or invalid bearer token".to_string(),
+            ),
+            ApiError::Domain(DomainError::Rejected(reason)) => {
+                (reject_status(&reason), "rejected", reason.to_string())
+            }
+            // Our fault, not the caller's: log the details, don't leak them.
+            ApiError::Domain(e) => {
+                error!(error = %e, "request failed");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal",
+                    "internal server error".to_string(),
+                )
+            }
+        };
+        let body = ErrorBody {
+            kind: kind.to_string(),
+            message,
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+fn reject_status(reason: &RejectReason) -> StatusCode {
+    match reason {
+        RejectReason::Unauthorized(_) => StatusCode::FORBIDDEN,
+        RejectReason::NotFound(_) => StatusCode::NOT_FOUND,
+        RejectReason::EmailExists | RejectReason::Precondition(_) => StatusCode::CONFLICT,
+        RejectReason::Validation(_) | RejectReason::AttributeMismatch => {
+            StatusCode::UNPROCESSABLE_ENTITY
+        }
+    }
+}
@@ -1 +1,5 @@
+pub mod auth;
+pub mod config;
+pub mod http;
 pub mod server;
+pub mod sync;
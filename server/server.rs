@@ -1,17 +1,23 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use gv_core::{
     actions::Action,
     delta_executor::AnyDeltaExecutor,
     error::{DbErr, Result},
     io::{Io, SystemIo},
-    mutators,
+    mutation_log::CommittedMutation,
+    mutators::{self, Mutation},
 };
 
 use sqlx::PgPool;
 use tracing::instrument;
+use uuid::Uuid;
 
-use gv_sql::postgres::{PostgresDeltaExecutor, PostgresQueryExecutor};
+use gv_sql::{
+    error::SqlErr,
+    postgres::{PostgresDeltaExecutor, PostgresMutationLog, PostgresQueryExecutor},
+};
 
 pub struct PostgresServer {
     pub pool: PgPool,
@@ -30,67 +36,53 @@ impl PostgresServer {
         PostgresServer { pool, io }
     }
 
-    #[instrument(skip(self), level = "info", err(level = "warn"))]
+    /// Derive the index columns of values stored before values were indexed
+    /// (`gv_sql::postgres::reindex_values`). Run at startup, after
+    /// migrations; returns the number of values reindexed. Not logged as a
+    /// mutation: clients derive the same columns from the same actuals.
+    pub async fn reindex_values(&self) -> Result<u64> {
+        let mut tx = self.pool.begin().await.db_err()?;
+        let reindexed = gv_sql::postgres::reindex_values(&mut tx).await?;
+        tx.commit().await.sql_err()?;
+        Ok(reindexed)
+    }
+
     pub async fn run_action(&self, action: Action) -> Result<mutators::Mutation> {
+        Ok(self.commit_action(action).await?.mutation)
+    }
+
+    /// Run an action and record it in the mutation log in the same
+    /// transaction, returning the mutation with its global sequence number.
+    pub async fn commit_action(&self, action: Action) -> Result<CommittedMutation> {
+        self.commit(action, None).await
+    }
+
+    /// Re-run a client's mutation authoritatively. The server's mutator decides
+    /// the deltas; the client's mutation id and timestamp are kept so the
+    /// client can match the ack to its local log entry.
+    pub async fn commit_client_mutation(&self, mutation: Mutation) -> Result<CommittedMutation> {
+        self.commit(mutation.action, Some((mutation.id, mutation.timestamp)))
+            .await
+    }
+
+    /// `origin` is the id and timestamp of the client mutation being
+    /// committed, if any; otherwise the mutator's own are kept.
+    #[instrument(skip(self), level = "info", err(level = "warn"))]
+    async fn commit(
+        &self,
+        action: Action,
+        origin: Option<(Uuid, DateTime<Utc>)>,
+    ) -> Result<CommittedMutation> {
         // Begin Postgres transaction.
         let mut tx = self.pool.begin().await.db_err()?;
         let mut executor = PostgresQueryExecutor::new(&mut tx);
 
         // Create mutation.
-        let mx = match action {
-            Action::CreateActivity(action) => {
-                mutators::create_activity(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateUser(action) => {
-                mutators::create_user(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateEntry(action) => {
-                mutators::create_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateEntryFromActivity(action) => {
-                mutators::create_entry_from_activity(&mut executor, self.io.as_ref(), action)
-                    .await?
-            }
-            Action::MoveEntry(action) => {
-                mutators::move_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::DeleteEntryRecursive(action) => {
-                mutators::delete_entry_recursive(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateAttribute(action) => {
-                mutators::create_attribute(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::CreateValue(action) => {
-                mutators::create_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::AttachValue(action) => {
-                mutators::attach_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::DeleteAttributeValue(action) => {
-                mutators::delete_attribute_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateEntryCompletion(action) => {
-                mutators::update_entry_completion(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateAttributeValue(action) => {
-                mutators::update_attribute_value(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateAttribute(action) => {
-                mutators::update_attribute(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::UpdateEntry(action) => {
-                mutators::update_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::ConvertToSets(action) => {
-                mutators::convert_to_sets(&mut executor, self.io.as_ref(), action).await?
-            }
-            Action::DuplicateEntry(action) => {
-                mutators::duplicate_entry(&mut executor, self.io.as_ref(), action).await?
-            }
-        };
-
-        // TODO: log mutation in this transaction.
-        // sync_service.log_mutation(mx);
+        let mut mx = run_mutator(&mut executor, self.io.as_ref(), action).await?;
+        if let Some((id, timestamp)) = origin {
+            mx.id = id;
+            mx.timestamp = timestamp;
+        }
 
         // Defer FK constraint checking until commit so delta order doesn't matter.
         sqlx::query("SET CONSTRAINTS ALL DEFERRED")
@@ -105,16 +97,75 @@ impl PostgresServer {
             delta_executor.apply_any_delta(delta).await?;
         }
 
-        // Commit the transaction.
-        tx.commit().await.db_err()?;
+        // Log the mutation last: taking a sequence number holds the counter
+        // lock until commit.
+        let committed = PostgresMutationLog::new(&mut tx).append(mx).await?;
+
+        // Commit the transaction. Deferred foreign keys are checked here, so a
+        // violation surfaces at commit and must still read as a rejection (a
+        // pushed mutation referencing a row the server doesn't have).
+        tx.commit().await.sql_err()?;
 
         // TODO: send mutation to service (or add to a pending_mutations queue).
         // sync_service.append_applied_mutation(mx);
 
-        Ok(mx)
+        Ok(committed)
     }
 
     pub fn io(&self) -> Arc<dyn Io> {
         self.io.clone()
     }
 }
+
+/// Run the mutator for `action` against `executor`, producing its mutation
+/// without applying it.
+async fn run_mutator(
+    executor: &mut PostgresQueryExecutor<'_>,
+    io: &dyn Io,
+    action: Action,
+) -> Result<Mutation> {
+    // Create mutation.
+    let mx = match action {
+        Action::CreateActivity(action) => mutators::create_activity(executor, io, action).await?,
+        Action::CreateUser(action) => mutators::create_user(executor, io, action).await?,
+        Action::CreateEntry(action) => mutators::create_entry(executor, io, action).await?,
+        Action::CreateEntryFromActivity(action) => {
+            mutators::create_entry_from_activity(executor, io, action).await?
+        }
+        Action::MoveEntry(action) => mutators::move_entry(executor, io, action).await?,
+        Action::DeleteEntryRecursive(action) => {
+            mutators::delete_entry_recursive(executor, io, action).await?
+        }
+        Action::CreateAttribute(action) => mutators::create_attribute(executor, io, action).await?,
+        Action::CreateValue(action) => mutators::create_value(executor, io, action).await?,
+        Action::AttachValue(action) => mutators::attach_value(executor, io, action).await?,
+        Action::DeleteAttributeValue(action) => {
+            mutators::delete_attribute_value(executor, io, action).await?
+        }
+        Action::UpdateEntryCompletion(action) => {
+            mutators::update_entry_completion(executor, io, action).await?
+        }
+        Action::UpdateAttributeValue(action) => {
+            mutators::update_attribute_value(executor, io, action).await?
+        }
+        Action::UpdateAttribute(action) => mutators::update_attribute(executor, io, action).await?,
+        Action::UpdateEntry(action) => mutators::update_entry(executor, io, action).await?,
+        Action::ConvertToSets(action) => mutators::convert_to_sets(executor, io, action).await?,
+        Action::DuplicateEntry(action) => mutators::duplicate_entry(executor, io, action).await?,
+        Action::Undo(action) => mutators::undo(executor, io, action).await?,
+        Action::Redo(action) => mutators::redo(executor, io, action).await?,
+        Action::GrantPermission(action) => mutators::grant_permission(executor, io, action).await?,
+        Action::AcceptGrant(action) => mutators::accept_grant(executor, io, action).await?,
+        Action::RevokeGrant(action) => mutators::revoke_grant(executor, io, action).await?,
+        Action::DeleteActivity(action) => mutators::delete_activity(executor, io, action).await?,
+        Action::ArchiveActivity(action) => mutators::archive_activity(executor, io, action).await?,
+        Action::UpdateActivity(action) => mutators::update_activity(executor, io, action).await?,
+        Action::AttributeMigration(action) => {
+            mutators::migrate_attribute(executor, io, action).await?
+        }
+        Action::ConvertAttributeType(action) => {
+            mutators::convert_attribute_type(executor, io, action).await?
+        }
+    };
+    Ok(mx)
+}
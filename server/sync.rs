@@ -0,0 +1,115 @@
+use gv_core::{
+    error::{DbErr, DomainError, RejectReason, Result},
+    queries::{FindSeqNumByMutationId, MutationsSince, VisibleTo},
+    query_executor::QueryExecutor,
+    sync::{
+        PullRequest, PullResponse, PushRequest, PushResponse, PushResult, SyncServer, scope_pulled,
+    },
+};
+use gv_sql::postgres::PostgresQueryExecutor;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::server::PostgresServer;
+
+/// The server as seen by one authenticated actor: the sync protocol runs
+/// through a session, never against the server directly, so every pushed
+/// mutation is checked against who is pushing it.
+#[derive(Clone, Copy)]
+pub struct SyncSession<'a> {
+    server: &'a PostgresServer,
+    actor_id: Uuid,
+}
+
+impl PostgresServer {
+    /// A sync session for `actor_id`, who the caller has already authenticated.
+    pub fn sync_session(&self, actor_id: Uuid) -> SyncSession<'_> {
+        SyncSession {
+            server: self,
+            actor_id,
+        }
+    }
+}
+
+impl SyncServer for SyncSession<'_> {
+    /// Commit each pushed mutation in order, each in its own transaction, so
+    /// one rejection doesn't take the rest of the batch with it. A rejected
+    /// mutation's dependents are rejected by their own mutators.
+    ///
+    /// Mutations acting as anyone but the session's actor are rejected
+    /// unseen, including ones already committed under their real author.
+    #[instrument(skip_all, fields(actor_id = %self.actor_id, n = request.mutations.len(), last_seen_server_seq = request.last_seen_server_seq))]
+    async fn push(&self, request: PushRequest) -> Result<PushResponse> {
+        let mut results = Vec::with_capacity(request.mutations.len());
+        for mutation in request.mutations {
+            let mutation_id = mutation.id;
+            if mutation.action.actor_id() != self.actor_id {
+                let reason = RejectReason::Unauthorized(format!(
+                    "mutation acts as {}, not the authenticated actor",
+                    mutation.action.actor_id()
+                ));
+                info!(%mutation_id, %reason, "rejected pushed mutation");
+                results.push(PushResult::Rejected {
+                    mutation_id,
+                    reason: reason.to_string(),
+                });
+                continue;
+            }
+            let already_committed = {
+                let mut conn = self.server.pool.acquire().await.db_err()?;
+                PostgresQueryExecutor::new(&mut conn)
+                    .execute(FindSeqNumByMutationId { mutation_id })
+                    .await?
+            };
+            if let Some(seq_num) = already_committed {
+                results.push(PushResult::Acked {
+                    mutation_id,
+                    seq_num,
+                });
+                continue;
+            }
+            let result = match self.server.commit_client_mutation(mutation).await {
+                Ok(committed) => PushResult::Acked {
+                    mutation_id,
+                    seq_num: committed.seq_num,
+                },
+                Err(DomainError::Rejected(reason)) => {
+                    info!(%mutation_id, %reason, "rejected pushed mutation");
+                    PushResult::Rejected {
+                        mutation_id,
+                        reason: reason.to_string(),
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+            results.push(result);
+        }
+        Ok(PushResponse { results })
+    }
+
+    /// A page of the log as the session's actor may see it: the page covers
+    /// `limit` mutations, but returns only the ones (and the deltas) that
+    /// touch rows the actor can read.
+    async fn pull(&self, request: PullRequest) -> Result<PullResponse> {
+        let mut conn = self.server.pool.acquire().await.db_err()?;
+        let mut executor = PostgresQueryExecutor::new(&mut conn);
+        let mutations = executor
+            .execute(MutationsSince {
+                after_seq_num: request.after_seq_num,
+                limit: request.limit,
+            })
+            .await?;
+        let through_seq_num = mutations
+            .last()
+            .map_or(request.after_seq_num, |m| m.seq_num);
+        let visible = executor
+            .execute(VisibleTo {
+                actor_id: self.actor_id,
+            })
+            .await?;
+        Ok(PullResponse {
+            mutations: scope_pulled(self.actor_id, &visible, mutations),
+            through_seq_num,
+        })
+    }
+}
@@ -0,0 +1,397 @@
+//! Drives the HTTP API over a real socket, against a fresh Postgres database
+//! per test (`sqlx::test`, using `DATABASE_URL`).
+
+use chrono::Utc;
+use gv_core::{
+    actions::{Action, CreateEntry, CreateUser},
+    models::{
+        entry::{Entry, Temporal},
+        user::User,
+    },
+    mutation_log::CommittedMutation,
+    mutators::Mutation,
+    queries::{AnyQuery, AnyQueryResponse, FindEntryById, FindUserById},
+    sync::{PullRequest, PullResponse, PushRequest, PushResponse, PushResult},
+    validation::{Email, Username},
+};
+use gv_server::{
+    auth::AuthKey,
+    config::{ConfigError, ServerConfig},
+    http::{self, ErrorBody, HealthBody, QueryRequest},
+    server::PostgresServer,
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use tokio::{net::TcpListener, sync::oneshot, task::JoinHandle};
+use uuid::Uuid;
+
+struct TestServer {
+    base_url: String,
+    client: reqwest::Client,
+    auth: AuthKey,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<std::io::Result<()>>,
+}
+
+impl TestServer {
+    async fn spawn(pool: PgPool) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (shutdown, signal) = oneshot::channel::<()>();
+        let auth = AuthKey::new("test secret");
+        let handle = tokio::spawn(http::serve(
+            listener,
+            PostgresServer::new(pool),
+            auth.clone(),
+            async {
+                let _ = signal.await;
+            },
+        ));
+        TestServer {
+            base_url,
+            client: reqwest::Client::new(),
+            auth,
+            shutdown,
+            handle,
+        }
+    }
+
+    async fn post<B: serde::Serialize>(&self, path: &str, body: &B) -> reqwest::Response {
+        self.client
+            .post(format!("{}{}", self.base_url, path))
+            .json(body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    /// `post`, authenticated as `actor_id`.
+    async fn post_as<B: serde::Serialize>(
+        &self,
+        actor_id: Uuid,
+        path: &str,
+        body: &B,
+    ) -> reqwest::Response {
+        self.client
+            .post(format!("{}{}", self.base_url, path))
+            .bearer_auth(self.auth.issue(actor_id))
+            .json(body)
+            .send()
+            .await
+            .unwrap()
+    }
+}
+
+fn user() -> User {
+    let id = Uuid::new_v4();
+    User {
+        actor_id: id,
+        username: Username::parse(format!("u{}", id.simple())).unwrap(),
+        email: Email::parse(format!("{}@test.com", id.simple())).unwrap(),
+    }
+}
+
+fn root_entry(owner_id: Uuid) -> Entry {
+    Entry {
+        id: Uuid::new_v4(),
+        activity_id: None,
+        owner_id,
+        name: None,
+        position: None,
+        is_template: false,
+        display_as_sets: false,
+        is_sequence: false,
+        is_complete: false,
+        temporal: Temporal::Start {
+            start: "2026-06-01T09:00:00Z".parse().unwrap(),
+        },
+    }
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_health(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let response = reqwest::get(format!("{}/health", server.base_url))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: HealthBody = response.json().await.unwrap();
+    assert_eq!(body.status, "ok");
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_submit_actions_and_query(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let user = user();
+
+    let response = server
+        .post_as(
+            user.actor_id,
+            "/actions",
+            &Action::from(CreateUser::from(user.clone())),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let committed: CommittedMutation = response.json().await.unwrap();
+    assert_eq!(committed.seq_num, 1);
+
+    let entry = root_entry(user.actor_id);
+    let response = server
+        .post_as(
+            user.actor_id,
+            "/actions",
+            &Action::from(CreateEntry::from(entry.clone())),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = server
+        .post_as(
+            user.actor_id,
+            "/queries",
+            &QueryRequest {
+                query: AnyQuery::from(FindUserById {
+                    actor_id: user.actor_id,
+                }),
+            },
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let found: AnyQueryResponse = response.json().await.unwrap();
+    assert_eq!(found, AnyQueryResponse::FindUserById(Some(user.clone())));
+
+    let find_entry = AnyQuery::from(FindEntryById { entry_id: entry.id });
+    let response = server
+        .post_as(
+            user.actor_id,
+            "/queries",
+            &QueryRequest {
+                query: find_entry.clone(),
+            },
+        )
+        .await;
+    let found: AnyQueryResponse = response.json().await.unwrap();
+    assert_eq!(found, AnyQueryResponse::FindEntryById(Some(entry)));
+
+    // Another user can't see it.
+    let other = crate::user();
+    server
+        .post_as(
+            other.actor_id,
+            "/actions",
+            &Action::from(CreateUser::from(other.clone())),
+        )
+        .await;
+    let response = server
+        .post_as(
+            other.actor_id,
+            "/queries",
+            &QueryRequest { query: find_entry },
+        )
+        .await;
+    let found: AnyQueryResponse = response.json().await.unwrap();
+    assert_eq!(found, AnyQueryResponse::FindEntryById(None));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_rejected_actions_are_client_errors(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let user = user();
+    let create_user = Action::from(CreateUser::from(user.clone()));
+    server
+        .post_as(user.actor_id, "/actions", &create_user)
+        .await;
+
+    // Same email again.
+    let response = server
+        .post_as(user.actor_id, "/actions", &create_user)
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body: ErrorBody = response.json().await.unwrap();
+    assert_eq!(body.kind, "rejected");
+    assert_eq!(body.message, "Email already exists");
+
+    // Owner that doesn't exist.
+    let missing = Uuid::new_v4();
+    let response = server
+        .post_as(
+            missing,
+            "/actions",
+            &Action::from(CreateEntry::from(root_entry(missing))),
+        )
+        .await;
+    assert!(response.status().is_client_error());
+    let body: ErrorBody = response.json().await.unwrap();
+    assert_eq!(body.kind, "rejected");
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_actions_and_queries_run_as_the_authenticated_actor(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let user = user();
+    let create_user = Action::from(CreateUser::from(user.clone()));
+
+    let response = server.post("/actions", &create_user).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let query = QueryRequest {
+        query: AnyQuery::from(FindUserById {
+            actor_id: user.actor_id,
+        }),
+    };
+    let response = server.post("/queries", &query).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Acting as someone else.
+    let response = server
+        .post_as(Uuid::new_v4(), "/actions", &create_user)
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body: ErrorBody = response.json().await.unwrap();
+    assert_eq!(body.kind, "rejected");
+    let response = server.post_as(user.actor_id, "/queries", &query).await;
+    let found: AnyQueryResponse = response.json().await.unwrap();
+    assert_eq!(found, AnyQueryResponse::FindUserById(None));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_malformed_body_is_bad_request(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let response = server
+        .post_as(
+            Uuid::new_v4(),
+            "/actions",
+            &serde_json::json!({ "NotAnAction": {} }),
+        )
+        .await;
+    assert!(response.status().is_client_error());
+    let body: ErrorBody = response.json().await.unwrap();
+    assert_eq!(body.kind, "bad_request");
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_sync_push_and_pull(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let user = user();
+    let mutation = Mutation {
+        id: Uuid::new_v4(),
+        timestamp: Utc::now(),
+        action: CreateUser::from(user.clone()).into(),
+        changes: vec![],
+    };
+
+    let request = PushRequest {
+        last_seen_server_seq: 0,
+        mutations: vec![mutation.clone()],
+    };
+    let response = server.post_as(user.actor_id, "/sync/push", &request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let pushed: PushResponse = response.json().await.unwrap();
+    assert_eq!(
+        pushed.results,
+        vec![PushResult::Acked {
+            mutation_id: mutation.id,
+            seq_num: 1
+        }]
+    );
+
+    let response = server
+        .post_as(
+            user.actor_id,
+            "/sync/pull",
+            &PullRequest {
+                after_seq_num: 0,
+                limit: 10,
+            },
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let pulled: PullResponse = response.json().await.unwrap();
+    assert_eq!(pulled.mutations.len(), 1);
+    assert_eq!(pulled.mutations[0].mutation.id, mutation.id);
+    assert_eq!(pulled.mutations[0].mutation.action, mutation.action);
+    // The server's mutator decides the deltas, not the client.
+    assert!(!pulled.mutations[0].mutation.changes.is_empty());
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_sync_requires_the_pushing_actor(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let user = user();
+    let request = PushRequest {
+        last_seen_server_seq: 0,
+        mutations: vec![Mutation {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            action: CreateUser::from(user.clone()).into(),
+            changes: vec![],
+        }],
+    };
+
+    let response = server.post("/sync/push", &request).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body: ErrorBody = response.json().await.unwrap();
+    assert_eq!(body.kind, "unauthenticated");
+
+    let forged = server
+        .client
+        .post(format!("{}/sync/push", server.base_url))
+        .bearer_auth(AuthKey::new("wrong secret").issue(user.actor_id))
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+
+    let response = server.post_as(Uuid::new_v4(), "/sync/push", &request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let pushed: PushResponse = response.json().await.unwrap();
+    assert!(matches!(pushed.results[..], [PushResult::Rejected { .. }]));
+}
+
+#[sqlx::test(migrations = "../gv-sql/postgres/migrations")]
+async fn test_graceful_shutdown(pool: PgPool) {
+    let server = TestServer::spawn(pool).await;
+    let health = format!("{}/health", server.base_url);
+    assert!(reqwest::get(&health).await.is_ok());
+
+    server.shutdown.send(()).unwrap();
+    server.handle.await.unwrap().unwrap();
+    assert!(reqwest::get(&health).await.is_err());
+}
+
+#[test]
+fn test_config_from_lookup() {
+    let config = ServerConfig::from_lookup(|var| match var {
+        "DATABASE_URL" => Some("postgres://localhost/gv".to_string()),
+        "GV_AUTH_SECRET" => Some("secret".to_string()),
+        "GV_BIND_ADDR" => Some("127.0.0.1:9000".to_string()),
+        _ => None,
+    })
+    .unwrap();
+    assert_eq!(config.bind_addr, "127.0.0.1:9000".parse().unwrap());
+    assert_eq!(config.max_connections, 10);
+
+    assert_eq!(
+        ServerConfig::from_lookup(|_| None),
+        Err(ConfigError::Missing("DATABASE_URL"))
+    );
+    assert_eq!(
+        ServerConfig::from_lookup(|var| match var {
+            "DATABASE_URL" => Some("postgres://localhost/gv".to_string()),
+            _ => None,
+        }),
+        Err(ConfigError::Missing("GV_AUTH_SECRET"))
+    );
+    assert_eq!(
+        ServerConfig::from_lookup(|var| match var {
+            "DATABASE_URL" => Some("postgres://localhost/gv".to_string()),
+            "GV_AUTH_SECRET" => Some("secret".to_string()),
+            "GV_DB_MAX_CONNECTIONS" => Some("many".to_string()),
+            _ => None,
+        }),
+        Err(ConfigError::Invalid {
+            var: "GV_DB_MAX_CONNECTIONS",
+            value: "many".to_string()
+        })
+    );
+}
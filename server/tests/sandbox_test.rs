@@ -36,6 +36,7 @@ async fn test_create_user_activity_entry(pool: PgPool) {
         source_activity_id: None,
         name: ActivityName::parse("Pull Up".to_string()).unwrap(),
         description: Some("Pull yourself up.".to_string()),
+        archived: false,
     };
     postgres_server
         .run_action(Action::CreateActivity(